@@ -5,6 +5,7 @@ use massa_models::endorsement::EndorsementId;
 use massa_models::operation::OperationId;
 use massa_models::slot::{IndexedSlot, Slot};
 use massa_models::{address::Address, amount::Amount, block_id::BlockId};
+use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 
 use crate::slot::SlotAmount;
@@ -177,3 +178,146 @@ impl std::fmt::Display for AddressFilter {
         }
     }
 }
+
+/// query of the state of an address at the end of a past final slot
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub enum AddressStateAtSlotQuery {
+    /// balance of the address
+    Balance {
+        /// Address
+        address: Address,
+        /// final slot at the end of which the balance is queried
+        slot: Slot,
+    },
+    /// value of a datastore entry of the address
+    DatastoreValue {
+        /// Address
+        address: Address,
+        /// datastore key
+        key: Vec<u8>,
+        /// final slot at the end of which the value is queried
+        slot: Slot,
+    },
+}
+
+/// state of an address at the end of a past final slot
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub enum AddressStateAtSlot {
+    /// balance of the address
+    Balance(Amount),
+    /// value of a datastore entry of the address
+    DatastoreValue(Vec<u8>),
+}
+
+/// way an address was involved in a final slot
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AddressHistoryKind {
+    /// the address created an executed operation
+    OperationCreated {
+        /// whether the operation execution succeeded
+        success: bool,
+    },
+    /// the address received coins from a successful operation
+    CoinsReceived,
+    /// the address bought rolls with a successful operation
+    RollBuy {
+        /// number of rolls bought
+        roll_count: u64,
+    },
+    /// the address sold rolls with a successful operation
+    RollSell {
+        /// number of rolls sold
+        roll_count: u64,
+    },
+    /// the address was denounced and slashed
+    Slashed,
+}
+
+/// entry of the final history of an address
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct AddressHistoryItem {
+    /// slot of the block in which the address was involved
+    pub slot: Slot,
+    /// operation involving the address, if any
+    pub operation_id: Option<OperationId>,
+    /// way the address was involved
+    pub kind: AddressHistoryKind,
+}
+
+/// position in the history of an address, ordered by slot then by index in the slot
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AddressHistoryCursor {
+    /// slot of the entry
+    pub slot: Slot,
+    /// index of the entry among the history entries of the slot
+    pub index_in_slot: u32,
+}
+
+/// page of the final history of an address
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct AddressHistoryPage {
+    /// entries of the page, ordered by slot
+    pub items: Vec<AddressHistoryItem>,
+    /// cursor to request the next page with, `None` if there are no more entries
+    pub next_cursor: Option<AddressHistoryCursor>,
+}
+
+/// what an address is drawn to produce
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AddressDrawKind {
+    /// the block of the slot
+    Block,
+    /// an endorsement of the slot
+    Endorsement {
+        /// index of the endorsement in the block
+        index: usize,
+    },
+}
+
+impl std::fmt::Display for AddressDrawKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressDrawKind::Block => write!(f, "block"),
+            AddressDrawKind::Endorsement { index } => write!(f, "endorsement {}", index),
+        }
+    }
+}
+
+/// upcoming draw of an address
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct AddressNextDraw {
+    /// slot of the draw
+    pub slot: Slot,
+    /// estimated start of the slot, in milliseconds since the UNIX epoch
+    pub timestamp: MassaTime,
+    /// what the address is drawn to produce
+    pub kind: AddressDrawKind,
+}
+
+/// upcoming draws of an address, over the cycles whose draws are already known
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct AddressNextDraws {
+    /// the address
+    pub address: Address,
+    /// draws, by slot
+    pub draws: Vec<AddressNextDraw>,
+}
+
+impl std::fmt::Display for AddressNextDraws {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Address {}:", self.address)?;
+        if self.draws.is_empty() {
+            writeln!(f, "\tNo upcoming draws")?;
+        }
+        for draw in &self.draws {
+            writeln!(
+                f,
+                "\tSlot {} ({}): {}",
+                draw.slot,
+                draw.timestamp.format_instant(),
+                draw.kind
+            )?;
+        }
+        Ok(())
+    }
+}
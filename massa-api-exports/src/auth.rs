@@ -0,0 +1,186 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bearer token authentication and role-based access control of the private APIs.
+//!
+//! Tokens are read from a JSON file listing, for each client, a name, a token and a role.
+//! Only the hashes of the tokens are kept in memory, and only the client names are logged.
+
+use displaydoc::Display;
+use massa_hash::Hash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Role given to a private API token.
+/// Roles are ordered: each role can call the methods allowed to the roles below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiRole {
+    /// can only read the node configuration (whitelists, staking addresses, status...)
+    ReadOnly,
+    /// can also manage the node: stop it, sign messages, ban peers, edit whitelists and blacklists
+    Operator,
+    /// can also add and remove staking keys
+    StakingAdmin,
+}
+
+impl std::fmt::Display for ApiRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiRole::ReadOnly => write!(f, "read-only"),
+            ApiRole::Operator => write!(f, "operator"),
+            ApiRole::StakingAdmin => write!(f, "staking-admin"),
+        }
+    }
+}
+
+/// An entry of the private API tokens file
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiTokenEntry {
+    /// name of the client, used in the audit logs
+    pub name: String,
+    /// bearer token of the client
+    pub token: String,
+    /// role of the client
+    pub role: ApiRole,
+}
+
+/// Errors of the private API authentication
+#[non_exhaustive]
+#[derive(Display, thiserror::Error, Debug)]
+pub enum AuthError {
+    /// missing or unknown bearer token
+    Unauthenticated,
+    /// client `{0}` is not allowed to call `{1}`
+    PermissionDenied(String, String),
+    /// could not load the private API tokens file: {0}
+    TokensFile(String),
+}
+
+/// Bearer tokens accepted by the private APIs, with the name and role of their client
+#[derive(Debug, Clone)]
+pub struct ApiAuth {
+    /// clients indexed by the hash of their token
+    clients: HashMap<Hash, (String, ApiRole)>,
+}
+
+impl ApiAuth {
+    /// Build the token table from the entries of a tokens file
+    pub fn new(entries: Vec<ApiTokenEntry>) -> Self {
+        ApiAuth {
+            clients: entries
+                .into_iter()
+                .map(|entry| {
+                    (
+                        Hash::compute_from(entry.token.as_bytes()),
+                        (entry.name, entry.role),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Load the token table from a JSON tokens file
+    pub fn load(path: &Path) -> Result<Self, AuthError> {
+        let content = std::fs::read(path)
+            .map_err(|err| AuthError::TokensFile(format!("{}: {}", path.display(), err)))?;
+        let entries: Vec<ApiTokenEntry> = serde_json::from_slice(&content)
+            .map_err(|err| AuthError::TokensFile(format!("{}: {}", path.display(), err)))?;
+        Ok(ApiAuth::new(entries))
+    }
+
+    /// Get the name and role of the client of an `Authorization` header value, if its bearer token is known
+    pub fn authenticate(&self, authorization: Option<&str>) -> Option<(&str, ApiRole)> {
+        let token = authorization?.strip_prefix("Bearer ")?.trim();
+        self.clients
+            .get(&Hash::compute_from(token.as_bytes()))
+            .map(|(name, role)| (name.as_str(), *role))
+    }
+
+    /// Check that the client of an `Authorization` header value can call a method.
+    /// Denied calls and allowed calls to methods that require more than the read-only role are logged for audit.
+    ///
+    /// # Arguments
+    /// * `api`: name of the API, for the audit logs
+    /// * `authorization`: value of the `Authorization` header of the request, if any
+    /// * `method`: name of the called method
+    /// * `required_role`: minimum role needed to call the method
+    pub fn authorize(
+        &self,
+        api: &str,
+        authorization: Option<&str>,
+        method: &str,
+        required_role: ApiRole,
+    ) -> Result<(), AuthError> {
+        let Some((name, role)) = self.authenticate(authorization) else {
+            warn!(
+                "audit: {}: unauthenticated call to `{}` denied",
+                api, method
+            );
+            return Err(AuthError::Unauthenticated);
+        };
+        if role < required_role {
+            warn!(
+                "audit: {}: `{}` ({}) denied calling `{}` (requires {})",
+                api, name, role, method, required_role
+            );
+            return Err(AuthError::PermissionDenied(
+                name.to_string(),
+                method.to_string(),
+            ));
+        }
+        if required_role > ApiRole::ReadOnly {
+            info!("audit: {}: `{}` ({}) called `{}`", api, name, role, method);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_auth_roles() {
+        let entries: Vec<ApiTokenEntry> = serde_json::from_str(
+            r#"[
+                {"name": "monitoring", "token": "t1", "role": "read-only"},
+                {"name": "ops", "token": "t2", "role": "operator"},
+                {"name": "staking", "token": "t3", "role": "staking-admin"}
+            ]"#,
+        )
+        .unwrap();
+        let auth = ApiAuth::new(entries);
+
+        assert_eq!(
+            auth.authenticate(Some("Bearer t2")),
+            Some(("ops", ApiRole::Operator))
+        );
+        assert_eq!(auth.authenticate(Some("t2")), None);
+        assert_eq!(auth.authenticate(Some("Bearer unknown")), None);
+
+        assert!(matches!(
+            auth.authorize("test", None, "stop_node", ApiRole::Operator),
+            Err(AuthError::Unauthenticated)
+        ));
+        assert!(matches!(
+            auth.authorize("test", Some("Bearer t1"), "stop_node", ApiRole::Operator),
+            Err(AuthError::PermissionDenied(_, _))
+        ));
+        assert!(auth
+            .authorize("test", Some("Bearer t1"), "get_status", ApiRole::ReadOnly)
+            .is_ok());
+        assert!(auth
+            .authorize("test", Some("Bearer t3"), "stop_node", ApiRole::Operator)
+            .is_ok());
+        assert!(auth
+            .authorize(
+                "test",
+                Some("Bearer t2"),
+                "add_staking_secret_keys",
+                ApiRole::StakingAdmin
+            )
+            .is_err());
+    }
+}
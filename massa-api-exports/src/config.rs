@@ -21,6 +21,8 @@ pub struct APIConfig {
     pub bind_api: SocketAddr,
     /// max argument count
     pub max_arguments: u64,
+    /// max number of upcoming draws returned per address by `get_next_draws`
+    pub max_next_draws: u64,
     /// openrpc specification path
     pub openrpc_spec_path: PathBuf,
     /// bootstrap whitelist path
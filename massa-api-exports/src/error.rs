@@ -9,6 +9,8 @@ use massa_time::TimeError;
 use massa_versioning::versioning_factory::FactoryError;
 use massa_wallet::WalletError;
 
+use crate::rate_limit::RateLimitError;
+
 /// Errors of the api component.
 #[non_exhaustive]
 #[derive(Display, thiserror::Error, Debug)]
@@ -47,6 +49,8 @@ pub enum ApiError {
     InternalServerError(String),
     /// Versioning Factory error: {0}
     FactoryError(#[from] FactoryError),
+    /// Rate limit error: {0}
+    RateLimitError(#[from] RateLimitError),
 }
 
 impl From<ApiError> for ErrorObjectOwned {
@@ -70,6 +74,9 @@ impl From<ApiError> for ErrorObjectOwned {
             ApiError::MissingConfig(_) => -32018,
             ApiError::WrongAPI => -32019,
             ApiError::FactoryError(_) => -32020,
+            // waiting does not help an execution over the gas burst
+            ApiError::RateLimitError(RateLimitError::ReadOnlyGasAboveBurst { .. }) => -32000,
+            ApiError::RateLimitError(_) => -32021,
         };
 
         ErrorObject::owned(code, err.to_string(), None::<()>)
@@ -27,6 +27,87 @@ pub struct ExecuteReadOnlyResponse {
     pub gas_cost: u64,
     /// state changes caused by the execution step
     pub state_changes: StateChanges,
+    /// call tree of the execution, if a trace was requested
+    pub trace: Option<ReadOnlyCallTrace>,
+}
+
+/// Trace of a call frame of a read-only execution
+///
+/// The execution runtime only reports the remaining gas when a frame starts running, not when it returns.
+/// The `gas_used` of the entry frame is exact, but the `gas_used` of a nested frame also counts the gas
+/// spent by its callers after it returned, up to the start of the next call or the end of the execution.
+/// Traces are only available through the JSON-RPC API.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadOnlyCallTrace {
+    /// address that made the call, if any
+    pub caller: Option<Address>,
+    /// called address
+    pub callee: Address,
+    /// called function, only known for the entry frame
+    pub function: Option<String>,
+    /// coins transferred to the callee
+    pub coins: Amount,
+    /// gas available when the frame started running
+    pub gas_limit: Option<u64>,
+    /// gas used by the frame and its sub-calls: exact for the entry frame, an upper bound for nested frames
+    pub gas_used: Option<u64>,
+    /// datastore entries read by the frame, as (address, key)
+    pub datastore_reads: Vec<(Address, Vec<u8>)>,
+    /// datastore entries set, appended to or deleted by the frame, as (address, key)
+    pub datastore_writes: Vec<(Address, Vec<u8>)>,
+    /// data of the events emitted by the frame
+    pub events: Vec<String>,
+    /// error that interrupted the execution, if it was raised in this frame
+    pub error: Option<String>,
+    /// calls made by the frame, in call order
+    pub calls: Vec<ReadOnlyCallTrace>,
+}
+
+impl ReadOnlyCallTrace {
+    /// Writes the frame and its sub-calls, indented by depth
+    fn fmt_tree(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let indent = "  ".repeat(depth);
+        writeln!(
+            f,
+            "{}{} -> {}{} (coins: {}, gas used: {})",
+            indent,
+            self.caller
+                .map_or_else(|| "-".to_string(), |caller| caller.to_string()),
+            self.callee,
+            self.function
+                .as_ref()
+                .map_or_else(String::new, |function| format!("::{}", function)),
+            self.coins,
+            match (self.gas_used, depth) {
+                (None, _) => "unknown".to_string(),
+                (Some(gas), 0) => gas.to_string(),
+                // the gas used by nested frames is an upper bound
+                (Some(gas), _) => format!("at most {}", gas),
+            }
+        )?;
+        for (address, key) in &self.datastore_reads {
+            writeln!(f, "{}  read {} {:?}", indent, address, key)?;
+        }
+        for (address, key) in &self.datastore_writes {
+            writeln!(f, "{}  write {} {:?}", indent, address, key)?;
+        }
+        for event in &self.events {
+            writeln!(f, "{}  event {}", indent, event)?;
+        }
+        for call in &self.calls {
+            call.fmt_tree(f, depth + 1)?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "{}  error: {}", indent, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ReadOnlyCallTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_tree(f, 0)
+    }
 }
 
 impl Display for ExecuteReadOnlyResponse {
@@ -48,6 +129,10 @@ impl Display for ExecuteReadOnlyResponse {
                 writeln!(f, "{}", event)?; // id already displayed in event
             }
         }
+        if let Some(trace) = &self.trace {
+            writeln!(f, "Call trace:")?;
+            write!(f, "{}", trace)?;
+        }
         Ok(())
     }
 }
@@ -65,6 +150,8 @@ pub struct ReadOnlyBytecodeExecution {
     pub operation_datastore: Option<Vec<u8>>,
     /// fee
     pub fee: Option<Amount>,
+    /// record the call tree of the execution, false if omitted
+    pub trace: Option<bool>,
 }
 
 /// read SC call request
@@ -84,4 +171,6 @@ pub struct ReadOnlyCall {
     pub coins: Option<Amount>,
     /// fee
     pub fee: Option<Amount>,
+    /// record the call tree of the execution, false if omitted
+    pub trace: Option<bool>,
 }
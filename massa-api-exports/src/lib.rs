@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 
 /// address related structures
 pub mod address;
+/// private API authentication
+pub mod auth;
 /// block-related structures
 pub mod block;
 /// node configuration
@@ -31,6 +33,8 @@ pub mod node;
 pub mod operation;
 /// page
 pub mod page;
+/// public API rate limiting
+pub mod rate_limit;
 /// rolls
 pub mod rolls;
 /// slots
@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    amount::Amount,
     block_id::BlockId,
     operation::{OperationId, SecureShareOperation},
 };
@@ -21,6 +22,31 @@ pub struct OperationInput {
     pub serialized_content: Vec<u8>,
 }
 
+/// Description of an operation for which a fee is estimated
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct FeeEstimationRequest {
+    /// serialized size of the operation, in bytes
+    pub size: u64,
+    /// max gas the operation can use
+    pub max_gas: u64,
+    /// number of periods within which the operation should be included
+    pub periods: u64,
+}
+
+/// Fee suggestions for an operation
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimation {
+    /// lowest fee currently scored high enough for inclusion within the requested periods
+    pub low: Amount,
+    /// at least the median fee of the operations included in recent final blocks
+    pub medium: Amount,
+    /// enough for inclusion in the next period, and at least the 90th percentile
+    /// of the fees of the operations included in recent final blocks
+    pub high: Amount,
+    /// number of operations of the recent final blocks the percentiles were computed from
+    pub sample_size: u64,
+}
+
 /// Operation and contextual info about it
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OperationInfo {
@@ -0,0 +1,465 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Per-client rate limiting of the public APIs.
+//!
+//! Each client has two token buckets: one for its requests and one for the gas of its read-only executions.
+//! Read-only executions are charged their declared `max_gas` before they run.
+//! Clients are identified by their IP address, or by their name if they present a bearer token listed in the
+//! rate limit tokens file, so that several clients behind the same address can get their own budgets.
+//! IPv6 clients are identified by their /64 prefix, the smallest block usually assigned to a subscriber.
+//!
+//! A tracked client is only forgotten once its budgets are full again, so that forgetting it cannot reset them.
+//! While all the tracked clients are active, the new ones share a single overflow budget.
+
+use displaydoc::Display;
+use massa_hash::Hash;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::net::{IpAddr, Ipv6Addr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Per-client rate limits of the public APIs
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitConfig {
+    /// requests allowed per second and per client, 0 to disable the limit
+    pub requests_per_second: u64,
+    /// requests a client can make at once after being idle
+    pub request_burst: u64,
+    /// read-only execution gas allowed per second and per client, 0 to disable the limit
+    pub read_only_gas_per_second: u64,
+    /// read-only execution gas a client can use at once after being idle
+    pub read_only_gas_burst: u64,
+    /// maximum number of clients whose budgets are tracked
+    pub max_tracked_clients: usize,
+    /// whether to identify JSON-RPC clients by the first address of the `X-Forwarded-For` header.
+    /// Only enable it behind a reverse proxy that sets this header.
+    pub trust_forwarded_for: bool,
+    /// JSON file of the bearer tokens that get their own budgets: `[{"name": "...", "token": "..."}]`
+    pub tokens_path: Option<PathBuf>,
+}
+
+/// An entry of the rate limit tokens file
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitTokenEntry {
+    /// name of the client, used as its rate limit key
+    pub name: String,
+    /// bearer token of the client
+    pub token: String,
+}
+
+/// Identity of a rate limited client
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RateLimitKey {
+    /// client presenting a known bearer token, by name
+    Token(String),
+    /// client identified by its IPv4 address or by its IPv6 /64 prefix
+    Ip(IpAddr),
+    /// client identified by its connection, when its address is unknown
+    Connection(u64),
+}
+
+impl RateLimitKey {
+    /// Identify a client by its IP address: IPv4 clients by their address, including when it is mapped to IPv6
+    /// by a dual-stack listener, and IPv6 clients by their /64 prefix
+    pub fn from_ip(ip: IpAddr) -> Self {
+        let ip = match ip {
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => IpAddr::V4(ip),
+                None => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & !u128::from(u64::MAX))),
+            },
+            ip => ip,
+        };
+        RateLimitKey::Ip(ip)
+    }
+}
+
+/// Errors of the rate limiter
+#[non_exhaustive]
+#[derive(Display, thiserror::Error, Debug)]
+pub enum RateLimitError {
+    /// rate limited, retry in {0:?}
+    RateLimited(Duration),
+    /// read-only execution gas {gas} exceeds the maximum of {burst} a client can use at once
+    ReadOnlyGasAboveBurst {
+        /// gas requested by the read-only execution
+        gas: u64,
+        /// read-only gas burst of the clients
+        burst: u64,
+    },
+    /// could not load the rate limit tokens file: {0}
+    TokensFile(String),
+}
+
+/// Totals of the requests and read-only executions refused since the node started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitStats {
+    /// requests refused because their client exceeded its request rate
+    pub limited_requests: u64,
+    /// read-only executions refused because their client exceeded its gas rate
+    pub limited_read_only_executions: u64,
+    /// number of clients whose budgets are tracked
+    pub tracked_clients: usize,
+}
+
+/// Token bucket
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// available amount
+    available: f64,
+    /// last time the bucket was refilled
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn full(capacity: u64, now: Instant) -> Self {
+        Bucket {
+            available: capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Refill the bucket at `rate` per second up to `capacity`
+    fn refill(&mut self, rate: u64, capacity: u64, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.available = (self.available + elapsed * rate as f64).min(capacity as f64);
+        self.last_refill = now;
+    }
+
+    /// Take `amount` from the bucket, or return the time to wait until it is available
+    fn take(
+        &mut self,
+        amount: u64,
+        rate: u64,
+        capacity: u64,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.refill(rate, capacity, now);
+        if self.available >= amount as f64 {
+            self.available -= amount as f64;
+            return Ok(());
+        }
+        let missing = amount.min(capacity) as f64 - self.available;
+        Err(Duration::from_secs_f64(missing.max(0.0) / rate as f64))
+    }
+
+    /// Time at which the bucket will be full again if nothing is taken from it
+    fn full_at(&self, rate: u64, capacity: u64) -> Instant {
+        let missing = capacity as f64 - self.available;
+        if missing <= 0.0 || rate == 0 {
+            return self.last_refill;
+        }
+        self.last_refill + Duration::from_secs_f64(missing / rate as f64)
+    }
+}
+
+/// Request and gas budgets of a client
+#[derive(Debug, Clone, Copy)]
+struct ClientBudget {
+    requests: Bucket,
+    read_only_gas: Bucket,
+    /// time at which both budgets will be full again
+    full_at: Instant,
+}
+
+impl ClientBudget {
+    fn full(config: &RateLimitConfig, now: Instant) -> Self {
+        ClientBudget {
+            requests: Bucket::full(config.request_burst, now),
+            read_only_gas: Bucket::full(config.read_only_gas_burst, now),
+            full_at: now,
+        }
+    }
+
+    /// Update the time at which both budgets will be full again
+    fn update_full_at(&mut self, config: &RateLimitConfig) {
+        self.full_at = self
+            .requests
+            .full_at(config.requests_per_second, config.request_burst)
+            .max(
+                self.read_only_gas
+                    .full_at(config.read_only_gas_per_second, config.read_only_gas_burst),
+            );
+    }
+}
+
+/// Budgets of the clients
+struct ClientBudgets {
+    /// budgets of the tracked clients
+    tracked: HashMap<RateLimitKey, ClientBudget>,
+    /// tracked clients sorted by the time at which their budgets will be full again
+    by_full_at: BTreeSet<(Instant, RateLimitKey)>,
+    /// budget shared by the clients that cannot be tracked while all the tracked ones are active
+    overflow: ClientBudget,
+}
+
+/// Per-client rate limiter of a public API
+pub struct RateLimiter {
+    /// rate limit config
+    config: RateLimitConfig,
+    /// names of the clients with their own budgets, indexed by the hash of their token
+    token_names: HashMap<Hash, String>,
+    /// budgets of the clients
+    clients: Mutex<ClientBudgets>,
+    /// requests refused since the node started
+    limited_requests: AtomicU64,
+    /// read-only executions refused since the node started
+    limited_read_only_executions: AtomicU64,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter, loading its tokens file if any
+    pub fn new(config: RateLimitConfig) -> Result<Self, RateLimitError> {
+        let token_names = match &config.tokens_path {
+            Some(path) => {
+                let content = std::fs::read(path).map_err(|err| {
+                    RateLimitError::TokensFile(format!("{}: {}", path.display(), err))
+                })?;
+                let entries: Vec<RateLimitTokenEntry> =
+                    serde_json::from_slice(&content).map_err(|err| {
+                        RateLimitError::TokensFile(format!("{}: {}", path.display(), err))
+                    })?;
+                entries
+                    .into_iter()
+                    .map(|entry| (Hash::compute_from(entry.token.as_bytes()), entry.name))
+                    .collect()
+            }
+            None => HashMap::new(),
+        };
+        let overflow = ClientBudget::full(&config, Instant::now());
+        Ok(RateLimiter {
+            config,
+            token_names,
+            clients: Mutex::new(ClientBudgets {
+                tracked: HashMap::new(),
+                by_full_at: BTreeSet::new(),
+                overflow,
+            }),
+            limited_requests: AtomicU64::new(0),
+            limited_read_only_executions: AtomicU64::new(0),
+        })
+    }
+
+    /// Get the rate limit config
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Identify a client by the bearer token of its `Authorization` header value if it is known,
+    /// or by the given fallback key otherwise
+    pub fn client_key(&self, authorization: Option<&str>, fallback: RateLimitKey) -> RateLimitKey {
+        authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| {
+                self.token_names
+                    .get(&Hash::compute_from(token.trim().as_bytes()))
+            })
+            .map_or(fallback, |name| RateLimitKey::Token(name.clone()))
+    }
+
+    /// Count a request of a client against its budget
+    pub fn check_request(&self, key: &RateLimitKey) -> Result<(), RateLimitError> {
+        if self.config.requests_per_second == 0 {
+            return Ok(());
+        }
+        let res = self.with_budget(key, |budget, now| {
+            budget.requests.take(
+                1,
+                self.config.requests_per_second,
+                self.config.request_burst,
+                now,
+            )
+        });
+        res.map_err(|retry_after| {
+            self.limited_requests.fetch_add(1, Ordering::Relaxed);
+            RateLimitError::RateLimited(retry_after)
+        })
+    }
+
+    /// Charge the gas of a read-only execution of a client against its budget
+    pub fn check_read_only_gas(&self, key: &RateLimitKey, gas: u64) -> Result<(), RateLimitError> {
+        if self.config.read_only_gas_per_second == 0 {
+            return Ok(());
+        }
+        // waiting would not help an execution that can never fit in a budget
+        if gas > self.config.read_only_gas_burst {
+            self.limited_read_only_executions
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(RateLimitError::ReadOnlyGasAboveBurst {
+                gas,
+                burst: self.config.read_only_gas_burst,
+            });
+        }
+        let res = self.with_budget(key, |budget, now| {
+            budget.read_only_gas.take(
+                gas,
+                self.config.read_only_gas_per_second,
+                self.config.read_only_gas_burst,
+                now,
+            )
+        });
+        res.map_err(|retry_after| {
+            self.limited_read_only_executions
+                .fetch_add(1, Ordering::Relaxed);
+            RateLimitError::RateLimited(retry_after)
+        })
+    }
+
+    /// Get the totals of the refused requests and read-only executions
+    pub fn get_stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            limited_requests: self.limited_requests.load(Ordering::Relaxed),
+            limited_read_only_executions: self.limited_read_only_executions.load(Ordering::Relaxed),
+            tracked_clients: self.clients.lock().tracked.len(),
+        }
+    }
+
+    /// Run `f` on the budget of a client, tracking it if needed.
+    /// When all the tracked clients are active, untracked clients are charged on the overflow budget.
+    fn with_budget<F>(&self, key: &RateLimitKey, f: F) -> Result<(), Duration>
+    where
+        F: FnOnce(&mut ClientBudget, Instant) -> Result<(), Duration>,
+    {
+        let now = Instant::now();
+        let mut clients = self.clients.lock();
+        let ClientBudgets {
+            tracked,
+            by_full_at,
+            overflow,
+        } = &mut *clients;
+        if !tracked.contains_key(key) && tracked.len() >= self.config.max_tracked_clients {
+            // forget the client whose budgets are full again the earliest, if they already are
+            match by_full_at.first() {
+                Some((full_at, _)) if *full_at <= now => {
+                    if let Some((_, idle_key)) = by_full_at.pop_first() {
+                        tracked.remove(&idle_key);
+                    }
+                }
+                _ => return f(overflow, now),
+            }
+        }
+        let budget = tracked
+            .entry(key.clone())
+            .or_insert_with(|| ClientBudget::full(&self.config, now));
+        by_full_at.remove(&(budget.full_at, key.clone()));
+        let res = f(budget, now);
+        budget.update_full_at(&self.config);
+        by_full_at.insert((budget.full_at, key.clone()));
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_budgets() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 1,
+            request_burst: 2,
+            read_only_gas_per_second: 1_000,
+            read_only_gas_burst: 10_000,
+            max_tracked_clients: 2,
+            trust_forwarded_for: false,
+            tokens_path: None,
+        })
+        .unwrap();
+        let client = RateLimitKey::Ip("1.2.3.4".parse().unwrap());
+        let other = RateLimitKey::Ip("5.6.7.8".parse().unwrap());
+
+        // the burst is allowed, then the client has to wait
+        assert!(limiter.check_request(&client).is_ok());
+        assert!(limiter.check_request(&client).is_ok());
+        match limiter.check_request(&client) {
+            Err(RateLimitError::RateLimited(retry_after)) => {
+                assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(1))
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+        // other clients have their own budgets
+        assert!(limiter.check_request(&other).is_ok());
+
+        assert!(limiter.check_read_only_gas(&client, 8_000).is_ok());
+        assert!(limiter.check_read_only_gas(&client, 8_000).is_err());
+        assert!(limiter.check_read_only_gas(&other, 8_000).is_ok());
+
+        // over the burst, the execution can never be allowed
+        assert!(matches!(
+            limiter.check_read_only_gas(&other, 20_000),
+            Err(RateLimitError::ReadOnlyGasAboveBurst {
+                gas: 20_000,
+                burst: 10_000
+            })
+        ));
+
+        // at capacity, active clients are kept and the new ones share the overflow budget
+        let third = RateLimitKey::Connection(0);
+        let fourth = RateLimitKey::Connection(1);
+        assert!(limiter.check_request(&third).is_ok());
+        assert!(limiter.check_request(&fourth).is_ok());
+        assert!(limiter.check_request(&third).is_err());
+        assert!(limiter.clients.lock().tracked.contains_key(&client));
+        assert!(!limiter.clients.lock().tracked.contains_key(&third));
+        assert_eq!(
+            limiter.get_stats(),
+            RateLimitStats {
+                limited_requests: 2,
+                limited_read_only_executions: 2,
+                tracked_clients: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_forgets_full_budgets() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 100,
+            request_burst: 10,
+            read_only_gas_per_second: 0,
+            read_only_gas_burst: 0,
+            max_tracked_clients: 1,
+            trust_forwarded_for: false,
+            tokens_path: None,
+        })
+        .unwrap();
+        let client = RateLimitKey::Connection(0);
+        let other = RateLimitKey::Connection(1);
+
+        assert!(limiter.check_request(&client).is_ok());
+        // the budget of the client is not full yet
+        assert!(limiter.check_request(&other).is_ok());
+        assert!(limiter.clients.lock().tracked.contains_key(&client));
+        // once it is full again, the client can be forgotten
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(limiter.check_request(&other).is_ok());
+        assert!(!limiter.clients.lock().tracked.contains_key(&client));
+        assert!(limiter.clients.lock().tracked.contains_key(&other));
+    }
+
+    #[test]
+    fn test_rate_limit_key_from_ip() {
+        // IPv6 clients are identified by their /64 prefix
+        assert_eq!(
+            RateLimitKey::from_ip("2001:db8:1:2:3:4:5:6".parse().unwrap()),
+            RateLimitKey::from_ip("2001:db8:1:2:ffff::1".parse().unwrap())
+        );
+        assert_ne!(
+            RateLimitKey::from_ip("2001:db8:1:2::1".parse().unwrap()),
+            RateLimitKey::from_ip("2001:db8:1:3::1".parse().unwrap())
+        );
+        // IPv4 clients keep their own address, even when mapped to IPv6
+        assert_eq!(
+            RateLimitKey::from_ip("::ffff:1.2.3.4".parse().unwrap()),
+            RateLimitKey::Ip("1.2.3.4".parse().unwrap())
+        );
+        assert_ne!(
+            RateLimitKey::from_ip("::ffff:1.2.3.4".parse().unwrap()),
+            RateLimitKey::from_ip("::ffff:1.2.3.5".parse().unwrap())
+        );
+    }
+}
@@ -1,22 +1,27 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 //! Json RPC API for a massa-node
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use crate::api_trait::MassaApiServer;
 use crate::{ApiServer, ApiV2, StopHandle, API};
 use async_trait::async_trait;
 use futures::future::{self, Either};
 use futures::StreamExt;
-use jsonrpsee::core::{Error as JsonRpseeError, RpcResult, SubscriptionResult};
+use jsonrpsee::core::{RpcResult, SubscriptionResult};
 use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
 use massa_api_exports::config::APIConfig;
 use massa_api_exports::error::ApiError;
 use massa_api_exports::page::{PageRequest, PagedVec, PagedVecV2};
+use massa_api_exports::rate_limit::RateLimiter;
 use massa_api_exports::ApiRequest;
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{
+    ExecutionChannels, ExecutionController, SCOutputEventTracker, SlotExecutionOutput,
+};
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::execution::EventFilter;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_models::version::Version;
@@ -31,17 +36,21 @@ impl API<ApiV2> {
         consensus_controller: Box<dyn ConsensusController>,
         consensus_broadcasts: ConsensusBroadcasts,
         execution_controller: Box<dyn ExecutionController>,
+        execution_channels: ExecutionChannels,
         pool_broadcasts: PoolBroadcasts,
         api_settings: APIConfig,
         version: Version,
+        rate_limiter: Option<Arc<RateLimiter>>,
     ) -> Self {
         API(ApiV2 {
             consensus_controller,
             consensus_broadcasts,
             execution_controller,
+            execution_channels,
             pool_broadcasts,
             api_settings,
             version,
+            rate_limiter,
         })
     }
 }
@@ -52,8 +61,9 @@ impl ApiServer for API<ApiV2> {
         self,
         url: &SocketAddr,
         api_config: &APIConfig,
-    ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+    ) -> Result<StopHandle, std::io::Error> {
+        let rate_limiter = self.0.rate_limiter.clone();
+        crate::serve(self.into_rpc(), url, api_config, None, rate_limiter).await
     }
 }
 
@@ -143,12 +153,40 @@ impl MassaApiServer for API<ApiV2> {
         .await
     }
 
+    async fn subscribe_new_block_graph_events(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0.consensus_broadcasts.block_graph_event_sender.clone(),
+            pending,
+        )
+        .await
+    }
+
     async fn subscribe_new_operations(
         &self,
         pending: PendingSubscriptionSink,
     ) -> SubscriptionResult {
         broadcast_via_ws(self.0.pool_broadcasts.operation_sender.clone(), pending).await
     }
+
+    async fn subscribe_new_sc_execution_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: EventFilter,
+        data_prefix: Option<String>,
+    ) -> SubscriptionResult {
+        broadcast_sc_events_via_ws(
+            self.0
+                .execution_channels
+                .slot_execution_output_sender
+                .clone(),
+            SCOutputEventTracker::new(filter, data_prefix),
+            pending,
+        )
+        .await
+    }
 }
 
 // Brodcast the stream(sender) content via a WebSocket
@@ -185,3 +223,41 @@ async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
         }
     }
 }
+
+// Brodcast the events of the slot execution outputs matching the tracker filter via a WebSocket
+async fn broadcast_sc_events_via_ws(
+    sender: tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    mut tracker: SCOutputEventTracker,
+    pending: PendingSubscriptionSink,
+) -> SubscriptionResult {
+    let sink = pending.accept().await?;
+    let closed = sink.closed();
+    let stream = BroadcastStream::new(sender.subscribe());
+    futures::pin_mut!(closed, stream);
+
+    loop {
+        match future::select(closed, stream.next()).await {
+            // subscription closed.
+            Either::Left((_, _)) => break Ok(()),
+
+            // received new slot execution output from the stream.
+            Either::Right((Some(Ok(slot_execution_output)), c)) => {
+                for notification in tracker.process(&slot_execution_output) {
+                    let notif = SubscriptionMessage::from_json(&notification)?;
+
+                    if sink.send(notif).await.is_err() {
+                        return Ok(());
+                    }
+                }
+
+                closed = c;
+            }
+
+            // Send back back the error.
+            Either::Right((Some(Err(e)), _)) => break Err(e.into()),
+
+            // Stream is closed.
+            Either::Right((None, _)) => break Ok(()),
+        }
+    }
+}
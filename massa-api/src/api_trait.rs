@@ -6,6 +6,7 @@ use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::execution::EventFilter;
 use massa_models::version::Version;
 
 /// Exposed API methods
@@ -50,6 +51,14 @@ pub trait MassaApi {
 	)]
     async fn subscribe_new_filled_blocks(&self) -> SubscriptionResult;
 
+    /// Blocks becoming stale or final and blockclique changes.
+    #[subscription(
+		name = "subscribe_new_block_graph_events" => "new_block_graph_events",
+		unsubscribe = "unsubscribe_new_block_graph_events",
+		item = BlockGraphEvent
+	)]
+    async fn subscribe_new_block_graph_events(&self) -> SubscriptionResult;
+
     /// New produced operations.
     #[subscription(
 		name = "subscribe_new_operations" => "new_operations",
@@ -57,4 +66,21 @@ pub trait MassaApi {
 		item = Operation
 	)]
     async fn subscribe_new_operations(&self) -> SubscriptionResult;
+
+    /// New smart contract execution events matching the filter and the optional data prefix,
+    /// along with their finality transitions (candidate, final, or dropped on re-execution).
+    ///
+    /// There is no gRPC counterpart: the gRPC services are generated from massa-proto-rs, which has no such stream.
+    /// gRPC clients can filter the events of `new_slot_execution_outputs`, which are notified when their slot is
+    /// executed as a candidate and as final, but never dropped.
+    #[subscription(
+		name = "subscribe_new_sc_execution_events" => "new_sc_execution_events",
+		unsubscribe = "unsubscribe_new_sc_execution_events",
+		item = SCOutputEventNotification
+	)]
+    async fn subscribe_new_sc_execution_events(
+        &self,
+        filter: EventFilter,
+        data_prefix: Option<String>,
+    ) -> SubscriptionResult;
 }
@@ -0,0 +1,195 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Bearer token authentication of the private API.
+//!
+//! jsonrpsee method handlers do not see the HTTP headers, so the tokens are checked by a tower layer
+//! that reads the method names of each request (single or batch) before handing it to the server.
+//! WebSocket messages cannot be inspected there, so WebSocket connections require the highest role.
+
+use futures::future::BoxFuture;
+use http_body::{LengthLimitError, Limited};
+use hyper::header::{AUTHORIZATION, UPGRADE, WWW_AUTHENTICATE};
+use hyper::{Body, Request, Response, StatusCode};
+use massa_api_exports::auth::{ApiAuth, ApiRole, AuthError};
+use serde_json::Value;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Name of the private JSON-RPC API in the audit logs
+const PRIVATE_API_NAME: &str = "private JSON-RPC API";
+
+/// Minimum role needed to call a method of the private API
+pub(crate) fn private_method_role(method: &str) -> ApiRole {
+    match method {
+        "get_staking_addresses"
+        | "get_staking_production"
+        | "node_peers_reputation"
+        | "node_peers_whitelist"
+        | "node_bootstrap_whitelist"
+        | "node_bootstrap_blacklist"
+        | "rpc.discover" => ApiRole::ReadOnly,
+        "stop_node"
+        | "node_sign_message"
+        | "node_ban_by_ip"
+        | "node_ban_by_id"
+        | "node_unban_by_ip"
+        | "node_unban_by_id"
+        | "node_add_to_peers_whitelist"
+        | "node_remove_from_peers_whitelist"
+        | "node_bootstrap_whitelist_allow_all"
+        | "node_add_to_bootstrap_whitelist"
+        | "node_remove_from_bootstrap_whitelist"
+        | "node_add_to_bootstrap_blacklist"
+        | "node_remove_from_bootstrap_blacklist" => ApiRole::Operator,
+        // staking keys management, and any method that was not classified yet
+        _ => ApiRole::StakingAdmin,
+    }
+}
+
+/// Get the method names of a JSON-RPC request or batch of requests
+fn request_methods(body: &[u8]) -> Vec<String> {
+    let method = |call: &Value| call.get("method").and_then(Value::as_str).map(String::from);
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(calls)) => calls.iter().filter_map(method).collect(),
+        Ok(call) => method(&call).into_iter().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// HTTP response of a denied request
+fn denied_response(err: AuthError) -> Response<Body> {
+    let (status, body) = match &err {
+        AuthError::Unauthenticated => (StatusCode::UNAUTHORIZED, err.to_string()),
+        _ => (StatusCode::FORBIDDEN, err.to_string()),
+    };
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    if status == StatusCode::UNAUTHORIZED {
+        response.headers_mut().insert(
+            WWW_AUTHENTICATE,
+            "Bearer".parse().expect("valid header value"),
+        );
+    }
+    response
+}
+
+/// Layer checking the bearer token and role of the private API requests
+#[derive(Clone)]
+pub(crate) struct AuthLayer {
+    auth: Arc<ApiAuth>,
+    max_request_body_size: usize,
+}
+
+impl AuthLayer {
+    pub(crate) fn new(auth: Arc<ApiAuth>, max_request_body_size: u32) -> Self {
+        AuthLayer {
+            auth,
+            max_request_body_size: usize::try_from(max_request_body_size).unwrap_or(usize::MAX),
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            auth: self.auth.clone(),
+            max_request_body_size: self.max_request_body_size,
+        }
+    }
+}
+
+/// Service checking the bearer token and role of the private API requests
+#[derive(Clone)]
+pub(crate) struct AuthService<S> {
+    inner: S,
+    auth: Arc<ApiAuth>,
+    max_request_body_size: usize,
+}
+
+impl<S> Service<Request<Body>> for AuthService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // the ready service must be the one that is called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let auth = self.auth.clone();
+        let max_request_body_size = self.max_request_body_size;
+        Box::pin(async move {
+            let authorization = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+
+            // reject unknown clients before reading their request
+            if let Err(err) = auth.authorize(
+                PRIVATE_API_NAME,
+                authorization.as_deref(),
+                request.uri().path(),
+                ApiRole::ReadOnly,
+            ) {
+                return Ok(denied_response(err));
+            }
+
+            let is_websocket = request
+                .headers()
+                .get(UPGRADE)
+                .and_then(|value| value.to_str().ok())
+                .map_or(false, |value| value.eq_ignore_ascii_case("websocket"));
+            if is_websocket {
+                return match auth.authorize(
+                    PRIVATE_API_NAME,
+                    authorization.as_deref(),
+                    "websocket connection",
+                    ApiRole::StakingAdmin,
+                ) {
+                    Ok(()) => inner.call(request).await,
+                    Err(err) => Ok(denied_response(err)),
+                };
+            }
+
+            // the body is buffered to read its method names, up to the size the server would accept
+            let (parts, body) = request.into_parts();
+            let body = match hyper::body::to_bytes(Limited::new(body, max_request_body_size)).await
+            {
+                Ok(body) => body,
+                Err(err) => {
+                    let mut response = Response::new(Body::from(err.to_string()));
+                    *response.status_mut() = if err.is::<LengthLimitError>() {
+                        StatusCode::PAYLOAD_TOO_LARGE
+                    } else {
+                        StatusCode::BAD_REQUEST
+                    };
+                    return Ok(response);
+                }
+            };
+            for method in request_methods(&body) {
+                if let Err(err) = auth.authorize(
+                    PRIVATE_API_NAME,
+                    authorization.as_deref(),
+                    &method,
+                    private_method_role(&method),
+                ) {
+                    return Ok(denied_response(err));
+                }
+            }
+            inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await
+        })
+    }
+}
@@ -5,14 +5,26 @@
 #![warn(unused_crate_dependencies)]
 
 use api_trait::MassaApiServer;
-use hyper::Method;
-use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use auth::AuthLayer;
+use futures::future::{self, BoxFuture};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
-use jsonrpsee::server::middleware::HostFilterLayer;
-use jsonrpsee::server::{BatchRequestConfig, ServerBuilder, ServerHandle};
+use jsonrpsee::server::middleware::http::HostFilterLayer;
+use jsonrpsee::server::ws::is_upgrade_request;
+use jsonrpsee::server::{
+    http::response::too_many_requests, stop_channel, BatchRequestConfig, ConnectionGuard, Methods,
+    PingConfig, RpcServiceBuilder, ServerBuilder, ServerHandle,
+};
 use jsonrpsee::RpcModule;
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{
+        AddressFilter, AddressHistoryCursor, AddressHistoryPage, AddressInfo, AddressNextDraws,
+        AddressStateAtSlot, AddressStateAtSlotQuery,
+    },
+    auth::ApiAuth,
     block::{BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
@@ -20,12 +32,14 @@ use massa_api_exports::{
     error::ApiError::WrongAPI,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
     node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    operation::{FeeEstimation, FeeEstimationRequest, OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    rate_limit::RateLimiter,
     TimeInterval,
 };
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_factory_exports::{ProductionLog, ProductionLogFilter, ProductionReport};
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
@@ -33,26 +47,36 @@ use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
-    execution::EventFilter, slot::Slot, version::Version,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    execution::{EventCursor, EventFilter, SCOutputEventPage},
+    slot::Slot,
+    version::Version,
 };
-use massa_pool_exports::{PoolBroadcasts, PoolController};
+use massa_pool_exports::{PoolBroadcasts, PoolController, PoolOperationFilter, PoolOperationInfo};
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{PeerReputation, ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
+use rate_limit::RateLimitLayer;
 use serde_json::Value;
+use std::error::Error as StdError;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Condvar, Mutex};
+use tower::Service;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 
 mod api;
 mod api_trait;
+mod auth;
 mod private;
 mod public;
+mod rate_limit;
 
 #[cfg(test)]
 mod tests;
@@ -81,6 +105,8 @@ pub struct Public {
     pub node_id: NodeId,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// per-client rate limiter, if rate limiting is enabled
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 /// Private API content
@@ -96,6 +122,10 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// draws of the staking addresses, recorded by the factories
+    pub production_log: Arc<RwLock<ProductionLog>>,
+    /// bearer tokens and roles of the clients allowed to call the API, if authentication is enabled
+    pub auth: Option<Arc<ApiAuth>>,
 }
 
 /// API v2 content
@@ -106,12 +136,16 @@ pub struct ApiV2 {
     pub consensus_broadcasts: ConsensusBroadcasts,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// channels with informations broadcasted by the execution
+    pub execution_channels: ExecutionChannels,
     /// channels with informations broadcasted by the pool
     pub pool_broadcasts: PoolBroadcasts,
     /// API settings
     pub api_settings: APIConfig,
     /// node version
     pub version: Version,
+    /// per-client rate limiter, if rate limiting is enabled
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 /// The API wrapper
@@ -125,7 +159,7 @@ pub trait RpcServer: MassaRpcServer {
         self,
         url: &SocketAddr,
         api_config: &APIConfig,
-    ) -> Result<StopHandle, JsonRpseeError>;
+    ) -> Result<StopHandle, std::io::Error>;
 }
 
 /// Used to manage the API
@@ -136,14 +170,16 @@ pub trait ApiServer: MassaApiServer {
         self,
         url: &SocketAddr,
         api_config: &APIConfig,
-    ) -> Result<StopHandle, JsonRpseeError>;
+    ) -> Result<StopHandle, std::io::Error>;
 }
 
 async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
     api_config: &APIConfig,
-) -> Result<StopHandle, JsonRpseeError> {
+    auth: Option<Arc<ApiAuth>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Result<StopHandle, std::io::Error> {
     let mut server_builder = ServerBuilder::new()
         .max_request_body_size(api_config.max_request_body_size)
         .max_response_body_size(api_config.max_response_body_size)
@@ -153,7 +189,12 @@ async fn serve<T>(
         } else {
             BatchRequestConfig::Disabled
         })
-        .ping_interval(api_config.ping_interval.to_duration());
+        .enable_ws_ping(
+            PingConfig::new()
+                .ping_interval(api_config.ping_interval.to_duration())
+                // the default inactivity limit of 40s would close idle connections between two pings
+                .inactive_limit(api_config.ping_interval.to_duration().saturating_mul(2)),
+        );
 
     if api_config.enable_http && !api_config.enable_ws {
         server_builder = server_builder.http_only();
@@ -168,7 +209,7 @@ async fn serve<T>(
         .allow_methods([Method::POST, Method::OPTIONS])
         // Allow requests from any origin
         .allow_origin(Any)
-        .allow_headers([hyper::header::CONTENT_TYPE]);
+        .allow_headers([hyper::header::CONTENT_TYPE, hyper::header::AUTHORIZATION]);
 
     let hosts = if api_config.allow_hosts.is_empty() {
         vec!["*:*"]
@@ -184,18 +225,72 @@ async fn serve<T>(
 
     let middleware = tower::ServiceBuilder::new()
         .layer(cors)
-        .layer(allowed_hosts);
-
-    let server = server_builder
-        .set_middleware(middleware)
-        .build(url)
-        .await
-        .expect("failed to build server");
-
-    let server_handler = server.start(api);
-    let stop_handler = StopHandle { server_handler };
+        .layer(allowed_hosts)
+        .option_layer(auth.map(|auth| AuthLayer::new(auth, api_config.max_request_body_size)));
+
+    let service_builder = server_builder
+        .set_http_middleware(middleware)
+        .to_service_builder();
+    let methods: Methods = api.into();
+    let (stop_handle, server_handler) = stop_channel();
+
+    // the server is driven by hyper directly so that the rate limits can be keyed on the IP of the connection
+    let server_stop_handle = stop_handle.clone();
+    // each TCP connection holds a permit until it is closed, the requests of the connections over the limit are refused.
+    // WebSocket sessions outlive their HTTP connection and are counted by the jsonrpsee service instead.
+    let connection_guard = ConnectionGuard::new(api_config.max_connections as usize);
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let remote_ip = conn.remote_addr().ip();
+        let connection_permit = connection_guard.try_acquire();
+        let service_builder = service_builder.clone();
+        let methods = methods.clone();
+        let stop_handle = stop_handle.clone();
+        let rate_limiter = rate_limiter.clone();
+
+        async move {
+            Ok::<_, Box<dyn StdError + Send + Sync>>(service_fn(
+                move |request: Request<Body>| -> BoxFuture<
+                    'static,
+                    Result<Response<Body>, Box<dyn StdError + Send + Sync>>,
+                > {
+                    if connection_permit.is_none() {
+                        return Box::pin(future::ready(Ok(too_many_requests())));
+                    }
+                    let rate_limit_layer = rate_limiter.as_ref().map(|limiter| {
+                        let key = rate_limit::client_key(limiter, remote_ip, &request);
+                        RateLimitLayer::new(limiter.clone(), key)
+                    });
+                    // opening a WebSocket connection is charged as a request, its calls are charged one by one
+                    if let Some(rate_limit_layer) = rate_limit_layer.as_ref() {
+                        if is_upgrade_request(&request) {
+                            if let Err(err) = rate_limit_layer.check_ws_connection() {
+                                return Box::pin(future::ready(Ok(
+                                    rate_limit::rate_limited_response(err),
+                                )));
+                            }
+                        }
+                    }
+                    let mut service = service_builder
+                        .clone()
+                        .set_rpc_middleware(RpcServiceBuilder::new().option_layer(rate_limit_layer))
+                        .build(methods.clone(), stop_handle.clone());
+                    service.call(request)
+                },
+            ))
+        }
+    });
+
+    let server = hyper::Server::try_bind(url)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::AddrInUse, err))?
+        .serve(make_service)
+        .with_graceful_shutdown(async move { server_stop_handle.shutdown().await });
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            warn!("API server error: {}", err);
+        }
+    });
 
-    Ok(stop_handler)
+    Ok(StopHandle { server_handler })
 }
 
 /// Used to be able to stop the API
@@ -256,6 +351,14 @@ pub trait MassaRpc {
     #[method(name = "get_staking_addresses")]
     async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>>;
 
+    /// Return the latest block and endorsement draws of the staking addresses, with their outcome,
+    /// the reason of the misses and the production latency, along with totals since the node started.
+    #[method(name = "get_staking_production")]
+    async fn get_staking_production(
+        &self,
+        arg: Option<ProductionLogFilter>,
+    ) -> RpcResult<ProductionReport>;
+
     /// Bans given IP address(es).
     /// No confirmation to expect.
     #[method(name = "node_ban_by_ip")]
@@ -321,6 +424,11 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Return the reputation score of the known peers, lowest first, and the end of their ban.
+    /// Peers are banned for a while when their score drops too low.
+    #[method(name = "node_peers_reputation")]
+    async fn node_peers_reputation(&self) -> RpcResult<Vec<PeerReputation>>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
@@ -340,6 +448,24 @@ pub trait MassaRpc {
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
 
+    /// Returns the operations pending in the pool, from best to worst score,
+    /// followed by the recently evicted ones along with their eviction reason.
+    /// Operations can be filtered by sender address and thread.
+    /// Pages hold at most `max_arguments` operations, the first page is returned without page request.
+    /// There is no gRPC counterpart: massa-proto-rs, which the gRPC services are generated from, has no such query.
+    #[method(name = "get_pool_operations")]
+    async fn get_pool_operations(
+        &self,
+        arg: PoolOperationFilter,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<PoolOperationInfo>>;
+
+    /// Returns low, medium and high fee suggestions for an operation of the given size and max gas
+    /// to be included within the given number of periods, based on the operations pending in the pool
+    /// and on the fees of the operations included in recent final blocks.
+    #[method(name = "estimate_fee")]
+    async fn estimate_fee(&self, arg: FeeEstimationRequest) -> RpcResult<FeeEstimation>;
+
     /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
     #[method(name = "get_endorsements")]
     async fn get_endorsements(&self, arg: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>>;
@@ -369,10 +495,37 @@ pub trait MassaRpc {
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Get the upcoming block and endorsement draws of addresses, with the estimated time of their slots,
+    /// over all the cycles whose draws are already known by the selector, up to `max_next_draws` draws per address.
+    #[method(name = "get_next_draws")]
+    async fn get_next_draws(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressNextDraws>>;
+
     /// Get addresses bytecode.
     #[method(name = "get_addresses_bytecode")]
     async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>>;
 
+    /// Get the balances or datastore values of addresses at the end of past final slots.
+    /// Items that did not exist at that time are returned as null.
+    /// Requires the ledger history to be enabled on the node, and only covers the slots it recorded.
+    #[method(name = "get_addresses_state_at_slot")]
+    async fn get_addresses_state_at_slot(
+        &self,
+        args: Vec<AddressStateAtSlotQuery>,
+    ) -> RpcResult<Vec<Option<AddressStateAtSlot>>>;
+
+    /// Get a page of the final history of an address, ordered by slot: the operations it created,
+    /// the operations it received coins from, its roll buys and sells and its slashes.
+    /// The next page is requested with the returned cursor, until it is null.
+    /// Pages contain at most `max_arguments` entries.
+    /// Requires the address history to be enabled on the node, and only covers the slots it recorded.
+    #[method(name = "get_address_history")]
+    async fn get_address_history(
+        &self,
+        address: Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<AddressHistoryPage>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[method(name = "send_operations")]
     async fn send_operations(&self, arg: Vec<OperationInput>) -> RpcResult<Vec<OperationId>>;
@@ -387,6 +540,17 @@ pub trait MassaRpc {
     async fn get_filtered_sc_output_event(&self, arg: EventFilter)
         -> RpcResult<Vec<SCOutputEvent>>;
 
+    /// Get a page of the events matching the filter, ordered by slot.
+    /// Final events are read from the node event archive when it is enabled.
+    /// The next page is requested with the returned cursor, until it is null.
+    #[method(name = "get_filtered_sc_output_event_page")]
+    async fn get_filtered_sc_output_event_page(
+        &self,
+        arg: EventFilter,
+        cursor: Option<EventCursor>,
+        limit: usize,
+    ) -> RpcResult<SCOutputEventPage>;
+
     /// Get OpenRPC specification.
     #[method(name = "rpc.discover")]
     async fn get_openrpc_spec(&self) -> RpcResult<Value>;
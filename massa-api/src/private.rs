@@ -3,9 +3,13 @@
 use crate::{MassaRpcServer, Private, RpcServer, StopHandle, Value, API};
 
 use async_trait::async_trait;
-use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::core::RpcResult;
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{
+        AddressFilter, AddressHistoryCursor, AddressHistoryPage, AddressInfo, AddressNextDraws,
+        AddressStateAtSlot, AddressStateAtSlotQuery,
+    },
+    auth::ApiAuth,
     block::{BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
@@ -13,18 +17,29 @@ use massa_api_exports::{
     error::ApiError,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
     node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    operation::{FeeEstimation, FeeEstimationRequest, OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     ListType, ScrudOperation, TimeInterval,
 };
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::{ProductionLog, ProductionLogFilter, ProductionReport};
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    clique::Clique,
+    composite::PubkeySig,
+    endorsement::EndorsementId,
+    execution::{EventCursor, EventFilter, SCOutputEventPage},
+    node::NodeId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_pool_exports::{PoolOperationFilter, PoolOperationInfo};
+use massa_protocol_exports::{PeerId, PeerReputation, ProtocolController};
 use massa_signature::KeyPair;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
@@ -46,6 +61,8 @@ impl API<Private> {
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        production_log: Arc<RwLock<ProductionLog>>,
+        auth: Option<Arc<ApiAuth>>,
     ) -> Self {
         API(Private {
             protocol_controller,
@@ -53,6 +70,8 @@ impl API<Private> {
             api_settings,
             stop_cv,
             node_wallet,
+            production_log,
+            auth,
         })
     }
 }
@@ -63,8 +82,9 @@ impl RpcServer for API<Private> {
         self,
         url: &SocketAddr,
         settings: &APIConfig,
-    ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+    ) -> Result<StopHandle, std::io::Error> {
+        let auth = self.0.auth.clone();
+        crate::serve(self.into_rpc(), url, settings, auth, None).await
     }
 }
 
@@ -147,6 +167,17 @@ impl MassaRpcServer for API<Private> {
         Ok(w_wallet.get_wallet_address_list())
     }
 
+    async fn get_staking_production(
+        &self,
+        filter: Option<ProductionLogFilter>,
+    ) -> RpcResult<ProductionReport> {
+        let production_log = self.0.production_log.read();
+        Ok(ProductionReport {
+            stats: production_log.get_stats(),
+            events: production_log.get_events(&filter.unwrap_or_default()),
+        })
+    }
+
     async fn node_ban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -183,6 +214,13 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
+    async fn node_peers_reputation(&self) -> RpcResult<Vec<PeerReputation>> {
+        self.0
+            .protocol_controller
+            .get_peer_reputations()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
     async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -211,6 +249,18 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
 
+    async fn get_pool_operations(
+        &self,
+        _: PoolOperationFilter,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<PoolOperationInfo>> {
+        crate::wrong_api::<PagedVec<PoolOperationInfo>>()
+    }
+
+    async fn estimate_fee(&self, _: FeeEstimationRequest) -> RpcResult<FeeEstimation> {
+        crate::wrong_api::<FeeEstimation>()
+    }
+
     async fn get_endorsements(&self, _: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
         crate::wrong_api::<Vec<EndorsementInfo>>()
     }
@@ -238,10 +288,30 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_next_draws(&self, _: Vec<Address>) -> RpcResult<Vec<AddressNextDraws>> {
+        crate::wrong_api::<Vec<AddressNextDraws>>()
+    }
+
     async fn get_addresses_bytecode(&self, _: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
         crate::wrong_api::<Vec<Vec<u8>>>()
     }
 
+    async fn get_addresses_state_at_slot(
+        &self,
+        _: Vec<AddressStateAtSlotQuery>,
+    ) -> RpcResult<Vec<Option<AddressStateAtSlot>>> {
+        crate::wrong_api::<Vec<Option<AddressStateAtSlot>>>()
+    }
+
+    async fn get_address_history(
+        &self,
+        _: Address,
+        _: Option<AddressHistoryCursor>,
+        _: Option<usize>,
+    ) -> RpcResult<AddressHistoryPage> {
+        crate::wrong_api::<AddressHistoryPage>()
+    }
+
     async fn send_operations(&self, _: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         crate::wrong_api::<Vec<OperationId>>()
     }
@@ -250,6 +320,15 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
+    async fn get_filtered_sc_output_event_page(
+        &self,
+        _: EventFilter,
+        _: Option<EventCursor>,
+        _: usize,
+    ) -> RpcResult<SCOutputEventPage> {
+        crate::wrong_api::<SCOutputEventPage>()
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -4,28 +4,38 @@
 use crate::{MassaRpcServer, Public, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use itertools::{izip, Itertools};
-use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::core::RpcResult;
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{
+        AddressDrawKind, AddressFilter, AddressHistoryCursor, AddressHistoryItem,
+        AddressHistoryKind, AddressHistoryPage, AddressInfo, AddressNextDraw, AddressNextDraws,
+        AddressStateAtSlot, AddressStateAtSlotQuery,
+    },
     block::{BlockInfo, BlockInfoContent, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
+    execution::{
+        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyCallTrace,
+        ReadOnlyResult,
+    },
     node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    operation::{FeeEstimation, FeeEstimationRequest, OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    rate_limit::RateLimiter,
     slot::SlotAmount,
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
 use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::{
-    ExecutionController, ExecutionQueryRequest, ExecutionQueryRequestItem,
-    ExecutionQueryResponseItem, ExecutionStackElement, ReadOnlyExecutionRequest,
+    ExecutionCallTrace, ExecutionController, ExecutionError, ExecutionQueryError,
+    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponseItem,
+    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
     ReadOnlyExecutionTarget,
 };
+use massa_factory_exports::{ProductionLogFilter, ProductionReport};
 use massa_models::{
     address::Address,
     amount::Amount,
@@ -38,7 +48,7 @@ use massa_models::{
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
     error::ModelsError,
-    execution::EventFilter,
+    execution::{EventCursor, EventFilter, SCOutputEventPage},
     node::NodeId,
     operation::OperationDeserializer,
     operation::OperationId,
@@ -51,9 +61,11 @@ use massa_models::{
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
 };
-use massa_pool_exports::PoolController;
+use massa_pool_exports::{PoolController, PoolOperationFilter, PoolOperationInfo};
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{
+    PeerConnectionType, PeerReputation, ProtocolConfig, ProtocolController,
+};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -63,6 +75,7 @@ use massa_versioning::{
 };
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 
 impl API<Public> {
     /// generate a new public API
@@ -78,6 +91,7 @@ impl API<Public> {
         node_id: NodeId,
         storage: Storage,
         mip_store: MipStore,
+        rate_limiter: Option<Arc<RateLimiter>>,
     ) -> Self {
         API(Public {
             consensus_controller,
@@ -91,6 +105,7 @@ impl API<Public> {
             protocol_config,
             storage,
             keypair_factory: KeyPairFactory { mip_store },
+            rate_limiter,
         })
     }
 }
@@ -101,8 +116,9 @@ impl RpcServer for API<Public> {
         self,
         url: &SocketAddr,
         api_config: &APIConfig,
-    ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+    ) -> Result<StopHandle, std::io::Error> {
+        let rate_limiter = self.0.rate_limiter.clone();
+        crate::serve(self.into_rpc(), url, api_config, None, rate_limiter).await
     }
 }
 
@@ -136,6 +152,7 @@ impl MassaRpcServer for API<Public> {
             bytecode,
             operation_datastore,
             fee,
+            trace,
         } in reqs
         {
             let address = if let Some(addr) = address {
@@ -183,10 +200,12 @@ impl MassaRpcServer for API<Public> {
                 }],
                 coins: None,
                 fee,
+                trace: trace.unwrap_or_default(),
             };
 
             // run
-            let result = self.0.execution_controller.execute_readonly_request(req);
+            let (result, trace) =
+                split_read_only_trace(self.0.execution_controller.execute_readonly_request(req));
 
             // map result
             let result = ExecuteReadOnlyResponse {
@@ -202,6 +221,7 @@ impl MassaRpcServer for API<Public> {
                     .as_ref()
                     .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
                 state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
+                trace,
             };
 
             res.push(result);
@@ -229,6 +249,7 @@ impl MassaRpcServer for API<Public> {
             caller_address,
             coins,
             fee,
+            trace,
         } in reqs
         {
             let caller_address = if let Some(addr) = caller_address {
@@ -267,10 +288,12 @@ impl MassaRpcServer for API<Public> {
                 ],
                 coins,
                 fee,
+                trace: trace.unwrap_or_default(),
             };
 
             // run
-            let result = self.0.execution_controller.execute_readonly_request(req);
+            let (result, trace) =
+                split_read_only_trace(self.0.execution_controller.execute_readonly_request(req));
 
             // map result
             let result = ExecuteReadOnlyResponse {
@@ -286,6 +309,7 @@ impl MassaRpcServer for API<Public> {
                     .as_ref()
                     .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
                 state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
+                trace,
             };
 
             res.push(result);
@@ -303,6 +327,13 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<PreHashSet<Address>>()
     }
 
+    async fn get_staking_production(
+        &self,
+        _: Option<ProductionLogFilter>,
+    ) -> RpcResult<ProductionReport> {
+        crate::wrong_api::<ProductionReport>()
+    }
+
     async fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -319,6 +350,10 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_peers_reputation(&self) -> RpcResult<Vec<PeerReputation>> {
+        crate::wrong_api::<Vec<PeerReputation>>()
+    }
+
     /// get status
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         let version = self.0.version;
@@ -477,6 +512,11 @@ impl MassaRpcServer for API<Public> {
         &self,
         operations_ids: Vec<OperationId>,
     ) -> RpcResult<Vec<OperationInfo>> {
+        let api_cfg = self.0.api_settings.clone();
+        if operations_ids.len() as u64 > api_cfg.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
         // get the operations and the list of blocks that contain them from storage
         let secure_share_operations: Vec<SecureShareOperation> = {
             let read_ops = self.0.storage.read_operations();
@@ -486,7 +526,7 @@ impl MassaRpcServer for API<Public> {
                 .collect()
         };
 
-        let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
+        let mut storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
             let read_blocks = self.0.storage.read_blocks();
             secure_share_operations
                 .into_iter()
@@ -503,14 +543,38 @@ impl MassaRpcServer for API<Public> {
                 .collect()
         };
 
-        // keep only the ops id (found in storage)
-        let ops: Vec<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
-
-        let api_cfg = self.0.api_settings.clone();
-        if ops.len() as u64 > api_cfg.max_arguments {
-            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        // final operations pruned from storage can still be found in the block archive
+        let mut archived_ops = PreHashSet::<OperationId>::default();
+        let found_ops: PreHashSet<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
+        let missing_ops: Vec<OperationId> = operations_ids
+            .iter()
+            .filter(|id| !found_ops.contains(id))
+            .copied()
+            .collect();
+        if !missing_ops.is_empty() {
+            let archived = self
+                .0
+                .consensus_controller
+                .get_archived_operations(&missing_ops);
+            for archived in archived.into_iter().flatten() {
+                archived_ops.insert(archived.operation.id);
+                storage_info.push((
+                    archived.operation,
+                    std::iter::once(archived.block_id).collect(),
+                ));
+            }
+            // keep the order of the request
+            let positions: PreHashMap<OperationId, usize> = operations_ids
+                .iter()
+                .enumerate()
+                .map(|(index, id)| (*id, index))
+                .collect();
+            storage_info.sort_by_key(|(op, _)| positions.get(&op.id).copied());
         }
 
+        // keep only the ops id (found in storage or in the archive)
+        let ops: Vec<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
+
         // ask pool whether it carries the operations
         let in_pool = self.0.pool_command_sender.contains_operations(&ops);
 
@@ -543,6 +607,12 @@ impl MassaRpcServer for API<Public> {
         for (id, (operation, in_blocks), in_pool, is_operation_final, op_exec_status) in
             zipped_iterator
         {
+            // archived operations are in final blocks
+            let is_operation_final = if archived_ops.contains(&id) {
+                Some(true)
+            } else {
+                is_operation_final
+            };
             res.push(OperationInfo {
                 id,
                 in_pool,
@@ -560,6 +630,72 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    /// get the pending and recently evicted operations of the pool
+    async fn get_pool_operations(
+        &self,
+        filter: PoolOperationFilter,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<PoolOperationInfo>> {
+        if let Some(thread) = filter.thread {
+            if thread >= self.0.api_settings.thread_count {
+                return Err(ApiError::BadRequest(format!("invalid thread: {}", thread)).into());
+            }
+        }
+
+        // the pool is only read up to the requested page, of at most `max_arguments` operations
+        let max_limit = usize::try_from(self.0.api_settings.max_arguments).unwrap_or(usize::MAX);
+        let (limit, offset) = match page_request {
+            Some(PageRequest { limit, offset }) => (std::cmp::min(limit, max_limit), offset),
+            None => (max_limit, 0),
+        };
+        let operations = self.0.pool_command_sender.get_operations_info(
+            &filter,
+            offset.saturating_mul(limit),
+            limit,
+        );
+
+        Ok(PagedVec::new(operations, None))
+    }
+
+    /// estimate the fee of an operation from the pool contents and the recent final blocks
+    async fn estimate_fee(&self, request: FeeEstimationRequest) -> RpcResult<FeeEstimation> {
+        if request.periods == 0 {
+            return Err(
+                ApiError::BadRequest("periods must be strictly positive".to_string()).into(),
+            );
+        }
+
+        let pool = &self.0.pool_command_sender;
+        let size = usize::try_from(request.size).unwrap_or(usize::MAX);
+        let low = pool
+            .estimate_inclusion_fee(size, request.max_gas, request.periods)
+            .ok_or_else(|| {
+                ApiError::BadRequest("the operation does not fit in a block".to_string())
+            })?;
+        let next_period = pool
+            .estimate_inclusion_fee(size, request.max_gas, 1)
+            .unwrap_or(low);
+
+        // fees of the operations included in the final blocks of the recent periods
+        let mut recent_fees = self.0.consensus_controller.get_recent_final_fees();
+        recent_fees.sort_unstable();
+        let percentile = |percent: usize| {
+            recent_fees
+                .get(recent_fees.len() * percent / 100)
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let medium = std::cmp::max(low, percentile(50));
+        let high = std::cmp::max(medium, std::cmp::max(next_period, percentile(90)));
+        Ok(FeeEstimation {
+            low,
+            medium,
+            high,
+            sample_size: recent_fees.len() as u64,
+        })
+    }
+
     /// get endorsements
     async fn get_endorsements(
         &self,
@@ -656,18 +792,41 @@ impl MassaRpcServer for API<Public> {
 
     /// get blocks
     /// Returns only active blocks are returned
-    async fn get_blocks(&self, mut ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
-        let mut blocks: Vec<Block> = Vec::with_capacity(ids.len());
-        {
+    async fn get_blocks(&self, ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
+        let mut blocks: Vec<Option<Block>> = {
             let block_storage_lock = self.0.storage.read_blocks();
-            ids.retain(|id| {
-                if let Some(wrapped_block) = block_storage_lock.get(id) {
-                    blocks.push(wrapped_block.content.clone());
-                    return true;
-                };
-                false
-            });
+            ids.iter()
+                .map(|id| {
+                    block_storage_lock
+                        .get(id)
+                        .map(|wrapped_block| wrapped_block.content.clone())
+                })
+                .collect()
+        };
+        // final blocks pruned from storage can still be found in the block archive
+        let missing_ids: Vec<BlockId> = ids
+            .iter()
+            .zip(&blocks)
+            .filter_map(|(id, block)| block.is_none().then_some(*id))
+            .collect();
+        if !missing_ids.is_empty() {
+            let mut archived_blocks = self
+                .0
+                .consensus_controller
+                .get_archived_blocks(&missing_ids)
+                .into_iter();
+            for block in blocks.iter_mut().filter(|block| block.is_none()) {
+                *block = archived_blocks
+                    .next()
+                    .flatten()
+                    .map(|archived| archived.block.content);
+            }
         }
+        let (ids, blocks): (Vec<BlockId>, Vec<Block>) = ids
+            .into_iter()
+            .zip(blocks)
+            .filter_map(|(id, block)| block.map(|block| (id, block)))
+            .unzip();
         let block_statuses = self.0.consensus_controller.get_block_statuses(&ids);
         let res = ids
             .into_iter()
@@ -694,17 +853,32 @@ impl MassaRpcServer for API<Public> {
             .consensus_controller
             .get_blockclique_block_at_slot(slot);
 
-        let block_id = match block_id_option {
-            Some(id) => id,
-            None => return Ok(None),
-        };
+        if let Some(block) = block_id_option.and_then(|block_id| {
+            self.0
+                .storage
+                .read_blocks()
+                .get(&block_id)
+                .map(|b| b.content.clone())
+        }) {
+            return Ok(Some(block));
+        }
 
+        // final blocks pruned from storage can still be found in the block archive
+        let Ok(next_slot) = slot.get_next_slot(self.0.api_settings.thread_count) else {
+            return Ok(None);
+        };
+        let archived_ids = self
+            .0
+            .consensus_controller
+            .search_archived_blocks(None, slot, next_slot, 1);
         let res = self
             .0
-            .storage
-            .read_blocks()
-            .get(&block_id)
-            .map(|b| b.content.clone());
+            .consensus_controller
+            .get_archived_blocks(&archived_ids)
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|archived| archived.block.content);
         Ok(res)
     }
 
@@ -946,6 +1120,75 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    /// get the upcoming draws of addresses
+    async fn get_next_draws(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressNextDraws>> {
+        let api_cfg = &self.0.api_settings;
+        if addresses.len() as u64 > api_cfg.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        // the draws are known up to the last cycle kept by the selector
+        let first_slot = match timeslots::get_current_latest_block_slot(
+            api_cfg.thread_count,
+            api_cfg.t0,
+            api_cfg.genesis_timestamp,
+        )
+        .map_err(ApiError::ModelsError)?
+        {
+            Some(cur_slot) => cur_slot
+                .get_next_slot(api_cfg.thread_count)
+                .map_err(ApiError::ModelsError)?,
+            None => Slot::new(0, 0),
+        };
+        let selections = self
+            .0
+            .selector_controller
+            .get_available_selections_in_range(
+                first_slot..=Slot::max(api_cfg.thread_count),
+                Some(&addresses.iter().copied().collect()),
+            )
+            .map_err(|err| {
+                ApiError::InternalServerError(format!("could not get the selector draws: {}", err))
+            })?;
+
+        let mut res = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            // keep the first draws of the address, and only compute the timestamps of those
+            let draws = selections
+                .iter()
+                .flat_map(move |(slot, selection)| {
+                    let block =
+                        (selection.producer == address).then_some((*slot, AddressDrawKind::Block));
+                    let endorsements = selection
+                        .endorsements
+                        .iter()
+                        .enumerate()
+                        .filter(move |(_, endorser)| **endorser == address)
+                        .map(move |(index, _)| (*slot, AddressDrawKind::Endorsement { index }));
+                    block.into_iter().chain(endorsements)
+                })
+                .take(api_cfg.max_next_draws as usize)
+                .map(|(slot, kind)| {
+                    let timestamp = timeslots::get_block_slot_timestamp(
+                        api_cfg.thread_count,
+                        api_cfg.t0,
+                        api_cfg.genesis_timestamp,
+                        slot,
+                    )
+                    .map_err(ApiError::ModelsError)?;
+                    Ok(AddressNextDraw {
+                        slot,
+                        timestamp,
+                        kind,
+                    })
+                })
+                .collect::<Result<Vec<_>, ApiError>>()?;
+            res.push(AddressNextDraws { address, draws });
+        }
+
+        Ok(res)
+    }
+
     /// get addresses bytecode
     async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
         let queries = args
@@ -989,6 +1232,99 @@ impl MassaRpcServer for API<Public> {
         Ok(res?)
     }
 
+    /// get addresses balances or datastore values at past final slots
+    async fn get_addresses_state_at_slot(
+        &self,
+        args: Vec<AddressStateAtSlotQuery>,
+    ) -> RpcResult<Vec<Option<AddressStateAtSlot>>> {
+        if args.is_empty() {
+            return Err(ApiError::BadRequest("no arguments specified".to_string()).into());
+        }
+
+        if args.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest(format!("too many arguments received. Only a maximum of {} arguments are accepted per request", self.0.api_settings.max_arguments)).into());
+        }
+
+        let queries = args
+            .into_iter()
+            .map(|arg| match arg {
+                AddressStateAtSlotQuery::Balance { address, slot } => {
+                    ExecutionQueryRequestItem::AddressBalanceAtSlot {
+                        addr: address,
+                        slot,
+                    }
+                }
+                AddressStateAtSlotQuery::DatastoreValue { address, key, slot } => {
+                    ExecutionQueryRequestItem::AddressDatastoreValueAtSlot {
+                        addr: address,
+                        key,
+                        slot,
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let responses = self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest { requests: queries })
+            .responses;
+
+        let res: Result<Vec<Option<AddressStateAtSlot>>, ApiError> = responses
+            .into_iter()
+            .map(|value| match value {
+                Ok(ExecutionQueryResponseItem::Amount(balance)) => {
+                    Ok(Some(AddressStateAtSlot::Balance(balance)))
+                }
+                Ok(ExecutionQueryResponseItem::DatastoreValue(value)) => {
+                    Ok(Some(AddressStateAtSlot::DatastoreValue(value)))
+                }
+                Ok(_) => Err(ApiError::InternalServerError(
+                    "unexpected response type".to_string(),
+                )),
+                Err(ExecutionQueryError::NotFound(_)) => Ok(None),
+                Err(err) => Err(ApiError::BadRequest(err.to_string())),
+            })
+            .collect();
+
+        Ok(res?)
+    }
+
+    async fn get_address_history(
+        &self,
+        address: Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<AddressHistoryPage> {
+        let max_entries = self.0.api_settings.max_arguments as usize;
+        let limit = limit.map_or(max_entries, |limit| std::cmp::min(limit, max_entries));
+
+        let page = self
+            .0
+            .execution_controller
+            .get_address_history(
+                &address,
+                cursor.map(|cursor| massa_execution_exports::AddressHistoryCursor {
+                    slot: cursor.slot,
+                    index_in_slot: cursor.index_in_slot,
+                }),
+                limit,
+            )
+            .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+        Ok(AddressHistoryPage {
+            items: page
+                .items
+                .into_iter()
+                .map(to_address_history_item)
+                .collect(),
+            next_cursor: page.next_cursor.map(|cursor| AddressHistoryCursor {
+                slot: cursor.slot,
+                index_in_slot: cursor.index_in_slot,
+            }),
+        })
+    }
+
     /// send operations
     async fn send_operations(&self, ops: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
@@ -1054,6 +1390,19 @@ impl MassaRpcServer for API<Public> {
         Ok(events)
     }
 
+    /// Get a page of the events matching the filter, starting at the cursor
+    async fn get_filtered_sc_output_event_page(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventCursor>,
+        limit: usize,
+    ) -> RpcResult<SCOutputEventPage> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_filtered_sc_output_event_page(filter, cursor, limit))
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         crate::wrong_api::<Vec<IpAddr>>()
     }
@@ -1198,3 +1547,68 @@ fn check_input_operation(
         .into())
     }
 }
+
+/// Separate the call trace from the result of a read-only execution
+fn split_read_only_trace(
+    result: Result<ReadOnlyExecutionOutput, ExecutionError>,
+) -> (
+    Result<ReadOnlyExecutionOutput, ExecutionError>,
+    Option<ReadOnlyCallTrace>,
+) {
+    match result {
+        Ok(mut output) => {
+            let trace = output.trace.take().map(to_read_only_call_trace);
+            (Ok(output), trace)
+        }
+        Err(ExecutionError::TracedError { error, trace }) => {
+            (Err(*error), Some(to_read_only_call_trace(*trace)))
+        }
+        Err(err) => (Err(err), None),
+    }
+}
+
+/// Convert a call trace of the execution module to its API representation
+fn to_read_only_call_trace(trace: ExecutionCallTrace) -> ReadOnlyCallTrace {
+    ReadOnlyCallTrace {
+        caller: trace.caller,
+        callee: trace.callee,
+        function: trace.function,
+        coins: trace.coins,
+        gas_limit: trace.gas_limit,
+        gas_used: trace.gas_used,
+        datastore_reads: trace.datastore_reads,
+        datastore_writes: trace.datastore_writes,
+        events: trace.events,
+        error: trace.error,
+        calls: trace
+            .calls
+            .into_iter()
+            .map(to_read_only_call_trace)
+            .collect(),
+    }
+}
+
+/// Convert an entry of the history of an address to its API representation
+fn to_address_history_item(
+    item: massa_execution_exports::AddressHistoryItem,
+) -> AddressHistoryItem {
+    use massa_execution_exports::AddressHistoryKind as ExecutionAddressHistoryKind;
+
+    AddressHistoryItem {
+        slot: item.slot,
+        operation_id: item.operation_id,
+        kind: match item.kind {
+            ExecutionAddressHistoryKind::OperationCreated { success } => {
+                AddressHistoryKind::OperationCreated { success }
+            }
+            ExecutionAddressHistoryKind::CoinsReceived => AddressHistoryKind::CoinsReceived,
+            ExecutionAddressHistoryKind::RollBuy { roll_count } => {
+                AddressHistoryKind::RollBuy { roll_count }
+            }
+            ExecutionAddressHistoryKind::RollSell { roll_count } => {
+                AddressHistoryKind::RollSell { roll_count }
+            }
+            ExecutionAddressHistoryKind::Slashed => AddressHistoryKind::Slashed,
+        },
+    }
+}
@@ -0,0 +1,159 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Per-client rate limiting of the public JSON-RPC APIs.
+//!
+//! Clients are identified by a known bearer token, by the `X-Forwarded-For` header when the node is behind a
+//! trusted reverse proxy, or else by the IP address of their connection.
+//! Each JSON-RPC call is charged one request, be it alone, in a batch or sent over a WebSocket, subscriptions
+//! included. Opening a WebSocket connection is charged one request too.
+
+use futures::future::{self, Either, Ready};
+use hyper::header::{AUTHORIZATION, RETRY_AFTER};
+use hyper::{Body, Request, Response, StatusCode};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::server::MethodResponse;
+use jsonrpsee::types::{ErrorObjectOwned, Request as RpcRequest};
+use massa_api_exports::error::ApiError;
+use massa_api_exports::rate_limit::{RateLimitError, RateLimitKey, RateLimiter};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tower::Layer;
+
+/// Methods whose calls are charged their `max_gas` against the read-only gas budget of the client
+const READ_ONLY_EXECUTION_METHODS: [&str; 2] =
+    ["execute_read_only_bytecode", "execute_read_only_call"];
+
+/// Sum the `max_gas` fields of a JSON value
+fn sum_max_gas(value: &Value) -> u64 {
+    match value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, field)| match (name.as_str(), field.as_u64()) {
+                ("max_gas", Some(gas)) => gas,
+                _ => sum_max_gas(field),
+            })
+            .fold(0, u64::saturating_add),
+        Value::Array(items) => items.iter().map(sum_max_gas).fold(0, u64::saturating_add),
+        _ => 0,
+    }
+}
+
+/// Get the total gas requested by the read-only executions of a JSON-RPC call
+fn read_only_gas(request: &RpcRequest) -> u64 {
+    if !READ_ONLY_EXECUTION_METHODS.contains(&request.method_name()) {
+        return 0;
+    }
+    request
+        .params
+        .as_ref()
+        .and_then(|params| serde_json::from_str::<Value>(params.get()).ok())
+        .map_or(0, |params| sum_max_gas(&params))
+}
+
+/// Identify the client of an HTTP request, WebSocket upgrades included
+///
+/// # Arguments
+/// * `limiter`: rate limiter of the API
+/// * `remote_ip`: IP address of the connection of the request
+/// * `request`: the HTTP request
+pub(crate) fn client_key(
+    limiter: &RateLimiter,
+    remote_ip: IpAddr,
+    request: &Request<Body>,
+) -> RateLimitKey {
+    let forwarded_ip = if limiter.config().trust_forwarded_for {
+        request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok())
+    } else {
+        None
+    };
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    limiter.client_key(
+        authorization,
+        RateLimitKey::from_ip(forwarded_ip.unwrap_or(remote_ip)),
+    )
+}
+
+/// HTTP response of a WebSocket upgrade refused by the rate limiter
+pub(crate) fn rate_limited_response(err: RateLimitError) -> Response<Body> {
+    let mut response = Response::new(Body::from(err.to_string()));
+    *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+    if let RateLimitError::RateLimited(retry_after) = err {
+        let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        response.headers_mut().insert(
+            RETRY_AFTER,
+            seconds.to_string().parse().expect("valid header value"),
+        );
+    }
+    response
+}
+
+/// RPC middleware layer charging each JSON-RPC call of a client against its budgets
+#[derive(Clone)]
+pub(crate) struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+    key: RateLimitKey,
+}
+
+impl RateLimitLayer {
+    pub(crate) fn new(limiter: Arc<RateLimiter>, key: RateLimitKey) -> Self {
+        RateLimitLayer { limiter, key }
+    }
+
+    /// Charge the opening of a WebSocket connection against the budget of the client
+    pub(crate) fn check_ws_connection(&self) -> Result<(), RateLimitError> {
+        self.limiter.check_request(&self.key)
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+/// RPC middleware charging each JSON-RPC call of a client against its budgets
+#[derive(Clone)]
+pub(crate) struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    key: RateLimitKey,
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimitService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+{
+    type Future = Either<S::Future, Ready<MethodResponse>>;
+
+    fn call(&self, request: RpcRequest<'a>) -> Self::Future {
+        let res = self.limiter.check_request(&self.key).and_then(|_| {
+            let gas = read_only_gas(&request);
+            if gas > 0 {
+                self.limiter.check_read_only_gas(&self.key, gas)
+            } else {
+                Ok(())
+            }
+        });
+        match res {
+            Ok(()) => Either::Left(self.inner.call(request)),
+            Err(err) => Either::Right(future::ready(MethodResponse::error(
+                request.id,
+                ErrorObjectOwned::from(ApiError::from(err)),
+            ))),
+        }
+    }
+}
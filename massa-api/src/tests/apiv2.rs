@@ -1,4 +1,9 @@
-use std::{collections::BTreeMap, net::SocketAddr, str::FromStr, time::Duration};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::SocketAddr,
+    str::FromStr,
+    time::Duration,
+};
 
 use jsonrpsee::{
     async_client::ClientBuilder,
@@ -7,16 +12,22 @@ use jsonrpsee::{
     rpc_params,
     ws_client::WsClientBuilder,
 };
-use massa_consensus_exports::MockConsensusController;
-use massa_execution_exports::MockExecutionController;
+use massa_consensus_exports::{block_graph_event::BlockGraphEvent, MockConsensusController};
+use massa_execution_exports::{
+    EventStore, ExecutionOutput, MockExecutionController, SlotExecutionOutput,
+};
+use massa_final_state::StateChanges;
 use massa_models::{
     address::Address,
     block::{FilledBlock, SecureShareBlock},
     block_header::BlockHeader,
     block_id::BlockId,
     config::VERSION,
+    execution::EventFilter,
     operation::SecureShareOperation,
+    output_event::{EventExecutionContext, SCOutputEvent},
     secure_share::SecureShare,
+    slot::Slot,
 };
 use massa_protocol_exports::test_exports::tools::{
     create_block, create_operation_with_expire_period,
@@ -287,6 +298,60 @@ async fn subscribe_new_filled_blocks() {
     api_handle.stop().await;
 }
 
+#[tokio::test]
+async fn subscribe_new_block_graph_events() {
+    let addr: SocketAddr = "[::]:5044".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<BlockGraphEvent>(10);
+
+    api_server.0.consensus_broadcasts.block_graph_event_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+    let block = create_block(&KeyPair::generate(0).unwrap());
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_new_block_graph_events",
+            rpc_params![],
+            "unsubscribe_new_block_graph_events",
+        )
+        .await
+        .unwrap();
+
+    let event = BlockGraphEvent::BlockcliqueChanged {
+        added: vec![],
+        removed: vec![block.id],
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx.send(event).unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    assert!(result.is_some());
+    let value = result.unwrap().unwrap().clone();
+    assert_eq!(
+        value["BlockcliqueChanged"]["removed"][0].as_str().unwrap(),
+        &block.id.to_string()
+    );
+
+    api_handle.stop().await;
+}
+
 #[tokio::test]
 async fn subscribe_new_operations() {
     let addr: SocketAddr = "[::]:5036".parse().unwrap();
@@ -333,3 +398,72 @@ async fn subscribe_new_operations() {
 
     api_handle.stop().await;
 }
+
+#[tokio::test]
+async fn subscribe_new_sc_execution_events() {
+    let addr: SocketAddr = "[::]:5043".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<SlotExecutionOutput>(10);
+
+    api_server.0.execution_channels.slot_execution_output_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_new_sc_execution_events",
+            rpc_params![EventFilter::default(), "massa"],
+            "unsubscribe_hello",
+        )
+        .await
+        .unwrap();
+
+    let slot = Slot::new(1, 0);
+    let events = ["hello", "massa"]
+        .iter()
+        .enumerate()
+        .map(|(index, data)| SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot: index as u64,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: data.to_string(),
+        })
+        .collect();
+    let to_send = SlotExecutionOutput::FinalizedSlot(ExecutionOutput {
+        slot,
+        block_info: None,
+        state_changes: StateChanges::default(),
+        events: EventStore(events),
+    });
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx.send(to_send).unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    let obj = result.unwrap().unwrap();
+    assert_eq!(obj["status"].as_str().unwrap(), "Final");
+    assert_eq!(obj["event"]["data"].as_str().unwrap(), "massa");
+
+    api_handle.stop().await;
+}
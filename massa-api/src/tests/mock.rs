@@ -2,11 +2,16 @@
 //!
 //!
 
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Condvar, Mutex},
+};
 
-use massa_api_exports::config::APIConfig;
+use massa_api_exports::{auth::ApiAuth, config::APIConfig};
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
-use massa_execution_exports::{GasCosts, MockExecutionController};
+use massa_execution_exports::{ExecutionChannels, GasCosts, MockExecutionController};
+use massa_factory_exports::ProductionLog;
 use massa_models::config::CHAINID;
 use massa_models::{
     config::{
@@ -24,11 +29,13 @@ use massa_protocol_exports::{MockProtocolController, PeerCategoryInfo, ProtocolC
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_wallet::Wallet;
 use num::rational::Ratio;
-use tempfile::NamedTempFile;
+use parking_lot::RwLock;
+use tempfile::{NamedTempFile, TempDir};
 use tokio::sync::broadcast;
 
-use crate::{ApiV2, Public, API};
+use crate::{ApiV2, Private, Public, API};
 
 pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
     let keypair = KeyPair::generate(0).unwrap();
@@ -38,6 +45,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         bind_api: *addr,
         draw_lookahead_period_count: 10,
         max_arguments: 128,
+        max_next_draws: 1000,
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
@@ -90,15 +98,22 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         block_header_sender: broadcast::channel(100).0,
         block_sender: broadcast::channel(100).0,
         filled_block_sender: broadcast::channel(100).0,
+        block_graph_event_sender: broadcast::channel(100).0,
+    };
+
+    let execution_channels = ExecutionChannels {
+        slot_execution_output_sender: broadcast::channel(100).0,
     };
 
     let api = API::<ApiV2>::new(
         Box::new(consensus_ctrl),
         consensus_broadcasts,
         Box::new(exec_ctrl),
+        execution_channels,
         pool_broadcasts,
         api_config.clone(),
         *VERSION,
+        None,
     );
 
     (api, api_config)
@@ -112,6 +127,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         bind_api: "[::]:0".parse().unwrap(),
         draw_lookahead_period_count: 10,
         max_arguments: 128,
+        max_next_draws: 1000,
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
@@ -238,6 +254,8 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
             unban_everyone_timer: MassaTime::from_millis(3600000),
+            peer_ban_base_duration: MassaTime::from_millis(600000),
+            peer_ban_max_duration: MassaTime::from_millis(86400000),
             routable_ip: None,
             max_in_connections: 10,
             debug: true,
@@ -258,7 +276,37 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         NodeId::new(keypair.get_public_key()),
         shared_storage,
         mip_store.clone(),
+        None,
     );
 
     (api_public, api_config)
 }
+
+pub(crate) fn start_private_api(
+    addr: SocketAddr,
+    auth: Option<Arc<ApiAuth>>,
+) -> (API<Private>, APIConfig, TempDir) {
+    let (_, mut api_config) = start_public_api("[::]:0".parse().unwrap());
+    api_config.bind_public = "[::]:0".parse().unwrap();
+    api_config.bind_private = addr;
+
+    let wallet_dir = TempDir::new().expect("cannot create temp dir");
+    let wallet = Wallet::new(
+        wallet_dir.path().to_path_buf(),
+        "test".to_string(),
+        *CHAINID,
+    )
+    .expect("cannot create wallet");
+
+    let api_private = API::<Private>::new(
+        Box::new(MockProtocolController::new()),
+        Box::new(MockExecutionController::new()),
+        api_config.clone(),
+        Arc::new((Mutex::new(false), Condvar::new())),
+        Arc::new(RwLock::new(wallet)),
+        Arc::new(RwLock::new(ProductionLog::new(100))),
+        auth,
+    );
+
+    (api_private, api_config, wallet_dir)
+}
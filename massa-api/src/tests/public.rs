@@ -5,32 +5,43 @@ use std::{
     collections::{BTreeMap, HashMap},
     net::{IpAddr, SocketAddr},
     str::FromStr,
+    sync::Arc,
 };
 
+use hyper::HeaderMap;
 use jsonrpsee::{
-    core::{client::ClientT, Error},
+    core::{client::ClientT, params::BatchRequestBuilder, ClientError},
     http_client::HttpClientBuilder,
     rpc_params,
+    ws_client::WsClientBuilder,
 };
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{
+        AddressDrawKind, AddressFilter, AddressHistoryCursor, AddressHistoryItem,
+        AddressHistoryKind, AddressHistoryPage, AddressInfo, AddressNextDraws, AddressStateAtSlot,
+        AddressStateAtSlotQuery,
+    },
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    operation::{OperationInfo, OperationInput},
+    operation::{FeeEstimation, FeeEstimationRequest, OperationInfo, OperationInput},
+    page::PageRequest,
+    rate_limit::{RateLimitConfig, RateLimiter},
     TimeInterval,
 };
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport, block_status::ExportCompiledBlock,
-    MockConsensusController,
+    archived_block::ArchivedOperation, block_graph_export::BlockGraphExport,
+    block_status::ExportCompiledBlock, MockConsensusController,
+};
+use massa_pool_exports::{
+    MockPoolController, OperationEvictionReason, PoolOperationFilter, PoolOperationInfo,
 };
-use massa_pool_exports::MockPoolController;
-use massa_pos_exports::MockSelectorController;
+use massa_pos_exports::{MockSelectorController, Selection};
 
 use crate::{tests::mock::start_public_api, RpcServer};
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionQueryResponse, ExecutionQueryResponseItem,
+    ExecutionAddressInfo, ExecutionQueryError, ExecutionQueryResponse, ExecutionQueryResponseItem,
     MockExecutionController, ReadOnlyExecutionOutput,
 };
 use massa_models::{
@@ -39,8 +50,9 @@ use massa_models::{
     block::{Block, BlockGraphStatus},
     bytecode::Bytecode,
     clique::Clique,
+    config::THREAD_COUNT,
     endorsement::EndorsementId,
-    execution::EventFilter,
+    execution::{EventCursor, EventFilter, SCOutputEventPage},
     node::NodeId,
     operation::OperationId,
     output_event::SCOutputEvent,
@@ -50,8 +62,9 @@ use massa_models::{
 };
 use massa_protocol_exports::{
     test_exports::tools::{
-        create_block, create_call_sc_op_with_too_much_gas, create_endorsement,
-        create_execute_sc_op_with_too_much_gas, create_operation_with_expire_period,
+        create_block, create_block_with_operations, create_call_sc_op_with_too_much_gas,
+        create_endorsement, create_execute_sc_op_with_too_much_gas,
+        create_operation_with_expire_period,
     },
     MockProtocolController,
 };
@@ -175,6 +188,13 @@ async fn get_operations() {
 
     api_public.0.storage.store_operations(vec![op.clone()]);
 
+    // an operation that was pruned from storage but is in the block archive
+    let archived_op = create_operation_with_expire_period(&keypair, 10);
+    let archived_block =
+        create_block_with_operations(&keypair, Slot::new(1, 0), vec![archived_op.clone()]);
+    let archived_op_id = archived_op.id;
+    let archived_block_id = archived_block.id;
+
     let mut pool_ctrl = MockPoolController::new();
     pool_ctrl
         .expect_contains_operations()
@@ -183,10 +203,25 @@ async fn get_operations() {
     let mut exec_ctrl = MockExecutionController::new();
     exec_ctrl
         .expect_get_ops_exec_status()
-        .returning(|op| op.iter().map(|_op| (Some(true), Some(true))).collect());
+        .returning(|op| op.iter().map(|_op| (Some(true), None)).collect());
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_archived_operations()
+        .returning(move |ids| {
+            ids.iter()
+                .map(|id| {
+                    (id == &archived_op_id).then(|| ArchivedOperation {
+                        operation: archived_op.clone(),
+                        block_id: archived_block_id,
+                    })
+                })
+                .collect()
+        });
 
     api_public.0.execution_controller = Box::new(exec_ctrl);
     api_public.0.pool_command_sender = Box::new(pool_ctrl);
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
 
     let api_public_handle = api_public
         .serve(&addr, &config)
@@ -201,11 +236,225 @@ async fn get_operations() {
         .unwrap();
     let params = rpc_params![vec![
         OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap(),
+        archived_op_id,
         op.id
     ]];
     let response: Vec<OperationInfo> = client.request("get_operations", params).await.unwrap();
 
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].id, archived_op_id);
+    assert_eq!(response[0].in_blocks, vec![archived_block_id]);
+    assert_eq!(response[0].is_operation_final, Some(true));
+    assert_eq!(response[1].id, op.id);
+    assert_eq!(response[1].is_operation_final, Some(false));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_pool_operations() {
+    let addr: SocketAddr = "[::]:5018".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    let keypair = KeyPair::generate(0).unwrap();
+    let sender = Address::from_public_key(&keypair.get_public_key());
+    let op = create_operation_with_expire_period(&keypair, 500000);
+    let op_id = op.id;
+
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl
+        .expect_get_operations_info()
+        .withf(move |filter, offset, limit| {
+            filter.sender == Some(sender)
+                && filter.thread.is_none()
+                && *offset == 0
+                && *limit == 128
+        })
+        .returning(move |_, _, _| {
+            vec![
+                PoolOperationInfo {
+                    id: op_id,
+                    sender,
+                    thread: 0,
+                    fee: Amount::zero(),
+                    validity_start_period: 499990,
+                    validity_end_period: 500000,
+                    score: Some(1.0),
+                    eviction_reason: None,
+                },
+                PoolOperationInfo {
+                    id: op_id,
+                    sender,
+                    thread: 0,
+                    fee: Amount::zero(),
+                    validity_start_period: 499990,
+                    validity_end_period: 500000,
+                    score: None,
+                    eviction_reason: Some(OperationEvictionReason::AlreadyExecuted),
+                },
+            ]
+        });
+    // the second page of one operation
+    pool_ctrl
+        .expect_get_operations_info()
+        .withf(move |filter, offset, limit| {
+            filter.sender == Some(sender) && *offset == 1 && *limit == 1
+        })
+        .returning(move |_, _, _| {
+            vec![PoolOperationInfo {
+                id: op_id,
+                sender,
+                thread: 0,
+                fee: Amount::zero(),
+                validity_start_period: 499990,
+                validity_end_period: 500000,
+                score: None,
+                eviction_reason: Some(OperationEvictionReason::AlreadyExecuted),
+            }]
+        });
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let filter = PoolOperationFilter {
+        sender: Some(sender),
+        thread: None,
+    };
+    let response: Vec<PoolOperationInfo> = client
+        .request("get_pool_operations", rpc_params![filter.clone()])
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 2);
+    assert!(response[0].eviction_reason.is_none());
+    assert_eq!(
+        response[1].eviction_reason,
+        Some(OperationEvictionReason::AlreadyExecuted)
+    );
+
+    // paginated
+    let response: Vec<PoolOperationInfo> = client
+        .request(
+            "get_pool_operations",
+            rpc_params![
+                filter,
+                PageRequest {
+                    limit: 1,
+                    offset: 1
+                }
+            ],
+        )
+        .await
+        .unwrap();
     assert_eq!(response.len(), 1);
+    assert!(response[0].eviction_reason.is_some());
+
+    // invalid thread
+    let response: Result<Vec<PoolOperationInfo>, ClientError> = client
+        .request(
+            "get_pool_operations",
+            rpc_params![PoolOperationFilter {
+                sender: None,
+                thread: Some(config.thread_count),
+            }],
+        )
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn estimate_fee() {
+    let addr: SocketAddr = "[::]:5020".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    // recent final blocks including operations paying 1 to 10 coins
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl.expect_get_recent_final_fees().returning(|| {
+        (1..=10u64)
+            .rev()
+            .map(|fee| Amount::from_str(&fee.to_string()).unwrap())
+            .collect()
+    });
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl
+        .expect_estimate_inclusion_fee()
+        .returning(|size, _, periods| match (size, periods) {
+            (size, _) if size > 1000 => None,
+            (_, 1) => Some(Amount::from_str("20").unwrap()),
+            _ => Some(Amount::from_str("2").unwrap()),
+        });
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: FeeEstimation = client
+        .request(
+            "estimate_fee",
+            rpc_params![FeeEstimationRequest {
+                size: 100,
+                max_gas: 0,
+                periods: 5,
+            }],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        FeeEstimation {
+            low: Amount::from_str("2").unwrap(),
+            medium: Amount::from_str("6").unwrap(),
+            high: Amount::from_str("20").unwrap(),
+            sample_size: 10,
+        }
+    );
+
+    // the operation does not fit in a block
+    let response: Result<FeeEstimation, ClientError> = client
+        .request(
+            "estimate_fee",
+            rpc_params![FeeEstimationRequest {
+                size: 100_000,
+                max_gas: 0,
+                periods: 5,
+            }],
+        )
+        .await;
+    assert!(response.is_err());
+
+    // no period to be included in
+    let response: Result<FeeEstimation, ClientError> = client
+        .request(
+            "estimate_fee",
+            rpc_params![FeeEstimationRequest {
+                size: 100,
+                max_gas: 0,
+                periods: 0,
+            }],
+        )
+        .await;
+    assert!(response.is_err());
 
     api_public_handle.stop().await;
 }
@@ -244,7 +493,7 @@ async fn get_endorsements() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Vec<EndorsementInfo>, Error> =
+    let response: Result<Vec<EndorsementInfo>, ClientError> =
         client.request("get_endorsements", params.clone()).await;
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
 
@@ -298,7 +547,7 @@ async fn get_blocks() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Vec<BlockInfo>, Error> =
+    let response: Result<Vec<BlockInfo>, ClientError> =
         client.request("get_blocks", params.clone()).await;
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
 
@@ -342,7 +591,7 @@ async fn get_blockclique_block_by_slot() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Option<Block>, Error> = client
+    let response: Result<Option<Block>, ClientError> = client
         .request("get_blockclique_block_by_slot", params.clone())
         .await;
 
@@ -575,6 +824,15 @@ async fn get_filtered_sc_output_event() {
                 data: "massa".to_string(),
             }]
         });
+    exec_ctrl
+        .expect_get_filtered_sc_output_event_page()
+        .withf(|_filter, cursor, limit| {
+            *cursor == Some(EventCursor::from_slot(Slot::new(2, 0))) && *limit == 10
+        })
+        .returning(|_filter, _cursor, _limit| SCOutputEventPage {
+            events: Vec::new(),
+            next_cursor: Some(EventCursor::from_slot(Slot::new(5, 0))),
+        });
 
     api_public.0.execution_controller = Box::new(exec_ctrl);
     let api_public_handle = api_public
@@ -589,14 +847,14 @@ async fn get_filtered_sc_output_event() {
         ))
         .unwrap();
 
-    let response: Result<Vec<SCOutputEvent>, Error> = client
+    let response: Result<Vec<SCOutputEvent>, ClientError> = client
         .request("get_filtered_sc_output_event", rpc_params![])
         .await;
 
     // assert invalid params
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
 
-    let response: Result<Vec<SCOutputEvent>, Error> = client
+    let response: Result<Vec<SCOutputEvent>, ClientError> = client
         .request(
             "get_filtered_sc_output_event",
             rpc_params![EventFilter {
@@ -610,6 +868,24 @@ async fn get_filtered_sc_output_event() {
         .await;
 
     assert_eq!(response.unwrap().len(), 1);
+
+    let response: Result<SCOutputEventPage, ClientError> = client
+        .request(
+            "get_filtered_sc_output_event_page",
+            rpc_params![
+                EventFilter::default(),
+                EventCursor::from_slot(Slot::new(2, 0)),
+                10
+            ],
+        )
+        .await;
+
+    let page = response.unwrap();
+    assert!(page.events.is_empty());
+    assert_eq!(
+        page.next_cursor,
+        Some(EventCursor::from_slot(Slot::new(5, 0)))
+    );
     api_public_handle.stop().await;
 }
 
@@ -634,6 +910,7 @@ async fn execute_read_only_bytecode() {
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                trace: None,
             })
         });
 
@@ -657,9 +934,10 @@ async fn execute_read_only_bytecode() {
             Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap()
         ),
         operation_datastore: None,
-        fee: None
+        fee: None,
+        trace: None,
     }]];
-    let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
+    let response: Result<Vec<ExecuteReadOnlyResponse>, ClientError> = client
         .request("execute_read_only_bytecode", params.clone())
         .await;
 
@@ -671,8 +949,9 @@ async fn execute_read_only_bytecode() {
         address: None,
         operation_datastore: None,
         fee: None,
+        trace: None,
     }]];
-    let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
+    let response: Result<Vec<ExecuteReadOnlyResponse>, ClientError> = client
         .request("execute_read_only_bytecode", params.clone())
         .await;
 
@@ -683,9 +962,10 @@ async fn execute_read_only_bytecode() {
         bytecode: "hi".as_bytes().to_vec(),
         address: None,
         operation_datastore: Some("hi".as_bytes().to_vec()),
-        fee: None
+        fee: None,
+        trace: None,
     }]];
-    let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
+    let response: Result<Vec<ExecuteReadOnlyResponse>, ClientError> = client
         .request("execute_read_only_bytecode", params.clone())
         .await;
 
@@ -714,6 +994,7 @@ async fn execute_read_only_call() {
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                trace: None,
             })
         });
 
@@ -732,7 +1013,7 @@ async fn execute_read_only_call() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
+    let response: Result<Vec<ExecuteReadOnlyResponse>, ClientError> = client
         .request("execute_read_only_call", params.clone())
         .await;
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
@@ -745,6 +1026,7 @@ async fn execute_read_only_call() {
         parameter: vec![],
         caller_address: None,
         fee: None,
+        trace: None,
         coins: None,
     }]];
     let response: Vec<ExecuteReadOnlyResponse> = client
@@ -798,7 +1080,7 @@ async fn get_addresses() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Vec<AddressInfo>, Error> =
+    let response: Result<Vec<AddressInfo>, ClientError> =
         client.request("get_addresses", params.clone()).await;
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
 
@@ -816,6 +1098,224 @@ async fn get_addresses() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_next_draws() {
+    let addr: SocketAddr = "[::]:5048".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let staker =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let other = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let mut selector_ctrl = MockSelectorController::new();
+    selector_ctrl
+        .expect_get_available_selections_in_range()
+        .returning(move |range, addrs| {
+            assert_eq!(*range.end(), Slot::max(THREAD_COUNT));
+            assert!(addrs.unwrap().contains(&staker));
+            let first_slot = *range.start();
+            let second_slot = first_slot.get_next_slot(THREAD_COUNT).unwrap();
+            Ok(BTreeMap::from([
+                (
+                    first_slot,
+                    Selection {
+                        producer: staker,
+                        endorsements: vec![other, staker],
+                    },
+                ),
+                (
+                    second_slot,
+                    Selection {
+                        producer: other,
+                        endorsements: vec![other, other],
+                    },
+                ),
+            ]))
+        });
+    api_public.0.selector_controller = Box::new(selector_ctrl);
+    api_public.0.api_settings.max_next_draws = 3;
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<AddressNextDraws> = client
+        .request("get_next_draws", rpc_params![vec![staker, other]])
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].address, staker);
+    let draws = &response[0].draws;
+    assert_eq!(draws.len(), 2);
+    assert_eq!(draws[0].kind, AddressDrawKind::Block);
+    assert_eq!(draws[1].kind, AddressDrawKind::Endorsement { index: 1 });
+    assert_eq!(draws[0].slot, draws[1].slot);
+    // the draws are upcoming
+    assert!(draws[0].timestamp > MassaTime::now());
+    // only the first `max_next_draws` draws of an address are returned
+    assert_eq!(response[1].address, other);
+    let draws = &response[1].draws;
+    assert_eq!(draws.len(), 3);
+    assert_eq!(draws[0].kind, AddressDrawKind::Endorsement { index: 0 });
+    assert_eq!(draws[1].kind, AddressDrawKind::Block);
+    assert_eq!(draws[2].kind, AddressDrawKind::Endorsement { index: 0 });
+    assert!(draws[1].slot > draws[0].slot);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_addresses_state_at_slot() {
+    let addr: SocketAddr = "[::]:5021".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl: MockExecutionController = MockExecutionController::new();
+    exec_ctrl
+        .expect_query_state()
+        .returning(|_| ExecutionQueryResponse {
+            responses: vec![
+                Ok(ExecutionQueryResponseItem::Amount(
+                    Amount::from_str("42").unwrap(),
+                )),
+                Err(ExecutionQueryError::NotFound(
+                    "datastore entry at slot".to_string(),
+                )),
+            ],
+            candidate_cursor: Slot::new(1, 2),
+            final_cursor: Slot::new(1, 7),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![Vec::<AddressStateAtSlotQuery>::new()];
+    let response: Result<Vec<Option<AddressStateAtSlot>>, ClientError> =
+        client.request("get_addresses_state_at_slot", params).await;
+    assert!(response.is_err());
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let params = rpc_params![vec![
+        AddressStateAtSlotQuery::Balance {
+            address,
+            slot: Slot::new(1, 0),
+        },
+        AddressStateAtSlotQuery::DatastoreValue {
+            address,
+            key: b"key".to_vec(),
+            slot: Slot::new(1, 0),
+        },
+    ]];
+    let response: Vec<Option<AddressStateAtSlot>> = client
+        .request("get_addresses_state_at_slot", params)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response,
+        vec![
+            Some(AddressStateAtSlot::Balance(Amount::from_str("42").unwrap())),
+            None
+        ]
+    );
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_address_history() {
+    let addr: SocketAddr = "[::]:5022".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_address_history()
+        .withf(|_, cursor, limit| {
+            *cursor
+                == Some(massa_execution_exports::AddressHistoryCursor {
+                    slot: Slot::new(2, 0),
+                    index_in_slot: 1,
+                })
+                && *limit == 2
+        })
+        .returning(|_, _, _| {
+            Ok(massa_execution_exports::AddressHistoryPage {
+                items: vec![massa_execution_exports::AddressHistoryItem {
+                    slot: Slot::new(3, 0),
+                    operation_id: None,
+                    kind: massa_execution_exports::AddressHistoryKind::RollBuy { roll_count: 2 },
+                }],
+                next_cursor: Some(massa_execution_exports::AddressHistoryCursor {
+                    slot: Slot::new(4, 0),
+                    index_in_slot: 0,
+                }),
+            })
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let params = rpc_params![
+        address,
+        AddressHistoryCursor {
+            slot: Slot::new(2, 0),
+            index_in_slot: 1
+        },
+        2
+    ];
+    let response: AddressHistoryPage = client.request("get_address_history", params).await.unwrap();
+
+    assert_eq!(
+        response,
+        AddressHistoryPage {
+            items: vec![AddressHistoryItem {
+                slot: Slot::new(3, 0),
+                operation_id: None,
+                kind: AddressHistoryKind::RollBuy { roll_count: 2 },
+            }],
+            next_cursor: Some(AddressHistoryCursor {
+                slot: Slot::new(4, 0),
+                index_in_slot: 0,
+            }),
+        }
+    );
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_addresses_bytecode() {
     let addr: SocketAddr = "[::]:5019".parse().unwrap();
@@ -848,7 +1348,7 @@ async fn get_addresses_bytecode() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Vec<Vec<u8>>, Error> = client
+    let response: Result<Vec<Vec<u8>>, ClientError> = client
         .request("get_addresses_bytecode", params.clone())
         .await;
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
@@ -938,13 +1438,13 @@ async fn wrong_api() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<(), Error> = client.request("stop_node", params.clone()).await;
+    let response: Result<(), ClientError> = client.request("stop_node", params.clone()).await;
     assert!(response
         .unwrap_err()
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_sign_message", rpc_params![Vec::<u8>::new()])
         .await;
     assert!(response
@@ -952,7 +1452,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "remove_staking_addresses",
             rpc_params![Vec::<Address>::new()],
@@ -963,7 +1463,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("get_staking_addresses", params.clone())
         .await;
     assert!(response
@@ -971,7 +1471,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_ban_by_ip", rpc_params![Vec::<IpAddr>::new()])
         .await;
     assert!(response
@@ -979,7 +1479,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_ban_by_id", rpc_params![Vec::<NodeId>::new()])
         .await;
     assert!(response
@@ -987,7 +1487,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_unban_by_ip", rpc_params![Vec::<IpAddr>::new()])
         .await;
     assert!(response
@@ -995,7 +1495,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_unban_by_id", rpc_params![Vec::<NodeId>::new()])
         .await;
     assert!(response
@@ -1003,13 +1503,14 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client.request("node_peers_whitelist", params.clone()).await;
+    let response: Result<(), ClientError> =
+        client.request("node_peers_whitelist", params.clone()).await;
     assert!(response
         .unwrap_err()
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_add_to_peers_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1020,7 +1521,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_remove_from_peers_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1031,7 +1532,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_bootstrap_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1042,7 +1543,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_bootstrap_whitelist_allow_all", rpc_params![])
         .await;
     assert!(response
@@ -1050,7 +1551,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_add_to_bootstrap_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1061,7 +1562,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_remove_from_peers_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1072,7 +1573,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_bootstrap_whitelist", rpc_params![])
         .await;
     assert!(response
@@ -1080,7 +1581,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_bootstrap_whitelist_allow_all", rpc_params![])
         .await;
     assert!(response
@@ -1088,7 +1589,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_add_to_bootstrap_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1099,7 +1600,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_remove_from_bootstrap_whitelist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1110,7 +1611,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("node_bootstrap_blacklist", rpc_params![])
         .await;
     assert!(response
@@ -1118,7 +1619,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_add_to_bootstrap_blacklist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1129,7 +1630,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request(
             "node_remove_from_bootstrap_blacklist",
             rpc_params![Vec::<IpAddr>::new()],
@@ -1140,7 +1641,7 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
-    let response: Result<(), Error> = client
+    let response: Result<(), ClientError> = client
         .request("add_staking_secret_keys", rpc_params![Vec::<String>::new()])
         .await;
     assert!(response
@@ -1207,7 +1708,7 @@ async fn get_openrpc_spec() {
         .unwrap();
     let params = rpc_params![];
 
-    let response: Result<Value, Error> = client.request("rpc.discover", params).await;
+    let response: Result<Value, ClientError> = client.request("rpc.discover", params).await;
 
     assert!(response
         .unwrap_err()
@@ -1268,3 +1769,157 @@ async fn get_stakers() {
 
     api_public_handle.stop().await;
 }
+
+#[tokio::test]
+async fn public_api_rate_limit() {
+    let addr: SocketAddr = "[::]:5046".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_execute_readonly_request()
+        .returning(|_req| {
+            Ok(ReadOnlyExecutionOutput {
+                out: massa_execution_exports::ExecutionOutput {
+                    slot: Slot::new(1, 5),
+                    block_info: None,
+                    state_changes: massa_final_state::StateChanges::default(),
+                    events: massa_execution_exports::EventStore::default(),
+                },
+                gas_cost: 100,
+                call_result: vec![],
+                trace: None,
+            })
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let rate_limiter = Arc::new(
+        RateLimiter::new(RateLimitConfig {
+            requests_per_second: 1,
+            request_burst: 2,
+            read_only_gas_per_second: 1_000,
+            read_only_gas_burst: 1_500_000,
+            max_tracked_clients: 100,
+            trust_forwarded_for: true,
+            tokens_path: None,
+        })
+        .unwrap(),
+    );
+    api_public.0.rate_limiter = Some(rate_limiter.clone());
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = |ip: &str| {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", ip.parse().unwrap());
+        HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(format!(
+                "http://localhost:{}",
+                addr.to_string().split(':').last().unwrap()
+            ))
+            .unwrap()
+    };
+
+    // the request burst is allowed, then the client has to wait
+    for _ in 0..2 {
+        let response: Result<(), ClientError> = client("10.0.0.1")
+            .request("unknown_method", rpc_params![])
+            .await;
+        assert!(response
+            .unwrap_err()
+            .to_string()
+            .contains("Method not found"));
+    }
+    let response: Result<(), ClientError> = client("10.0.0.1")
+        .request("unknown_method", rpc_params![])
+        .await;
+    assert!(response.unwrap_err().to_string().contains("rate limited"));
+
+    // each call of a batch is charged
+    let mut batch = BatchRequestBuilder::new();
+    for _ in 0..3 {
+        batch.insert("unknown_method", rpc_params![]).unwrap();
+    }
+    let responses: Vec<String> = client("10.0.0.3")
+        .batch_request::<()>(batch)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|response| response.unwrap_err().to_string())
+        .collect();
+    assert!(responses[0].contains("Method not found"));
+    assert!(responses[1].contains("Method not found"));
+    assert!(responses[2].contains("rate limited"));
+
+    // opening a WebSocket connection is charged, then each of its calls
+    let ws_client = || {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "10.0.0.4".parse().unwrap());
+        WsClientBuilder::default()
+            .set_headers(headers)
+            .build(format!(
+                "ws://localhost:{}",
+                addr.to_string().split(':').last().unwrap()
+            ))
+    };
+    let ws = ws_client().await.unwrap();
+    let response: Result<(), ClientError> = ws.request("unknown_method", rpc_params![]).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("Method not found"));
+    let response: Result<(), ClientError> = ws.request("unknown_method", rpc_params![]).await;
+    assert!(response.unwrap_err().to_string().contains("rate limited"));
+    assert!(ws_client().await.is_err());
+
+    // read-only executions are charged their max gas, other clients have their own budgets
+    let params = rpc_params![vec![ReadOnlyCall {
+        max_gas: 1_000_000,
+        target_address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+            .unwrap(),
+        target_function: "hello".to_string(),
+        parameter: vec![],
+        caller_address: None,
+        fee: None,
+        trace: None,
+        coins: None,
+    }]];
+    let response: Vec<ExecuteReadOnlyResponse> = client("10.0.0.2")
+        .request("execute_read_only_call", params.clone())
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 1);
+    let response: Result<Vec<ExecuteReadOnlyResponse>, ClientError> = client("10.0.0.2")
+        .request("execute_read_only_call", params)
+        .await;
+    assert!(response.unwrap_err().to_string().contains("rate limited"));
+
+    // executions over the gas burst are refused with a distinct error, waiting would not help
+    let params = rpc_params![vec![ReadOnlyCall {
+        max_gas: 2_000_000,
+        target_address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+            .unwrap(),
+        target_function: "hello".to_string(),
+        parameter: vec![],
+        caller_address: None,
+        fee: None,
+        trace: None,
+        coins: None,
+    }]];
+    let response: Result<Vec<ExecuteReadOnlyResponse>, ClientError> = client("10.0.0.5")
+        .request("execute_read_only_call", params)
+        .await;
+    let err = response.unwrap_err().to_string();
+    assert!(err.contains("exceeds the maximum") && !err.contains("rate limited"));
+
+    let stats = rate_limiter.get_stats();
+    assert_eq!(stats.limited_requests, 4);
+    assert_eq!(stats.limited_read_only_executions, 2);
+    assert_eq!(stats.tracked_clients, 5);
+
+    api_public_handle.stop().await;
+}
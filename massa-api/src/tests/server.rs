@@ -1,11 +1,26 @@
-use std::{net::SocketAddr, str::FromStr};
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
 
+use hyper::{header::AUTHORIZATION, HeaderMap};
 use jsonrpsee::{
     client_transport::ws::Url, core::client::ClientT, http_client::HttpClientBuilder, rpc_params,
     ws_client::WsClientBuilder,
 };
-use massa_api_exports::operation::OperationInfo;
-use massa_models::operation::OperationId;
+use massa_api_exports::{
+    auth::{ApiAuth, ApiRole, ApiTokenEntry},
+    operation::OperationInfo,
+};
+use massa_factory_exports::{
+    ProductionEvent, ProductionFailure, ProductionKind, ProductionLogFilter, ProductionOutcome,
+    ProductionReport,
+};
+use massa_models::{address::Address, operation::OperationId, prehash::PreHashSet, slot::Slot};
+use massa_protocol_exports::{MockProtocolController, PeerId, PeerReputation};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
 
 use crate::{tests::mock::get_apiv2_server, ApiServer, RpcServer};
 
@@ -42,6 +57,48 @@ async fn max_conn() {
     api_handle.stop().await;
 }
 
+#[tokio::test]
+async fn max_http_conn() {
+    let addr: SocketAddr = "[::]:5051".parse().unwrap();
+    let (mut api_server, mut api_config) = get_apiv2_server(&addr);
+
+    api_server.0.api_settings.max_connections = 2;
+    api_config.max_connections = 2;
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let local_addr = ("localhost", addr.port());
+    let request_status = |mut stream: TcpStream| async move {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"get_version","params":[]}"#;
+        let request = format!(
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = [0u8; 12];
+        stream.read_exact(&mut response).await.unwrap();
+        String::from_utf8_lossy(&response).to_string()
+    };
+
+    // idle keep-alive connections count until they are closed
+    let idle_connection = TcpStream::connect(local_addr).await.unwrap();
+    let _other_idle_connection = TcpStream::connect(local_addr).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let status = request_status(TcpStream::connect(local_addr).await.unwrap()).await;
+    assert_eq!(status, "HTTP/1.1 429");
+
+    drop(idle_connection);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let status = request_status(TcpStream::connect(local_addr).await.unwrap()).await;
+    assert_eq!(status, "HTTP/1.1 200");
+
+    api_handle.stop().await;
+}
+
 #[tokio::test]
 async fn max_request_size() {
     let addr: SocketAddr = "[::]:5038".parse().unwrap();
@@ -83,7 +140,7 @@ async fn max_request_size() {
         OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap(),
         OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap(),
     ]];
-    let response: Result<Vec<OperationInfo>, jsonrpsee::core::Error> =
+    let response: Result<Vec<OperationInfo>, jsonrpsee::core::ClientError> =
         client.request("get_operations", params).await;
 
     assert!(response
@@ -142,7 +199,7 @@ async fn http_disabled() {
         ))
         .unwrap();
 
-    let response: Result<Vec<OperationInfo>, jsonrpsee::core::Error> =
+    let response: Result<Vec<OperationInfo>, jsonrpsee::core::ClientError> =
         client.request("get_operations", rpc_params![]).await;
 
     assert!(response
@@ -178,7 +235,7 @@ async fn host_allowed() {
         ))
         .unwrap();
 
-    let response: Result<Vec<OperationInfo>, jsonrpsee::core::Error> =
+    let response: Result<Vec<OperationInfo>, jsonrpsee::core::ClientError> =
         client.request("get_operations", rpc_params![]).await;
 
     // response OK but invalid params (no params provided)
@@ -205,7 +262,7 @@ async fn host_allowed() {
         ))
         .unwrap();
 
-    let response: Result<Vec<OperationInfo>, jsonrpsee::core::Error> =
+    let response: Result<Vec<OperationInfo>, jsonrpsee::core::ClientError> =
         client.request("get_operations", rpc_params![]).await;
 
     // host not allowed
@@ -217,3 +274,247 @@ async fn host_allowed() {
     api_handle.stop().await;
     api_handle2.stop().await;
 }
+
+#[tokio::test]
+async fn private_api_auth() {
+    let addr: SocketAddr = "[::]:5045".parse().unwrap();
+    let auth = ApiAuth::new(vec![
+        ApiTokenEntry {
+            name: "monitoring".to_string(),
+            token: "read-token".to_string(),
+            role: ApiRole::ReadOnly,
+        },
+        ApiTokenEntry {
+            name: "ops".to_string(),
+            token: "ops-token".to_string(),
+            role: ApiRole::Operator,
+        },
+    ]);
+    let (api_private, api_config, _wallet_dir) =
+        crate::tests::mock::start_private_api(addr, Some(Arc::new(auth)));
+    let stop_cv = api_private.0.stop_cv.clone();
+
+    let api_handle = api_private
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PRIVATE API");
+
+    let client = |token: Option<&str>| {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            headers.insert(AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+        }
+        HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(format!(
+                "http://localhost:{}",
+                addr.to_string().split(':').last().unwrap()
+            ))
+            .unwrap()
+    };
+
+    // unknown clients are rejected
+    let response: Result<PreHashSet<Address>, jsonrpsee::core::ClientError> = client(None)
+        .request("get_staking_addresses", rpc_params![])
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("status code: 401"));
+    let response: Result<PreHashSet<Address>, jsonrpsee::core::ClientError> = client(Some("wrong"))
+        .request("get_staking_addresses", rpc_params![])
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("status code: 401"));
+
+    // read-only clients can read the node configuration but not manage it
+    let response: PreHashSet<Address> = client(Some("read-token"))
+        .request("get_staking_addresses", rpc_params![])
+        .await
+        .unwrap();
+    assert!(response.is_empty());
+    let response: Result<(), jsonrpsee::core::ClientError> = client(Some("read-token"))
+        .request("stop_node", rpc_params![])
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("status code: 403"));
+    assert!(!*stop_cv.0.lock().unwrap());
+
+    // operators can manage the node but not its staking keys
+    let response: Result<(), jsonrpsee::core::ClientError> = client(Some("ops-token"))
+        .request(
+            "add_staking_secret_keys",
+            rpc_params![vec![KeyPair::generate(0).unwrap().to_string()]],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("status code: 403"));
+    let _: () = client(Some("ops-token"))
+        .request("stop_node", rpc_params![])
+        .await
+        .unwrap();
+    assert!(*stop_cv.0.lock().unwrap());
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn private_api_auth_max_request_size() {
+    let addr: SocketAddr = "[::]:5050".parse().unwrap();
+    let auth = ApiAuth::new(vec![ApiTokenEntry {
+        name: "monitoring".to_string(),
+        token: "read-token".to_string(),
+        role: ApiRole::ReadOnly,
+    }]);
+    let (api_private, mut api_config, _wallet_dir) =
+        crate::tests::mock::start_private_api(addr, Some(Arc::new(auth)));
+    api_config.max_request_body_size = 10;
+
+    let api_handle = api_private
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PRIVATE API");
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, "Bearer read-token".parse().unwrap());
+    let client = HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    // the body is not buffered beyond the maximum request size to read its methods
+    let response: Result<PreHashSet<Address>, jsonrpsee::core::ClientError> =
+        client.request("get_staking_addresses", rpc_params![]).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("status code: 413"));
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_staking_production() {
+    let addr: SocketAddr = "[::]:5047".parse().unwrap();
+    let (api_private, api_config, _wallet_dir) = crate::tests::mock::start_private_api(addr, None);
+    let staking_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    {
+        let mut production_log = api_private.0.production_log.write();
+        production_log.push(ProductionEvent {
+            slot: Slot::new(3, 0),
+            kind: ProductionKind::Endorsement,
+            index: Some(2),
+            address: Some(staking_address),
+            outcome: ProductionOutcome::Produced,
+            failure: None,
+            details: None,
+            latency: None,
+            block_id: None,
+        });
+        production_log.push(ProductionEvent {
+            slot: Slot::new(4, 1),
+            kind: ProductionKind::Block,
+            index: None,
+            address: Some(staking_address),
+            outcome: ProductionOutcome::Missed,
+            failure: Some(ProductionFailure::NoConnections),
+            details: Some("no connections".to_string()),
+            latency: None,
+            block_id: None,
+        });
+    }
+
+    let api_handle = api_private
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PRIVATE API");
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let report: ProductionReport = client
+        .request("get_staking_production", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(report.events.len(), 2);
+    assert_eq!(report.stats.endorsements_produced, 1);
+    assert_eq!(report.stats.blocks_missed, 1);
+    assert_eq!(
+        report.stats.failures.get(&ProductionFailure::NoConnections),
+        Some(&1)
+    );
+
+    // only the missed draws
+    let report: ProductionReport = client
+        .request(
+            "get_staking_production",
+            rpc_params![ProductionLogFilter {
+                addresses: Some(vec![staking_address]),
+                kind: None,
+                failed_only: true,
+            }],
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.events.len(), 1);
+    assert_eq!(report.events[0].slot, Slot::new(4, 1));
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn node_peers_reputation() {
+    let addr: SocketAddr = "[::]:5049".parse().unwrap();
+    let (mut api_private, api_config, _wallet_dir) =
+        crate::tests::mock::start_private_api(addr, None);
+    let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    let reputations = vec![PeerReputation {
+        peer_id,
+        score: 0,
+        banned: true,
+        ban_count: 2,
+        banned_until: Some(MassaTime::from_millis(1_700_000_000_000)),
+    }];
+    let mut protocol_ctrl = MockProtocolController::new();
+    protocol_ctrl
+        .expect_get_peer_reputations()
+        .returning(move || Ok(reputations.clone()));
+    api_private.0.protocol_controller = Box::new(protocol_ctrl);
+
+    let api_handle = api_private
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PRIVATE API");
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let reputations: Vec<PeerReputation> = client
+        .request("node_peers_reputation", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(reputations.len(), 1);
+    assert_eq!(reputations[0].peer_id, peer_id);
+    assert!(reputations[0].banned);
+    assert_eq!(
+        reputations[0].banned_until,
+        Some(MassaTime::from_millis(1_700_000_000_000))
+    );
+
+    api_handle.stop().await;
+}
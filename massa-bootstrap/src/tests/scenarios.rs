@@ -2,6 +2,7 @@
 
 use super::universe_client::{BootstrapClientForeignControllers, BootstrapClientTestUniverse};
 use super::universe_server::BootstrapServerTestUniverseBuilder;
+use crate::white_black_list::SharedWhiteBlackList;
 use crate::BootstrapConfig;
 use crate::BootstrapError;
 use massa_models::amount::Amount;
@@ -11,6 +12,7 @@ use massa_models::{address::Address, node::NodeId};
 use massa_signature::KeyPair;
 use massa_test_framework::TestUniverse;
 use serial_test::serial;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 #[test]
@@ -43,6 +45,30 @@ fn test_bootstrap_not_whitelisted() {
     drop(server_universe);
 }
 
+#[test]
+fn test_allow_everyone_to_bootstrap() {
+    let dir = tempfile::tempdir().unwrap();
+    let white_path = dir.path().join("bootstrap_whitelist.json");
+    std::fs::write(&white_path, r#"["127.0.0.1"]"#).unwrap();
+    let list = SharedWhiteBlackList::new(
+        white_path.clone(),
+        dir.path().join("bootstrap_blacklist.json"),
+    )
+    .unwrap();
+    let remote_addr: SocketAddr = "10.0.0.1:31245".parse().unwrap();
+    assert!(matches!(
+        list.is_ip_allowed(&remote_addr),
+        Err(BootstrapError::WhiteListed(_))
+    ));
+
+    list.allow_everyone().unwrap();
+    assert!(!white_path.exists());
+    assert_eq!(list.get_white_list(), None);
+    list.is_ip_allowed(&remote_addr).unwrap();
+    // allowing everyone again is not an error
+    list.allow_everyone().unwrap();
+}
+
 #[test]
 #[serial]
 fn test_bootstrap_server() {
@@ -93,6 +93,21 @@ impl SharedWhiteBlackList<'_> {
         Ok(())
     }
 
+    /// Allow everyone to bootstrap by removing the white list and its file
+    pub fn allow_everyone(&self) -> Result<(), BootstrapError> {
+        let mut write_lock = self.inner.write();
+        match std::fs::remove_file(&self.white_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(error = ?e, "failed to remove whitelist file");
+                return Err(BootstrapError::IoError(e));
+            }
+        }
+        write_lock.white_list = None;
+        Ok(())
+    }
+
     /// write list to file
     fn write_to_file(
         &self,
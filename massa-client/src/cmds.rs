@@ -1,7 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::display::Output;
-use crate::{client_warning, rpc_error};
+use crate::{ask_export_password, client_warning, grpc_error, rpc_error};
 use anyhow::{anyhow, bail, Result};
 use console::style;
 use massa_api_exports::{
@@ -10,6 +10,7 @@ use massa_api_exports::{
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
     operation::OperationInput,
 };
+use massa_models::config::THREAD_COUNT;
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -22,9 +23,10 @@ use massa_models::{
     operation::{Operation, OperationId, OperationType},
     slot::Slot,
 };
-use massa_sdk::Client;
-use massa_signature::KeyPair;
-use massa_wallet::Wallet;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_sdk::{Client, OperationWaitTarget};
+use massa_signature::{KeyPair, PublicKey};
+use massa_wallet::{DerivedAddress, Wallet};
 
 use serde::Serialize;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -142,6 +144,13 @@ pub enum Command {
     )]
     node_peers_whitelist,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the status of the MIPs (network upgrades) known by the node (gRPC only)"
+    )]
+    node_get_mip_status,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -156,6 +165,13 @@ pub enum Command {
     )]
     get_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address1 Address2 ...", pwd_not_needed = "true"),
+        message = "show when a list of addresses will next produce blocks or endorsements, as far as the draws are known"
+    )]
+    get_next_draws,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address Key", pwd_not_needed = "true"),
@@ -194,6 +210,26 @@ pub enum Command {
     )]
     get_filtered_sc_output_event,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "StartSlot EndSlot Address1 Address2 ...",
+            pwd_not_needed = "true"
+        ),
+        message = "show the block and endorsement producers drawn between two slots (period,thread), optionally for some addresses only (gRPC only)"
+    )]
+    get_selector_draws,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "address_exists_final=Address address_balance_final=Address address_bytecode_final=Address address_datastore_keys_final=Address[,Prefix] address_datastore_value_final=Address,Key address_rolls_final=Address address_deferred_credits_final=Address op_execution_status_final=OperationId (or the same with _candidate)",
+            pwd_not_needed = "true"
+        ),
+        message = "query the final or candidate execution state (gRPC only)"
+    )]
+    query_state,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "show-all-keys"),
@@ -221,6 +257,29 @@ pub enum Command {
     )]
     wallet_generate_secret_key,
 
+    #[strum(
+        ascii_case_insensitive,
+        message = "generate a 24-word mnemonic for the wallet and derive its first address. Write the mnemonic down: it recovers all the addresses derived from it"
+    )]
+    wallet_create_mnemonic,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Word1 Word2 ... Word24 AddressCount"),
+        message = "set the mnemonic of the wallet and recover the addresses derived from it, address count is optional (default 1)"
+    )]
+    wallet_import_mnemonic,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Thread"),
+        message = "derive the next address from the wallet mnemonic, optionally in the given thread, or one address per thread with \"all\""
+    )]
+    wallet_derive_address,
+
+    #[strum(ascii_case_insensitive, message = "show the mnemonic of the wallet")]
+    wallet_get_mnemonic,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SecretKey1 SecretKey2 ..."),
@@ -235,6 +294,34 @@ pub enum Command {
     )]
     wallet_remove_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address Label"),
+        message = "set the label of an address of the wallet, or remove it if no label is given"
+    )]
+    wallet_set_label,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "AddressOrPublicKey1 AddressOrPublicKey2 ..."),
+        message = "follow addresses without their secret key: their balances and rolls are shown by wallet_info"
+    )]
+    wallet_add_watch_only,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToFile"),
+        message = "export the whole wallet (keys, watch-only addresses, labels and mnemonic) to a single file encrypted with an export password"
+    )]
+    wallet_export,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToFile"),
+        message = "import a file written by wallet_export into the wallet"
+    )]
+    wallet_import,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address string"),
@@ -294,6 +381,13 @@ pub enum Command {
     )]
     read_only_call,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToScript", pwd_not_needed = "true"),
+        message = "run a file of commands, one per line. \"let name = command ...\" stores the output of a command in $name, \"wait_included/wait_final [timeout=Millis] OperationId1 ...\" wait for operations, \"sleep Millis\" pauses the script. Stops at the first error"
+    )]
+    run_script,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -330,8 +424,12 @@ pub enum ListOperation {
 /// TODO re-factor me
 #[derive(Debug, Serialize)]
 pub(crate) struct ExtendedWalletEntry {
-    /// the keypair
-    pub keypair: KeyPair,
+    /// the keypair, None for a watch-only address
+    pub keypair: Option<KeyPair>,
+    /// the public key, if known
+    pub public_key: Option<PublicKey>,
+    /// the label of the address
+    pub label: Option<String>,
     /// address and balance information
     pub address_info: CompactAddressInfo,
     /// whether to display the public/secret keys or just the address info
@@ -340,9 +438,19 @@ pub(crate) struct ExtendedWalletEntry {
 
 impl Display for ExtendedWalletEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(label) = &self.label {
+            writeln!(f, "Label: {}", label)?;
+        }
+        if self.keypair.is_none() {
+            writeln!(f, "Watch-only")?;
+        }
         if self.show_keys {
-            writeln!(f, "Secret key: {}", self.keypair)?;
-            writeln!(f, "Public key: {}", self.keypair.get_public_key())?;
+            if let Some(keypair) = &self.keypair {
+                writeln!(f, "Secret key: {}", keypair)?;
+            }
+            if let Some(public_key) = &self.public_key {
+                writeln!(f, "Public key: {}", public_key)?;
+            }
         }
         writeln!(f, "{}", self.address_info)?;
         writeln!(f, "\n=====\n")?;
@@ -350,6 +458,15 @@ impl Display for ExtendedWalletEntry {
     }
 }
 
+/// Mnemonic of the wallet with the addresses derived from it
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletMnemonic {
+    /// the mnemonic phrase
+    pub mnemonic: String,
+    /// the derived addresses
+    pub derived_addresses: Vec<DerivedAddress>,
+}
+
 /// Aggregation of the local, with some useful information as the balance, etc
 /// to be printed by the client.
 #[derive(Debug, Serialize)]
@@ -362,14 +479,16 @@ impl ExtendedWallet {
             addresses_info
                 .iter()
                 .map(|x| {
-                    let keypair = wallet
-                        .keys
-                        .get(&x.address)
-                        .ok_or_else(|| anyhow!("missing key"))?;
+                    let keypair = wallet.keys.get(&x.address).cloned();
+                    if keypair.is_none() && !wallet.get_watch_only().contains_key(&x.address) {
+                        bail!("missing key");
+                    }
                     Ok((
                         x.address,
                         ExtendedWalletEntry {
-                            keypair: keypair.clone(),
+                            keypair,
+                            public_key: wallet.find_associated_public_key(&x.address),
+                            label: wallet.get_label(&x.address).cloned(),
                             address_info: x.compact(),
                             show_keys,
                         },
@@ -428,12 +547,16 @@ impl Command {
     /// - parameters: the parsed parameters
     /// - json: true if --json was passed as an option
     ///     it means that we don't want to print anything we just want the json output
+    /// - grpc: true if the commands that have a gRPC equivalent must use the gRPC APIs
+    /// - wait: true if the commands sending operations must wait for their final execution
     pub(crate) async fn run(
         &self,
         client: &mut Client,
         wallet_opt: &mut Option<Wallet>,
         parameters: &[String],
         json: bool,
+        grpc: bool,
+        wait: bool,
     ) -> Result<Box<dyn Output>> {
         if let Ok(node_status) = client.public.get_status().await {
             if node_status.chain_id != client.chain_id {
@@ -475,14 +598,16 @@ impl Command {
 
             Command::node_unban_by_id => {
                 let ids = parse_vec::<NodeId>(parameters)?;
-                match client.private.node_unban_by_id(ids).await {
-                    Ok(()) => {
-                        if !json {
-                            println!("Request of unbanning successfully sent!")
-                        }
+                if grpc {
+                    if let Err(e) = client.grpc.unban_nodes_by_ids(ids).await {
+                        grpc_error!(e)
                     }
-                    Err(e) => rpc_error!(e),
-                };
+                } else if let Err(e) = client.private.node_unban_by_id(ids).await {
+                    rpc_error!(e)
+                }
+                if !json {
+                    println!("Request of unbanning successfully sent!")
+                }
                 Ok(Box::new(()))
             }
 
@@ -501,13 +626,15 @@ impl Command {
 
             Command::node_ban_by_id => {
                 let ids = parse_vec::<NodeId>(parameters)?;
-                match client.private.node_ban_by_id(ids).await {
-                    Ok(()) => {
-                        if !json {
-                            println!("Request of banning successfully sent!")
-                        }
+                if grpc {
+                    if let Err(e) = client.grpc.ban_nodes_by_ids(ids).await {
+                        grpc_error!(e)
                     }
-                    Err(e) => rpc_error!(e),
+                } else if let Err(e) = client.private.node_ban_by_id(ids).await {
+                    rpc_error!(e)
+                }
+                if !json {
+                    println!("Request of banning successfully sent!")
                 }
                 Ok(Box::new(()))
             }
@@ -564,15 +691,39 @@ impl Command {
                 }
             }
 
-            Command::get_status => match client.public.get_status().await {
-                Ok(node_status) => Ok(Box::new(node_status)),
-                Err(e) => rpc_error!(e),
-            },
+            Command::get_status => {
+                if grpc {
+                    match client.grpc.get_status().await {
+                        Ok(status) => Ok(Box::new(status)),
+                        Err(e) => grpc_error!(e),
+                    }
+                } else {
+                    match client.public.get_status().await {
+                        Ok(node_status) => Ok(Box::new(node_status)),
+                        Err(e) => rpc_error!(e),
+                    }
+                }
+            }
 
             Command::get_addresses => {
                 let addresses = parse_vec::<Address>(parameters)?;
-                match client.public.get_addresses(addresses).await {
-                    Ok(addresses_info) => Ok(Box::new(addresses_info)),
+                if grpc {
+                    match client.grpc.get_addresses(addresses).await {
+                        Ok(balances) => Ok(Box::new(balances)),
+                        Err(e) => grpc_error!(e),
+                    }
+                } else {
+                    match client.public.get_addresses(addresses).await {
+                        Ok(addresses_info) => Ok(Box::new(addresses_info)),
+                        Err(e) => rpc_error!(e),
+                    }
+                }
+            }
+
+            Command::get_next_draws => {
+                let addresses = parse_vec::<Address>(parameters)?;
+                match client.public.get_next_draws(addresses).await {
+                    Ok(draws) => Ok(Box::new(draws)),
                     Err(e) => rpc_error!(e),
                 }
             }
@@ -598,9 +749,16 @@ impl Command {
                     bail!("wrong param numbers, expecting at least one block id")
                 }
                 let block_ids = parse_vec::<BlockId>(parameters)?;
-                match client.public.get_blocks(block_ids).await {
-                    Ok(blocks_info) => Ok(Box::new(blocks_info)),
-                    Err(e) => rpc_error!(e),
+                if grpc {
+                    match client.grpc.get_blocks(block_ids).await {
+                        Ok(blocks) => Ok(Box::new(blocks)),
+                        Err(e) => grpc_error!(e),
+                    }
+                } else {
+                    match client.public.get_blocks(block_ids).await {
+                        Ok(blocks_info) => Ok(Box::new(blocks_info)),
+                        Err(e) => rpc_error!(e),
+                    }
                 }
             }
 
@@ -614,9 +772,16 @@ impl Command {
 
             Command::get_operations => {
                 let operations = parse_vec::<OperationId>(parameters)?;
-                match client.public.get_operations(operations).await {
-                    Ok(operations_info) => Ok(Box::new(operations_info)),
-                    Err(e) => rpc_error!(e),
+                if grpc {
+                    match client.grpc.get_operations(operations).await {
+                        Ok(operations) => Ok(Box::new(operations)),
+                        Err(e) => grpc_error!(e),
+                    }
+                } else {
+                    match client.public.get_operations(operations).await {
+                        Ok(operations_info) => Ok(Box::new(operations_info)),
+                        Err(e) => rpc_error!(e),
+                    }
                 }
             }
 
@@ -662,11 +827,13 @@ impl Command {
                 if !json && show_keys {
                     client_warning!("do not share your secret key");
                 }
-                match client
-                    .public
-                    .get_addresses(wallet.get_full_wallet().keys().copied().collect())
-                    .await
-                {
+                let addresses = wallet
+                    .get_full_wallet()
+                    .keys()
+                    .chain(wallet.get_watch_only().keys())
+                    .copied()
+                    .collect();
+                match client.public.get_addresses(addresses).await {
                     Ok(addresses_info) => Ok(Box::new(ExtendedWallet::new(
                         wallet,
                         &addresses_info,
@@ -748,14 +915,16 @@ impl Command {
                     .map(|s| format!("{}", s.unwrap()))
                     .collect();
 
-                match client.private.add_staking_secret_keys(secret_str).await {
-                    Ok(()) => {
-                        if !json {
-                            println!("Keys successfully added!")
-                        }
+                if grpc {
+                    if let Err(e) = client.grpc.add_staking_secret_keys(secret_str).await {
+                        grpc_error!(e)
                     }
-                    Err(e) => rpc_error!(e),
-                };
+                } else if let Err(e) = client.private.add_staking_secret_keys(secret_str).await {
+                    rpc_error!(e)
+                }
+                if !json {
+                    println!("Keys successfully added!")
+                }
                 Ok(Box::new(()))
             }
 
@@ -799,6 +968,64 @@ impl Command {
                 }
             }
 
+            Command::wallet_create_mnemonic => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let (mnemonic, derived_address) = wallet.create_mnemonic()?;
+                if !json {
+                    client_warning!("write down your mnemonic and do not share it, it gives access to all the addresses derived from it");
+                }
+                Ok(Box::new(WalletMnemonic {
+                    mnemonic,
+                    derived_addresses: vec![derived_address],
+                }))
+            }
+
+            Command::wallet_import_mnemonic => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                // words cannot be numbers, so a trailing number is the address count
+                let (words, address_count) = match parameters.split_last() {
+                    Some((last, words)) if last.parse::<u32>().is_ok() => {
+                        (words, last.parse::<u32>()?)
+                    }
+                    _ => (parameters, 1),
+                };
+                if words.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                Ok(Box::new(
+                    wallet.import_mnemonic(&words.join(" "), address_count)?,
+                ))
+            }
+
+            Command::wallet_derive_address => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let derived_addresses = match parameters.first().map(String::as_str) {
+                    None => vec![wallet.derive_next_address(None, THREAD_COUNT)?],
+                    Some("all") => wallet.derive_address_per_thread(THREAD_COUNT)?,
+                    Some(thread) => {
+                        vec![wallet.derive_next_address(Some(thread.parse()?), THREAD_COUNT)?]
+                    }
+                };
+                Ok(Box::new(derived_addresses))
+            }
+
+            Command::wallet_get_mnemonic => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                match wallet.get_mnemonic() {
+                    Some(mnemonic) => {
+                        if !json {
+                            client_warning!("do not share your mnemonic");
+                        }
+                        Ok(Box::new(mnemonic))
+                    }
+                    None => bail!("the wallet has no mnemonic, use 'wallet_create_mnemonic' or 'wallet_import_mnemonic'"),
+                }
+            }
+
             Command::wallet_add_secret_keys => {
                 if parameters.is_empty() {
                     bail!("wrong number of parameters");
@@ -843,6 +1070,100 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::wallet_set_label => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let address = Address::from_str(&parameters[0])?;
+                let label = parameters[1..].join(" ");
+                wallet.set_label(address, Some(label).filter(|label| !label.is_empty()))?;
+                if !json {
+                    match wallet.get_label(&address) {
+                        Some(label) => println!("Address {} labeled \"{}\"", address, label),
+                        None => println!("Label of address {} removed", address),
+                    }
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_add_watch_only => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let entries = parameters
+                    .iter()
+                    .map(|parameter| match PublicKey::from_str(parameter) {
+                        Ok(public_key) => {
+                            Ok((Address::from_public_key(&public_key), Some(public_key)))
+                        }
+                        Err(_) => Address::from_str(parameter)
+                            .map(|address| (address, None))
+                            .map_err(|_| {
+                                anyhow!("{} is neither an address nor a public key", parameter)
+                            }),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let addresses = wallet.add_watch_only(entries)?;
+                if json {
+                    return Ok(Box::new(addresses));
+                } else {
+                    for address in addresses {
+                        if wallet.keys.contains_key(&address) {
+                            client_warning!(format!(
+                                "the secret key of {} is already in the wallet",
+                                address
+                            ));
+                        } else {
+                            println!("Watching address {}", address);
+                        }
+                    }
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_export => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let path = PathBuf::from(&parameters[0]);
+                if path.exists() {
+                    bail!("{} already exists", path.display());
+                }
+                if !json {
+                    client_warning!(
+                        "the export file contains your secret keys, keep it and its password safe"
+                    );
+                }
+                wallet.export_to_file(&path, &ask_export_password(true))?;
+                if !json {
+                    println!("Wallet exported to {}", path.display());
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_import => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let path = PathBuf::from(&parameters[0]);
+                let addresses = wallet.import_from_file(&path, &ask_export_password(false))?;
+                if json {
+                    return Ok(Box::new(addresses));
+                } else {
+                    println!("Imported {} addresses to the wallet.", addresses.len());
+                    println!("Type `wallet_info` to show them.\n");
+                }
+                Ok(Box::new(()))
+            }
+
             Command::buy_rolls => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -895,6 +1216,8 @@ impl Command {
                     fee,
                     addr,
                     json,
+                    grpc,
+                    wait,
                 )
                 .await
             }
@@ -931,6 +1254,8 @@ impl Command {
                     fee,
                     addr,
                     json,
+                    grpc,
+                    wait,
                 )
                 .await
             }
@@ -971,9 +1296,13 @@ impl Command {
                     fee,
                     addr,
                     json,
+                    grpc,
+                    wait,
                 )
                 .await
             }
+            Command::run_script => bail!("scripts cannot be nested"),
+
             Command::when_moon => {
                 let res = "At night 🌔.";
                 if !json {
@@ -1030,6 +1359,8 @@ impl Command {
                     fee,
                     addr,
                     json,
+                    grpc,
+                    wait,
                 )
                 .await
             }
@@ -1085,6 +1416,8 @@ impl Command {
                     fee,
                     addr,
                     json,
+                    grpc,
+                    wait,
                 )
                 .await
             }
@@ -1126,6 +1459,7 @@ impl Command {
                         address,
                         operation_datastore: None, // TODO - #3072
                         fee,
+                        trace: None,
                     })
                     .await
                 {
@@ -1162,6 +1496,7 @@ impl Command {
                         max_gas,
                         coins,
                         fee,
+                        trace: None,
                     })
                     .await
                 {
@@ -1171,9 +1506,16 @@ impl Command {
             }
             Command::node_bootstrap_blacklist => {
                 if parameters.is_empty() {
-                    match client.private.node_bootstrap_blacklist().await {
-                        Ok(bootstraplist_ips) => Ok(Box::new(bootstraplist_ips)),
-                        Err(e) => rpc_error!(e),
+                    if grpc {
+                        match client.grpc.get_bootstrap_blacklist().await {
+                            Ok(bootstraplist_ips) => Ok(Box::new(bootstraplist_ips)),
+                            Err(e) => grpc_error!(e),
+                        }
+                    } else {
+                        match client.private.node_bootstrap_blacklist().await {
+                            Ok(bootstraplist_ips) => Ok(Box::new(bootstraplist_ips)),
+                            Err(e) => rpc_error!(e),
+                        }
                     }
                 } else {
                     let cli_op = match parameters[0].parse::<ListOperation>() {
@@ -1187,49 +1529,72 @@ impl Command {
                         bail!("[IpAddr] parameter shouldn't be empty");
                     }
                     let ips = parse_vec::<IpAddr>(args)?;
-                    let res: Result<Box<dyn Output>> = match cli_op {
+                    match cli_op {
                         ListOperation::Add => {
-                            match client.private.node_add_to_bootstrap_blacklist(ips).await {
-                                Ok(()) => {
-                                    if !json {
-                                        println!(
-                                            "Request of bootstrap blacklisting successfully sent!"
-                                        )
-                                    }
-                                    Ok(Box::new(()))
+                            if grpc {
+                                if let Err(e) = client.grpc.add_to_bootstrap_blacklist(ips).await {
+                                    grpc_error!(e)
                                 }
-                                Err(e) => rpc_error!(e),
+                            } else if let Err(e) =
+                                client.private.node_add_to_bootstrap_blacklist(ips).await
+                            {
+                                rpc_error!(e)
+                            }
+                            if !json {
+                                println!("Request of bootstrap blacklisting successfully sent!")
                             }
                         }
                         ListOperation::Remove => {
-                            match client
+                            if grpc {
+                                if let Err(e) =
+                                    client.grpc.remove_from_bootstrap_blacklist(ips).await
+                                {
+                                    grpc_error!(e)
+                                }
+                            } else if let Err(e) = client
                                 .private
                                 .node_remove_from_bootstrap_blacklist(ips)
                                 .await
                             {
-                                Ok(()) => {
-                                    if !json {
-                                        println!("Request of remove from bootstrap blacklist successfully sent!")
-                                    }
-                                    Ok(Box::new(()))
-                                }
-                                Err(e) => rpc_error!(e),
+                                rpc_error!(e)
+                            }
+                            if !json {
+                                println!(
+                                    "Request of remove from bootstrap blacklist successfully sent!"
+                                )
                             }
                         }
                         ListOperation::AllowAll => {
                             bail!("\"allow-all\" command is not implemented")
                         }
                     };
-                    res
+                    Ok(Box::new(()))
                 }
             }
             Command::node_bootstrap_whitelist => {
                 if parameters.is_empty() {
-                    match client.private.node_bootstrap_whitelist().await {
+                    let res = if grpc {
+                        client
+                            .grpc
+                            .get_bootstrap_whitelist()
+                            .await
+                            .map_err(|e| e.to_string())
+                    } else {
+                        client
+                            .private
+                            .node_bootstrap_whitelist()
+                            .await
+                            .map_err(|e| e.to_string())
+                    };
+                    match res {
                         Ok(bootstraplist_ips) => Ok(Box::new(bootstraplist_ips)),
                         Err(e) => {
                             client_warning!("if bootstrap whitelist configuration file does't exists, bootstrap is allowed for everyone !!!");
-                            rpc_error!(e)
+                            if grpc {
+                                grpc_error!(e)
+                            } else {
+                                rpc_error!(e)
+                            }
                         }
                     }
                 } else {
@@ -1240,60 +1605,100 @@ impl Command {
                         ),
                     };
                     let args = &parameters[1..];
-                    let res: Result<Box<dyn Output>> = match cli_op {
+                    match cli_op {
                         ListOperation::Add => {
                             if args.is_empty() {
                                 bail!("[IpAddr] parameter shouldn't be empty");
                             }
-                            match client
-                                .private
-                                .node_add_to_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
-                                .await
-                            {
-                                Ok(()) => {
-                                    if !json {
-                                        println!(
-                                            "Request of bootstrap whitelisting successfully sent!"
-                                        )
-                                    }
-                                    Ok(Box::new(()))
+                            let ips = parse_vec::<IpAddr>(args)?;
+                            if grpc {
+                                if let Err(e) = client.grpc.add_to_bootstrap_whitelist(ips).await {
+                                    grpc_error!(e)
                                 }
-                                Err(e) => rpc_error!(e),
+                            } else if let Err(e) =
+                                client.private.node_add_to_bootstrap_whitelist(ips).await
+                            {
+                                rpc_error!(e)
+                            }
+                            if !json {
+                                println!("Request of bootstrap whitelisting successfully sent!")
                             }
                         }
                         ListOperation::Remove => {
                             if args.is_empty() {
                                 bail!("[IpAddr] parameter shouldn't be empty");
                             }
-                            match client
+                            let ips = parse_vec::<IpAddr>(args)?;
+                            if grpc {
+                                if let Err(e) =
+                                    client.grpc.remove_from_bootstrap_whitelist(ips).await
+                                {
+                                    grpc_error!(e)
+                                }
+                            } else if let Err(e) = client
                                 .private
-                                .node_remove_from_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_remove_from_bootstrap_whitelist(ips)
                                 .await
                             {
-                                Ok(()) => {
-                                    if !json {
-                                        println!("Request of remove from bootstrap whitelist successfully sent!")
-                                    }
-                                    Ok(Box::new(()))
-                                }
-                                Err(e) => rpc_error!(e),
+                                rpc_error!(e)
+                            }
+                            if !json {
+                                println!(
+                                    "Request of remove from bootstrap whitelist successfully sent!"
+                                )
                             }
                         }
                         ListOperation::AllowAll => {
-                            match client.private.node_bootstrap_whitelist_allow_all().await {
-                                Ok(()) => {
-                                    if !json {
-                                        println!(
-                                            "Request of bootstrap whitelisting everyone successfully sent!"
-                                        )
-                                    }
-                                    Ok(Box::new(()))
+                            if grpc {
+                                if let Err(e) = client.grpc.allow_everyone_to_bootstrap().await {
+                                    grpc_error!(e)
                                 }
-                                Err(e) => rpc_error!(e),
+                            } else if let Err(e) =
+                                client.private.node_bootstrap_whitelist_allow_all().await
+                            {
+                                rpc_error!(e)
+                            }
+                            if !json {
+                                println!(
+                                    "Request of bootstrap whitelisting everyone successfully sent!"
+                                )
                             }
                         }
                     };
-                    res
+                    Ok(Box::new(()))
+                }
+            }
+            Command::node_get_mip_status => match client.grpc.get_mip_status().await {
+                Ok(mip_status) => Ok(Box::new(mip_status)),
+                Err(e) => grpc_error!(e),
+            },
+            Command::get_selector_draws => {
+                if parameters.len() < 2 {
+                    bail!("wrong number of parameters");
+                }
+                let start_slot = parameters[0].parse::<Slot>()?;
+                let end_slot = parameters[1].parse::<Slot>()?;
+                let addresses = parse_vec::<Address>(&parameters[2..])?;
+                match client
+                    .grpc
+                    .get_selector_draws(start_slot, end_slot, addresses)
+                    .await
+                {
+                    Ok(draws) => Ok(Box::new(draws)),
+                    Err(e) => grpc_error!(e),
+                }
+            }
+            Command::query_state => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let queries = parameters
+                    .iter()
+                    .map(|query| parse_query_state_item(query))
+                    .collect::<Result<Vec<_>>>()?;
+                match client.grpc.query_state(queries).await {
+                    Ok(result) => Ok(Box::new(result)),
+                    Err(e) => grpc_error!(e),
                 }
             }
             Command::node_peers_whitelist => {
@@ -1353,18 +1758,26 @@ impl Command {
 
 /// helper to wrap and send an operation with proper validity period
 async fn send_operation(
-    client: &Client,
+    client: &mut Client,
     wallet: &Wallet,
     op: OperationType,
     fee: Amount,
     addr: Address,
     json: bool,
+    grpc: bool,
+    wait: bool,
 ) -> Result<Box<dyn Output>> {
-    let cfg = match client.public.get_status().await {
-        Ok(node_status) => node_status,
-        Err(e) => rpc_error!(e),
-    }
-    .config;
+    let cfg = if grpc {
+        match client.grpc.get_status().await {
+            Ok(status) => status.config,
+            Err(e) => grpc_error!(e),
+        }
+    } else {
+        match client.public.get_status().await {
+            Ok(node_status) => node_status.config,
+            Err(e) => rpc_error!(e),
+        }
+    };
 
     let slot = get_current_latest_block_slot(cfg.thread_count, cfg.t0, cfg.genesis_timestamp)?
         .unwrap_or_else(|| Slot::new(0, 0));
@@ -1382,23 +1795,61 @@ async fn send_operation(
         addr,
     )?;
 
-    match client
-        .public
-        .send_operations(vec![OperationInput {
-            creator_public_key: op.content_creator_pub_key,
-            serialized_content: op.serialized_data,
-            signature: op.signature,
-        }])
-        .await
-    {
-        Ok(operation_ids) => {
-            if !json {
-                println!("Sent operation IDs:");
-            }
-            Ok(Box::new(operation_ids))
+    let operation_ids = if grpc {
+        match client.grpc.send_operations(vec![op]).await {
+            Ok(operation_ids) => operation_ids,
+            Err(e) => grpc_error!(e),
+        }
+    } else {
+        match client
+            .public
+            .send_operations(vec![OperationInput {
+                creator_public_key: op.content_creator_pub_key,
+                serialized_content: op.serialized_data,
+                signature: op.signature,
+            }])
+            .await
+        {
+            Ok(operation_ids) => operation_ids,
+            Err(e) => rpc_error!(e),
+        }
+    };
+
+    if !wait {
+        if !json {
+            println!("Sent operation IDs:");
+        }
+        return Ok(Box::new(operation_ids));
+    }
+
+    // poll about once per slot
+    let poll_interval = cfg
+        .t0
+        .checked_div_u64(u64::from(cfg.thread_count))
+        .unwrap_or(cfg.t0);
+    let mut outcomes = Vec::with_capacity(operation_ids.len());
+    for operation_id in operation_ids {
+        if !json {
+            println!(
+                "Sent operation {}, waiting for its final execution...",
+                operation_id
+            );
+        }
+        match client
+            .wait_for_operation(
+                operation_id,
+                Some(expire_period),
+                OperationWaitTarget::Final,
+                poll_interval,
+                None,
+            )
+            .await
+        {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => rpc_error!(e),
         }
-        Err(e) => rpc_error!(e),
     }
+    Ok(Box::new(outcomes))
 }
 
 /// TODO: ugly utilities functions
@@ -1415,6 +1866,115 @@ where
         .collect()
 }
 
+/// parse a `query_state` item: `kind=Address`, `kind=Address,Key` or `kind=OperationId`
+fn parse_query_state_item(query: &str) -> Result<grpc_api::ExecutionQueryRequestItem> {
+    use grpc_api::execution_query_request_item::RequestItem;
+
+    let Some((kind, value)) = query.split_once('=') else {
+        bail!(
+            "invalid query: {}, type \"help query_state\" to get the list of valid queries",
+            query
+        );
+    };
+    if kind.starts_with("op_") {
+        OperationId::from_str(value)?;
+        let operation_id = value.to_string();
+        let request_item = match kind {
+            "op_execution_status_final" => {
+                RequestItem::OpExecutionStatusFinal(grpc_api::OpExecutionStatusFinal {
+                    operation_id,
+                })
+            }
+            "op_execution_status_candidate" => {
+                RequestItem::OpExecutionStatusCandidate(grpc_api::OpExecutionStatusCandidate {
+                    operation_id,
+                })
+            }
+            _ => bail!(
+                "invalid query: {}, type \"help query_state\" to get the list of valid queries",
+                query
+            ),
+        };
+        return Ok(grpc_api::ExecutionQueryRequestItem {
+            request_item: Some(request_item),
+        });
+    }
+
+    let (address, arg) = match value.split_once(',') {
+        Some((address, arg)) => (address, Some(arg.as_bytes().to_vec())),
+        None => (value, None),
+    };
+    Address::from_str(address)?;
+    let address = address.to_string();
+    let request_item = match (kind, arg) {
+        ("address_exists_final", None) => {
+            RequestItem::AddressExistsFinal(grpc_api::AddressExistsFinal { address })
+        }
+        ("address_exists_candidate", None) => {
+            RequestItem::AddressExistsCandidate(grpc_api::AddressExistsCandidate { address })
+        }
+        ("address_balance_final", None) => {
+            RequestItem::AddressBalanceFinal(grpc_api::AddressBalanceFinal { address })
+        }
+        ("address_balance_candidate", None) => {
+            RequestItem::AddressBalanceCandidate(grpc_api::AddressBalanceCandidate { address })
+        }
+        ("address_bytecode_final", None) => {
+            RequestItem::AddressBytecodeFinal(grpc_api::AddressBytecodeFinal { address })
+        }
+        ("address_bytecode_candidate", None) => {
+            RequestItem::AddressBytecodeCandidate(grpc_api::AddressBytecodeCandidate { address })
+        }
+        ("address_datastore_keys_final", prefix) => {
+            RequestItem::AddressDatastoreKeysFinal(grpc_api::AddressDatastoreKeysFinal {
+                address,
+                prefix: prefix.unwrap_or_default(),
+            })
+        }
+        ("address_datastore_keys_candidate", prefix) => {
+            RequestItem::AddressDatastoreKeysCandidate(grpc_api::AddressDatastoreKeysCandidate {
+                address,
+                prefix: prefix.unwrap_or_default(),
+            })
+        }
+        ("address_datastore_value_final", Some(key)) => {
+            RequestItem::AddressDatastoreValueFinal(grpc_api::AddressDatastoreValueFinal {
+                address,
+                key,
+            })
+        }
+        ("address_datastore_value_candidate", Some(key)) => {
+            RequestItem::AddressDatastoreValueCandidate(grpc_api::AddressDatastoreValueCandidate {
+                address,
+                key,
+            })
+        }
+        ("address_rolls_final", None) => {
+            RequestItem::AddressRollsFinal(grpc_api::AddressRollsFinal { address })
+        }
+        ("address_rolls_candidate", None) => {
+            RequestItem::AddressRollsCandidate(grpc_api::AddressRollsCandidate { address })
+        }
+        ("address_deferred_credits_final", None) => {
+            RequestItem::AddressDeferredCreditsFinal(grpc_api::AddressDeferredCreditsFinal {
+                address,
+            })
+        }
+        ("address_deferred_credits_candidate", None) => {
+            RequestItem::AddressDeferredCreditsCandidate(
+                grpc_api::AddressDeferredCreditsCandidate { address },
+            )
+        }
+        _ => bail!(
+            "invalid query: {}, type \"help query_state\" to get the list of valid queries",
+            query
+        ),
+    };
+    Ok(grpc_api::ExecutionQueryRequestItem {
+        request_item: Some(request_item),
+    })
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
@@ -1,20 +1,29 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::cmds::ExtendedWallet;
+use crate::cmds::{ExtendedWallet, WalletMnemonic};
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::{AddressDrawKind, AddressInfo, AddressNextDraws},
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::ExecuteReadOnlyResponse,
+    node::NodeStatus,
     operation::OperationInfo,
 };
 use massa_models::composite::PubkeySig;
+use massa_models::config::THREAD_COUNT;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
+use massa_sdk::{
+    AddressBalances, BlockOverview, MipStatus, OperationOverview, OperationWaitOutcome,
+    OperationWaitStatus, PublicStatus, QueryStateResult, SlotDraws,
+};
 use massa_signature::{KeyPair, PublicKey};
-use massa_wallet::Wallet;
+use massa_wallet::{DerivedAddress, Wallet};
 use std::net::IpAddr;
 use std::str;
 
@@ -120,6 +129,16 @@ impl dyn Output {
         self.erased_serialize(&mut format)?;
         Ok(())
     }
+
+    pub(crate) fn to_json_value(&self) -> anyhow::Result<serde_json::Value> {
+        let mut bytes = Vec::new();
+        {
+            let json = &mut serde_json::Serializer::new(&mut bytes);
+            let mut format: Box<dyn Serializer> = Box::new(<dyn Serializer>::erase(json));
+            self.erased_serialize(&mut format)?;
+        }
+        Ok(serde_json::from_slice(&bytes)?)
+    }
 }
 
 impl Output for Wallet {
@@ -135,17 +154,26 @@ impl Output for ExtendedWallet {
         }
         println!("{}", Style::Separator.style("====="));
         for entry in self.0.values() {
+            if let Some(label) = &entry.label {
+                println!("Label: {}", Style::Id.style(label));
+            }
             if entry.show_keys {
-                println!("Secret key: {}", Style::Secret.style(&entry.keypair));
-                println!(
-                    "Public key: {}",
-                    Style::Wallet.style(entry.keypair.get_public_key())
-                );
+                if let Some(keypair) = &entry.keypair {
+                    println!("Secret key: {}", Style::Secret.style(keypair));
+                }
+                if let Some(public_key) = &entry.public_key {
+                    println!("Public key: {}", Style::Wallet.style(public_key));
+                }
             }
             println!(
-                "Address: {} (thread {}):",
+                "Address: {} (thread {}){}:",
                 Style::Wallet.style(entry.address_info.address),
                 Style::Protocol.style(entry.address_info.thread),
+                if entry.keypair.is_none() {
+                    Style::Unknown.style(" watch-only").to_string()
+                } else {
+                    String::new()
+                },
             );
             println!(
                 "\tBalance: {}={}, {}={}",
@@ -168,6 +196,26 @@ impl Output for ExtendedWallet {
     }
 }
 
+impl Output for Vec<DerivedAddress> {
+    fn pretty_print(&self) {
+        for derived in self {
+            println!(
+                "Address: {} (thread {}), derivation path {}",
+                Style::Wallet.style(derived.address),
+                Style::Protocol.style(derived.address.get_thread(THREAD_COUNT)),
+                Style::Id.style(&derived.path)
+            );
+        }
+    }
+}
+
+impl Output for WalletMnemonic {
+    fn pretty_print(&self) {
+        println!("Mnemonic: {}", Style::Secret.style(&self.mnemonic));
+        self.derived_addresses.pretty_print();
+    }
+}
+
 impl Output for Vec<(Address, PublicKey)> {
     fn pretty_print(&self) {
         match self.len() {
@@ -598,3 +646,237 @@ impl Output for ExecuteReadOnlyResponse {
         println!("{}", self);
     }
 }
+
+impl Output for Vec<SlotDraws> {
+    fn pretty_print(&self) {
+        for draws in self {
+            println!("Slot: {}", Style::Protocol.style(draws.slot));
+            match draws.block_producer {
+                Some(producer) => println!("\tBlock producer: {}", Style::Wallet.style(producer)),
+                None => println!("\tBlock producer: {}", Style::Unknown.style("not drawn")),
+            }
+            for (index, producer) in draws.endorsement_producers.iter().enumerate() {
+                println!(
+                    "\tEndorsement {} producer: {}",
+                    index,
+                    Style::Wallet.style(producer)
+                );
+            }
+        }
+    }
+}
+
+impl Output for Vec<AddressNextDraws> {
+    fn pretty_print(&self) {
+        for address_draws in self {
+            println!("{}", Style::Separator.style("========"));
+            println!("Address {}:", Style::Wallet.style(address_draws.address));
+            if address_draws.draws.is_empty() {
+                println!("\t{}", Style::Unknown.style("no upcoming draws"));
+            }
+            for draw in &address_draws.draws {
+                let kind = match draw.kind {
+                    AddressDrawKind::Block => Style::Block.style("block"),
+                    AddressDrawKind::Endorsement { index } => {
+                        Style::Id.style(format!("endorsement {}", index))
+                    }
+                };
+                println!(
+                    "\tSlot {} at {}: {}",
+                    Style::Protocol.style(draw.slot),
+                    Style::Time.style(draw.timestamp.format_instant()),
+                    kind
+                );
+            }
+        }
+    }
+}
+
+impl Output for QueryStateResult {
+    fn pretty_print(&self) {
+        if let Some(final_cursor) = self.final_cursor {
+            println!("Final cursor: {}", Style::Finished.style(final_cursor));
+        }
+        if let Some(candidate_cursor) = self.candidate_cursor {
+            println!(
+                "Candidate cursor: {}",
+                Style::Pending.style(candidate_cursor)
+            );
+        }
+        println!(
+            "Final state fingerprint: {}",
+            Style::Id.style(&self.final_state_fingerprint)
+        );
+        for (index, response) in self.responses.iter().enumerate() {
+            match response {
+                Ok(value) => println!("Query {}: {}", index, value),
+                Err(e) => println!("Query {}: {}", index, Style::Bad.style(e)),
+            }
+        }
+    }
+}
+
+impl Output for Vec<MipStatus> {
+    fn pretty_print(&self) {
+        for mip in self {
+            println!(
+                "{} (version {}): {}",
+                Style::Id.style(&mip.name),
+                mip.version,
+                Style::Protocol.style(&mip.state)
+            );
+            for (component, version) in &mip.components {
+                println!("\t{} version {}", component, version);
+            }
+            println!(
+                "\tVote from {} to {}, activation delay {}",
+                Style::Time.style(mip.start.format_instant()),
+                Style::Time.style(mip.timeout.format_instant()),
+                Style::Time.style(mip.activation_delay.format_duration().unwrap_or_default())
+            );
+        }
+    }
+}
+
+impl Output for PublicStatus {
+    fn pretty_print(&self) {
+        println!("Node's ID: {}", Style::Id.style(&self.node_id));
+        println!("Version: {}", Style::Id.style(&self.version));
+        self.config.pretty_print();
+        println!();
+
+        println!("Current time: {}", self.current_time.format_instant());
+        println!(
+            "Current cycle: {} (started at {}, next one at {})",
+            Style::Protocol.style(self.current_cycle),
+            Style::Time.style(self.current_cycle_time.format_instant()),
+            Style::Time.style(self.next_cycle_time.format_instant())
+        );
+        if let Some(slot) = self.last_executed_final_slot {
+            println!("Last executed final slot: {}", Style::Finished.style(slot));
+        }
+        if let Some(slot) = self.last_executed_candidate_slot {
+            println!(
+                "Last executed candidate slot: {}",
+                Style::Pending.style(slot)
+            );
+        }
+        println!(
+            "Final state fingerprint: {}",
+            Style::Id.style(&self.final_state_fingerprint)
+        );
+        println!();
+        println!("Chain id: {}", self.chain_id);
+    }
+}
+
+impl Output for Vec<BlockOverview> {
+    fn pretty_print(&self) {
+        for block in self {
+            println!("{}", Style::Separator.style("========"));
+            println!(
+                "Block {} ({}):",
+                Style::Block.style(block.id),
+                Style::Protocol.style(&block.status)
+            );
+            println!("\tSlot: {}", Style::Protocol.style(block.slot));
+            println!("\tCreator: {}", Style::Wallet.style(block.creator_address));
+            println!("\tParents:");
+            for parent in &block.parents {
+                println!("\t\t{}", Style::Block.style(parent));
+            }
+            println!(
+                "\tEndorsements: {}",
+                Style::Protocol.style(block.endorsement_count)
+            );
+            println!("\tOperations:");
+            for operation_id in &block.operations {
+                println!("\t\t{}", Style::Id.style(operation_id));
+            }
+        }
+    }
+}
+
+impl Output for Vec<OperationOverview> {
+    fn pretty_print(&self) {
+        for operation in self {
+            println!("{}", Style::Separator.style("========"));
+            println!(
+                "Operation {} (thread {}):",
+                Style::Id.style(operation.id),
+                Style::Protocol.style(operation.thread)
+            );
+            println!(
+                "\tCreator: {}",
+                Style::Wallet.style(operation.creator_address)
+            );
+            println!("\tType: {}", Style::Protocol.style(&operation.op_type));
+            println!("\tFee: {}", Style::Coins.style(operation.fee));
+            println!(
+                "\tExpire period: {}",
+                Style::Protocol.style(operation.expire_period)
+            );
+            if operation.block_ids.is_empty() {
+                println!("\t{}", Style::Pending.style("Not included in any block"));
+            } else {
+                println!("\tIn blocks:");
+                for block_id in &operation.block_ids {
+                    println!("\t\t{}", Style::Block.style(block_id));
+                }
+            }
+        }
+    }
+}
+
+impl Output for Vec<AddressBalances> {
+    fn pretty_print(&self) {
+        for balances in self {
+            println!("{}", Style::Separator.style("========"));
+            println!("Address {}:", Style::Wallet.style(balances.address));
+            println!(
+                "\tBalance: {}={}, {}={}",
+                Style::Finished.style("final"),
+                Style::Coins.style(balances.final_balance),
+                Style::Pending.style("candidate"),
+                Style::Coins.style(balances.candidate_balance),
+            );
+            println!(
+                "\tRolls: {}={}, {}={}",
+                Style::Finished.style("final"),
+                Style::Protocol.style(balances.final_roll_count),
+                Style::Pending.style("candidate"),
+                Style::Protocol.style(balances.candidate_roll_count),
+            );
+        }
+    }
+}
+
+impl Output for Vec<OperationWaitOutcome> {
+    fn pretty_print(&self) {
+        for outcome in self {
+            let status = match outcome.status {
+                OperationWaitStatus::Included => Style::Pending.style("included"),
+                OperationWaitStatus::Final => Style::Finished.style("final"),
+                OperationWaitStatus::Expired => Style::Bad.style("expired"),
+                OperationWaitStatus::Pending => Style::Unknown.style("still pending"),
+            };
+            let execution = match outcome.execution_success {
+                Some(true) => Style::Good.style("execution succeeded"),
+                Some(false) => Style::Bad.style("execution failed"),
+                None => Style::Unknown.style("execution status unknown"),
+            };
+            println!(
+                "Operation {}: {}, {}",
+                Style::Id.style(&outcome.operation_id),
+                status,
+                execution
+            );
+            for block_id in &outcome.in_blocks {
+                println!("\tIn block {}", Style::Block.style(block_id));
+            }
+            for event in &outcome.events {
+                println!("{}", event);
+            }
+        }
+    }
+}
@@ -9,7 +9,7 @@ use cmds::Command;
 use console::style;
 use dialoguer::Password;
 use is_terminal::IsTerminal;
-use massa_sdk::{Client, ClientConfig, HttpConfig};
+use massa_sdk::{Client, ClientConfig, GrpcConfig, GrpcTlsConfig, HttpConfig};
 use massa_wallet::Wallet;
 use serde::Serialize;
 use std::env;
@@ -20,6 +20,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 mod cmds;
 mod display;
 mod repl;
+mod script;
 mod settings;
 
 #[cfg(test)]
@@ -58,6 +59,12 @@ struct Args {
     /// Enable a mode where input/output are serialized as JSON
     #[arg(short = 'j', long = "json")]
     json: bool,
+    /// Send the commands that have a gRPC equivalent through the gRPC APIs
+    #[arg(long = "grpc")]
+    grpc: bool,
+    /// Wait for the operations sent by a command to be final, and print their execution status and events
+    #[arg(long = "wait")]
+    wait: bool,
     #[arg(short = 'p', long = "pwd")]
     /// Wallet password
     password: Option<String>,
@@ -85,6 +92,22 @@ pub(crate) fn ask_password(wallet_path: &Path) -> String {
     }
 }
 
+/// Ask for the password of a wallet export file, unless `MASSA_CLIENT_EXPORT_PASSWORD` is set
+/// When exporting, the password has to be confirmed
+pub(crate) fn ask_export_password(confirm: bool) -> String {
+    if let Ok(password) = env::var("MASSA_CLIENT_EXPORT_PASSWORD") {
+        return password;
+    }
+    let prompt = Password::new().with_prompt("Enter export file password");
+    if confirm {
+        prompt.with_confirmation("Confirm password", "Passwords mismatching")
+    } else {
+        prompt
+    }
+    .interact()
+    .expect("IO error: Password reading failed")
+}
+
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     let tokio_rt = tokio::runtime::Builder::new_multi_thread()
@@ -116,6 +139,30 @@ async fn run(args: Args) -> Result<()> {
         enabled: SETTINGS.client.http.enabled,
     };
 
+    let grpc_tls_config = |tls: &settings::GrpcTlsSettings| GrpcTlsConfig {
+        domain_name: tls.domain_name.clone(),
+        certificate_authority_root_path: tls.certificate_authority_root_path.clone(),
+        client_certificate_path: tls.client_certificate_path.clone(),
+        client_private_key_path: tls.client_private_key_path.clone(),
+    };
+    let grpc_config = GrpcConfig {
+        request_timeout: SETTINGS.client.request_timeout,
+        headers: SETTINGS.client.headers.clone(),
+        public_tls: SETTINGS
+            .client
+            .grpc
+            .public_tls
+            .as_ref()
+            .map(grpc_tls_config),
+        private_tls: SETTINGS
+            .client
+            .grpc
+            .private_tls
+            .as_ref()
+            .map(grpc_tls_config),
+    };
+    let grpc = args.grpc || SETTINGS.client.grpc.enabled;
+
     // TODO: move settings loading in another crate ... see #1277
     let settings = SETTINGS.clone();
 
@@ -166,14 +213,29 @@ async fn run(args: Args) -> Result<()> {
         grpc_priv_port,
         chain_id,
         &http_config,
+        &grpc_config,
     )
     .await?;
     if std::io::stdout().is_terminal() && args.command == Command::help && !args.json {
         // Interactive mode
-        repl::run(&mut client, &args.wallet, args.password).await?;
+        repl::run(&mut client, &args.wallet, args.password, grpc, args.wait).await?;
     } else {
         // Non-Interactive mode
 
+        // The script prints the outputs of its commands and loads the wallet if they need it
+        if args.command == Command::run_script {
+            return script::run(
+                &mut client,
+                &args.wallet,
+                args.password,
+                &mut None,
+                &args.parameters,
+                args.json,
+                grpc,
+            )
+            .await;
+        }
+
         // Only prompt for password if the command needs wallet access.
         let mut wallet_opt = match args.command.is_pwd_needed() {
             true => {
@@ -191,7 +253,14 @@ async fn run(args: Args) -> Result<()> {
 
         match args
             .command
-            .run(&mut client, &mut wallet_opt, &args.parameters, args.json)
+            .run(
+                &mut client,
+                &mut wallet_opt,
+                &args.parameters,
+                args.json,
+                grpc,
+                args.wait,
+            )
             .await
         {
             Ok(output) => {
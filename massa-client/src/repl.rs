@@ -3,6 +3,7 @@
 use crate::ask_password;
 use crate::cmds::Command;
 use crate::massa_fancy_ascii_art_logo;
+use crate::script;
 use crate::settings::SETTINGS;
 use anyhow::Result;
 use console::style;
@@ -18,7 +19,7 @@ use std::path::Path;
 use strum::IntoEnumIterator;
 use strum::ParseError;
 
-fn group_parameters(parameters: Vec<String>) -> Vec<String> {
+pub(crate) fn group_parameters(parameters: Vec<String>) -> Vec<String> {
     let mut new_parameters = Vec::new();
     let mut has_opening_simple_quote = false;
     let mut temp_simple_quote = String::new();
@@ -80,6 +81,8 @@ pub(crate) async fn run(
     client: &mut Client,
     wallet_path: &Path,
     args_password: Option<String>,
+    grpc: bool,
+    wait: bool,
 ) -> Result<()> {
     massa_fancy_ascii_art_logo!();
     println!("Use 'exit' or 'CTRL+D or CTRL+C' to quit the prompt");
@@ -125,6 +128,21 @@ pub(crate) async fn run(
                 let parameters = input[1..].to_vec();
                 // Print result of evaluated command
                 match cmd {
+                    Ok(Command::run_script) => {
+                        if let Err(e) = script::run(
+                            client,
+                            wallet_path,
+                            args_password.clone(),
+                            &mut wallet_opt,
+                            &parameters,
+                            false,
+                            grpc,
+                        )
+                        .await
+                        {
+                            println!("{}", style(format!("Error: {}", e)).red())
+                        }
+                    }
                     Ok(command) => {
                         // Check if we need to prompt the user for their wallet password
                         if command.is_pwd_needed() && wallet_opt.is_none() {
@@ -150,7 +168,7 @@ pub(crate) async fn run(
                         }
 
                         match command
-                            .run(client, &mut wallet_opt, &parameters, false)
+                            .run(client, &mut wallet_opt, &parameters, false, grpc, wait)
                             .await
                         {
                             Ok(output) => output.pretty_print(),
@@ -0,0 +1,398 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Batch mode: run a script file of client commands.
+//!
+//! A script has one command per line, written as in the interactive mode.
+//! Blank lines and lines starting with `#` are ignored. On top of the client commands, a script can use:
+//! - `let name = command args...` to store the output of a command in a variable. An output that is a string,
+//!   or a list of one string like the operation id returned by `send_transaction`, is stored as this string.
+//!   Other outputs are stored as their JSON text.
+//! - `$name` or `${name}` in the arguments of a command, replaced by the value of the variable.
+//! - `wait_included [timeout=Millis] OperationId1 OperationId2 ...` to wait until operations are in a block.
+//! - `wait_final [timeout=Millis] OperationId1 OperationId2 ...` to wait until operations are final,
+//!   failing if one of them expired or was not executed successfully.
+//! - `sleep Millis` to pause the script.
+//!
+//! The script stops at the first failing command, and the client then exits with a non-zero code.
+
+use crate::ask_password;
+use crate::cmds::{parse_vec, Command};
+use crate::display::Output;
+use crate::repl::group_parameters;
+use anyhow::{anyhow, bail, Result};
+use console::style;
+use massa_models::operation::OperationId;
+use massa_sdk::{Client, OperationWaitOutcome, OperationWaitStatus, OperationWaitTarget};
+use massa_time::MassaTime;
+use massa_wallet::Wallet;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+/// Default timeout of `wait_included` and `wait_final`, in milliseconds
+const DEFAULT_WAIT_TIMEOUT: u64 = 120_000;
+
+/// Interval between two checks of the operations awaited by `wait_included` and `wait_final`
+const WAIT_POLL_INTERVAL: MassaTime = MassaTime::from_millis(1000);
+
+/// What a script line does
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// run a client command
+    Command(Command),
+    /// wait until operations are in a block
+    WaitIncluded,
+    /// wait until operations are final
+    WaitFinal,
+    /// pause the script
+    Sleep,
+}
+
+/// A line of a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScriptLine {
+    /// line number in the script file, starting at 1
+    number: usize,
+    /// variable receiving the output of the line
+    variable: Option<String>,
+    /// what the line does
+    step: Step,
+    /// parameters of the line, before variable substitution
+    parameters: Vec<String>,
+    /// text of the line
+    text: String,
+}
+
+/// Returns true if `name` can be used as a variable name
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse a script, checking its syntax before anything runs
+fn parse_script(content: &str) -> Result<Vec<ScriptLine>> {
+    let mut lines = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let mut words = group_parameters(text.split_whitespace().map(|x| x.to_string()).collect());
+        let variable = if words[0] == "let" {
+            if words.len() < 4 || words[2] != "=" || !is_variable_name(&words[1]) {
+                bail!("line {}: expected \"let name = command args...\"", number);
+            }
+            let variable = words[1].clone();
+            words.drain(..3);
+            Some(variable)
+        } else {
+            None
+        };
+        let step = match words[0].as_str() {
+            "wait_included" => Step::WaitIncluded,
+            "wait_final" => Step::WaitFinal,
+            "sleep" => Step::Sleep,
+            name => match name.parse::<Command>() {
+                Ok(Command::run_script) => bail!("line {}: scripts cannot be nested", number),
+                Ok(command) => Step::Command(command),
+                Err(_) => bail!("line {}: unknown command \"{}\"", number, name),
+            },
+        };
+        lines.push(ScriptLine {
+            number,
+            variable,
+            step,
+            parameters: words[1..].to_vec(),
+            text: text.to_string(),
+        });
+    }
+    Ok(lines)
+}
+
+/// Replace the `$name` and `${name}` references of a parameter by the values of the variables
+fn substitute(parameter: &str, variables: &HashMap<String, String>) -> Result<String> {
+    let mut res = String::with_capacity(parameter.len());
+    let mut chars = parameter.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            res.push(c);
+            continue;
+        }
+        let mut name = String::new();
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) => name.push(c),
+                    None => bail!("unclosed variable reference in \"{}\"", parameter),
+                }
+            }
+        } else {
+            while let Some(c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || *c == '_') {
+                    break;
+                }
+                name.push(*c);
+                chars.next();
+            }
+            if name.is_empty() {
+                res.push('$');
+                continue;
+            }
+        }
+        match variables.get(&name) {
+            Some(value) => res.push_str(value),
+            None => bail!("unknown variable \"{}\"", name),
+        }
+    }
+    Ok(res)
+}
+
+/// Value stored in a variable for the JSON output of a command
+fn variable_value(output: &Value) -> String {
+    match output {
+        Value::String(value) => value.clone(),
+        Value::Array(items) if items.len() == 1 && items[0].is_string() => {
+            variable_value(&items[0])
+        }
+        _ => output.to_string(),
+    }
+}
+
+/// Parse the `[timeout=Millis] OperationId1 OperationId2 ...` parameters of the wait steps
+fn parse_wait_parameters(parameters: &[String]) -> Result<(u64, Vec<OperationId>)> {
+    let (timeout, ids) = match parameters.first().and_then(|p| p.strip_prefix("timeout=")) {
+        Some(timeout) => (
+            timeout
+                .parse::<u64>()
+                .map_err(|e| anyhow!("invalid timeout \"{}\": {}", timeout, e))?,
+            &parameters[1..],
+        ),
+        None => (DEFAULT_WAIT_TIMEOUT, parameters),
+    };
+    let ids = parse_vec::<OperationId>(ids)?;
+    if ids.is_empty() {
+        bail!("wrong number of parameters");
+    }
+    Ok((timeout, ids))
+}
+
+/// Wait until all the given operations are in a block, or final if `wait_final` is set
+async fn wait_operations(
+    client: &mut Client,
+    parameters: &[String],
+    wait_final: bool,
+) -> Result<Vec<OperationWaitOutcome>> {
+    let (timeout, ids) = parse_wait_parameters(parameters)?;
+    let (target, expected) = if wait_final {
+        (OperationWaitTarget::Final, "final")
+    } else {
+        (OperationWaitTarget::Included, "included")
+    };
+    let deadline = MassaTime::now().saturating_add(MassaTime::from_millis(timeout));
+    let mut outcomes = Vec::with_capacity(ids.len());
+    for id in ids {
+        let outcome = client
+            .wait_for_operation(
+                id,
+                None,
+                target,
+                WAIT_POLL_INTERVAL,
+                Some(deadline.saturating_sub(MassaTime::now())),
+            )
+            .await
+            .map_err(|e| anyhow!("check if your node is running: {}", e))?;
+        match outcome.status {
+            OperationWaitStatus::Pending => {
+                bail!(
+                    "timeout while waiting for operation {} to be {}",
+                    id,
+                    expected
+                )
+            }
+            OperationWaitStatus::Expired => bail!("operation {} expired", id),
+            OperationWaitStatus::Included | OperationWaitStatus::Final => {}
+        }
+        if wait_final && outcome.execution_success == Some(false) {
+            bail!("operation {} was not executed successfully", id);
+        }
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+/// Run a script file of client commands
+///
+/// # parameters
+/// - client: the RPC client
+/// - wallet_path, args_password: used to open the wallet when a command of the script needs it
+/// - wallet_opt: the wallet, if already open
+/// - parameters: the parameters of `run_script`, that is the path of the script
+/// - json: print the outputs of the script as JSON lines
+/// - grpc: true if the commands that have a gRPC equivalent must use the gRPC APIs
+pub(crate) async fn run(
+    client: &mut Client,
+    wallet_path: &Path,
+    args_password: Option<String>,
+    wallet_opt: &mut Option<Wallet>,
+    parameters: &[String],
+    json: bool,
+    grpc: bool,
+) -> Result<()> {
+    if parameters.len() != 1 {
+        bail!("wrong number of parameters");
+    }
+    let content = tokio::fs::read_to_string(&parameters[0])
+        .await
+        .map_err(|e| anyhow!("could not read script {}: {}", parameters[0], e))?;
+    let lines = parse_script(&content)?;
+
+    let mut variables = HashMap::new();
+    for line in lines {
+        if !json {
+            println!("{} {}", style(">").color256(8), line.text);
+        }
+        let res = run_line(
+            client,
+            wallet_path,
+            &args_password,
+            wallet_opt,
+            &line,
+            &variables,
+            json,
+            grpc,
+        )
+        .await
+        .and_then(|output| Ok((output.to_json_value()?, output)));
+        match res {
+            Ok((value, output)) => {
+                if json {
+                    println!(
+                        "{}",
+                        json!({"line": line.number, "command": line.text, "output": value})
+                    );
+                } else {
+                    output.pretty_print();
+                }
+                if let Some(variable) = line.variable {
+                    variables.insert(variable, variable_value(&value));
+                }
+            }
+            Err(e) => {
+                if json {
+                    println!(
+                        "{}",
+                        json!({"line": line.number, "command": line.text, "error": format!("{:?}", e)})
+                    );
+                }
+                bail!("line {}: {}", line.number, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run a line of a script
+#[allow(clippy::too_many_arguments)]
+async fn run_line(
+    client: &mut Client,
+    wallet_path: &Path,
+    args_password: &Option<String>,
+    wallet_opt: &mut Option<Wallet>,
+    line: &ScriptLine,
+    variables: &HashMap<String, String>,
+    json: bool,
+    grpc: bool,
+) -> Result<Box<dyn Output>> {
+    let parameters = line
+        .parameters
+        .iter()
+        .map(|parameter| substitute(parameter, variables))
+        .collect::<Result<Vec<_>>>()?;
+    match &line.step {
+        Step::Command(command) => {
+            if command.is_pwd_needed() && wallet_opt.is_none() {
+                let password = match (args_password.clone(), env::var("MASSA_CLIENT_PASSWORD")) {
+                    (Some(pwd), _) => pwd,
+                    (_, Ok(pwd)) => pwd,
+                    _ => ask_password(wallet_path),
+                };
+                *wallet_opt = Some(Wallet::new(
+                    wallet_path.to_path_buf(),
+                    password,
+                    client.chain_id,
+                )?);
+            }
+            // the outputs of the commands sending operations stay their ids, for the script variables
+            command
+                .run(client, wallet_opt, &parameters, json, grpc, false)
+                .await
+        }
+        Step::WaitIncluded => Ok(Box::new(wait_operations(client, &parameters, false).await?)),
+        Step::WaitFinal => Ok(Box::new(wait_operations(client, &parameters, true).await?)),
+        Step::Sleep => {
+            let [millis] = parameters.as_slice() else {
+                bail!("wrong number of parameters");
+            };
+            let millis = millis
+                .parse()
+                .map_err(|e| anyhow!("invalid duration \"{}\": {}", millis, e))?;
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            Ok(Box::new(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let lines = parse_script(
+            "# send coins\n\nlet op = send_transaction AU1a AU1b 1 0.01\nwait_final timeout=1000 $op\n",
+        )
+        .unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].number, 3);
+        assert_eq!(lines[0].variable.as_deref(), Some("op"));
+        assert_eq!(lines[0].step, Step::Command(Command::send_transaction));
+        assert_eq!(lines[0].parameters, ["AU1a", "AU1b", "1", "0.01"]);
+        assert_eq!(lines[1].step, Step::WaitFinal);
+
+        assert!(parse_script("let op send_transaction").is_err());
+        assert!(parse_script("unknown_command").is_err());
+        assert!(parse_script("run_script other.txt").is_err());
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = HashMap::from([("op".to_string(), "O1abc".to_string())]);
+        assert_eq!(substitute("$op", &variables).unwrap(), "O1abc");
+        assert_eq!(substitute("id=${op}_x", &variables).unwrap(), "id=O1abc_x");
+        assert_eq!(substitute("5$", &variables).unwrap(), "5$");
+        assert!(substitute("$other", &variables).is_err());
+        assert!(substitute("${op", &variables).is_err());
+
+        assert_eq!(variable_value(&json!(["O1abc"])), "O1abc");
+        assert_eq!(variable_value(&json!({"a": 1})), "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_parse_wait_parameters() {
+        let id = "O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC".to_string();
+        let (timeout, ids) =
+            parse_wait_parameters(&["timeout=1000".to_string(), id.clone()]).unwrap();
+        assert_eq!(timeout, 1000);
+        assert_eq!(ids, [id.parse::<OperationId>().unwrap()]);
+        let (timeout, _) = parse_wait_parameters(&[id.clone()]).unwrap();
+        assert_eq!(timeout, DEFAULT_WAIT_TIMEOUT);
+
+        let err = parse_wait_parameters(&["timeout=1s".to_string(), id]).unwrap_err();
+        assert!(err.to_string().starts_with("invalid timeout \"1s\""));
+        assert!(parse_wait_parameters(&["timeout=1000".to_string()]).is_err());
+    }
+}
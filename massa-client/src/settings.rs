@@ -41,6 +41,7 @@ pub struct ClientSettings {
     pub max_log_length: u32,
     pub headers: Vec<(String, String)>,
     pub http: HttpSettings,
+    pub grpc: GrpcSettings,
 }
 
 ///TODO add WebSocket to CLI
@@ -51,6 +52,27 @@ pub struct HttpSettings {
     pub enabled: bool,
 }
 
+/// gRPC client settings.
+/// the gRPC client settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct GrpcSettings {
+    /// whether to send the commands that have a gRPC equivalent through the gRPC APIs.
+    pub enabled: bool,
+    /// TLS settings of the public API connection, in plain text if not set.
+    pub public_tls: Option<GrpcTlsSettings>,
+    /// TLS settings of the private API connection, in plain text if not set.
+    pub private_tls: Option<GrpcTlsSettings>,
+}
+
+/// gRPC client TLS settings.
+#[derive(Debug, Deserialize, Clone)]
+pub struct GrpcTlsSettings {
+    pub domain_name: String,
+    pub certificate_authority_root_path: PathBuf,
+    pub client_certificate_path: Option<PathBuf>,
+    pub client_private_key_path: Option<PathBuf>,
+}
+
 #[cfg(test)]
 #[test]
 fn test_load_client_config() {
@@ -0,0 +1,109 @@
+//! Final blocks kept in the block archive after being pruned from the graph
+
+use massa_models::{
+    block::{Block, BlockDeserializer, BlockDeserializerArgs, SecureShareBlock},
+    block_id::BlockId,
+    operation::{OperationsDeserializer, OperationsSerializer, SecureShareOperation},
+    secure_share::{SecureShareDeserializer, SecureShareSerializer},
+};
+use massa_serialization::{Deserializer, SerializeError, Serializer};
+use nom::{
+    error::{context, ContextError, ParseError},
+    sequence::tuple,
+    IResult, Parser,
+};
+use serde::{Deserialize, Serialize};
+
+/// A final block read from the block archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedBlock {
+    /// The block, including its header and endorsements
+    pub block: SecureShareBlock,
+    /// The operations included in the block, in block order
+    pub operations: Vec<SecureShareOperation>,
+}
+
+/// Serializer of `ArchivedBlock`.
+/// The block and its operations are stored as signed, so that they can be verified again once read back.
+#[derive(Default)]
+pub struct ArchivedBlockSerializer {
+    sec_share_serializer: SecureShareSerializer,
+    operations_serializer: OperationsSerializer,
+}
+
+impl ArchivedBlockSerializer {
+    /// Create a new `ArchivedBlockSerializer`
+    pub fn new() -> Self {
+        ArchivedBlockSerializer {
+            sec_share_serializer: SecureShareSerializer::new(),
+            operations_serializer: OperationsSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<ArchivedBlock> for ArchivedBlockSerializer {
+    fn serialize(&self, value: &ArchivedBlock, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.sec_share_serializer.serialize(&value.block, buffer)?;
+        self.operations_serializer
+            .serialize(&value.operations, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer of `ArchivedBlock`
+pub struct ArchivedBlockDeserializer {
+    sec_share_block_deserializer: SecureShareDeserializer<Block, BlockDeserializer>,
+    operations_deserializer: OperationsDeserializer,
+}
+
+impl ArchivedBlockDeserializer {
+    /// Create a new `ArchivedBlockDeserializer`
+    ///
+    /// # Arguments
+    /// * `block_der_args`: limits of the archived blocks
+    /// * `operations_deserializer`: deserializer of the operations of a block,
+    ///   bounded by the maximum number of operations in a block
+    pub fn new(
+        block_der_args: BlockDeserializerArgs,
+        operations_deserializer: OperationsDeserializer,
+    ) -> Self {
+        let chain_id = block_der_args.chain_id;
+        ArchivedBlockDeserializer {
+            sec_share_block_deserializer: SecureShareDeserializer::new(
+                BlockDeserializer::new(block_der_args),
+                chain_id,
+            ),
+            operations_deserializer,
+        }
+    }
+}
+
+impl Deserializer<ArchivedBlock> for ArchivedBlockDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], ArchivedBlock, E> {
+        context(
+            "Failed ArchivedBlock deserialization",
+            tuple((
+                context("Failed block deserialization", |input| {
+                    self.sec_share_block_deserializer.deserialize(input)
+                }),
+                context("Failed operations deserialization", |input| {
+                    self.operations_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(|(block, operations)| ArchivedBlock { block, operations })
+        .parse(buffer)
+    }
+}
+
+/// A final operation read from the block archive
+#[derive(Debug, Clone)]
+pub struct ArchivedOperation {
+    /// The operation
+    pub operation: SecureShareOperation,
+    /// The id of the archived block that includes it
+    pub block_id: BlockId,
+}
@@ -0,0 +1,31 @@
+//! Changes of the block graph broadcast to the clients that follow candidate blocks
+
+use massa_models::{block_id::BlockId, slot::Slot};
+use serde::{Deserialize, Serialize};
+
+/// A change of the status of blocks in the graph.
+/// Clients that applied the effects of candidate blocks can use them to roll back the blocks that were dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockGraphEvent {
+    /// A block was discarded because it is incompatible with the final blocks
+    BlockBecameStale {
+        /// id of the block
+        block_id: BlockId,
+        /// slot of the block
+        slot: Slot,
+    },
+    /// A block became final
+    BlockBecameFinal {
+        /// id of the block
+        block_id: BlockId,
+        /// slot of the block
+        slot: Slot,
+    },
+    /// The blockclique switched. Blocks that left it by becoming final are not listed as removed.
+    BlockcliqueChanged {
+        /// blocks that entered the blockclique
+        added: Vec<BlockId>,
+        /// blocks that left the blockclique without becoming final
+        removed: Vec<BlockId>,
+    },
+}
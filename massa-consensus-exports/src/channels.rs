@@ -8,6 +8,7 @@ use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolController;
 
+use crate::block_graph_event::BlockGraphEvent;
 use crate::events::ConsensusEvent;
 
 /// Contains links to other modules of the node to be able to interact with them.
@@ -36,4 +37,6 @@ pub struct ConsensusBroadcasts {
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    /// Channel used for Websocket broadcast (if enabled) of blocks becoming stale or final and of blockclique changes
+    pub block_graph_event_sender: tokio::sync::broadcast::Sender<BlockGraphEvent>,
 }
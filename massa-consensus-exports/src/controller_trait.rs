@@ -1,10 +1,12 @@
+use crate::archived_block::{ArchivedBlock, ArchivedOperation};
 use crate::block_graph_export::BlockGraphExport;
 use crate::{bootstrapable_graph::BootstrapableGraph, error::ConsensusError};
 use massa_models::prehash::PreHashSet;
 use massa_models::streaming_step::StreamingStep;
 use massa_models::{
-    block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId, clique::Clique,
-    secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
+    address::Address, amount::Amount, block::BlockGraphStatus, block_header::BlockHeader,
+    block_id::BlockId, clique::Clique, operation::OperationId, secure_share::SecureShare,
+    slot::Slot, stats::ConsensusStats,
 };
 use massa_storage::Storage;
 
@@ -89,6 +91,12 @@ pub trait ConsensusController: Send + Sync {
     /// The block id of the block at the specified slot if exists
     fn get_blockclique_block_at_slot(&self, slot: Slot) -> Option<BlockId>;
 
+    /// Get the fees of the operations included in the latest final blocks
+    ///
+    /// # Returns
+    /// The fees of the operations of the final blocks of the last `fee_estimation_final_periods` periods, unsorted
+    fn get_recent_final_fees(&self) -> Vec<Amount>;
+
     /// Get the latest block, that is in the blockclique, in the thread of the given slot and before this `slot`.
     ///
     /// # Arguments:
@@ -98,6 +106,45 @@ pub trait ConsensusController: Send + Sync {
     /// The block id of the latest block in the thread of the given slot and before this slot
     fn get_latest_blockclique_block_at_slot(&self, slot: Slot) -> BlockId;
 
+    /// Get final blocks from the block archive
+    ///
+    /// # Arguments
+    /// * `ids`: the list of block ids to look for
+    ///
+    /// # Returns
+    /// The archived blocks sorted by the order of the input list, `None` for blocks that are not archived
+    /// or if the archive is disabled
+    fn get_archived_blocks(&self, ids: &[BlockId]) -> Vec<Option<ArchivedBlock>>;
+
+    /// Get final operations from the block archive, along with the id of the block that includes them.
+    /// Each archived block is only read once, however many of the operations it includes.
+    ///
+    /// # Arguments
+    /// * `ids`: the list of operation ids to look for
+    ///
+    /// # Returns
+    /// The archived operations sorted by the order of the input list,
+    /// `None` for operations that are not archived or if the archive is disabled
+    fn get_archived_operations(&self, ids: &[OperationId]) -> Vec<Option<ArchivedOperation>>;
+
+    /// Search the block archive
+    ///
+    /// # Arguments
+    /// * `creator`: only return blocks created by this address, if any
+    /// * `start_slot`: first slot of the search (included)
+    /// * `end_slot`: last slot of the search (excluded)
+    /// * `limit`: maximum number of block ids to return
+    ///
+    /// # Returns
+    /// The ids of the matching archived blocks sorted by slot
+    fn search_archived_blocks(
+        &self,
+        creator: Option<Address>,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Vec<BlockId>;
+
     /// Register a block in the graph
     ///
     /// # Arguments
@@ -5,6 +5,8 @@ mod channels;
 mod controller_trait;
 mod settings;
 
+pub mod archived_block;
+pub mod block_graph_event;
 pub mod block_graph_export;
 pub mod block_status;
 pub mod bootstrapable_graph;
@@ -1,6 +1,7 @@
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConsensusConfig {
@@ -50,8 +51,30 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// block graph events channel capacity
+    pub broadcast_block_graph_events_channel_capacity: usize,
     /// last start period
     pub last_start_period: u64,
     /// chain id
     pub chain_id: u64,
+    /// max operations per block
+    pub max_operations_per_block: u32,
+    /// max denunciations in block header
+    pub max_denunciations_per_block_header: u32,
+    /// max datastore value length
+    pub max_datastore_value_length: u64,
+    /// max function name length
+    pub max_function_name_length: u16,
+    /// max parameters size
+    pub max_parameters_size: u32,
+    /// max op datastore entry count
+    pub max_op_datastore_entry_count: u64,
+    /// max op datastore key length
+    pub max_op_datastore_key_length: u8,
+    /// max op datastore value length
+    pub max_op_datastore_value_length: u64,
+    /// where to archive final blocks, none to disable the block archive
+    pub block_archive_path: Option<PathBuf>,
+    /// number of recent final periods whose blocks' operation fees are kept for fee estimation
+    pub fee_estimation_final_periods: u64,
 }
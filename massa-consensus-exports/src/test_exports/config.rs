@@ -1,7 +1,10 @@
 use massa_models::config::{
     constants::{
         CHANNEL_SIZE, DELTA_F0, ENDORSEMENT_COUNT, GENESIS_KEY, GENESIS_TIMESTAMP,
-        MAX_GAS_PER_BLOCK, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, T0, THREAD_COUNT,
+        MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_FUNCTION_NAME_LENGTH,
+        MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        MAX_PARAMETERS_SIZE, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, T0, THREAD_COUNT,
     },
     CHAINID, CONSENSUS_BOOTSTRAP_PART_SIZE,
 };
@@ -35,8 +38,19 @@ impl Default for ConsensusConfig {
             broadcast_blocks_headers_channel_capacity: 128,
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
+            broadcast_block_graph_events_channel_capacity: 128,
             last_start_period: 0,
             chain_id: *CHAINID,
+            max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+            max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+            max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+            max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+            max_parameters_size: MAX_PARAMETERS_SIZE,
+            max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+            block_archive_path: None,
+            fee_estimation_final_periods: 10,
         }
     }
 }
@@ -0,0 +1,346 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Optional on-disk archive of final blocks.
+//!
+//! Final blocks are pruned from the graph and from storage after `force_keep_final_periods`.
+//! When the archive is enabled, every block is written to it with its operations as it becomes final,
+//! and it is indexed by slot, creator address and operation id so that the API can still serve them.
+
+use massa_consensus_exports::archived_block::{
+    ArchivedBlock, ArchivedBlockDeserializer, ArchivedBlockSerializer, ArchivedOperation,
+};
+use massa_consensus_exports::ConsensusConfig;
+use massa_db_exports::{address_index_prefix, CF_ERROR, CRUD_ERROR, OPEN_ERROR};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::address::Address;
+use massa_models::block::BlockDeserializerArgs;
+use massa_models::block_id::BlockId;
+use massa_models::operation::{OperationId, OperationsDeserializer};
+use massa_models::prehash::PreHashMap;
+use massa_models::secure_share::Id;
+use massa_models::slot::Slot;
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::path::PathBuf;
+use tracing::warn;
+
+const BLOCK_SER_ERROR: &str = "critical: archived block serialization failed";
+
+const BLOCKS_CF: &str = "blocks";
+const SLOT_INDEX_CF: &str = "slot_index";
+const CREATOR_INDEX_CF: &str = "creator_index";
+const OPERATION_INDEX_CF: &str = "operation_index";
+
+/// Reads a block id stored as the value of an index entry
+fn block_id_from_bytes(bytes: &[u8]) -> Option<BlockId> {
+    let hash_bytes: &[u8; HASH_SIZE_BYTES] = bytes.try_into().ok()?;
+    Some(BlockId::new(Hash::from_bytes(hash_bytes)))
+}
+
+/// On-disk store of final blocks
+pub(crate) struct BlockArchive {
+    /// RocksDB database
+    db: DB,
+    /// serializer of the archived blocks
+    block_serializer: ArchivedBlockSerializer,
+    /// deserializer of the archived blocks
+    block_deserializer: ArchivedBlockDeserializer,
+}
+
+impl BlockArchive {
+    /// Open (or create) the block archive
+    ///
+    /// # Arguments
+    /// * path: where to store the db
+    /// * config: consensus configuration, giving the limits of the archived blocks
+    pub fn new(path: PathBuf, config: &ConsensusConfig) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![
+                ColumnFamilyDescriptor::new(BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(SLOT_INDEX_CF, Options::default()),
+                ColumnFamilyDescriptor::new(CREATOR_INDEX_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATION_INDEX_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR);
+        let block_deserializer = ArchivedBlockDeserializer::new(
+            BlockDeserializerArgs {
+                thread_count: config.thread_count,
+                max_operations_per_block: config.max_operations_per_block,
+                endorsement_count: config.endorsement_count,
+                max_denunciations_per_block_header: config.max_denunciations_per_block_header,
+                last_start_period: None,
+                chain_id: config.chain_id,
+            },
+            OperationsDeserializer::new(
+                config.max_operations_per_block,
+                config.max_datastore_value_length,
+                config.max_function_name_length,
+                config.max_parameters_size,
+                config.max_op_datastore_entry_count,
+                config.max_op_datastore_key_length,
+                config.max_op_datastore_value_length,
+                config.chain_id,
+            ),
+        );
+        BlockArchive {
+            db,
+            block_serializer: ArchivedBlockSerializer::new(),
+            block_deserializer,
+        }
+    }
+
+    /// Archive a batch of final blocks along with their index entries
+    pub fn archive_blocks<'a>(&self, blocks: impl IntoIterator<Item = &'a ArchivedBlock>) {
+        let blocks_cf = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        let slot_cf = self.db.cf_handle(SLOT_INDEX_CF).expect(CF_ERROR);
+        let creator_cf = self.db.cf_handle(CREATOR_INDEX_CF).expect(CF_ERROR);
+        let operation_cf = self.db.cf_handle(OPERATION_INDEX_CF).expect(CF_ERROR);
+
+        let mut batch = WriteBatch::default();
+        for archived in blocks {
+            let block_id = archived.block.id.get_hash().to_bytes();
+            let slot_key = archived.block.content.header.content.slot.to_bytes_key();
+            let mut serialized = Vec::new();
+            self.block_serializer
+                .serialize(archived, &mut serialized)
+                .expect(BLOCK_SER_ERROR);
+            batch.put_cf(blocks_cf, block_id, serialized);
+            batch.put_cf(slot_cf, slot_key, block_id);
+            batch.put_cf(
+                creator_cf,
+                [
+                    address_index_prefix(&archived.block.content_creator_address),
+                    slot_key.to_vec(),
+                ]
+                .concat(),
+                block_id,
+            );
+            for operation in &archived.operations {
+                batch.put_cf(operation_cf, operation.id.get_hash().to_bytes(), block_id);
+            }
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Get an archived block
+    pub fn get_block(&self, block_id: &BlockId) -> Option<ArchivedBlock> {
+        let blocks_cf = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        match self
+            .db
+            .get_cf(blocks_cf, block_id.get_hash().to_bytes())
+            .expect(CRUD_ERROR)
+        {
+            Some(value) => match self
+                .block_deserializer
+                .deserialize::<DeserializeError>(&value)
+            {
+                Ok((_, archived)) => Some(archived),
+                Err(err) => {
+                    warn!("could not deserialize archived block {}: {}", block_id, err);
+                    None
+                }
+            },
+            None => None,
+        }
+    }
+
+    /// Check if a block is archived
+    pub fn contains_block(&self, block_id: &BlockId) -> bool {
+        let blocks_cf = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        self.db
+            .get_pinned_cf(blocks_cf, block_id.get_hash().to_bytes())
+            .expect(CRUD_ERROR)
+            .is_some()
+    }
+
+    /// Get archived operations along with the id of the block that includes them.
+    /// Each block is only read once, however many of the operations it includes.
+    ///
+    /// # Returns
+    /// The archived operations sorted by the order of the input list, `None` for operations that are not archived
+    pub fn get_operations(&self, operation_ids: &[OperationId]) -> Vec<Option<ArchivedOperation>> {
+        let operation_cf = self.db.cf_handle(OPERATION_INDEX_CF).expect(CF_ERROR);
+        let block_ids: Vec<Option<BlockId>> = operation_ids
+            .iter()
+            .map(|operation_id| {
+                self.db
+                    .get_cf(operation_cf, operation_id.get_hash().to_bytes())
+                    .expect(CRUD_ERROR)
+                    .and_then(|value| block_id_from_bytes(&value))
+            })
+            .collect();
+        let mut blocks: PreHashMap<BlockId, Option<ArchivedBlock>> = PreHashMap::default();
+        for block_id in block_ids.iter().flatten() {
+            if !blocks.contains_key(block_id) {
+                blocks.insert(*block_id, self.get_block(block_id));
+            }
+        }
+        operation_ids
+            .iter()
+            .zip(block_ids)
+            .map(|(operation_id, block_id)| {
+                let block_id = block_id?;
+                let operation = blocks
+                    .get(&block_id)?
+                    .as_ref()?
+                    .operations
+                    .iter()
+                    .find(|operation| operation.id == *operation_id)?
+                    .clone();
+                Some(ArchivedOperation {
+                    operation,
+                    block_id,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the ids of the archived blocks of a slot range, optionally restricted to a creator, sorted by slot
+    ///
+    /// # Arguments
+    /// * `creator`: only return blocks created by this address, if any
+    /// * `start_slot`: first slot of the range (included)
+    /// * `end_slot`: last slot of the range (excluded)
+    /// * `limit`: maximum number of block ids to return
+    pub fn search_blocks(
+        &self,
+        creator: Option<&Address>,
+        start_slot: &Slot,
+        end_slot: &Slot,
+        limit: usize,
+    ) -> Vec<BlockId> {
+        let (index_cf, prefix) = match creator {
+            Some(creator) => (CREATOR_INDEX_CF, address_index_prefix(creator)),
+            None => (SLOT_INDEX_CF, Vec::new()),
+        };
+        let handle = self.db.cf_handle(index_cf).expect(CF_ERROR);
+        let seek_key = [prefix.clone(), start_slot.to_bytes_key().to_vec()].concat();
+        let end_key = [prefix.clone(), end_slot.to_bytes_key().to_vec()].concat();
+        self.db
+            .iterator_cf(handle, IteratorMode::From(&seek_key, Direction::Forward))
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(&prefix) && key.as_ref() < end_key.as_slice())
+            .filter_map(|(_, value)| block_id_from_bytes(&value))
+            .take(limit)
+            .collect()
+    }
+}
+
+#[test]
+fn test_block_archive_indexes() {
+    use massa_models::{
+        amount::Amount,
+        block::{Block, BlockSerializer},
+        block_header::{BlockHeader, BlockHeaderSerializer},
+        config::CHAINID,
+        operation::{Operation, OperationSerializer, OperationType},
+        secure_share::SecureShareContent,
+    };
+    use massa_signature::KeyPair;
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let creator = Address::from_public_key(&keypair.get_public_key());
+    let other = KeyPair::generate(0).unwrap();
+    let tmp_dir = tempfile::TempDir::new().unwrap();
+    let archive = BlockArchive::new(tmp_dir.path().to_path_buf(), &ConsensusConfig::default());
+
+    let blocks: Vec<ArchivedBlock> = (1..=6u64)
+        .map(|period| {
+            let keypair = if period % 2 == 0 { &keypair } else { &other };
+            let operations: Vec<_> = (0..2u64)
+                .map(|index| {
+                    Operation::new_verifiable(
+                        Operation {
+                            fee: Amount::from_raw(index),
+                            expire_period: period + 10,
+                            op: OperationType::Transaction {
+                                recipient_address: creator,
+                                amount: Amount::from_raw(period),
+                            },
+                        },
+                        OperationSerializer::new(),
+                        keypair,
+                        *CHAINID,
+                    )
+                    .unwrap()
+                })
+                .collect();
+            let operation_ids: Vec<OperationId> =
+                operations.iter().map(|operation| operation.id).collect();
+            let header = BlockHeader::new_verifiable(
+                BlockHeader {
+                    current_version: 0,
+                    announced_version: None,
+                    slot: Slot::new(period, 0),
+                    parents: vec![],
+                    operation_merkle_root: Hash::compute_from(&Vec::new()),
+                    endorsements: vec![],
+                    denunciations: vec![],
+                },
+                BlockHeaderSerializer::new(),
+                keypair,
+                *CHAINID,
+            )
+            .unwrap();
+            let block = Block::new_verifiable(
+                Block {
+                    header,
+                    operations: operation_ids,
+                },
+                BlockSerializer::new(),
+                keypair,
+                *CHAINID,
+            )
+            .unwrap();
+            ArchivedBlock { block, operations }
+        })
+        .collect();
+    archive.archive_blocks(blocks.iter());
+
+    let found = archive.search_blocks(None, &Slot::new(2, 0), &Slot::new(5, 0), 10);
+    assert_eq!(
+        found,
+        vec![blocks[1].block.id, blocks[2].block.id, blocks[3].block.id]
+    );
+    let found = archive.search_blocks(Some(&creator), &Slot::new(0, 0), &Slot::new(10, 0), 2);
+    assert_eq!(found, vec![blocks[1].block.id, blocks[3].block.id]);
+
+    // blocks are read back with their signed contents
+    let archived = archive.get_block(&blocks[0].block.id).unwrap();
+    assert_eq!(archived.block.content.header.content.slot, Slot::new(1, 0));
+    assert_eq!(
+        archived.block.serialized_data,
+        blocks[0].block.serialized_data
+    );
+    archived.block.verify_signature().unwrap();
+    assert_eq!(archived.operations.len(), 2);
+    assert_eq!(archived.operations[1].id, blocks[0].operations[1].id);
+    assert!(archive.contains_block(&blocks[5].block.id));
+
+    // operations are found in their block, in the order of the request
+    let operations = archive.get_operations(&[
+        blocks[2].operations[1].id,
+        blocks[5].operations[0].id,
+        blocks[2].operations[0].id,
+        OperationId::new(Hash::compute_from(b"unknown")),
+    ]);
+    let found: Vec<Option<(OperationId, BlockId)>> = operations
+        .into_iter()
+        .map(|archived| archived.map(|archived| (archived.operation.id, archived.block_id)))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            Some((blocks[2].operations[1].id, blocks[2].block.id)),
+            Some((blocks[5].operations[0].id, blocks[5].block.id)),
+            Some((blocks[2].operations[0].id, blocks[2].block.id)),
+            None,
+        ]
+    );
+}
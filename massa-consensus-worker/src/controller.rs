@@ -1,11 +1,17 @@
 use massa_channel::sender::MassaSender;
 use massa_consensus_exports::ConsensusBroadcasts;
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport, block_status::BlockStatus,
-    bootstrapable_graph::BootstrapableGraph, error::ConsensusError,
-    export_active_block::ExportActiveBlock, ConsensusController,
+    archived_block::{ArchivedBlock, ArchivedOperation},
+    block_graph_export::BlockGraphExport,
+    block_status::BlockStatus,
+    bootstrapable_graph::BootstrapableGraph,
+    error::ConsensusError,
+    export_active_block::ExportActiveBlock,
+    ConsensusController,
 };
 use massa_models::{
+    address::Address,
+    amount::Amount,
     block::{BlockGraphStatus, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
@@ -22,7 +28,7 @@ use parking_lot::RwLock;
 use std::sync::Arc;
 use tracing::{debug, trace, warn};
 
-use crate::{commands::ConsensusCommand, state::ConsensusState};
+use crate::{block_archive::BlockArchive, commands::ConsensusCommand, state::ConsensusState};
 
 /// The retrieval of data is made using a shared state and modifications are asked by sending message to a channel.
 /// This is done mostly to be able to:
@@ -38,6 +44,7 @@ pub struct ConsensusControllerImpl {
     shared_state: Arc<RwLock<ConsensusState>>,
     bootstrap_part_size: u64,
     broadcast_enabled: bool,
+    block_archive: Option<Arc<BlockArchive>>,
 }
 
 impl ConsensusControllerImpl {
@@ -47,6 +54,7 @@ impl ConsensusControllerImpl {
         shared_state: Arc<RwLock<ConsensusState>>,
         bootstrap_part_size: u64,
         broadcast_enabled: bool,
+        block_archive: Option<Arc<BlockArchive>>,
     ) -> Self {
         Self {
             command_sender,
@@ -54,6 +62,7 @@ impl ConsensusControllerImpl {
             shared_state,
             bootstrap_part_size,
             broadcast_enabled,
+            block_archive,
         }
     }
 }
@@ -85,9 +94,24 @@ impl ConsensusController for ConsensusControllerImpl {
     /// # Returns:
     /// A vector of statuses sorted by the order of the block ids
     fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus> {
-        let read_shared_state = self.shared_state.read();
+        let statuses: Vec<BlockGraphStatus> = {
+            let read_shared_state = self.shared_state.read();
+            ids.iter()
+                .map(|id| read_shared_state.get_block_status(id))
+                .collect()
+        };
+        // blocks pruned from the graph are still known as final if they were archived
+        let Some(block_archive) = &self.block_archive else {
+            return statuses;
+        };
         ids.iter()
-            .map(|id| read_shared_state.get_block_status(id))
+            .zip(statuses)
+            .map(|(id, status)| match status {
+                BlockGraphStatus::NotFound if block_archive.contains_block(id) => {
+                    BlockGraphStatus::Final
+                }
+                status => status,
+            })
             .collect()
     }
 
@@ -217,6 +241,20 @@ impl ConsensusController for ConsensusControllerImpl {
             .get_blockclique_block_at_slot(&slot)
     }
 
+    /// Get the fees of the operations included in the latest final blocks
+    ///
+    /// # Returns:
+    /// The fees of the operations of the final blocks of the last `fee_estimation_final_periods` periods, unsorted
+    fn get_recent_final_fees(&self) -> Vec<Amount> {
+        self.shared_state
+            .read()
+            .recent_final_fees
+            .iter()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
     /// Get the latest block, that is in the blockclique, in the thread of the given slot and before this `slot`.
     ///
     /// # Arguments:
@@ -230,6 +268,59 @@ impl ConsensusController for ConsensusControllerImpl {
             .get_latest_blockclique_block_at_slot(&slot)
     }
 
+    /// Get final blocks from the block archive
+    ///
+    /// # Arguments:
+    /// * `ids`: the list of block ids to look for
+    ///
+    /// # Returns:
+    /// The archived blocks sorted by the order of the input list
+    fn get_archived_blocks(&self, ids: &[BlockId]) -> Vec<Option<ArchivedBlock>> {
+        match &self.block_archive {
+            Some(block_archive) => ids.iter().map(|id| block_archive.get_block(id)).collect(),
+            None => vec![None; ids.len()],
+        }
+    }
+
+    /// Get final operations from the block archive, along with the id of the block that includes them
+    ///
+    /// # Arguments:
+    /// * `ids`: the list of operation ids to look for
+    ///
+    /// # Returns:
+    /// The archived operations sorted by the order of the input list
+    fn get_archived_operations(&self, ids: &[OperationId]) -> Vec<Option<ArchivedOperation>> {
+        match &self.block_archive {
+            Some(block_archive) => block_archive.get_operations(ids),
+            None => vec![None; ids.len()],
+        }
+    }
+
+    /// Search the block archive
+    ///
+    /// # Arguments:
+    /// * `creator`: only return blocks created by this address, if any
+    /// * `start_slot`: first slot of the search (included)
+    /// * `end_slot`: last slot of the search (excluded)
+    /// * `limit`: maximum number of block ids to return
+    ///
+    /// # Returns:
+    /// The ids of the matching archived blocks sorted by slot
+    fn search_archived_blocks(
+        &self,
+        creator: Option<Address>,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Vec<BlockId> {
+        match &self.block_archive {
+            Some(block_archive) => {
+                block_archive.search_blocks(creator.as_ref(), &start_slot, &end_slot, limit)
+            }
+            None => Vec::new(),
+        }
+    }
+
     fn register_block(&self, block_id: BlockId, slot: Slot, block_storage: Storage, created: bool) {
         if self.broadcast_enabled {
             if let Some(verifiable_block) = block_storage.read_blocks().get(&block_id) {
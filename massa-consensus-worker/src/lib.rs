@@ -24,7 +24,9 @@
 //!    * If it's the third or more we ignore the block unless we asked for it explicitly as a dependency.
 //! If a queued block reaches the slot time at which it should be processed, the worker wakes up to check it and trigger, if necessary, the consensus algorithm.
 //! It then prunes the block graph and the caches.
+//! If the block archive is enabled, final blocks are written to it with their operations before being pruned.
 
+mod block_archive;
 mod commands;
 mod controller;
 mod manager;
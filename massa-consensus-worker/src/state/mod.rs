@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, VecDeque},
+    sync::Arc,
     vec,
 };
 
@@ -14,6 +15,7 @@ use massa_metrics::MassaMetrics;
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
+    amount::Amount,
     block::BlockGraphStatus,
     block_header::SecuredHeader,
     block_id::BlockId,
@@ -26,6 +28,7 @@ use massa_time::MassaTime;
 use tracing::debug;
 
 use self::blocks_state::BlocksState;
+use crate::block_archive::BlockArchive;
 
 pub mod blocks_state;
 mod clique_computation;
@@ -91,6 +94,11 @@ pub struct ConsensusState {
     pub nonfinal_active_blocks_per_slot: HashMap<Slot, PreHashSet<BlockId>>,
     /// massa metrics
     pub(crate) massa_metrics: MassaMetrics,
+    /// archive of final blocks, if enabled
+    pub(crate) block_archive: Option<Arc<BlockArchive>>,
+    /// operation fees of the latest final blocks, one entry per block in slot order,
+    /// at most `fee_estimation_final_periods * thread_count` blocks
+    pub(crate) recent_final_fees: VecDeque<Vec<Amount>>,
 }
 
 impl ConsensusState {
@@ -4,6 +4,8 @@ use std::{
 };
 
 use massa_consensus_exports::{
+    archived_block::ArchivedBlock,
+    block_graph_event::BlockGraphEvent,
     block_status::{BlockStatus, DiscardReason, HeaderOrBlock, StorageOrBlock},
     error::ConsensusError,
 };
@@ -22,7 +24,7 @@ use massa_models::{
 use massa_signature::PublicKey;
 use massa_storage::Storage;
 use massa_time::MassaTime;
-use tracing::{debug, info};
+use tracing::{debug, info, trace, warn};
 
 use crate::state::{
     clique_computation::compute_max_cliques,
@@ -591,6 +593,74 @@ impl ConsensusState {
         }
     }
 
+    /// Write newly final blocks and their operations to the block archive, if enabled
+    fn archive_final_blocks(&self, finalized_blocks: &HashMap<Slot, BlockId>) {
+        let Some(block_archive) = &self.block_archive else {
+            return;
+        };
+        let archived_blocks: Vec<ArchivedBlock> = finalized_blocks
+            .values()
+            .filter_map(|b_id| match self.blocks_state.get(b_id) {
+                Some(BlockStatus::Active {
+                    storage_or_block: StorageOrBlock::Storage(storage),
+                    ..
+                }) => {
+                    let block = storage.read_blocks().get(b_id).cloned()?;
+                    let stored_ops = storage.read_operations();
+                    let operations = block
+                        .content
+                        .operations
+                        .iter()
+                        .filter_map(|op_id| stored_ops.get(op_id).cloned())
+                        .collect();
+                    Some(ArchivedBlock { block, operations })
+                }
+                _ => {
+                    warn!("final block {} could not be archived: not in storage", b_id);
+                    None
+                }
+            })
+            .collect();
+        block_archive.archive_blocks(archived_blocks.iter());
+    }
+
+    /// Keep the operation fees of newly final blocks for fee estimation,
+    /// forgetting the blocks older than `fee_estimation_final_periods` periods
+    fn record_final_fees(&mut self, finalized_blocks: &HashMap<Slot, BlockId>) {
+        let mut final_blocks: Vec<(&Slot, &BlockId)> = finalized_blocks.iter().collect();
+        final_blocks.sort_unstable();
+        for (_, b_id) in final_blocks {
+            let Some(BlockStatus::Active {
+                storage_or_block: StorageOrBlock::Storage(storage),
+                ..
+            }) = self.blocks_state.get(b_id)
+            else {
+                continue;
+            };
+            let fees = {
+                let stored_blocks = storage.read_blocks();
+                let Some(block) = stored_blocks.get(b_id) else {
+                    continue;
+                };
+                let stored_ops = storage.read_operations();
+                block
+                    .content
+                    .operations
+                    .iter()
+                    .filter_map(|op_id| stored_ops.get(op_id).map(|op| op.content.fee))
+                    .collect()
+            };
+            self.recent_final_fees.push_back(fees);
+        }
+        let max_blocks = self
+            .config
+            .fee_estimation_final_periods
+            .saturating_mul(self.config.thread_count as u64);
+        while self.recent_final_fees.len() as u64 > max_blocks {
+            self.recent_final_fees.pop_front();
+        }
+    }
+
     /// Notify execution about blockclique changes and finalized blocks.
     ///
     /// # Arguments:
@@ -678,6 +748,18 @@ impl ConsensusState {
             );
     }
 
+    /// Broadcast a block graph event to the API subscribers
+    fn broadcast_block_graph_event(&self, event: BlockGraphEvent) {
+        if let Err(err) = self
+            .channels
+            .broadcasts
+            .block_graph_event_sender
+            .send(event)
+        {
+            trace!("error, failed to broadcast block graph event: {}", err);
+        }
+    }
+
     /// call me if the block database changed
     /// Processing of final blocks, pruning.
     ///
@@ -743,14 +825,59 @@ impl ConsensusState {
             // add stale blocks to stats
             let new_stale_block_ids_creators_slots = mem::take(&mut self.new_stale_blocks);
             let timestamp = MassaTime::now();
-            for (_b_id, (_b_creator, _b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
+            for (b_id, (_b_creator, b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
                 self.stale_block_stats.push_back(timestamp);
+                if self.config.broadcast_enabled {
+                    self.broadcast_block_graph_event(BlockGraphEvent::BlockBecameStale {
+                        block_id: b_id,
+                        slot: b_slot,
+                    });
+                }
             }
             final_block_slots
         };
 
-        // notify execution
+        // archive final blocks before they get pruned
+        self.archive_final_blocks(&final_block_slots);
+        self.record_final_fees(&final_block_slots);
+
+        // notify execution, keeping track of the blockclique changes to broadcast them
+        let mut final_block_ids: PreHashSet<BlockId> = PreHashSet::default();
+        let mut prev_blockclique_ids: PreHashSet<BlockId> = PreHashSet::default();
+        if self.config.broadcast_enabled {
+            let mut final_blocks: Vec<(&Slot, &BlockId)> = final_block_slots.iter().collect();
+            final_blocks.sort_unstable();
+            for (slot, block_id) in final_blocks {
+                self.broadcast_block_graph_event(BlockGraphEvent::BlockBecameFinal {
+                    block_id: *block_id,
+                    slot: *slot,
+                });
+            }
+            final_block_ids = final_block_slots.values().copied().collect();
+            prev_blockclique_ids = self.prev_blockclique.keys().copied().collect();
+        }
         self.notify_execution(final_block_slots);
+        if self.config.broadcast_enabled {
+            let added: Vec<BlockId> = self
+                .prev_blockclique
+                .keys()
+                .filter(|b_id| !prev_blockclique_ids.contains(b_id))
+                .copied()
+                .collect();
+            let removed: Vec<BlockId> = prev_blockclique_ids
+                .iter()
+                .filter(|b_id| {
+                    !self.prev_blockclique.contains_key(b_id) && !final_block_ids.contains(b_id)
+                })
+                .copied()
+                .collect();
+            if !added.is_empty() || !removed.is_empty() {
+                self.broadcast_block_graph_event(BlockGraphEvent::BlockcliqueChanged {
+                    added,
+                    removed,
+                });
+            }
+        }
 
         // notify protocol of block wishlist
         let new_wishlist = self.get_block_wishlist()?;
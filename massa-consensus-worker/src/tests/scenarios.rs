@@ -4,11 +4,11 @@ use std::{
 };
 
 use super::{
-    tools::{consensus_test, register_block},
+    tools::{consensus_test, consensus_test_with_block_graph_events, register_block},
     universe::{ConsensusForeignControllers, ConsensusTestUniverse},
 };
 use crate::tests::tools::create_block;
-use massa_consensus_exports::ConsensusConfig;
+use massa_consensus_exports::{block_graph_event::BlockGraphEvent, ConsensusConfig};
 use massa_execution_exports::MockExecutionController;
 use massa_models::{
     address::Address, block::BlockGraphStatus, block_id::BlockId, config::ENDORSEMENT_COUNT,
@@ -306,6 +306,147 @@ fn test_parallel_incompatibility() {
     );
 }
 
+#[test]
+fn test_block_graph_events() {
+    let thread_count = 2;
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let cfg = ConsensusConfig {
+        t0: MassaTime::from_millis(100),
+        thread_count,
+        genesis_timestamp: MassaTime::now(),
+        force_keep_final_periods_without_ops: 128,
+        force_keep_final_periods: 10,
+        delta_f0: 4,
+        broadcast_enabled: true,
+        ..ConsensusConfig::default()
+    };
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&staking_key.get_public_key());
+
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_update_blockclique_status()
+        .returning(|_, _, _| {});
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    selector_controller
+        .expect_get_selection()
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+    consensus_test_with_block_graph_events(
+        cfg,
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        move |consensus_controller, mut block_graph_events| {
+            let genesis = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status")
+                .genesis_blocks;
+
+            let block_1 = create_block(Slot::new(1, 0), vec![genesis[0], genesis[1]], &staking_key);
+            register_block(&consensus_controller, block_1.clone(), storage.clone());
+
+            let block_2 = create_block(Slot::new(1, 1), vec![genesis[0], genesis[1]], &staking_key);
+            register_block(&consensus_controller, block_2.clone(), storage.clone());
+
+            // block_4 enters the blockclique first
+            let block_4 = create_block(Slot::new(2, 1), vec![genesis[0], block_2.id], &staking_key);
+            register_block(&consensus_controller, block_4.clone(), storage.clone());
+            std::thread::sleep(Duration::from_millis(400));
+            let status = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status");
+            assert_eq!(status.max_cliques.len(), 1);
+            assert!(status.max_cliques[0].block_ids.contains(&block_4.id));
+
+            // block_3 is incompatible with block_4, and its branch is then extended
+            let block_3 = create_block(Slot::new(2, 0), vec![block_1.id, genesis[1]], &staking_key);
+            register_block(&consensus_controller, block_3.clone(), storage.clone());
+            let block_5 = create_block(Slot::new(3, 0), vec![block_3.id, block_2.id], &staking_key);
+            register_block(&consensus_controller, block_5.clone(), storage.clone());
+            std::thread::sleep(Duration::from_millis(100));
+            for extend_i in 0..10 {
+                let status = consensus_controller
+                    .get_block_graph_status(None, None)
+                    .expect("could not get block graph status");
+                let block = create_block(
+                    Slot::new(4 + extend_i, 0),
+                    status.best_parents.iter().map(|(b, _p)| *b).collect(),
+                    &staking_key,
+                );
+                register_block(&consensus_controller, block.clone(), storage.clone());
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            std::thread::sleep(Duration::from_millis(200));
+
+            let mut events = Vec::new();
+            while let Ok(event) = block_graph_events.try_recv() {
+                events.push(event);
+            }
+
+            // block_4 left the blockclique without becoming final, then became stale
+            let removed_at = events
+                .iter()
+                .position(|event| {
+                    matches!(event, BlockGraphEvent::BlockcliqueChanged { removed, .. } if removed.contains(&block_4.id))
+                })
+                .expect("block_4 was not removed from the blockclique");
+            let stale_at = events
+                .iter()
+                .position(|event| {
+                    event
+                        == &BlockGraphEvent::BlockBecameStale {
+                            block_id: block_4.id,
+                            slot: Slot::new(2, 1),
+                        }
+                })
+                .expect("block_4 did not become stale");
+            assert!(removed_at < stale_at);
+            assert!(!events.iter().any(|event| matches!(
+                event,
+                BlockGraphEvent::BlockBecameFinal { block_id, .. } if block_id == &block_4.id
+            )));
+
+            // the blocks of the winning branch became final in slot order
+            let final_slots: Vec<Slot> = events
+                .iter()
+                .filter_map(|event| match event {
+                    BlockGraphEvent::BlockBecameFinal { slot, .. } if slot.thread == 0 => {
+                        Some(*slot)
+                    }
+                    _ => None,
+                })
+                .collect();
+            let mut sorted_final_slots = final_slots.clone();
+            sorted_final_slots.sort_unstable();
+            assert_eq!(final_slots, sorted_final_slots);
+            for block in [&block_1, &block_3, &block_5] {
+                assert!(
+                    events.contains(&BlockGraphEvent::BlockBecameFinal {
+                        block_id: block.id,
+                        slot: block.content.header.content.slot,
+                    }),
+                    "missing final block"
+                );
+            }
+        },
+    );
+}
+
 #[test]
 fn test_parent_in_the_future() {
     let staking_key: KeyPair = KeyPair::generate(0).unwrap();
@@ -3,7 +3,8 @@ use std::{time::Duration, vec};
 use crate::start_consensus_worker;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::{
-    ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusController,
+    block_graph_event::BlockGraphEvent, ConsensusBroadcasts, ConsensusChannels, ConsensusConfig,
+    ConsensusController,
 };
 use massa_execution_exports::MockExecutionController;
 use massa_hash::Hash;
@@ -22,6 +23,7 @@ use massa_pos_exports::MockSelectorController;
 use massa_protocol_exports::MockProtocolController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use tokio::sync::broadcast;
 
 pub fn consensus_test<F>(
     cfg: ConsensusConfig,
@@ -31,6 +33,25 @@ pub fn consensus_test<F>(
     test: F,
 ) where
     F: FnOnce(Box<dyn ConsensusController>),
+{
+    consensus_test_with_block_graph_events(
+        cfg,
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        |consensus_controller, _block_graph_events| test(consensus_controller),
+    );
+}
+
+/// Same as `consensus_test`, also giving the test a receiver of the broadcast block graph events
+pub fn consensus_test_with_block_graph_events<F>(
+    cfg: ConsensusConfig,
+    execution_controller: Box<MockExecutionController>,
+    pool_controller: Box<MockPoolController>,
+    selector_controller: Box<MockSelectorController>,
+    test: F,
+) where
+    F: FnOnce(Box<dyn ConsensusController>, broadcast::Receiver<BlockGraphEvent>),
 {
     let storage: Storage = Storage::create_root();
     // mock protocol
@@ -52,6 +73,7 @@ pub fn consensus_test<F>(
     let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
     let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
     let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+    let (block_graph_event_sender, block_graph_event_receiver) = broadcast::channel(1000);
     let (consensus_controller, mut consensus_manager) = start_consensus_worker(
         cfg.clone(),
         ConsensusChannels {
@@ -59,6 +81,7 @@ pub fn consensus_test<F>(
                 block_sender,
                 block_header_sender,
                 filled_block_sender,
+                block_graph_event_sender,
             },
             controller_event_tx: consensus_event_sender,
             execution_controller,
@@ -78,7 +101,7 @@ pub fn consensus_test<F>(
     );
 
     // Call test func.
-    test(consensus_controller);
+    test(consensus_controller, block_graph_event_receiver);
     // stop controller while ignoring all commands
     consensus_manager.stop();
 }
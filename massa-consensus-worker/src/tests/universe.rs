@@ -63,6 +63,8 @@ impl TestUniverse for ConsensusTestUniverse {
         let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
         let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
         let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+        let (block_graph_event_sender, _block_graph_event_receiver) =
+            tokio::sync::broadcast::channel(10);
         let (consensus_controller, _) = start_consensus_worker(
             config,
             ConsensusChannels {
@@ -70,6 +72,7 @@ impl TestUniverse for ConsensusTestUniverse {
                     block_sender,
                     block_header_sender,
                     filled_block_sender,
+                    block_graph_event_sender,
                 },
                 controller_event_tx: consensus_event_sender,
                 execution_controller: foreign_controllers.execution_controller,
@@ -16,6 +16,7 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
 
+use crate::block_archive::BlockArchive;
 use crate::commands::ConsensusCommand;
 use crate::controller::ConsensusControllerImpl;
 use crate::manager::ConsensusManagerImpl;
@@ -64,6 +65,10 @@ pub fn start_consensus_worker(
     let stats_desync_detection_timespan =
         config.t0.checked_mul(config.periods_per_cycle * 2).unwrap();
     let broadcasts = channels.broadcasts.clone();
+    let block_archive = config
+        .block_archive_path
+        .clone()
+        .map(|path| Arc::new(BlockArchive::new(path, &config)));
     let shared_state = Arc::new(RwLock::new(ConsensusState {
         storage: storage.clone(),
         config: config.clone(),
@@ -97,6 +102,8 @@ pub fn start_consensus_worker(
         prev_blockclique: Default::default(),
         nonfinal_active_blocks_per_slot: Default::default(),
         massa_metrics,
+        block_archive: block_archive.clone(),
+        recent_final_fees: Default::default(),
     }));
 
     let shared_state_cloned = shared_state.clone();
@@ -118,6 +125,7 @@ pub fn start_consensus_worker(
         shared_state,
         bootstrap_part_size,
         config.broadcast_enabled,
+        block_archive,
     );
 
     (Box::new(controller), Box::new(manager))
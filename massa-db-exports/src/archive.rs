@@ -0,0 +1,14 @@
+//! Helpers shared by the optional on-disk archives of the node
+//! (block archive, event archive, ledger history and address history).
+
+use massa_models::address::Address;
+
+/// Builds the prefix of the index entries of an address in an archive.
+/// The serialized address is length-prefixed so that no address prefix can collide with another.
+pub fn address_index_prefix(address: &Address) -> Vec<u8> {
+    let address_bytes = address.to_prefixed_bytes();
+    let mut prefix = Vec::with_capacity(address_bytes.len() + 1);
+    prefix.push(address_bytes.len() as u8);
+    prefix.extend(address_bytes);
+    prefix
+}
@@ -1,9 +1,11 @@
+mod archive;
 mod constants;
 mod controller;
 mod db_batch;
 mod error;
 mod settings;
 
+pub use archive::*;
 pub use constants::*;
 pub use controller::*;
 pub use db_batch::*;
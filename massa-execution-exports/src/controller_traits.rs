@@ -3,15 +3,16 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    AddressHistoryCursor, AddressHistoryPage, ExecutionBlockMetadata, ExecutionQueryRequest,
+    ExecutionQueryResponse, ReadOnlyExecutionRequest,
 };
-use crate::ExecutionError;
 use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use crate::{ExecutionError, ExecutionQueryError};
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
+use massa_models::execution::{EventCursor, EventFilter, SCOutputEventPage};
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
@@ -47,6 +48,36 @@ pub trait ExecutionController: Send + Sync {
     /// * operation id
     fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent>;
 
+    /// Get a page of the execution events matching the filter, ordered by slot.
+    /// When the event archive is enabled, final events older than the in-memory cache are included.
+    /// A page may hold fewer than `limit` events while more remain: the archive scan of a query is bounded.
+    ///
+    /// # Arguments
+    /// * `filter`: event filter
+    /// * `cursor`: position to start the page at, as returned with the previous page
+    /// * `limit`: maximum number of events to return
+    fn get_filtered_sc_output_event_page(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventCursor>,
+        limit: usize,
+    ) -> SCOutputEventPage;
+
+    /// Get a page of the final history of an address, ordered by slot:
+    /// the operations it created, the operations it received coins from,
+    /// its roll buys and sells and its slashes.
+    ///
+    /// # Arguments
+    /// * `address`: address to get the history of
+    /// * `cursor`: position to start the page at, the start of the history if `None`
+    /// * `limit`: maximum number of history entries to return
+    fn get_address_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> Result<AddressHistoryPage, ExecutionQueryError>;
+
     /// Get the final and active values of balance.
     ///
     /// # Return value
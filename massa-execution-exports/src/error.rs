@@ -2,6 +2,7 @@
 
 //! this file defines all possible execution error categories
 
+use crate::types::ExecutionCallTrace;
 use displaydoc::Display;
 use massa_module_cache::error::CacheError;
 use massa_sc_runtime::VMError;
@@ -62,6 +63,14 @@ pub enum ExecutionError {
         error: VMError,
     },
 
+    /// Traced read-only execution error: {error}
+    TracedError {
+        /// error that interrupted the execution
+        error: Box<ExecutionError>,
+        /// call tree recorded until the error
+        trace: Box<ExecutionCallTrace>,
+    },
+
     /// Cache error: {0}
     CacheError(#[from] CacheError),
 
@@ -74,4 +83,6 @@ pub enum ExecutionError {
 pub enum ExecutionQueryError {
     /// Not found: {0}
     NotFound(String),
+    /// Not available: {0}
+    NotAvailable(String),
 }
@@ -5,6 +5,7 @@
 
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::Slot;
 use std::collections::VecDeque;
 
 /// Store for events emitted by smart contracts
@@ -46,6 +47,11 @@ impl EventStore {
         }
     }
 
+    /// Get the slot of the oldest event of the store, if any
+    pub fn first_slot(&self) -> Option<Slot> {
+        self.0.front().map(|event| event.context.slot)
+    }
+
     /// Get events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -56,44 +62,7 @@ impl EventStore {
     pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
         self.0
             .iter()
-            .filter(|x| {
-                if let Some(start) = filter.start {
-                    if x.context.slot < start {
-                        return false;
-                    }
-                }
-                if let Some(end) = filter.end {
-                    if x.context.slot >= end {
-                        return false;
-                    }
-                }
-                if let Some(is_final) = filter.is_final {
-                    if x.context.is_final != is_final {
-                        return false;
-                    }
-                }
-                if let Some(is_error) = filter.is_error {
-                    if x.context.is_error != is_error {
-                        return false;
-                    }
-                }
-                match (filter.original_caller_address, x.context.call_stack.front()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.emitter_address, x.context.call_stack.back()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_operation_id, x.context.origin_operation_id) {
-                    (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                true
-            })
+            .filter(|x| filter.matches(x))
             .cloned()
             .collect()
     }
@@ -102,7 +71,6 @@ impl EventStore {
 #[test]
 fn test_prune() {
     use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
-    use massa_models::slot::Slot;
 
     let mut store = EventStore(VecDeque::new());
     for i in 0..10 {
@@ -0,0 +1,198 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module tracks the execution events notified to an event stream subscriber,
+//! in order to notify their finality transitions: candidate, final,
+//! or dropped when the candidate slot that emitted them is re-executed without them.
+
+use crate::types::SlotExecutionOutput;
+use massa_models::execution::EventFilter;
+use massa_models::output_event::{SCOutputEvent, SCOutputEventNotification, SCOutputEventStatus};
+use massa_models::slot::Slot;
+use std::collections::BTreeMap;
+
+/// Tracks the events of the slot execution outputs broadcast by the execution worker
+/// that match a subscriber's filter
+#[derive(Debug, Clone)]
+pub struct SCOutputEventTracker {
+    /// filter applied to the events, regardless of their finality
+    filter: EventFilter,
+    /// optional prefix that the event data must start with
+    data_prefix: Option<String>,
+    /// whether candidate (and dropped) events are notified
+    notify_candidate: bool,
+    /// whether final events are notified
+    notify_final: bool,
+    /// candidate events already notified, indexed by slot
+    candidate_events: BTreeMap<Slot, Vec<SCOutputEvent>>,
+}
+
+impl SCOutputEventTracker {
+    /// Creates a new tracker
+    ///
+    /// # Arguments
+    /// * `filter`: events filter. Its `is_final` field selects the notified transitions.
+    /// * `data_prefix`: optional prefix that the event data must start with
+    pub fn new(mut filter: EventFilter, data_prefix: Option<String>) -> Self {
+        let is_final = filter.is_final.take();
+        SCOutputEventTracker {
+            filter,
+            data_prefix,
+            notify_candidate: is_final != Some(true),
+            notify_final: is_final != Some(false),
+            candidate_events: BTreeMap::new(),
+        }
+    }
+
+    /// Returns true if the event matches the filter and the data prefix
+    fn matches(&self, event: &SCOutputEvent) -> bool {
+        if let Some(prefix) = &self.data_prefix {
+            if !event.data.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        self.filter.matches(event)
+    }
+
+    /// Processes a new slot execution output and returns the resulting notifications
+    pub fn process(
+        &mut self,
+        slot_execution_output: &SlotExecutionOutput,
+    ) -> Vec<SCOutputEventNotification> {
+        let (exec_output, is_final) = match slot_execution_output {
+            SlotExecutionOutput::ExecutedSlot(exec_output) => (exec_output, false),
+            SlotExecutionOutput::FinalizedSlot(exec_output) => (exec_output, true),
+        };
+
+        let events: Vec<SCOutputEvent> = exec_output
+            .events
+            .0
+            .iter()
+            .filter(|event| self.matches(event))
+            .cloned()
+            .map(|mut event| {
+                event.context.is_final = is_final;
+                event
+            })
+            .collect();
+
+        let mut notifications = Vec::new();
+
+        // the slot was re-executed or finalized:
+        // the previously notified candidate events that were not emitted again are dropped
+        let previous_events = self
+            .candidate_events
+            .remove(&exec_output.slot)
+            .unwrap_or_default();
+        for previous_event in previous_events.iter() {
+            if !events.iter().any(|event| same_event(event, previous_event)) {
+                notifications.push(SCOutputEventNotification {
+                    status: SCOutputEventStatus::Dropped,
+                    event: previous_event.clone(),
+                });
+            }
+        }
+
+        if is_final {
+            if self.notify_final {
+                notifications.extend(events.into_iter().map(|event| SCOutputEventNotification {
+                    status: SCOutputEventStatus::Final,
+                    event,
+                }));
+            }
+        } else if self.notify_candidate && !events.is_empty() {
+            notifications.extend(
+                events
+                    .iter()
+                    .filter(|event| {
+                        !previous_events
+                            .iter()
+                            .any(|previous_event| same_event(event, previous_event))
+                    })
+                    .map(|event| SCOutputEventNotification {
+                        status: SCOutputEventStatus::Candidate,
+                        event: event.clone(),
+                    }),
+            );
+            self.candidate_events.insert(exec_output.slot, events);
+        }
+
+        notifications
+    }
+}
+
+/// Returns true if both events were emitted at the same place of the same slot with the same content
+fn same_event(a: &SCOutputEvent, b: &SCOutputEvent) -> bool {
+    a.context.slot == b.context.slot
+        && a.context.index_in_slot == b.context.index_in_slot
+        && a.context.block == b.context.block
+        && a.context.call_stack == b.context.call_stack
+        && a.context.origin_operation_id == b.context.origin_operation_id
+        && a.context.is_error == b.context.is_error
+        && a.data == b.data
+}
+
+#[test]
+fn test_event_tracker_transitions() {
+    use crate::event_store::EventStore;
+    use crate::types::ExecutionOutput;
+    use massa_final_state::StateChanges;
+    use massa_models::output_event::EventExecutionContext;
+    use std::collections::VecDeque;
+
+    let slot = Slot::new(1, 0);
+    let output = |data: &[&str]| ExecutionOutput {
+        slot,
+        block_info: None,
+        state_changes: StateChanges::default(),
+        events: EventStore(
+            data.iter()
+                .enumerate()
+                .map(|(index, data)| SCOutputEvent {
+                    context: EventExecutionContext {
+                        slot,
+                        block: None,
+                        read_only: false,
+                        index_in_slot: index as u64,
+                        call_stack: VecDeque::new(),
+                        origin_operation_id: None,
+                        is_final: false,
+                        is_error: false,
+                    },
+                    data: data.to_string(),
+                })
+                .collect(),
+        ),
+    };
+    let statuses = |notifications: Vec<SCOutputEventNotification>| {
+        notifications
+            .into_iter()
+            .map(|notification| (notification.status, notification.event.data))
+            .collect::<Vec<_>>()
+    };
+
+    let mut tracker = SCOutputEventTracker::new(EventFilter::default(), Some("ok".to_string()));
+
+    let notifications = tracker.process(&SlotExecutionOutput::ExecutedSlot(output(&[
+        "ok_a", "ko", "ok_b",
+    ])));
+    assert_eq!(
+        statuses(notifications),
+        vec![
+            (SCOutputEventStatus::Candidate, "ok_a".to_string()),
+            (SCOutputEventStatus::Candidate, "ok_b".to_string())
+        ]
+    );
+
+    // re-execution of the slot without the second event
+    let notifications = tracker.process(&SlotExecutionOutput::ExecutedSlot(output(&["ok_a"])));
+    assert_eq!(
+        statuses(notifications),
+        vec![(SCOutputEventStatus::Dropped, "ok_b".to_string())]
+    );
+
+    let notifications = tracker.process(&SlotExecutionOutput::FinalizedSlot(output(&["ok_a"])));
+    assert_eq!(
+        statuses(notifications),
+        vec![(SCOutputEventStatus::Final, "ok_a".to_string())]
+    );
+}
@@ -34,6 +34,9 @@
 //! ## `event_store.rs`
 //! Defines an indexed, finite-size storage system for execution events.
 //!
+//! ## `event_tracker.rs`
+//! Tracks the finality transitions of the execution events notified to event stream subscribers.
+//!
 //! ## `types.rs`
 //! Defines useful shared structures.
 //!
@@ -48,6 +51,7 @@ mod channels;
 mod controller_traits;
 mod error;
 mod event_store;
+mod event_tracker;
 /// mapping grpc
 pub mod mapping_grpc;
 mod settings;
@@ -59,14 +63,17 @@ pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
 pub use event_store::EventStore;
+pub use event_tracker::SCOutputEventTracker;
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
-    ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
-    ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
-    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AddressHistoryCursor, AddressHistoryItem, AddressHistoryKind, AddressHistoryPage,
+    ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionCallTrace,
+    ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus,
+    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
+    ExecutionQueryResponseItem, ExecutionQueryStakerInfo, ExecutionStackElement,
+    ReadOnlyCallRequest, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 
 #[cfg(any(feature = "test-exports", feature = "gas_calibration"))]
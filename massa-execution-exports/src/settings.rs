@@ -82,6 +82,12 @@ pub struct ExecutionConfig {
     pub hd_cache_size: usize,
     /// Amount of entries removed when `hd_cache_size` is reached
     pub snip_amount: usize,
+    /// Path to the on-disk archive of final SC output events (disabled if None)
+    pub event_archive_path: Option<PathBuf>,
+    /// Path to the on-disk history of final balances and datastore entries (disabled if None)
+    pub ledger_archive_path: Option<PathBuf>,
+    /// Path to the on-disk index of the final history of each address (disabled if None)
+    pub address_history_path: Option<PathBuf>,
     /// Number of roll to remove per denunciation
     pub roll_count_to_slash_on_denunciation: u64,
     /// Denunciation expire delta
@@ -61,6 +61,9 @@ impl Default for ExecutionConfig {
             lru_cache_size: 1000,
             hd_cache_size: 10_000,
             snip_amount: 10,
+            event_archive_path: None,
+            ledger_archive_path: None,
+            address_history_path: None,
             roll_count_to_slash_on_denunciation: 1,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             broadcast_enabled: true,
@@ -91,6 +91,25 @@ pub enum ExecutionQueryRequestItem {
         key: Vec<u8>,
     },
 
+    /// gets the balance of an address at the end of a past final slot, returns ExecutionQueryResponseItem::Amount(balance)
+    /// or an error if the address did not exist at that time or if the ledger history of that slot is not available
+    AddressBalanceAtSlot {
+        /// Address for which to query the balance
+        addr: Address,
+        /// Final slot at the end of which the balance is queried
+        slot: Slot,
+    },
+    /// gets a datastore value of an address at the end of a past final slot, returns ExecutionQueryResponseItem::DatastoreValue(value)
+    /// or an error if the entry did not exist at that time or if the ledger history of that slot is not available
+    AddressDatastoreValueAtSlot {
+        /// Address for which to query the datastore
+        addr: Address,
+        /// Key of the entry
+        key: Vec<u8>,
+        /// Final slot at the end of which the value is queried
+        slot: Slot,
+    },
+
     /// gets the execution status (candidate) for an operation, returns ExecutionQueryResponseItem::ExecutionStatus(status)
     OpExecutionStatusCandidate(OperationId),
     /// gets the execution status (final) for an operation, returns ExecutionQueryResponseItem::ExecutionStatus(status)
@@ -246,6 +265,114 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// Call tree of the execution, if tracing was requested
+    pub trace: Option<ExecutionCallTrace>,
+}
+
+/// Trace of a call frame recorded during a traced read-only execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionCallTrace {
+    /// Address that made the call, if any
+    pub caller: Option<Address>,
+    /// Called address
+    pub callee: Address,
+    /// Called function. The runtime only reports it for the entry frame.
+    pub function: Option<String>,
+    /// Coins transferred to the callee
+    pub coins: Amount,
+    /// Gas available when the frame started running
+    pub gas_limit: Option<u64>,
+    /// Gas used by the frame and its sub-calls.
+    /// For nested frames, this is an upper bound that also counts the caller's instructions
+    /// until its next call or the end of the execution.
+    pub gas_used: Option<u64>,
+    /// Datastore entries read by the frame, as (address, key)
+    pub datastore_reads: Vec<(Address, Vec<u8>)>,
+    /// Datastore entries set, appended to or deleted by the frame, as (address, key)
+    pub datastore_writes: Vec<(Address, Vec<u8>)>,
+    /// Data of the events emitted by the frame
+    pub events: Vec<String>,
+    /// Error that interrupted the execution, if it was raised in this frame
+    pub error: Option<String>,
+    /// Calls made by the frame, in call order
+    pub calls: Vec<ExecutionCallTrace>,
+}
+
+impl ExecutionCallTrace {
+    /// Create the trace of a frame that has just been entered
+    pub fn new(
+        caller: Option<Address>,
+        callee: Address,
+        function: Option<String>,
+        coins: Amount,
+    ) -> Self {
+        ExecutionCallTrace {
+            caller,
+            callee,
+            function,
+            coins,
+            gas_limit: None,
+            gas_used: None,
+            datastore_reads: Vec::new(),
+            datastore_writes: Vec::new(),
+            events: Vec::new(),
+            error: None,
+            calls: Vec::new(),
+        }
+    }
+}
+
+/// Way an address was involved in a final slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressHistoryKind {
+    /// The address created an executed operation
+    OperationCreated {
+        /// Whether the operation execution succeeded
+        success: bool,
+    },
+    /// The address received coins from a successful operation
+    CoinsReceived,
+    /// The address bought rolls with a successful operation
+    RollBuy {
+        /// Number of rolls bought
+        roll_count: u64,
+    },
+    /// The address sold rolls with a successful operation
+    RollSell {
+        /// Number of rolls sold
+        roll_count: u64,
+    },
+    /// The address was denounced and slashed
+    Slashed,
+}
+
+/// Entry of the final history of an address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressHistoryItem {
+    /// Slot of the block in which the address was involved
+    pub slot: Slot,
+    /// Operation involving the address, if any
+    pub operation_id: Option<OperationId>,
+    /// Way the address was involved
+    pub kind: AddressHistoryKind,
+}
+
+/// Position in the history of an address, ordered by slot then by index in the slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AddressHistoryCursor {
+    /// Slot of the entry
+    pub slot: Slot,
+    /// Index of the entry among the history entries of the slot
+    pub index_in_slot: u32,
+}
+
+/// A page of the final history of an address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressHistoryPage {
+    /// Entries of the page, ordered by slot
+    pub items: Vec<AddressHistoryItem>,
+    /// Cursor to request the next page with, `None` if there are no more entries
+    pub next_cursor: Option<AddressHistoryCursor>,
 }
 
 /// structure describing different types of read-only execution request
@@ -261,6 +388,8 @@ pub struct ReadOnlyExecutionRequest {
     pub coins: Option<Amount>,
     /// Fee
     pub fee: Option<Amount>,
+    /// Record the call tree of the execution (see `ExecutionCallTrace`)
+    pub trace: bool,
 }
 
 /// structure describing different possible targets of a read-only execution request
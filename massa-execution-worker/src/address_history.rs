@@ -0,0 +1,358 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements an optional on-disk index of the final history of each address.
+//!
+//! It is fed with the operations and denunciations of each final block, filtered by the executed operation
+//! and denunciation ids of the final execution output of the slot. For each address, it records the operations
+//! it created, the operations it received coins from, its roll buys and sells and its slashes.
+//! Entries are keyed by address then slot, so that the history of an address can be paginated without a full scan.
+
+use massa_db_exports::{address_index_prefix, CF_ERROR, CRUD_ERROR, OPEN_ERROR};
+use massa_execution_exports::{
+    AddressHistoryCursor, AddressHistoryItem, AddressHistoryKind, AddressHistoryPage,
+};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::address::Address;
+use massa_models::operation::{OperationId, OperationType, SecureShareOperation};
+use massa_models::prehash::PreHashMap;
+use massa_models::secure_share::Id;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::path::PathBuf;
+use tracing::warn;
+
+const HISTORY_CF: &str = "address_history";
+
+/// Size of the suffix of a history key: the slot key followed by the big-endian index of the entry in the slot
+const ENTRY_KEY_SUFFIX_SIZE: usize = SLOT_KEY_SIZE + 4;
+
+const OPERATION_CREATED_TAG: u8 = 0;
+const COINS_RECEIVED_TAG: u8 = 1;
+const ROLL_BUY_TAG: u8 = 2;
+const ROLL_SELL_TAG: u8 = 3;
+const SLASHED_TAG: u8 = 4;
+
+/// Serializes the kind and operation id of a history entry (the slot is part of the key)
+fn entry_value(operation_id: Option<&OperationId>, kind: &AddressHistoryKind) -> Vec<u8> {
+    let mut value = Vec::with_capacity(2 + HASH_SIZE_BYTES + 8);
+    match kind {
+        AddressHistoryKind::OperationCreated { success } => {
+            value.push(OPERATION_CREATED_TAG);
+            value.push(u8::from(*success));
+        }
+        AddressHistoryKind::CoinsReceived => value.push(COINS_RECEIVED_TAG),
+        AddressHistoryKind::RollBuy { roll_count } => {
+            value.push(ROLL_BUY_TAG);
+            value.extend(roll_count.to_be_bytes());
+        }
+        AddressHistoryKind::RollSell { roll_count } => {
+            value.push(ROLL_SELL_TAG);
+            value.extend(roll_count.to_be_bytes());
+        }
+        AddressHistoryKind::Slashed => value.push(SLASHED_TAG),
+    }
+    if let Some(operation_id) = operation_id {
+        value.extend(operation_id.get_hash().to_bytes());
+    }
+    value
+}
+
+/// Reads the position of a history entry from its key suffix
+fn cursor_from_key_suffix(key_suffix: &[u8]) -> Option<AddressHistoryCursor> {
+    Some(AddressHistoryCursor {
+        slot: Slot::from_bytes_key(key_suffix.get(..SLOT_KEY_SIZE)?.try_into().ok()?),
+        index_in_slot: u32::from_be_bytes(key_suffix.get(SLOT_KEY_SIZE..)?.try_into().ok()?),
+    })
+}
+
+/// Deserializes a history entry from its key suffix and its value
+fn entry_from_bytes(key_suffix: &[u8], value: &[u8]) -> Option<AddressHistoryItem> {
+    let slot = Slot::from_bytes_key(key_suffix.get(..SLOT_KEY_SIZE)?.try_into().ok()?);
+    let (kind, rest) = match value.split_first()? {
+        (&OPERATION_CREATED_TAG, rest) => (
+            AddressHistoryKind::OperationCreated {
+                success: *rest.first()? != 0,
+            },
+            &rest[1..],
+        ),
+        (&COINS_RECEIVED_TAG, rest) => (AddressHistoryKind::CoinsReceived, rest),
+        (&ROLL_BUY_TAG, rest) => (
+            AddressHistoryKind::RollBuy {
+                roll_count: u64::from_be_bytes(rest.get(..8)?.try_into().ok()?),
+            },
+            &rest[8..],
+        ),
+        (&ROLL_SELL_TAG, rest) => (
+            AddressHistoryKind::RollSell {
+                roll_count: u64::from_be_bytes(rest.get(..8)?.try_into().ok()?),
+            },
+            &rest[8..],
+        ),
+        (&SLASHED_TAG, rest) => (AddressHistoryKind::Slashed, rest),
+        _ => return None,
+    };
+    let operation_id = match rest.len() {
+        0 => None,
+        HASH_SIZE_BYTES => Some(OperationId::new(Hash::from_bytes(rest.try_into().ok()?))),
+        _ => return None,
+    };
+    Some(AddressHistoryItem {
+        slot,
+        operation_id,
+        kind,
+    })
+}
+
+/// On-disk index of the final history of each address
+pub(crate) struct AddressHistory {
+    /// RocksDB database
+    db: DB,
+}
+
+impl AddressHistory {
+    /// Open (or create) the address history index
+    ///
+    /// # Arguments
+    /// * path: where to store the db
+    pub fn new(path: PathBuf) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![ColumnFamilyDescriptor::new(HISTORY_CF, Options::default())],
+        )
+        .expect(OPEN_ERROR);
+        AddressHistory { db }
+    }
+
+    /// Index the history entries of a final slot
+    ///
+    /// # Arguments
+    /// * `slot`: the final slot
+    /// * `operations`: the operations of the block of the slot, in block order
+    /// * `executed_ops`: the operations executed at this slot, with their execution success
+    /// * `slashed`: the addresses that were denounced and slashed at this slot
+    pub fn index_slot(
+        &self,
+        slot: &Slot,
+        operations: &[SecureShareOperation],
+        executed_ops: &PreHashMap<OperationId, (bool, Slot)>,
+        slashed: &[Address],
+    ) {
+        let mut entries: Vec<(Address, Option<OperationId>, AddressHistoryKind)> = Vec::new();
+        for operation in operations {
+            let Some((success, _)) = executed_ops.get(&operation.id) else {
+                continue;
+            };
+            let creator = operation.content_creator_address;
+            entries.push((
+                creator,
+                Some(operation.id),
+                AddressHistoryKind::OperationCreated { success: *success },
+            ));
+            if !success {
+                continue;
+            }
+            match &operation.content.op {
+                OperationType::Transaction {
+                    recipient_address, ..
+                } => entries.push((
+                    *recipient_address,
+                    Some(operation.id),
+                    AddressHistoryKind::CoinsReceived,
+                )),
+                OperationType::CallSC {
+                    target_addr, coins, ..
+                } if !coins.is_zero() => entries.push((
+                    *target_addr,
+                    Some(operation.id),
+                    AddressHistoryKind::CoinsReceived,
+                )),
+                OperationType::RollBuy { roll_count } => entries.push((
+                    creator,
+                    Some(operation.id),
+                    AddressHistoryKind::RollBuy {
+                        roll_count: *roll_count,
+                    },
+                )),
+                OperationType::RollSell { roll_count } => entries.push((
+                    creator,
+                    Some(operation.id),
+                    AddressHistoryKind::RollSell {
+                        roll_count: *roll_count,
+                    },
+                )),
+                _ => {}
+            }
+        }
+        entries.extend(
+            slashed
+                .iter()
+                .map(|address| (*address, None, AddressHistoryKind::Slashed)),
+        );
+
+        let history_cf = self.db.cf_handle(HISTORY_CF).expect(CF_ERROR);
+        let mut batch = WriteBatch::default();
+        for (index, (address, operation_id, kind)) in entries.iter().enumerate() {
+            let mut key = address_index_prefix(address);
+            key.extend(slot.to_bytes_key());
+            key.extend((index as u32).to_be_bytes());
+            batch.put_cf(history_cf, key, entry_value(operation_id.as_ref(), kind));
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Get a page of the history of an address, ordered by slot.
+    /// The iteration seeks straight to the cursor, so the cost of a page does not depend on its position.
+    ///
+    /// # Arguments
+    /// * `address`: address to get the history of
+    /// * `cursor`: position to start the page at, the start of the history if `None`
+    /// * `limit`: maximum number of entries to return
+    pub fn get_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> AddressHistoryPage {
+        let history_cf = self.db.cf_handle(HISTORY_CF).expect(CF_ERROR);
+        let prefix = address_index_prefix(address);
+        let mut seek_key = prefix.clone();
+        if let Some(cursor) = cursor {
+            seek_key.extend(cursor.slot.to_bytes_key());
+            seek_key.extend(cursor.index_in_slot.to_be_bytes());
+        }
+        let mut page = AddressHistoryPage::default();
+        for (key, value) in self
+            .db
+            .iterator_cf(
+                history_cf,
+                IteratorMode::From(&seek_key, Direction::Forward),
+            )
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(&prefix))
+        {
+            let key_suffix = &key[key.len() - ENTRY_KEY_SUFFIX_SIZE..];
+            if page.items.len() >= limit {
+                page.next_cursor = cursor_from_key_suffix(key_suffix);
+                break;
+            }
+            match entry_from_bytes(key_suffix, &value) {
+                Some(entry) => page.items.push(entry),
+                None => warn!("could not deserialize address history entry of {}", address),
+            }
+        }
+        page
+    }
+}
+
+#[test]
+fn test_address_history_index() {
+    use massa_models::{
+        amount::Amount,
+        config::CHAINID,
+        operation::{Operation, OperationSerializer},
+        secure_share::SecureShareContent,
+    };
+    use massa_signature::KeyPair;
+    use std::str::FromStr;
+
+    let sender_keypair = KeyPair::generate(0).unwrap();
+    let sender = Address::from_public_key(&sender_keypair.get_public_key());
+    let recipient = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let tmp_dir = tempfile::TempDir::new().unwrap();
+    let history = AddressHistory::new(tmp_dir.path().to_path_buf());
+
+    let create_op = |op: OperationType| {
+        Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op,
+            },
+            OperationSerializer::new(),
+            &sender_keypair,
+            *CHAINID,
+        )
+        .unwrap()
+    };
+    let transfer = create_op(OperationType::Transaction {
+        recipient_address: recipient,
+        amount: Amount::from_str("1").unwrap(),
+    });
+    let roll_buy = create_op(OperationType::RollBuy { roll_count: 2 });
+    let failed_roll_sell = create_op(OperationType::RollSell { roll_count: 3 });
+    let not_executed = create_op(OperationType::RollSell { roll_count: 4 });
+
+    let mut executed_ops = PreHashMap::default();
+    executed_ops.insert(transfer.id, (true, Slot::new(10, 0)));
+    executed_ops.insert(roll_buy.id, (true, Slot::new(10, 0)));
+    executed_ops.insert(failed_roll_sell.id, (false, Slot::new(10, 0)));
+    history.index_slot(
+        &Slot::new(1, 0),
+        &[
+            transfer.clone(),
+            roll_buy.clone(),
+            failed_roll_sell,
+            not_executed,
+        ],
+        &executed_ops,
+        &[],
+    );
+    history.index_slot(&Slot::new(2, 0), &[], &PreHashMap::default(), &[sender]);
+
+    assert_eq!(
+        history.get_history(&recipient, None, 10),
+        AddressHistoryPage {
+            items: vec![AddressHistoryItem {
+                slot: Slot::new(1, 0),
+                operation_id: Some(transfer.id),
+                kind: AddressHistoryKind::CoinsReceived,
+            }],
+            next_cursor: None,
+        }
+    );
+
+    let sender_history = history.get_history(&sender, None, 10).items;
+    assert_eq!(sender_history.len(), 5);
+    assert_eq!(
+        sender_history[2],
+        AddressHistoryItem {
+            slot: Slot::new(1, 0),
+            operation_id: Some(roll_buy.id),
+            kind: AddressHistoryKind::RollBuy { roll_count: 2 },
+        }
+    );
+    assert_eq!(
+        sender_history[3].kind,
+        AddressHistoryKind::OperationCreated { success: false }
+    );
+
+    // pages follow each other through their cursors
+    let first_page = history.get_history(&sender, None, 3);
+    assert_eq!(first_page.items, sender_history[..3]);
+    let cursor = first_page.next_cursor.unwrap();
+    assert_eq!(cursor.slot, Slot::new(1, 0));
+    let last_page = history.get_history(&sender, Some(cursor), 3);
+    assert_eq!(last_page.items, sender_history[3..]);
+    assert_eq!(last_page.next_cursor, None);
+    assert_eq!(
+        history
+            .get_history(
+                &sender,
+                Some(AddressHistoryCursor {
+                    slot: Slot::new(2, 0),
+                    index_in_slot: 0,
+                }),
+                10
+            )
+            .items,
+        vec![AddressHistoryItem {
+            slot: Slot::new(2, 0),
+            operation_id: None,
+            kind: AddressHistoryKind::Slashed,
+        }]
+    );
+}
@@ -7,13 +7,14 @@ use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
-    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    AddressHistoryCursor, AddressHistoryPage, ExecutionAddressInfo, ExecutionBlockMetadata,
+    ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager, ExecutionQueryError,
+    ExecutionQueryExecutionStatus, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionQueryResponse, ExecutionQueryResponseItem, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest,
 };
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
+use massa_models::execution::{EventCursor, EventFilter, SCOutputEventPage};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::stats::ExecutionStats;
@@ -138,6 +139,7 @@ impl ExecutionController for ExecutionControllerImpl {
             final_cursor: execution_lock.final_cursor,
             final_state_fingerprint: execution_lock.get_final_state_fingerprint(),
         };
+        let mut event_queries = Vec::new();
         for req_item in req.requests {
             let resp_item = match req_item {
                 ExecutionQueryRequestItem::AddressExistsCandidate(addr) => {
@@ -226,6 +228,26 @@ impl ExecutionController for ExecutionControllerImpl {
                         ))),
                     }
                 }
+                ExecutionQueryRequestItem::AddressBalanceAtSlot { addr, slot } => {
+                    match execution_lock.get_final_balance_at_slot(&addr, &slot) {
+                        Ok(Some(balance)) => Ok(ExecutionQueryResponseItem::Amount(balance)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} at slot {}",
+                            addr, slot
+                        ))),
+                        Err(err) => Err(err),
+                    }
+                }
+                ExecutionQueryRequestItem::AddressDatastoreValueAtSlot { addr, key, slot } => {
+                    match execution_lock.get_final_data_entry_at_slot(&addr, &key, &slot) {
+                        Ok(Some(value)) => Ok(ExecutionQueryResponseItem::DatastoreValue(value)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} datastore entry {:?} at slot {}",
+                            addr, key, slot
+                        ))),
+                        Err(err) => Err(err),
+                    }
+                }
                 ExecutionQueryRequestItem::OpExecutionStatusCandidate(id) => {
                     let (speculative_v, _final_v) = execution_lock
                         .get_ops_exec_status(&[id])
@@ -320,13 +342,20 @@ impl ExecutionController for ExecutionControllerImpl {
                     }
                 }
                 ExecutionQueryRequestItem::Events(filter) => {
-                    Ok(ExecutionQueryResponseItem::Events(
-                        execution_lock.get_filtered_sc_output_event(filter),
-                    ))
+                    // completed with the archived events once the execution state is unlocked
+                    let events = execution_lock.get_filtered_sc_output_event(&filter, false);
+                    event_queries.push((resp.responses.len(), filter, events));
+                    Ok(ExecutionQueryResponseItem::Events(Vec::new()))
                 }
             };
             resp.responses.push(resp_item);
         }
+        drop(execution_lock);
+        for (index, filter, events) in event_queries {
+            resp.responses[index] = Ok(ExecutionQueryResponseItem::Events(
+                events.into_events(&filter),
+            ));
+        }
         resp
     }
 
@@ -337,9 +366,39 @@ impl ExecutionController for ExecutionControllerImpl {
     /// * original caller address
     /// * operation id
     fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
-        self.execution_state
+        // the event archive is read once the execution state is unlocked
+        let events = self
+            .execution_state
+            .read()
+            .get_filtered_sc_output_event(&filter, false);
+        events.into_events(&filter)
+    }
+
+    /// Get a page of the generated execution events matching the filter, ordered by slot
+    fn get_filtered_sc_output_event_page(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventCursor>,
+        limit: usize,
+    ) -> SCOutputEventPage {
+        // the event archive is read once the execution state is unlocked
+        let events = self
+            .execution_state
             .read()
-            .get_filtered_sc_output_event(filter)
+            .get_filtered_sc_output_event(&filter, true);
+        events.into_page(&filter, cursor, limit)
+    }
+
+    /// Get a page of the final history of an address, ordered by slot
+    fn get_address_history(
+        &self,
+        address: &Address,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> Result<AddressHistoryPage, ExecutionQueryError> {
+        // the index is read after releasing the execution lock
+        let address_history = self.execution_state.read().get_address_history()?;
+        Ok(address_history.get_history(address, cursor, limit))
     }
 
     /// Get the final and candidate values of balance.
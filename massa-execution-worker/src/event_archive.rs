@@ -0,0 +1,370 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements an optional on-disk archive of final SC output events.
+//!
+//! Events are stored in their own RocksDB instance (they are not part of the final state hash)
+//! and are indexed by slot, emitter address, original caller address and origin operation id,
+//! so that range queries over arbitrarily old final slots do not require a full scan.
+
+use massa_db_exports::{address_index_prefix, CF_ERROR, CRUD_ERROR, OPEN_ERROR};
+use massa_models::execution::{EventCursor, EventFilter};
+use massa_models::operation::OperationId;
+use massa_models::output_event::{
+    SCOutputEvent, SCOutputEventDeserializer, SCOutputEventSerializer,
+};
+use massa_models::secure_share::Id;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::path::PathBuf;
+use tracing::warn;
+
+const EVENT_SER_ERROR: &str = "critical: event serialization failed";
+
+/// Maximum length of the call stack of an archived event,
+/// far above the call depth reachable with the gas of a slot
+const MAX_EVENT_CALL_STACK_LENGTH: u32 = 10_000;
+
+const EVENTS_CF: &str = "events";
+const EMITTER_INDEX_CF: &str = "emitter_index";
+const CALLER_INDEX_CF: &str = "caller_index";
+const OPERATION_INDEX_CF: &str = "operation_index";
+
+/// Maximum number of archive entries read by a single query,
+/// so that a filter matching few events cannot make a query scan the whole archive
+pub(crate) const MAX_SCANNED_EVENTS: usize = 10_000;
+
+/// Size of an event key: the slot key followed by the big-endian index of the event in the slot
+const EVENT_KEY_SIZE: usize = SLOT_KEY_SIZE + 8;
+
+/// Builds the sortable key of an event
+fn event_key(slot: &Slot, index_in_slot: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(EVENT_KEY_SIZE);
+    key.extend(slot.to_bytes_key());
+    key.extend(index_in_slot.to_be_bytes());
+    key
+}
+
+/// Position of the event of a key
+fn key_cursor(key: &[u8]) -> EventCursor {
+    let slot_key: &[u8; SLOT_KEY_SIZE] = key[..SLOT_KEY_SIZE]
+        .try_into()
+        .expect("event key shorter than a slot key");
+    let index_bytes: [u8; 8] = key[SLOT_KEY_SIZE..EVENT_KEY_SIZE]
+        .try_into()
+        .expect("event key without an index");
+    EventCursor {
+        slot: Slot::from_bytes_key(slot_key),
+        index_in_slot: u64::from_be_bytes(index_bytes),
+    }
+}
+
+/// Builds the prefix of the index entries of an operation id
+fn operation_index_prefix(operation_id: &OperationId) -> Vec<u8> {
+    operation_id.get_hash().to_bytes().to_vec()
+}
+
+/// Events read from the archive by a bounded scan
+pub(crate) struct ArchiveScan {
+    /// matching events, ordered by slot
+    pub events: Vec<SCOutputEvent>,
+    /// position of the first entry left unread, if the scan stopped before its end
+    pub resume_at: Option<EventCursor>,
+}
+
+/// Progress of a scan of the archive
+struct ScanState {
+    last_slot: Slot,
+    limit: usize,
+    max_scanned: usize,
+    scanned: usize,
+    scan: ArchiveScan,
+}
+
+/// On-disk store of final SC output events
+pub(crate) struct EventArchive {
+    /// RocksDB database
+    db: DB,
+    /// serializer of the archived events
+    event_serializer: SCOutputEventSerializer,
+    /// deserializer of the archived events
+    event_deserializer: SCOutputEventDeserializer,
+}
+
+impl EventArchive {
+    /// Open (or create) the event archive
+    ///
+    /// # Arguments
+    /// * path: where to store the db
+    /// * thread_count: number of threads
+    /// * max_event_size: maximum length of the data of an event
+    pub fn new(path: PathBuf, thread_count: u8, max_event_size: usize) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![
+                ColumnFamilyDescriptor::new(EVENTS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(EMITTER_INDEX_CF, Options::default()),
+                ColumnFamilyDescriptor::new(CALLER_INDEX_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATION_INDEX_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR);
+        EventArchive {
+            db,
+            event_serializer: SCOutputEventSerializer::new(),
+            event_deserializer: SCOutputEventDeserializer::new(
+                thread_count,
+                MAX_EVENT_CALL_STACK_LENGTH,
+                max_event_size as u64,
+            ),
+        }
+    }
+
+    /// Archive a batch of final events along with their index entries
+    pub fn archive_events<'a>(&self, events: impl IntoIterator<Item = &'a SCOutputEvent>) {
+        let events_cf = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+        let emitter_cf = self.db.cf_handle(EMITTER_INDEX_CF).expect(CF_ERROR);
+        let caller_cf = self.db.cf_handle(CALLER_INDEX_CF).expect(CF_ERROR);
+        let operation_cf = self.db.cf_handle(OPERATION_INDEX_CF).expect(CF_ERROR);
+
+        let mut batch = WriteBatch::default();
+        for event in events {
+            let key = event_key(&event.context.slot, event.context.index_in_slot);
+            let mut serialized = Vec::new();
+            self.event_serializer
+                .serialize(event, &mut serialized)
+                .expect(EVENT_SER_ERROR);
+            batch.put_cf(events_cf, &key, serialized);
+            if let Some(emitter) = event.context.call_stack.back() {
+                batch.put_cf(
+                    emitter_cf,
+                    [address_index_prefix(emitter), key.clone()].concat(),
+                    b"",
+                );
+            }
+            if let Some(caller) = event.context.call_stack.front() {
+                batch.put_cf(
+                    caller_cf,
+                    [address_index_prefix(caller), key.clone()].concat(),
+                    b"",
+                );
+            }
+            if let Some(operation_id) = &event.context.origin_operation_id {
+                batch.put_cf(
+                    operation_cf,
+                    [operation_index_prefix(operation_id), key].concat(),
+                    b"",
+                );
+            }
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Read the archived events matching the filter, ordered by slot, from `from` up to the end of `last_slot`.
+    ///
+    /// The most selective index available in the filter is used
+    /// (operation id, then emitter address, then original caller address),
+    /// and the remaining criteria are checked on each event.
+    /// The scan stops once `limit` events are found or `max_scanned` entries are read,
+    /// and then tells where to resume it.
+    pub fn scan_events(
+        &self,
+        filter: &EventFilter,
+        from: EventCursor,
+        last_slot: Slot,
+        limit: usize,
+        max_scanned: usize,
+    ) -> ArchiveScan {
+        let index = if let Some(operation_id) = &filter.original_operation_id {
+            Some((OPERATION_INDEX_CF, operation_index_prefix(operation_id)))
+        } else if let Some(emitter) = &filter.emitter_address {
+            Some((EMITTER_INDEX_CF, address_index_prefix(emitter)))
+        } else {
+            filter
+                .original_caller_address
+                .as_ref()
+                .map(|caller| (CALLER_INDEX_CF, address_index_prefix(caller)))
+        };
+
+        let mut state = ScanState {
+            last_slot,
+            limit,
+            max_scanned,
+            scanned: 0,
+            scan: ArchiveScan {
+                events: Vec::new(),
+                resume_at: None,
+            },
+        };
+        let from_key = event_key(&from.slot, from.index_in_slot);
+        match index {
+            Some((index_cf, prefix)) => {
+                let handle = self.db.cf_handle(index_cf).expect(CF_ERROR);
+                let seek_key = [prefix.clone(), from_key].concat();
+                for item in self
+                    .db
+                    .iterator_cf(handle, IteratorMode::From(&seek_key, Direction::Forward))
+                {
+                    let (key, _) = item.expect(CRUD_ERROR);
+                    if !key.starts_with(&prefix)
+                        || !self.scan_entry(
+                            filter,
+                            &mut state,
+                            &key[key.len() - EVENT_KEY_SIZE..],
+                            None,
+                        )
+                    {
+                        break;
+                    }
+                }
+            }
+            None => {
+                let handle = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+                for item in self
+                    .db
+                    .iterator_cf(handle, IteratorMode::From(&from_key, Direction::Forward))
+                {
+                    let (key, value) = item.expect(CRUD_ERROR);
+                    if !self.scan_entry(filter, &mut state, &key, Some(&value)) {
+                        break;
+                    }
+                }
+            }
+        }
+        state.scan
+    }
+
+    /// Visit the archive entry of the event of key `key` during a scan, reading the event if `value` is not given.
+    /// Returns false when the scan has to stop.
+    fn scan_entry(
+        &self,
+        filter: &EventFilter,
+        state: &mut ScanState,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> bool {
+        let cursor = key_cursor(key);
+        if cursor.slot > state.last_slot || filter.end.map_or(false, |end| cursor.slot >= end) {
+            return false;
+        }
+        if state.scan.events.len() >= state.limit || state.scanned >= state.max_scanned {
+            state.scan.resume_at = Some(cursor);
+            return false;
+        }
+        state.scanned += 1;
+        let read_value;
+        let value = match value {
+            Some(value) => value,
+            None => {
+                let events_cf = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+                match self.db.get_cf(events_cf, key).expect(CRUD_ERROR) {
+                    Some(value) => {
+                        read_value = value;
+                        &read_value
+                    }
+                    None => return true,
+                }
+            }
+        };
+        match self
+            .event_deserializer
+            .deserialize::<DeserializeError>(value)
+        {
+            Ok((_, event)) if filter.matches(&event) => state.scan.events.push(event),
+            Ok(_) => {}
+            Err(err) => warn!("could not deserialize archived event: {}", err),
+        }
+        true
+    }
+}
+
+#[test]
+fn test_event_archive_filters() {
+    use massa_models::address::Address;
+    use massa_models::output_event::EventExecutionContext;
+    use massa_signature::KeyPair;
+    use std::collections::VecDeque;
+
+    let emitter = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let other = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let tmp_dir = tempfile::TempDir::new().unwrap();
+    let archive = EventArchive::new(tmp_dir.path().to_path_buf(), 32, 1_000);
+
+    let events: Vec<SCOutputEvent> = (0..10u64)
+        .map(|i| SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(i, 0),
+                block: None,
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: VecDeque::from(vec![if i % 2 == 0 { emitter } else { other }]),
+                origin_operation_id: None,
+                is_final: true,
+                is_error: false,
+            },
+            data: i.to_string(),
+        })
+        .collect();
+    archive.archive_events(events.iter());
+
+    let last_slot = Slot::new(9, 0);
+    let data = |scan: &ArchiveScan| -> Vec<String> {
+        scan.events.iter().map(|event| event.data.clone()).collect()
+    };
+
+    let filter = EventFilter {
+        start: Some(Slot::new(3, 0)),
+        end: Some(Slot::new(8, 0)),
+        ..Default::default()
+    };
+    let from = EventCursor::from_slot(Slot::new(3, 0));
+    let scan = archive.scan_events(&filter, from, last_slot, 100, MAX_SCANNED_EVENTS);
+    assert_eq!(data(&scan), vec!["3", "4", "5", "6", "7"]);
+    assert_eq!(scan.resume_at, None);
+    // the context of the events survives their binary serialization
+    let context = &scan.events[0].context;
+    assert_eq!(context.slot, Slot::new(3, 0));
+    assert_eq!(context.call_stack, VecDeque::from(vec![other]));
+    assert!(context.is_final && context.block.is_none());
+
+    let filter = EventFilter {
+        start: Some(Slot::new(3, 0)),
+        emitter_address: Some(emitter),
+        ..Default::default()
+    };
+    let scan = archive.scan_events(&filter, from, last_slot, 100, MAX_SCANNED_EVENTS);
+    assert_eq!(data(&scan), vec!["4", "6", "8"]);
+    assert_eq!(scan.resume_at, None);
+
+    // the scan stops at the limit and resumes where it stopped
+    let scan = archive.scan_events(&filter, from, last_slot, 2, MAX_SCANNED_EVENTS);
+    assert_eq!(data(&scan), vec!["4", "6"]);
+    let resume_at = scan.resume_at.expect("the scan should stop at the limit");
+    assert_eq!(resume_at, EventCursor::from_slot(Slot::new(8, 0)));
+    let scan = archive.scan_events(&filter, resume_at, last_slot, 2, MAX_SCANNED_EVENTS);
+    assert_eq!(data(&scan), vec!["8"]);
+    assert_eq!(scan.resume_at, None);
+
+    // the scan stops after reading the maximum number of entries, even without a match
+    let filter = EventFilter {
+        emitter_address: Some(emitter),
+        original_caller_address: Some(other),
+        ..Default::default()
+    };
+    let from = EventCursor::from_slot(Slot::new(0, 0));
+    let scan = archive.scan_events(&filter, from, last_slot, 100, 3);
+    assert!(scan.events.is_empty());
+    assert_eq!(
+        scan.resume_at,
+        Some(EventCursor::from_slot(Slot::new(6, 0)))
+    );
+
+    // nothing is read past the last slot
+    let scan = archive.scan_events(&EventFilter::default(), from, Slot::new(1, 0), 100, 3);
+    assert_eq!(data(&scan), vec!["0", "1"]);
+    assert_eq!(scan.resume_at, None);
+}
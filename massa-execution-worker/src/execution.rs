@@ -9,15 +9,19 @@
 //! * the output of the execution is extracted from the context
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::address_history::AddressHistory;
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
+use crate::event_archive::{EventArchive, MAX_SCANNED_EVENTS};
 use crate::interface_impl::InterfaceImpl;
+use crate::ledger_archive::LedgerArchive;
 use crate::stats::ExecutionStatsCounter;
+use crate::trace::CallTraceRecorder;
 use massa_async_pool::AsyncMessage;
 use massa_execution_exports::{
     EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryError,
+    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 use massa_final_state::FinalStateController;
 use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
@@ -26,7 +30,7 @@ use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::denunciation::{Denunciation, DenunciationIndex};
-use massa_models::execution::EventFilter;
+use massa_models::execution::{EventCursor, EventFilter, SCOutputEventPage};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::ExecutionStats;
@@ -55,6 +59,86 @@ macro_rules! context_guard {
     };
 }
 
+/// Execution events matching a filter, taken from the execution state
+/// and completed with the final events of the archive once the execution state is unlocked
+pub(crate) struct FilteredEvents {
+    /// event archive to read the final events from, and the last final slot it contains
+    archive: Option<(Arc<EventArchive>, Slot)>,
+    /// matching events kept in RAM, ordered by slot
+    events: Vec<SCOutputEvent>,
+    /// maximum number of events read from the archive
+    max_events: usize,
+}
+
+impl FilteredEvents {
+    /// All the matching events, final events first.
+    /// When the archive is read, the events kept in RAM are all returned and the archived events fill up
+    /// the rest of `max_final_events`: the oldest ones are returned, the others have to be read by pages.
+    pub fn into_events(self, filter: &EventFilter) -> Vec<SCOutputEvent> {
+        match self.archive {
+            Some((event_archive, last_final_slot)) => {
+                let from = EventCursor::from_slot(filter.start.unwrap_or_else(|| Slot::new(0, 0)));
+                let archive_limit = self.max_events.saturating_sub(self.events.len());
+                if archive_limit == 0 {
+                    return self.events;
+                }
+                let mut events = event_archive
+                    .scan_events(
+                        filter,
+                        from,
+                        last_final_slot,
+                        archive_limit,
+                        MAX_SCANNED_EVENTS,
+                    )
+                    .events;
+                events.extend(self.events);
+                events
+            }
+            None => self.events,
+        }
+    }
+
+    /// A page of at most `limit` matching events (capped by `max_final_events`) starting at `cursor`,
+    /// final events first, along with the cursor of the next page
+    pub fn into_page(
+        self,
+        filter: &EventFilter,
+        cursor: Option<EventCursor>,
+        limit: usize,
+    ) -> SCOutputEventPage {
+        let limit = limit.clamp(1, std::cmp::max(self.max_events, 1));
+        let mut from = EventCursor::from_slot(filter.start.unwrap_or_else(|| Slot::new(0, 0)));
+        if let Some(cursor) = cursor {
+            from = std::cmp::max(from, cursor);
+        }
+        let mut events = Vec::new();
+        if let Some((event_archive, last_final_slot)) = self.archive {
+            let scan =
+                event_archive.scan_events(filter, from, last_final_slot, limit, MAX_SCANNED_EVENTS);
+            if scan.resume_at.is_some() {
+                return SCOutputEventPage {
+                    events: scan.events,
+                    next_cursor: scan.resume_at,
+                };
+            }
+            events = scan.events;
+        }
+        let mut remaining = self
+            .events
+            .into_iter()
+            .filter(|event| EventCursor::of_event(event) >= from);
+        events.extend(remaining.by_ref().take(limit - events.len()));
+        let next_cursor = match remaining.next() {
+            Some(_) => events.last().map(EventCursor::after_event),
+            None => None,
+        };
+        SCOutputEventPage {
+            events,
+            next_cursor,
+        }
+    }
+}
+
 /// Structure holding consistent speculative and final execution states,
 /// and allowing access to them.
 pub(crate) struct ExecutionState {
@@ -72,6 +156,12 @@ pub(crate) struct ExecutionState {
     pub final_cursor: Slot,
     // store containing execution events that became final
     final_events: EventStore,
+    // optional on-disk archive of all the execution events that became final
+    event_archive: Option<Arc<EventArchive>>,
+    // optional on-disk history of final balances and datastore entries
+    ledger_archive: Option<LedgerArchive>,
+    // optional on-disk index of the final history of each address
+    address_history: Option<Arc<AddressHistory>>,
     // final state with atomic R/W access
     final_state: Arc<RwLock<dyn FinalStateController>>,
     // execution context (see documentation in context.rs)
@@ -160,6 +250,24 @@ impl ExecutionState {
             active_history,
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
+            // the event archive is persistent across restarts
+            event_archive: config.event_archive_path.clone().map(|path| {
+                Arc::new(EventArchive::new(
+                    path,
+                    config.thread_count,
+                    config.max_event_size,
+                ))
+            }),
+            // the ledger history is persistent across restarts, as long as it has no gaps
+            ledger_archive: config
+                .ledger_archive_path
+                .clone()
+                .map(|path| LedgerArchive::new(path, last_final_slot)),
+            // the address history is persistent across restarts
+            address_history: config
+                .address_history_path
+                .clone()
+                .map(|path| Arc::new(AddressHistory::new(path))),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
@@ -211,6 +319,15 @@ impl ExecutionState {
         // as it will also write the MIP store on disk
         self.update_versioning_stats(&exec_out.block_info, &exec_out.slot);
 
+        // keep the values overwritten by the ledger changes
+        if let Some(ledger_archive) = &mut self.ledger_archive {
+            ledger_archive.archive_slot_changes(
+                exec_out.slot,
+                &exec_out.state_changes.ledger_changes,
+                self.final_state.read().get_ledger().as_ref(),
+            );
+        }
+
         let exec_out_2 = exec_out.clone();
         // apply state changes to the final ledger
         self.final_state
@@ -228,6 +345,9 @@ impl ExecutionState {
 
         // append generated events to the final event store
         exec_out.events.finalize();
+        if let Some(event_archive) = &self.event_archive {
+            event_archive.archive_events(exec_out.events.0.iter());
+        }
         self.final_events.extend(exec_out.events);
         self.final_events.prune(self.config.max_final_events);
 
@@ -1331,6 +1451,7 @@ impl ExecutionState {
             {
                 // speculative execution front result matches what we want to compute
                 // apply the cached output and return
+                self.index_address_history(&exec_out, exec_target);
                 self.apply_final_execution_output(exec_out);
                 return;
             } else {
@@ -1357,6 +1478,7 @@ impl ExecutionState {
         let exec_out = self.execute_slot(slot, exec_target, selector);
 
         // apply execution output to final state
+        self.index_address_history(&exec_out, exec_target);
         self.apply_final_execution_output(exec_out);
 
         debug!(
@@ -1364,6 +1486,66 @@ impl ExecutionState {
         );
     }
 
+    /// Record the final history entries of the addresses involved in a final slot, if the address history is enabled
+    ///
+    /// # Arguments
+    /// * `exec_out`: final execution output of the slot
+    /// * `exec_target`: metadata of the block executed at this slot, if not miss
+    fn index_address_history(
+        &self,
+        exec_out: &ExecutionOutput,
+        exec_target: Option<&(BlockId, ExecutionBlockMetadata)>,
+    ) {
+        let Some(address_history) = &self.address_history else {
+            return;
+        };
+        let Some((block_id, Some(block_store))) = exec_target
+            .map(|(block_id, block_metadata)| (block_id, block_metadata.storage.as_ref()))
+        else {
+            return;
+        };
+        let Some(stored_block) = block_store.read_blocks().get(block_id).cloned() else {
+            return;
+        };
+        let operations: Vec<SecureShareOperation> = {
+            let ops = block_store.read_operations();
+            stored_block
+                .content
+                .operations
+                .iter()
+                .filter_map(|op_id| ops.get(op_id).cloned())
+                .collect()
+        };
+        let slashed: Vec<Address> = stored_block
+            .content
+            .header
+            .content
+            .denunciations
+            .iter()
+            .filter(|denunciation| {
+                exec_out
+                    .state_changes
+                    .executed_denunciations_changes
+                    .contains(&DenunciationIndex::from(*denunciation))
+            })
+            .map(|denunciation| Address::from_public_key(denunciation.get_public_key()))
+            .collect();
+        address_history.index_slot(
+            &exec_out.slot,
+            &operations,
+            &exec_out.state_changes.executed_ops_changes,
+            &slashed,
+        );
+    }
+
+    /// Gets the address history index.
+    /// It is returned as a shared handle so that it can be read after releasing the execution lock.
+    pub fn get_address_history(&self) -> Result<Arc<AddressHistory>, ExecutionQueryError> {
+        self.address_history.clone().ok_or_else(|| {
+            ExecutionQueryError::NotAvailable("address history is disabled".to_string())
+        })
+    }
+
     /// Runs a read-only execution request.
     /// The executed bytecode appears to be able to read and write the consensus state,
     /// but all accumulated changes are simply returned as an `ExecutionOutput` object,
@@ -1377,6 +1559,22 @@ impl ExecutionState {
     pub(crate) fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        // on failure, return the call tree recorded until the error along with it
+        self.run_readonly_request(req)
+            .map_err(|error| match context_guard!(self).trace.take() {
+                Some(trace) => ExecutionError::TracedError {
+                    trace: Box::new(trace.fail(error.to_string())),
+                    error: Box::new(error),
+                },
+                None => error,
+            })
+    }
+
+    /// Runs a read-only execution request, see `execute_readonly_request`
+    fn run_readonly_request(
+        &self,
+        req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
         // TODO ensure that speculative things are reset after every execution ends (incl. on error and readonly)
         // otherwise, on prod stats accumulation etc... from the API we might be counting the remainder of this speculative execution
@@ -1415,6 +1613,18 @@ impl ExecutionState {
 
                     let call_stack_addr = context.get_call_stack();
 
+                    if req.trace {
+                        if let Some(addr) = call_stack_addr.last() {
+                            context.trace = Some(CallTraceRecorder::new(
+                                None,
+                                *addr,
+                                Some("main".to_string()),
+                                Amount::zero(),
+                                req.max_gas,
+                            ));
+                        }
+                    }
+
                     // transfer fee
                     if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.get(0)) {
                         context.transfer_coins(Some(*addr), None, fee, false)?;
@@ -1455,11 +1665,21 @@ impl ExecutionState {
                     let mut context = context_guard!(self);
                     *context = execution_context;
 
+                    let call_stack_addr = context.get_call_stack();
+
+                    if req.trace {
+                        context.trace = Some(CallTraceRecorder::new(
+                            call_stack_addr.first().copied(),
+                            target_addr,
+                            Some(target_func.clone()),
+                            req.coins.unwrap_or_default(),
+                            req.max_gas,
+                        ));
+                    }
+
                     // Ensure that the target address is an SC address and exists
                     context.check_target_sc_address(target_addr)?;
 
-                    let call_stack_addr = context.get_call_stack();
-
                     // transfer fee
                     if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.get(0)) {
                         context.transfer_coins(Some(*addr), None, fee, false)?;
@@ -1507,7 +1727,14 @@ impl ExecutionState {
         };
 
         // return the execution output
-        let execution_output = context_guard!(self).settle_slot(None);
+        let (execution_output, trace) = {
+            let mut context = context_guard!(self);
+            let trace = context
+                .trace
+                .take()
+                .map(|trace| trace.finish(exec_response.remaining_gas));
+            (context.settle_slot(None), trace)
+        };
         let exact_exec_cost = req.max_gas.saturating_sub(exec_response.remaining_gas);
 
         // compute a gas cost, estimating the gas of the last SC call to be max_instance_cost
@@ -1534,9 +1761,44 @@ impl ExecutionState {
             out: execution_output,
             gas_cost: estimated_cost,
             call_result: exec_response.ret,
+            trace,
         })
     }
 
+    /// Gets the balance of an address at the end of a past final slot, from the ledger history
+    pub fn get_final_balance_at_slot(
+        &self,
+        address: &Address,
+        slot: &Slot,
+    ) -> Result<Option<Amount>, ExecutionQueryError> {
+        let ledger_archive = self.ledger_archive.as_ref().ok_or_else(|| {
+            ExecutionQueryError::NotAvailable("ledger history is disabled".to_string())
+        })?;
+        ledger_archive.get_balance_at_slot(
+            address,
+            slot,
+            self.final_state.read().get_ledger().as_ref(),
+        )
+    }
+
+    /// Gets a datastore value of an address at the end of a past final slot, from the ledger history
+    pub fn get_final_data_entry_at_slot(
+        &self,
+        address: &Address,
+        key: &[u8],
+        slot: &Slot,
+    ) -> Result<Option<Vec<u8>>, ExecutionQueryError> {
+        let ledger_archive = self.ledger_archive.as_ref().ok_or_else(|| {
+            ExecutionQueryError::NotAvailable("ledger history is disabled".to_string())
+        })?;
+        ledger_archive.get_data_entry_at_slot(
+            address,
+            key,
+            slot,
+            self.final_state.read().get_ledger().as_ref(),
+        )
+    }
+
     /// Gets a balance both at the latest final and candidate executed slots
     pub fn get_final_and_candidate_balance(
         &self,
@@ -1692,32 +1954,45 @@ impl ExecutionState {
     /// * original caller address
     /// * operation id
     /// * event state (final, candidate or both)
-    pub fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
-        match filter.is_final {
-            Some(true) => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .collect(),
-            Some(false) => self
-                .active_history
-                .read()
-                .0
-                .iter()
-                .flat_map(|item| item.events.get_filtered_sc_output_events(&filter))
-                .collect(),
-            None => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .chain(
-                    self.active_history
-                        .read()
-                        .0
-                        .iter()
-                        .flat_map(|item| item.events.get_filtered_sc_output_events(&filter)),
-                )
-                .collect(),
+    ///
+    /// The events kept in RAM are read right away. Final events are read from the event archive (if enabled)
+    /// when `paged` is true or when the requested start slot is older than the events kept in RAM.
+    /// Reading the archive is left to the returned `FilteredEvents`,
+    /// so that it can be done after releasing the lock on the execution state.
+    pub(crate) fn get_filtered_sc_output_event(
+        &self,
+        filter: &EventFilter,
+        paged: bool,
+    ) -> FilteredEvents {
+        let use_archive = self.event_archive.is_some()
+            && filter.is_final != Some(false)
+            && (paged
+                || match (filter.start, self.final_events.first_slot()) {
+                    (Some(start), Some(first_slot)) => start < first_slot,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                });
+        let mut events = Vec::new();
+        if filter.is_final != Some(false) && !use_archive {
+            events.extend(self.final_events.get_filtered_sc_output_events(filter));
+        }
+        if filter.is_final != Some(true) {
+            events.extend(
+                self.active_history
+                    .read()
+                    .0
+                    .iter()
+                    .flat_map(|item| item.events.get_filtered_sc_output_events(filter)),
+            );
+        }
+        FilteredEvents {
+            archive: self
+                .event_archive
+                .clone()
+                .filter(|_| use_archive)
+                .map(|event_archive| (event_archive, self.final_cursor)),
+            events,
+            max_events: self.config.max_final_events,
         }
     }
 
@@ -1894,3 +2169,50 @@ impl ExecutionState {
         );
     }
 }
+
+#[test]
+fn test_filtered_events_truncate_the_archived_events() {
+    use massa_models::output_event::EventExecutionContext;
+    use std::collections::VecDeque;
+
+    let event = |period: u64, is_final: bool| SCOutputEvent {
+        context: EventExecutionContext {
+            slot: Slot::new(period, 0),
+            block: None,
+            read_only: false,
+            index_in_slot: 0,
+            call_stack: VecDeque::new(),
+            origin_operation_id: None,
+            is_final,
+            is_error: false,
+        },
+        data: period.to_string(),
+    };
+    let tmp_dir = tempfile::TempDir::new().unwrap();
+    let archive = Arc::new(EventArchive::new(tmp_dir.path().to_path_buf(), 32, 1_000));
+    let archived: Vec<SCOutputEvent> = (0..5).map(|period| event(period, true)).collect();
+    archive.archive_events(archived.iter());
+
+    let filtered = |candidates: std::ops::Range<u64>| FilteredEvents {
+        archive: Some((archive.clone(), Slot::new(4, 0))),
+        events: candidates.map(|period| event(period, false)).collect(),
+        max_events: 4,
+    };
+    let data = |events: Vec<SCOutputEvent>| -> Vec<String> {
+        events.into_iter().map(|event| event.data).collect()
+    };
+    let filter = EventFilter {
+        start: Some(Slot::new(0, 0)),
+        ..Default::default()
+    };
+
+    // the candidate events are kept, the archived ones fill up the rest of the limit
+    assert_eq!(
+        data(filtered(5..7).into_events(&filter)),
+        vec!["0", "1", "5", "6"]
+    );
+    assert_eq!(
+        data(filtered(5..10).into_events(&filter)),
+        vec!["5", "6", "7", "8", "9"]
+    );
+}
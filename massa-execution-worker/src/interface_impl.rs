@@ -280,6 +280,9 @@ impl Interface for InterfaceImpl {
             owned_addresses: vec![to_address],
             operation_datastore: None,
         });
+        if let Some(trace) = context.trace.as_mut() {
+            trace.enter_call(from_address, to_address, coins);
+        }
 
         // return the target bytecode
         Ok(bytecode.0)
@@ -293,6 +296,9 @@ impl Interface for InterfaceImpl {
         if context.stack.pop().is_none() {
             bail!("call stack out of bounds")
         }
+        if let Some(trace) = context.trace.as_mut() {
+            trace.exit_call();
+        }
 
         Ok(())
     }
@@ -441,8 +447,11 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data(&self, key: &[u8]) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_read(addr, key);
+        }
         match context.get_data_entry(&addr, key) {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
@@ -461,7 +470,10 @@ impl Interface for InterfaceImpl {
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> Result<Vec<u8>> {
         let addr = &massa_models::address::Address::from_str(address)?;
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_read(*addr, key);
+        }
         match context.get_data_entry(addr, key) {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
@@ -477,8 +489,11 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn get_ds_value_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_read(address, key);
+        }
 
         match context.get_data_entry(&address, key) {
             Some(value) => Ok(value),
@@ -499,6 +514,9 @@ impl Interface for InterfaceImpl {
     fn raw_set_data(&self, key: &[u8], value: &[u8]) -> Result<()> {
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(addr, key);
+        }
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
     }
@@ -516,6 +534,9 @@ impl Interface for InterfaceImpl {
     fn raw_set_data_for(&self, address: &str, key: &[u8], value: &[u8]) -> Result<()> {
         let addr = massa_models::address::Address::from_str(address)?;
         let mut context = context_guard!(self);
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(addr, key);
+        }
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
     }
@@ -523,6 +544,9 @@ impl Interface for InterfaceImpl {
     fn set_ds_value_wasmv1(&self, key: &[u8], value: &[u8], address: Option<String>) -> Result<()> {
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(address, key);
+        }
 
         context.set_data_entry(&address, key.to_vec(), value.to_vec())?;
         Ok(())
@@ -539,6 +563,9 @@ impl Interface for InterfaceImpl {
     fn raw_append_data(&self, key: &[u8], value: &[u8]) -> Result<()> {
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(addr, key);
+        }
         context.append_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
     }
@@ -554,7 +581,11 @@ impl Interface for InterfaceImpl {
     /// [DeprecatedByNewRuntime] Replaced by `raw_append_data_wasmv1`
     fn raw_append_data_for(&self, address: &str, key: &[u8], value: &[u8]) -> Result<()> {
         let addr = massa_models::address::Address::from_str(address)?;
-        context_guard!(self).append_data_entry(&addr, key.to_vec(), value.to_vec())?;
+        let mut context = context_guard!(self);
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(addr, key);
+        }
+        context.append_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
     }
 
@@ -573,6 +604,9 @@ impl Interface for InterfaceImpl {
     ) -> Result<()> {
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(address, key);
+        }
 
         context.append_data_entry(&address, key.to_vec(), value.to_vec())?;
         Ok(())
@@ -588,6 +622,9 @@ impl Interface for InterfaceImpl {
     fn raw_delete_data(&self, key: &[u8]) -> Result<()> {
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(addr, key);
+        }
         context.delete_data_entry(&addr, key)?;
         Ok(())
     }
@@ -602,7 +639,11 @@ impl Interface for InterfaceImpl {
     /// [DeprecatedByNewRuntime] Replaced by `raw_delete_data_wasmv1`
     fn raw_delete_data_for(&self, address: &str, key: &[u8]) -> Result<()> {
         let addr = &massa_models::address::Address::from_str(address)?;
-        context_guard!(self).delete_data_entry(addr, key)?;
+        let mut context = context_guard!(self);
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(*addr, key);
+        }
+        context.delete_data_entry(addr, key)?;
         Ok(())
     }
 
@@ -615,6 +656,9 @@ impl Interface for InterfaceImpl {
     fn delete_ds_entry_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<()> {
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_write(address, key);
+        }
 
         context.delete_data_entry(&address, key)?;
         Ok(())
@@ -1083,6 +1127,9 @@ impl Interface for InterfaceImpl {
         };
 
         let mut context = context_guard!(self);
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_event(data.clone());
+        }
         let event = context.event_create(data, false);
         context.event_emit(event);
         Ok(())
@@ -1099,6 +1146,9 @@ impl Interface for InterfaceImpl {
 
         let data_str = String::from_utf8(data.clone()).unwrap_or(format!("{:?}", data));
         let mut context = context_guard!(self);
+        if let Some(trace) = context.trace.as_mut() {
+            trace.record_event(data_str.clone());
+        }
         let event = context.event_create(data_str, false);
         context.event_emit(event);
 
@@ -1376,6 +1426,9 @@ impl Interface for InterfaceImpl {
             owned_addresses: vec![to_address],
             operation_datastore: None,
         });
+        if let Some(trace) = context.trace.as_mut() {
+            trace.enter_call(from_address, to_address, coins);
+        }
 
         // return the target bytecode
         Ok(bytecode.0)
@@ -1680,6 +1733,9 @@ impl Interface for InterfaceImpl {
         match self.context.try_lock() {
             Some(mut context) => {
                 context.gas_remaining_before_subexecution = Some(gas_remaining);
+                if let Some(trace) = context.trace.as_mut() {
+                    trace.report_gas_remaining(gas_remaining);
+                }
             }
             None => {
                 warn!("Context is locked, cannot save gas remaining before subexecution");
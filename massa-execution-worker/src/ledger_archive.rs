@@ -0,0 +1,349 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements an optional on-disk archive of final ledger history.
+//!
+//! For every final slot, the values that balances and datastore entries had right before
+//! the ledger changes of that slot were applied are stored, indexed by address (and datastore key) then slot.
+//! The value of an entry at the end of a past slot S is then the previous value recorded
+//! at the first slot after S that changed it, or the current final value if it was not changed since.
+
+use massa_db_exports::{address_index_prefix, CF_ERROR, CRUD_ERROR, OPEN_ERROR};
+use massa_execution_exports::ExecutionQueryError;
+use massa_ledger_exports::{LedgerChanges, LedgerController, SetOrKeep, SetUpdateOrDelete};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use tracing::warn;
+
+const BALANCES_CF: &str = "balances";
+const DATASTORE_CF: &str = "datastore";
+const METADATA_CF: &str = "metadata";
+
+/// Slot at the end of which the archived history starts
+const START_SLOT_KEY: &[u8] = b"start_slot";
+/// Last slot whose changes were archived
+const LAST_SLOT_KEY: &[u8] = b"last_slot";
+
+/// Builds the prefix of the history of a datastore entry.
+/// The address is length-prefixed (see `address_index_prefix`), and the key as well (datastore keys are at most 255 bytes long).
+fn datastore_prefix(address: &Address, key: &[u8]) -> Vec<u8> {
+    let mut prefix = address_index_prefix(address);
+    prefix.push(key.len() as u8);
+    prefix.extend(key);
+    prefix
+}
+
+/// Serializes a previous value: a presence tag followed by the value
+fn encode_value(value: Option<&[u8]>) -> Vec<u8> {
+    match value {
+        Some(value) => [&[1u8][..], value].concat(),
+        None => vec![0u8],
+    }
+}
+
+/// Deserializes a previous value
+fn decode_value(bytes: &[u8]) -> Option<Vec<u8>> {
+    match bytes.split_first() {
+        Some((1, value)) => Some(value.to_vec()),
+        _ => None,
+    }
+}
+
+/// Deserializes a previous balance
+fn decode_balance(bytes: &[u8]) -> Option<Amount> {
+    decode_value(bytes)
+        .and_then(|value| value.try_into().ok())
+        .map(|raw| Amount::from_raw(u64::from_be_bytes(raw)))
+}
+
+/// On-disk history of final balances and datastore entries
+pub(crate) struct LedgerArchive {
+    /// RocksDB database
+    db: DB,
+    /// slot at the end of which the archived history starts
+    start_slot: Slot,
+    /// last slot whose changes were archived
+    last_slot: Slot,
+}
+
+impl LedgerArchive {
+    /// Open (or create) the ledger archive.
+    ///
+    /// If the archive does not end at the current final slot (for example because the node
+    /// bootstrapped or ran without the archive in the meantime), its history has gaps:
+    /// it is erased and restarts from the current final slot.
+    ///
+    /// # Arguments
+    /// * path: where to store the db
+    /// * final_slot: slot of the current final state
+    pub fn new(path: PathBuf, final_slot: Slot) -> Self {
+        let mut archive = LedgerArchive {
+            db: Self::open_db(&path),
+            start_slot: final_slot,
+            last_slot: final_slot,
+        };
+        let metadata_cf = archive.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let read_slot = |key: &[u8]| {
+            archive
+                .db
+                .get_cf(metadata_cf, key)
+                .expect(CRUD_ERROR)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(|bytes| Slot::from_bytes_key(&bytes))
+        };
+        let archived_slots = (read_slot(START_SLOT_KEY), read_slot(LAST_SLOT_KEY));
+        match archived_slots {
+            (Some(start_slot), Some(last_slot)) if last_slot == final_slot => {
+                archive.start_slot = start_slot;
+            }
+            (start_slot, _) => {
+                if start_slot.is_some() {
+                    warn!(
+                        "ledger archive does not end at the final slot {}: restarting its history",
+                        final_slot
+                    );
+                    drop(archive);
+                    DB::destroy(&Options::default(), &path).expect(CRUD_ERROR);
+                    archive = LedgerArchive {
+                        db: Self::open_db(&path),
+                        start_slot: final_slot,
+                        last_slot: final_slot,
+                    };
+                }
+                let metadata_cf = archive.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+                let mut batch = WriteBatch::default();
+                batch.put_cf(metadata_cf, START_SLOT_KEY, final_slot.to_bytes_key());
+                batch.put_cf(metadata_cf, LAST_SLOT_KEY, final_slot.to_bytes_key());
+                archive.db.write(batch).expect(CRUD_ERROR);
+            }
+        }
+        archive
+    }
+
+    fn open_db(path: &PathBuf) -> DB {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![
+                ColumnFamilyDescriptor::new(BALANCES_CF, Options::default()),
+                ColumnFamilyDescriptor::new(DATASTORE_CF, Options::default()),
+                ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR)
+    }
+
+    /// Archive the previous values of the entries changed by a final slot.
+    /// Must be called before the changes are applied to the final ledger.
+    ///
+    /// # Arguments
+    /// * slot: the final slot
+    /// * changes: the ledger changes of that slot
+    /// * ledger: the final ledger, as it was at the end of the previous slot
+    pub fn archive_slot_changes(
+        &mut self,
+        slot: Slot,
+        changes: &LedgerChanges,
+        ledger: &dyn LedgerController,
+    ) {
+        let balances_cf = self.db.cf_handle(BALANCES_CF).expect(CF_ERROR);
+        let datastore_cf = self.db.cf_handle(DATASTORE_CF).expect(CF_ERROR);
+        let metadata_cf = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let slot_key = slot.to_bytes_key();
+
+        let mut batch = WriteBatch::default();
+        for (address, change) in changes.0.iter() {
+            let (balance_changed, changed_keys): (bool, BTreeSet<Vec<u8>>) = match change {
+                // the whole entry is replaced or removed: all its previous keys are affected
+                SetUpdateOrDelete::Set(entry) => {
+                    let mut keys = ledger.get_datastore_keys(address, &[]).unwrap_or_default();
+                    keys.extend(entry.datastore.keys().cloned());
+                    (true, keys)
+                }
+                SetUpdateOrDelete::Update(update) => (
+                    matches!(update.balance, SetOrKeep::Set(_)),
+                    update.datastore.keys().cloned().collect(),
+                ),
+                SetUpdateOrDelete::Delete => (
+                    true,
+                    ledger.get_datastore_keys(address, &[]).unwrap_or_default(),
+                ),
+            };
+            if balance_changed {
+                let previous_balance = ledger
+                    .get_balance(address)
+                    .map(|balance| balance.to_raw().to_be_bytes());
+                batch.put_cf(
+                    balances_cf,
+                    [address_index_prefix(address), slot_key.to_vec()].concat(),
+                    encode_value(previous_balance.as_ref().map(|bytes| &bytes[..])),
+                );
+            }
+            for key in changed_keys {
+                let previous_value = ledger.get_data_entry(address, &key);
+                batch.put_cf(
+                    datastore_cf,
+                    [datastore_prefix(address, &key), slot_key.to_vec()].concat(),
+                    encode_value(previous_value.as_deref()),
+                );
+            }
+        }
+        batch.put_cf(metadata_cf, LAST_SLOT_KEY, slot_key);
+        self.db.write(batch).expect(CRUD_ERROR);
+        self.last_slot = slot;
+    }
+
+    /// Check that the state at the end of a slot can be rebuilt from the archive
+    fn check_slot(&self, slot: &Slot) -> Result<(), ExecutionQueryError> {
+        if *slot < self.start_slot || *slot > self.last_slot {
+            return Err(ExecutionQueryError::NotAvailable(format!(
+                "ledger history is only available from slot {} to slot {}",
+                self.start_slot, self.last_slot
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the previous value recorded at the first change strictly after `slot`,
+    /// or `None` if the entry was not changed since.
+    fn get_first_change_after(&self, cf: &str, prefix: Vec<u8>, slot: &Slot) -> Option<Vec<u8>> {
+        let handle = self.db.cf_handle(cf).expect(CF_ERROR);
+        let slot_key = slot.to_bytes_key();
+        let seek_key = [prefix.clone(), slot_key.to_vec()].concat();
+        self.db
+            .iterator_cf(handle, IteratorMode::From(&seek_key, Direction::Forward))
+            .flatten()
+            .take_while(|(key, _)| {
+                key.len() == prefix.len() + SLOT_KEY_SIZE && key.starts_with(&prefix)
+            })
+            .find(|(key, _)| key[prefix.len()..] != slot_key)
+            .map(|(_, value)| value.to_vec())
+    }
+
+    /// Get the balance of an address at the end of a past final slot
+    ///
+    /// # Returns
+    /// The balance, or None if the address did not exist at that time
+    pub fn get_balance_at_slot(
+        &self,
+        address: &Address,
+        slot: &Slot,
+        ledger: &dyn LedgerController,
+    ) -> Result<Option<Amount>, ExecutionQueryError> {
+        self.check_slot(slot)?;
+        match self.get_first_change_after(BALANCES_CF, address_index_prefix(address), slot) {
+            Some(previous) => Ok(decode_balance(&previous)),
+            None => Ok(ledger.get_balance(address)),
+        }
+    }
+
+    /// Get a datastore value of an address at the end of a past final slot
+    ///
+    /// # Returns
+    /// The value, or None if the entry did not exist at that time
+    pub fn get_data_entry_at_slot(
+        &self,
+        address: &Address,
+        key: &[u8],
+        slot: &Slot,
+        ledger: &dyn LedgerController,
+    ) -> Result<Option<Vec<u8>>, ExecutionQueryError> {
+        self.check_slot(slot)?;
+        match self.get_first_change_after(DATASTORE_CF, datastore_prefix(address, key), slot) {
+            Some(previous) => Ok(decode_value(&previous)),
+            None => Ok(ledger.get_data_entry(address, key)),
+        }
+    }
+}
+
+#[test]
+fn test_ledger_archive_history() {
+    use massa_ledger_exports::{LedgerEntryUpdate, MockLedgerController, SetOrDelete};
+    use massa_models::prehash::PreHashMap;
+    use massa_signature::KeyPair;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let key = b"key".to_vec();
+    let tmp_dir = tempfile::TempDir::new().unwrap();
+    let mut archive = LedgerArchive::new(tmp_dir.path().to_path_buf(), Slot::new(1, 0));
+
+    // the balance goes 10 -> 20 at slot (2, 0), then 20 -> 30 at slot (4, 0)
+    // the datastore entry is created with value 1 at slot (3, 0)
+    let update = |balance: Option<&str>, value: Option<u8>| {
+        let mut changes = LedgerChanges(PreHashMap::default());
+        changes.0.insert(
+            address,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: balance.map_or(SetOrKeep::Keep, |balance| {
+                    SetOrKeep::Set(Amount::from_str(balance).unwrap())
+                }),
+                bytecode: SetOrKeep::Keep,
+                datastore: value
+                    .map(|value| BTreeMap::from([(b"key".to_vec(), SetOrDelete::Set(vec![value]))]))
+                    .unwrap_or_default(),
+            }),
+        );
+        changes
+    };
+    let ledger_at = |balance: &'static str, value: Option<u8>| {
+        let mut ledger = MockLedgerController::new();
+        ledger
+            .expect_get_balance()
+            .returning(move |_| Some(Amount::from_str(balance).unwrap()));
+        ledger
+            .expect_get_data_entry()
+            .returning(move |_, _| value.map(|value| vec![value]));
+        ledger
+    };
+    archive.archive_slot_changes(
+        Slot::new(2, 0),
+        &update(Some("20"), None),
+        &ledger_at("10", None),
+    );
+    archive.archive_slot_changes(
+        Slot::new(3, 0),
+        &update(None, Some(1)),
+        &ledger_at("20", None),
+    );
+    archive.archive_slot_changes(
+        Slot::new(4, 0),
+        &update(Some("30"), None),
+        &ledger_at("20", Some(1)),
+    );
+    let final_ledger = ledger_at("30", Some(1));
+
+    let balance_at = |period| {
+        archive
+            .get_balance_at_slot(&address, &Slot::new(period, 0), &final_ledger)
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(balance_at(1), Amount::from_str("10").unwrap());
+    assert_eq!(balance_at(2), Amount::from_str("20").unwrap());
+    assert_eq!(balance_at(3), Amount::from_str("20").unwrap());
+    assert_eq!(balance_at(4), Amount::from_str("30").unwrap());
+
+    let value_at = |period| {
+        archive
+            .get_data_entry_at_slot(&address, &key, &Slot::new(period, 0), &final_ledger)
+            .unwrap()
+    };
+    assert_eq!(value_at(2), None);
+    assert_eq!(value_at(3), Some(vec![1]));
+
+    // out of the archived history
+    assert!(archive
+        .get_balance_at_slot(&address, &Slot::new(0, 0), &final_ledger)
+        .is_err());
+    assert!(archive
+        .get_balance_at_slot(&address, &Slot::new(5, 0), &final_ledger)
+        .is_err());
+}
@@ -75,15 +75,30 @@
 //!
 //! ## `stats.rs`
 //! Defines a structure that gathers execution statistics.
+//!
+//! ## `event_archive.rs`
+//! An optional on-disk archive of final execution events, indexed to answer historical event queries.
+//!
+//! ## `ledger_archive.rs`
+//! An optional on-disk history of final balances and datastore entries, used to rebuild their values at past final slots.
+//!
+//! ## `address_history.rs`
+//! An optional on-disk index of the final history of each address: created operations, received coins, roll buys and sells, slashes.
+//!
+//! ## `trace.rs`
+//! Records the call tree of read-only executions that request a trace.
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
 mod active_history;
+mod address_history;
 mod context;
 mod controller;
+mod event_archive;
 mod execution;
 mod interface_impl;
+mod ledger_archive;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
@@ -92,6 +107,7 @@ mod speculative_executed_ops;
 mod speculative_ledger;
 mod speculative_roll_state;
 mod stats;
+mod trace;
 mod worker;
 
 use massa_db_exports as _;
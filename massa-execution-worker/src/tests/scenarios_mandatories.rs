@@ -269,11 +269,17 @@ fn test_readonly_execution() {
             ),
             coins: None,
             fee: Some(Amount::from_str("40").unwrap()),
+            trace: true,
         })
         .expect("readonly execution failed");
 
     assert!(res.gas_cost > 0);
     assert_eq!(res.out.events.take().len(), 1, "wrong number of events");
+    let trace = res.trace.expect("missing execution trace");
+    assert_eq!(trace.callee, addr);
+    assert_eq!(trace.events.len(), 1, "wrong number of traced events");
+    assert!(trace.gas_used.is_some());
+    assert!(trace.error.is_none());
     assert_eq!(
         res.out.state_changes.ledger_changes.0.get(&addr).unwrap(),
         &SetUpdateOrDelete::Update(LedgerEntryUpdate {
@@ -314,6 +320,7 @@ fn test_readonly_execution() {
             },
             coins: Some(Amount::from_str("20").unwrap()),
             fee: Some(Amount::from_str("30").unwrap()),
+            trace: false,
         })
         .expect("readonly execution failed");
 
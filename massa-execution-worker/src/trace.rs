@@ -0,0 +1,168 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module records the call tree of traced read-only executions.
+//!
+//! Frames are opened by `init_call` and closed by `finish_call` in `interface_impl.rs`.
+//! The runtime does not report the remaining gas when a call returns, so the gas used by a nested frame
+//! is measured up to the next gas report of the runtime (the start of the next call, or the end of the execution).
+
+use massa_execution_exports::ExecutionCallTrace;
+use massa_models::{address::Address, amount::Amount};
+
+/// Records the call tree of a read-only execution
+pub(crate) struct CallTraceRecorder {
+    /// frames that are currently running, the entry frame first
+    open: Vec<ExecutionCallTrace>,
+}
+
+impl CallTraceRecorder {
+    /// Start recording with the entry frame of the execution
+    pub fn new(
+        caller: Option<Address>,
+        callee: Address,
+        function: Option<String>,
+        coins: Amount,
+        max_gas: u64,
+    ) -> Self {
+        let mut root = ExecutionCallTrace::new(caller, callee, function, coins);
+        root.gas_limit = Some(max_gas);
+        CallTraceRecorder { open: vec![root] }
+    }
+
+    /// Enter a nested call
+    pub fn enter_call(&mut self, caller: Address, callee: Address, coins: Amount) {
+        self.open
+            .push(ExecutionCallTrace::new(Some(caller), callee, None, coins));
+    }
+
+    /// Return from the current nested call
+    pub fn exit_call(&mut self) {
+        if self.open.len() < 2 {
+            return;
+        }
+        if let Some(frame) = self.open.pop() {
+            if let Some(parent) = self.open.last_mut() {
+                parent.calls.push(frame);
+            }
+        }
+    }
+
+    /// Process a gas report of the runtime, made when a frame starts running
+    pub fn report_gas_remaining(&mut self, gas_remaining: u64) {
+        self.settle_returned_calls(gas_remaining);
+        if let Some(frame) = self.open.last_mut() {
+            if frame.gas_limit.is_none() {
+                frame.gas_limit = Some(gas_remaining);
+            }
+        }
+    }
+
+    /// Record a datastore read in the current frame
+    pub fn record_read(&mut self, address: Address, key: &[u8]) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.datastore_reads.push((address, key.to_vec()));
+        }
+    }
+
+    /// Record a datastore write in the current frame
+    pub fn record_write(&mut self, address: Address, key: &[u8]) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.datastore_writes.push((address, key.to_vec()));
+        }
+    }
+
+    /// Record an event emitted by the current frame
+    pub fn record_event(&mut self, data: String) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.events.push(data);
+        }
+    }
+
+    /// Complete a successful execution and return its call tree
+    pub fn finish(mut self, gas_remaining: u64) -> ExecutionCallTrace {
+        self.settle_returned_calls(gas_remaining);
+        self.close_all();
+        let mut root = self.open.pop().expect("entry frame missing from the trace");
+        root.gas_used = root
+            .gas_limit
+            .map(|gas_limit| gas_limit.saturating_sub(gas_remaining));
+        root
+    }
+
+    /// Complete a failed execution and return its call tree.
+    /// The error is attached to the innermost frame that was still running.
+    pub fn fail(mut self, error: String) -> ExecutionCallTrace {
+        if let Some(frame) = self.open.last_mut() {
+            frame.error = Some(error);
+        }
+        self.close_all();
+        self.open.pop().expect("entry frame missing from the trace")
+    }
+
+    /// Close the frames that did not return, down to the entry frame
+    fn close_all(&mut self) {
+        while self.open.len() > 1 {
+            self.exit_call();
+        }
+    }
+
+    /// Set the gas used by the calls that returned since the last gas report
+    fn settle_returned_calls(&mut self, gas_remaining: u64) {
+        for frame in self.open.iter_mut() {
+            // calls that returned since the last report are at the end of the call lists
+            let mut last_call = frame.calls.last_mut();
+            while let Some(call) = last_call {
+                if call.gas_used.is_some() {
+                    break;
+                }
+                call.gas_used = call
+                    .gas_limit
+                    .map(|gas_limit| gas_limit.saturating_sub(gas_remaining));
+                last_call = call.calls.last_mut();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_call_trace_recorder() {
+    use massa_signature::KeyPair;
+
+    let user = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let sc = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    let mut recorder = CallTraceRecorder::new(
+        Some(user),
+        sc,
+        Some("main".to_string()),
+        Amount::zero(),
+        1000,
+    );
+    recorder.report_gas_remaining(900);
+    recorder.record_read(sc, b"a");
+    recorder.enter_call(sc, user, Amount::zero());
+    recorder.report_gas_remaining(800);
+    recorder.record_write(user, b"b");
+    recorder.record_event("nested".to_string());
+    recorder.exit_call();
+    recorder.enter_call(sc, user, Amount::zero());
+    recorder.report_gas_remaining(600);
+    recorder.exit_call();
+    let trace = recorder.finish(500);
+
+    assert_eq!(trace.gas_limit, Some(1000));
+    assert_eq!(trace.gas_used, Some(500));
+    assert_eq!(trace.datastore_reads, vec![(sc, b"a".to_vec())]);
+    assert_eq!(trace.calls.len(), 2);
+    assert_eq!(trace.calls[0].gas_used, Some(200));
+    assert_eq!(trace.calls[0].datastore_writes, vec![(user, b"b".to_vec())]);
+    assert_eq!(trace.calls[0].events, vec!["nested".to_string()]);
+    assert_eq!(trace.calls[1].gas_used, Some(100));
+
+    // an error is attached to the frame that was running when it happened
+    let mut recorder = CallTraceRecorder::new(None, sc, None, Amount::zero(), 1000);
+    recorder.enter_call(sc, user, Amount::zero());
+    let trace = recorder.fail("out of gas".to_string());
+    assert!(trace.error.is_none());
+    assert_eq!(trace.calls[0].error, Some("out of gas".to_string()));
+}
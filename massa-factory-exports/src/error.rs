@@ -10,4 +10,8 @@ pub type FactoryResult<T, E = FactoryError> = core::result::Result<T, E>;
 pub enum FactoryError {
     /// Generic error: {0}
     GenericError(String),
+    /// Signing history error: {0}
+    SigningHistoryError(String),
+    /// Double production refused: {0}
+    DoubleProductionError(String),
 }
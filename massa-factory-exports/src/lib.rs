@@ -9,11 +9,18 @@
 mod config;
 mod controller_traits;
 mod error;
+mod production_log;
+mod signing_history;
 mod types;
 
 pub use config::FactoryConfig;
 pub use controller_traits::FactoryManager;
 pub use error::*;
+pub use production_log::{
+    ProductionEvent, ProductionFailure, ProductionKind, ProductionLog, ProductionLogFilter,
+    ProductionLogStats, ProductionOutcome, ProductionReport,
+};
+pub use signing_history::{AddressSigningHistory, SigningHistory};
 pub use types::*;
 
 /// Tests utils
@@ -0,0 +1,288 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Log of the block and endorsement draws of the staking addresses, with what became of them.
+//!
+//! The factories record every draw of a managed address: whether the block or endorsement was produced,
+//! why it was not, and how long it took. The produced blocks are then followed until they are final or stale.
+
+use massa_models::{address::Address, block_id::BlockId, slot::Slot};
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// What was drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductionKind {
+    /// block production
+    Block,
+    /// endorsement production
+    Endorsement,
+}
+
+/// What became of a draw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductionOutcome {
+    /// produced and broadcast
+    Produced,
+    /// produced, and the block is final
+    Final,
+    /// produced, but the block was discarded
+    Stale,
+    /// not produced
+    Missed,
+}
+
+/// Why a draw was missed, or why a produced block went stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductionFailure {
+    /// the selector could not give the draws of the slot
+    SelectorError,
+    /// the node had no connections, and `stop_production_when_zero_connections` is set
+    NoConnections,
+    /// the signer could not be reached or could not sign
+    SignerError,
+    /// the signing history refused a possible double production
+    DoubleProductionRefused,
+    /// the signing history could not be written
+    SigningHistoryError,
+    /// the pool returned more operations than a block can hold
+    TooManyOperations,
+    /// the block was broadcast more than half a period after its slot start, and went stale
+    ProducedLate,
+    /// the block was broadcast in time, but went stale
+    Discarded,
+}
+
+/// A draw of a managed address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductionEvent {
+    /// slot of the draw
+    pub slot: Slot,
+    /// block or endorsement
+    pub kind: ProductionKind,
+    /// index of the endorsement
+    pub index: Option<u32>,
+    /// drawn address, unknown when the selector could not give the draws of the slot
+    pub address: Option<Address>,
+    /// what became of the draw
+    pub outcome: ProductionOutcome,
+    /// why the draw was missed or the block went stale
+    pub failure: Option<ProductionFailure>,
+    /// details of the failure
+    pub details: Option<String>,
+    /// time from the slot start to the broadcast. Endorsements are due half a period before their slot,
+    /// so their latency is counted from that time.
+    pub latency: Option<MassaTime>,
+    /// id of the produced block
+    pub block_id: Option<BlockId>,
+}
+
+/// Totals of the production log, since the node started
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductionLogStats {
+    /// blocks produced
+    pub blocks_produced: u64,
+    /// produced blocks that became final
+    pub blocks_final: u64,
+    /// produced blocks that went stale
+    pub blocks_stale: u64,
+    /// block draws missed
+    pub blocks_missed: u64,
+    /// endorsements produced
+    pub endorsements_produced: u64,
+    /// endorsement draws missed
+    pub endorsements_missed: u64,
+    /// missed draws and stale blocks by failure
+    pub failures: BTreeMap<ProductionFailure, u64>,
+    /// latency of the last produced block
+    pub last_block_latency: Option<MassaTime>,
+}
+
+/// Filter of the production log
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProductionLogFilter {
+    /// only the draws of these addresses
+    pub addresses: Option<Vec<Address>>,
+    /// only the draws of this kind
+    pub kind: Option<ProductionKind>,
+    /// only the missed draws and the stale blocks
+    #[serde(default)]
+    pub failed_only: bool,
+}
+
+/// Production log with its totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductionReport {
+    /// totals since the node started
+    pub stats: ProductionLogStats,
+    /// latest draws matching the filter, oldest first
+    pub events: Vec<ProductionEvent>,
+}
+
+/// Latest draws of the managed addresses
+#[derive(Debug, Clone)]
+pub struct ProductionLog {
+    /// maximum number of kept events
+    max_events: usize,
+    /// latest events, oldest first
+    events: VecDeque<ProductionEvent>,
+    /// totals
+    stats: ProductionLogStats,
+}
+
+impl ProductionLog {
+    /// Creates a log keeping the latest `max_events` draws
+    pub fn new(max_events: usize) -> Self {
+        ProductionLog {
+            max_events,
+            events: VecDeque::with_capacity(max_events),
+            stats: ProductionLogStats::default(),
+        }
+    }
+
+    /// Records a draw
+    pub fn push(&mut self, event: ProductionEvent) {
+        match (event.kind, event.outcome) {
+            (ProductionKind::Block, ProductionOutcome::Missed) => self.stats.blocks_missed += 1,
+            (ProductionKind::Block, _) => {
+                self.stats.blocks_produced += 1;
+                self.stats.last_block_latency = event.latency;
+            }
+            (ProductionKind::Endorsement, ProductionOutcome::Missed) => {
+                self.stats.endorsements_missed += 1
+            }
+            (ProductionKind::Endorsement, _) => self.stats.endorsements_produced += 1,
+        }
+        if let Some(failure) = event.failure {
+            *self.stats.failures.entry(failure).or_default() += 1;
+        }
+        if self.max_events == 0 {
+            return;
+        }
+        if self.events.len() >= self.max_events {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Produced blocks whose finality is not known yet, with their latency
+    pub fn get_pending_blocks(&self) -> Vec<(BlockId, Option<MassaTime>)> {
+        self.events
+            .iter()
+            .filter(|event| event.outcome == ProductionOutcome::Produced)
+            .filter_map(|event| event.block_id.map(|block_id| (block_id, event.latency)))
+            .collect()
+    }
+
+    /// Records that a produced block became final, or went stale because of `failure`
+    pub fn set_block_outcome(
+        &mut self,
+        block_id: &BlockId,
+        outcome: ProductionOutcome,
+        failure: Option<ProductionFailure>,
+    ) {
+        let Some(event) = self.events.iter_mut().find(|event| {
+            event.block_id.as_ref() == Some(block_id)
+                && event.outcome == ProductionOutcome::Produced
+        }) else {
+            return;
+        };
+        event.outcome = outcome;
+        event.failure = failure;
+        match outcome {
+            ProductionOutcome::Final => self.stats.blocks_final += 1,
+            ProductionOutcome::Stale => self.stats.blocks_stale += 1,
+            _ => {}
+        }
+        if let Some(failure) = failure {
+            *self.stats.failures.entry(failure).or_default() += 1;
+        }
+    }
+
+    /// Latest draws matching a filter, oldest first
+    pub fn get_events(&self, filter: &ProductionLogFilter) -> Vec<ProductionEvent> {
+        self.events
+            .iter()
+            .filter(|event| {
+                filter.addresses.as_ref().map_or(true, |addresses| {
+                    event
+                        .address
+                        .map_or(false, |address| addresses.contains(&address))
+                })
+            })
+            .filter(|event| filter.kind.map_or(true, |kind| event.kind == kind))
+            .filter(|event| !filter.failed_only || event.failure.is_some())
+            .cloned()
+            .collect()
+    }
+
+    /// Totals since the node started
+    pub fn get_stats(&self) -> ProductionLogStats {
+        self.stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+
+    fn event(slot: Slot, outcome: ProductionOutcome, block_id: Option<BlockId>) -> ProductionEvent {
+        ProductionEvent {
+            slot,
+            kind: ProductionKind::Block,
+            index: None,
+            address: None,
+            outcome,
+            failure: (outcome == ProductionOutcome::Missed)
+                .then_some(ProductionFailure::NoConnections),
+            details: None,
+            latency: Some(MassaTime::from_millis(120)),
+            block_id,
+        }
+    }
+
+    #[test]
+    fn test_production_log() {
+        let block_id = BlockId::generate_from_hash(Hash::compute_from(b"block"));
+        let mut log = ProductionLog::new(2);
+        log.push(event(Slot::new(1, 0), ProductionOutcome::Missed, None));
+        log.push(event(
+            Slot::new(2, 0),
+            ProductionOutcome::Produced,
+            Some(block_id),
+        ));
+        log.push(event(Slot::new(3, 0), ProductionOutcome::Missed, None));
+
+        assert_eq!(
+            log.get_pending_blocks(),
+            vec![(block_id, Some(MassaTime::from_millis(120)))]
+        );
+        log.set_block_outcome(
+            &block_id,
+            ProductionOutcome::Stale,
+            Some(ProductionFailure::ProducedLate),
+        );
+        assert!(log.get_pending_blocks().is_empty());
+
+        let stats = log.get_stats();
+        assert_eq!(stats.blocks_produced, 1);
+        assert_eq!(stats.blocks_stale, 1);
+        assert_eq!(stats.blocks_missed, 2);
+        assert_eq!(stats.failures[&ProductionFailure::NoConnections], 2);
+        assert_eq!(stats.failures[&ProductionFailure::ProducedLate], 1);
+
+        // only the latest events are kept
+        let events = log.get_events(&ProductionLogFilter::default());
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].slot, Slot::new(2, 0));
+        let events = log.get_events(&ProductionLogFilter {
+            kind: Some(ProductionKind::Endorsement),
+            ..Default::default()
+        });
+        assert!(events.is_empty());
+    }
+}
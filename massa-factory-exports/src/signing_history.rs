@@ -0,0 +1,360 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! On-disk history of the blocks and endorsements signed by the staking addresses.
+//!
+//! The factories consult it before every signature and refuse to sign anything that could conflict with what
+//! was already signed, even before a restart or on another host: a block at a slot that is not above the highest
+//! block slot signed by the address, or an endorsement at a slot below its last endorsed slot, or at the same
+//! slot and index. The history is written to disk before the signature is released. The endorsements of all the
+//! staking addresses at a slot are recorded together, with a single write.
+//!
+//! The history file, and the exports made to move it to another host, are JSON objects with a `version`
+//! (currently 1) and an `addresses` list of `[address, history]` pairs, where the history holds the
+//! `highest_block_slot`, the `last_endorsed_slot` and the `last_endorsed_indexes` of the address.
+//! A file is replaced atomically: the new content is written and synced to a `.tmp` file next to it, which is
+//! then renamed over it. If it cannot be written, the new record is rolled back and the signature refused.
+
+use crate::{FactoryError, FactoryResult};
+use massa_models::{address::Address, prehash::PreHashMap, slot::Slot};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const SIGNING_HISTORY_VERSION: u64 = 1;
+
+/// What an address signed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressSigningHistory {
+    /// highest slot of the blocks signed by the address
+    pub highest_block_slot: Option<Slot>,
+    /// highest slot of the endorsements signed by the address
+    pub last_endorsed_slot: Option<Slot>,
+    /// indexes of the endorsements signed by the address at `last_endorsed_slot`
+    pub last_endorsed_indexes: BTreeSet<u32>,
+}
+
+impl AddressSigningHistory {
+    /// Merge another history of the same address, keeping the most restrictive watermarks
+    fn merge(&mut self, other: AddressSigningHistory) {
+        self.highest_block_slot = self.highest_block_slot.max(other.highest_block_slot);
+        match self.last_endorsed_slot.cmp(&other.last_endorsed_slot) {
+            std::cmp::Ordering::Less => {
+                self.last_endorsed_slot = other.last_endorsed_slot;
+                self.last_endorsed_indexes = other.last_endorsed_indexes;
+            }
+            std::cmp::Ordering::Equal => {
+                self.last_endorsed_indexes
+                    .extend(other.last_endorsed_indexes);
+            }
+            std::cmp::Ordering::Greater => {}
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+/// Signing history file, also used to export the history to another host
+struct SigningHistoryFileFormat {
+    version: u64,
+    addresses: Vec<(Address, AddressSigningHistory)>,
+}
+
+/// History of the blocks and endorsements signed by the staking addresses, persisted in a file
+#[derive(Debug)]
+pub struct SigningHistory {
+    /// path of the history file
+    path: PathBuf,
+    /// history of each address
+    addresses: PreHashMap<Address, AddressSigningHistory>,
+}
+
+/// Read a signing history file
+fn read_file(path: &Path) -> FactoryResult<PreHashMap<Address, AddressSigningHistory>> {
+    let content = std::fs::read(path).map_err(|err| {
+        FactoryError::SigningHistoryError(format!("could not read {}: {}", path.display(), err))
+    })?;
+    let file: SigningHistoryFileFormat = serde_json::from_slice(&content).map_err(|err| {
+        FactoryError::SigningHistoryError(format!("could not parse {}: {}", path.display(), err))
+    })?;
+    if file.version != SIGNING_HISTORY_VERSION {
+        return Err(FactoryError::SigningHistoryError(format!(
+            "unsupported signing history version {} in {}",
+            file.version,
+            path.display()
+        )));
+    }
+    Ok(file.addresses.into_iter().collect())
+}
+
+impl SigningHistory {
+    /// Load the signing history from its file, starting an empty one if the file does not exist
+    pub fn load(path: PathBuf) -> FactoryResult<Self> {
+        let addresses = if path.exists() {
+            read_file(&path)?
+        } else {
+            PreHashMap::default()
+        };
+        Ok(SigningHistory { path, addresses })
+    }
+
+    /// Get the history of an address
+    pub fn get(&self, address: &Address) -> Option<&AddressSigningHistory> {
+        self.addresses.get(address)
+    }
+
+    /// Check that `address` can sign a block at `slot`, and record it.
+    /// Returns an error, and records nothing, if the block could be a double production.
+    pub fn check_and_record_block(&mut self, address: Address, slot: Slot) -> FactoryResult<()> {
+        let history = self.addresses.entry(address).or_default();
+        if let Some(highest_slot) = history.highest_block_slot {
+            if slot <= highest_slot {
+                return Err(FactoryError::DoubleProductionError(format!(
+                    "address {} already signed a block at slot {}, refusing to sign a block at slot {}",
+                    address, highest_slot, slot
+                )));
+            }
+        }
+        let previous = history.highest_block_slot.replace(slot);
+        self.save().map_err(|err| {
+            // do not sign what could not be recorded
+            if let Some(history) = self.addresses.get_mut(&address) {
+                history.highest_block_slot = previous;
+            }
+            err
+        })
+    }
+
+    /// Check that `address` can sign the endorsement of index `index` at `slot`, and record it.
+    /// Returns an error, and records nothing, if the endorsement could be a double production.
+    pub fn check_and_record_endorsement(
+        &mut self,
+        address: Address,
+        slot: Slot,
+        index: u32,
+    ) -> FactoryResult<()> {
+        self.check_and_record_endorsements(slot, &[(address, index)])
+            .remove(0)
+    }
+
+    /// Check that each address of `draws` can sign its endorsement at `slot`, and record the ones it can
+    /// with a single write of the history.
+    /// Returns the result of each draw, in order: an error, and nothing recorded, if the endorsement could be a
+    /// double production. If the history cannot be written, none of the draws is recorded.
+    pub fn check_and_record_endorsements(
+        &mut self,
+        slot: Slot,
+        draws: &[(Address, u32)],
+    ) -> Vec<FactoryResult<()>> {
+        let mut previous: PreHashMap<Address, AddressSigningHistory> = PreHashMap::default();
+        let mut results = Vec::with_capacity(draws.len());
+        for (address, index) in draws {
+            let history = self.addresses.entry(*address).or_default();
+            previous.entry(*address).or_insert_with(|| history.clone());
+            let result = match history.last_endorsed_slot {
+                Some(last_slot) if slot < last_slot => Err(FactoryError::DoubleProductionError(format!(
+                    "address {} already signed an endorsement at slot {}, refusing to sign an endorsement at slot {}",
+                    address, last_slot, slot
+                ))),
+                Some(last_slot) if slot == last_slot => {
+                    if history.last_endorsed_indexes.insert(*index) {
+                        Ok(())
+                    } else {
+                        Err(FactoryError::DoubleProductionError(format!(
+                            "address {} already signed the endorsement of index {} at slot {}",
+                            address, index, slot
+                        )))
+                    }
+                }
+                _ => {
+                    history.last_endorsed_slot = Some(slot);
+                    history.last_endorsed_indexes = BTreeSet::from([*index]);
+                    Ok(())
+                }
+            };
+            results.push(result);
+        }
+        if !results.iter().any(Result::is_ok) {
+            return results;
+        }
+        if let Err(err) = self.save() {
+            // do not sign what could not be recorded
+            self.addresses.extend(previous);
+            let message = err.to_string();
+            for result in results.iter_mut().filter(|result| result.is_ok()) {
+                *result = Err(FactoryError::SigningHistoryError(message.clone()));
+            }
+        }
+        results
+    }
+
+    /// Write the history to its file
+    pub fn save(&self) -> FactoryResult<()> {
+        self.write_file(&self.path)
+    }
+
+    /// Export the history to a file, to be imported on another host
+    pub fn export(&self, path: &Path) -> FactoryResult<()> {
+        self.write_file(path)
+    }
+
+    /// Merge an exported history into this one, keeping the most restrictive watermarks of each address,
+    /// and write the result to the history file
+    pub fn import(&mut self, path: &Path) -> FactoryResult<()> {
+        for (address, history) in read_file(path)? {
+            self.addresses.entry(address).or_default().merge(history);
+        }
+        self.save()
+    }
+
+    /// Write the history to a file, through a temporary file so that a crash never leaves a truncated history
+    fn write_file(&self, path: &Path) -> FactoryResult<()> {
+        let error = |err: std::io::Error| {
+            FactoryError::SigningHistoryError(format!(
+                "could not write {}: {}",
+                path.display(),
+                err
+            ))
+        };
+        let file = SigningHistoryFileFormat {
+            version: SIGNING_HISTORY_VERSION,
+            addresses: self
+                .addresses
+                .iter()
+                .map(|(address, history)| (*address, history.clone()))
+                .collect(),
+        };
+        let content = serde_json::to_vec_pretty(&file)
+            .map_err(|err| FactoryError::SigningHistoryError(err.to_string()))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(error)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        let mut tmp_file = File::create(&tmp_path).map_err(error)?;
+        tmp_file.write_all(&content).map_err(error)?;
+        tmp_file.sync_all().map_err(error)?;
+        std::fs::rename(&tmp_path, path).map_err(error)?;
+        // sync the directory too, so that the rename survives a crash
+        #[cfg(unix)]
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            File::open(parent)
+                .and_then(|dir| dir.sync_all())
+                .map_err(error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_refuse_double_production_after_reload() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("signing_history.json");
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let mut history = SigningHistory::load(path.clone()).unwrap();
+        history
+            .check_and_record_block(address, Slot::new(10, 3))
+            .unwrap();
+        let results =
+            history.check_and_record_endorsements(Slot::new(10, 3), &[(address, 4), (address, 7)]);
+        assert!(results.iter().all(Result::is_ok));
+        // the same endorsement twice in a batch is signed once
+        let results =
+            history.check_and_record_endorsements(Slot::new(10, 3), &[(address, 8), (address, 8)]);
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(FactoryError::DoubleProductionError(_))
+        ));
+
+        let mut history = SigningHistory::load(path).unwrap();
+        assert!(history
+            .check_and_record_block(address, Slot::new(10, 3))
+            .is_err());
+        assert!(history
+            .check_and_record_block(address, Slot::new(9, 5))
+            .is_err());
+        history
+            .check_and_record_block(address, Slot::new(10, 4))
+            .unwrap();
+        assert!(history
+            .check_and_record_endorsement(address, Slot::new(10, 3), 4)
+            .is_err());
+        assert!(history
+            .check_and_record_endorsement(address, Slot::new(10, 3), 8)
+            .is_err());
+        assert!(history
+            .check_and_record_endorsement(address, Slot::new(10, 2), 0)
+            .is_err());
+        history
+            .check_and_record_endorsement(address, Slot::new(10, 3), 5)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_unsaved_record_is_rolled_back() {
+        let folder = TempDir::new().unwrap();
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        // the parent of the history file is a file, so the history cannot be written
+        let parent = folder.path().join("not_a_directory");
+        std::fs::write(&parent, "").unwrap();
+
+        let mut history = SigningHistory::load(parent.join("signing_history.json")).unwrap();
+        assert!(history
+            .check_and_record_block(address, Slot::new(10, 3))
+            .is_err());
+        let other = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let results =
+            history.check_and_record_endorsements(Slot::new(10, 3), &[(address, 4), (other, 5)]);
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Err(FactoryError::SigningHistoryError(_)))));
+        assert_eq!(history.get(&other), Some(&AddressSigningHistory::default()));
+        assert_eq!(
+            history.get(&address),
+            Some(&AddressSigningHistory::default())
+        );
+    }
+
+    #[test]
+    fn test_import_keeps_highest_watermarks() {
+        let folder = TempDir::new().unwrap();
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let mut first = SigningHistory::load(folder.path().join("first.json")).unwrap();
+        first
+            .check_and_record_block(address, Slot::new(5, 0))
+            .unwrap();
+        first
+            .check_and_record_endorsement(address, Slot::new(8, 1), 2)
+            .unwrap();
+        let mut second = SigningHistory::load(folder.path().join("second.json")).unwrap();
+        second
+            .check_and_record_block(address, Slot::new(7, 0))
+            .unwrap();
+        second
+            .check_and_record_endorsement(address, Slot::new(8, 1), 3)
+            .unwrap();
+
+        let export_path = folder.path().join("export.json");
+        second.export(&export_path).unwrap();
+        first.import(&export_path).unwrap();
+        assert_eq!(
+            first.get(&address),
+            Some(&AddressSigningHistory {
+                highest_block_slot: Some(Slot::new(7, 0)),
+                last_endorsed_slot: Some(Slot::new(8, 1)),
+                last_endorsed_indexes: BTreeSet::from([2, 3]),
+            })
+        );
+    }
+}
@@ -1,9 +1,13 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_channel::receiver::MassaReceiver;
-use massa_factory_exports::{FactoryChannels, FactoryConfig};
+use massa_factory_exports::{
+    FactoryChannels, FactoryConfig, FactoryError, ProductionEvent, ProductionFailure,
+    ProductionKind, ProductionLog, ProductionOutcome, SigningHistory,
+};
 use massa_models::{
-    block::{Block, BlockSerializer},
+    address::Address,
+    block::{Block, BlockGraphStatus, BlockSerializer},
     block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
     block_id::BlockId,
     endorsement::SecureShareEndorsement,
@@ -12,21 +16,26 @@ use massa_models::{
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
 };
+use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
-use massa_wallet::Wallet;
-use parking_lot::RwLock;
+use massa_wallet::{sign_content, Signer};
+use parking_lot::{Mutex, RwLock};
 use std::{sync::Arc, thread, time::Instant};
 use tracing::{info, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct BlockFactoryWorker {
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
+    signing_history: Arc<Mutex<SigningHistory>>,
+    production_log: Arc<RwLock<ProductionLog>>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
     mip_store: MipStore,
     op_id_serializer: OperationIdSerializer,
+    /// keypair required by `Block::new_verifiable`, which ignores it and takes the signature of the block header
+    block_keypair: KeyPair,
 }
 
 impl BlockFactoryWorker {
@@ -34,7 +43,9 @@ impl BlockFactoryWorker {
     /// needed by the factory worker thread.
     pub(crate) fn spawn(
         cfg: FactoryConfig,
-        wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
+        signing_history: Arc<Mutex<SigningHistory>>,
+        production_log: Arc<RwLock<ProductionLog>>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
         mip_store: MipStore,
@@ -44,11 +55,14 @@ impl BlockFactoryWorker {
             .spawn(|| {
                 let mut this = Self {
                     cfg,
-                    wallet,
+                    signer,
+                    signing_history,
+                    production_log,
                     channels,
                     factory_receiver,
                     mip_store,
                     op_id_serializer: OperationIdSerializer::new(),
+                    block_keypair: KeyPair::generate(0).expect("failed to generate keypair"),
                 };
                 this.run();
             })
@@ -120,8 +134,64 @@ impl BlockFactoryWorker {
         }
     }
 
+    /// Record in the production log a block draw that was missed
+    fn log_missed_block(
+        &self,
+        slot: Slot,
+        address: Option<Address>,
+        failure: ProductionFailure,
+        details: String,
+    ) {
+        self.production_log.write().push(ProductionEvent {
+            slot,
+            kind: ProductionKind::Block,
+            index: None,
+            address,
+            outcome: ProductionOutcome::Missed,
+            failure: Some(failure),
+            details: Some(details),
+            latency: None,
+            block_id: None,
+        });
+    }
+
+    /// Follow the blocks produced earlier, recording in the production log those that became final or went stale
+    fn update_produced_blocks(&self) {
+        let pending_blocks = self.production_log.read().get_pending_blocks();
+        if pending_blocks.is_empty() {
+            return;
+        }
+        let block_ids: Vec<BlockId> = pending_blocks.iter().map(|(id, _)| *id).collect();
+        let statuses = self.channels.consensus.get_block_statuses(&block_ids);
+        let late_threshold = self.cfg.t0.checked_div_u64(2).unwrap_or(self.cfg.t0);
+        let mut production_log = self.production_log.write();
+        for ((block_id, latency), status) in pending_blocks.into_iter().zip(statuses) {
+            match status {
+                BlockGraphStatus::Final => {
+                    production_log.set_block_outcome(&block_id, ProductionOutcome::Final, None)
+                }
+                BlockGraphStatus::Discarded => {
+                    let failure = if latency.map_or(false, |latency| latency > late_threshold) {
+                        ProductionFailure::ProducedLate
+                    } else {
+                        ProductionFailure::Discarded
+                    };
+                    production_log.set_block_outcome(
+                        &block_id,
+                        ProductionOutcome::Stale,
+                        Some(failure),
+                    )
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Process a slot: produce a block at that slot if one of the managed keys is drawn.
     fn process_slot(&mut self, slot: Slot) {
+        // follow the blocks produced at earlier slots
+        self.update_produced_blocks();
+
         // get block producer address for that slot
         let block_producer_addr = match self.channels.selector.get_producer(slot) {
             Ok(addr) => addr,
@@ -130,21 +200,36 @@ impl BlockFactoryWorker {
                     "block factory could not get selector draws for slot {}: {}",
                     slot, err
                 );
+                self.log_missed_block(
+                    slot,
+                    None,
+                    ProductionFailure::SelectorError,
+                    err.to_string(),
+                );
                 return;
             }
         };
 
-        // check if the block producer address is handled by the wallet
-        let block_producer_keypair_ref = self.wallet.read();
-        let block_producer_keypair = if let Some(kp) =
-            block_producer_keypair_ref.find_associated_keypair(&block_producer_addr)
-        {
+        // check if the block producer address is handled by the signer
+        match self.signer.get_public_key(&block_producer_addr) {
             // the selected block producer is managed locally => continue to attempt block production
-            kp
-        } else {
+            Ok(Some(_)) => {}
             // the selected block producer is not managed locally => quit
-            return;
-        };
+            Ok(None) => return,
+            Err(err) => {
+                warn!(
+                    "block factory could not check the key of {} for slot {}: {}",
+                    block_producer_addr, slot, err
+                );
+                self.log_missed_block(
+                    slot,
+                    Some(block_producer_addr),
+                    ProductionFailure::SignerError,
+                    err.to_string(),
+                );
+                return;
+            }
+        }
         let mut block_storage = self.channels.storage.clone_without_refs();
         {
             let block_lock = block_storage.read_blocks();
@@ -165,6 +250,12 @@ impl BlockFactoryWorker {
             if let Ok(stats) = self.channels.protocol.get_stats() {
                 if stats.1.is_empty() {
                     warn!("block factory could not produce block for slot {} because there are no connections", slot);
+                    self.log_missed_block(
+                        slot,
+                        Some(block_producer_addr),
+                        ProductionFailure::NoConnections,
+                        "no connections".to_string(),
+                    );
                     return;
                 }
             }
@@ -203,15 +294,47 @@ impl BlockFactoryWorker {
         let (op_ids, op_storage) = self.channels.pool.get_block_operations(&slot);
         if op_ids.len() > self.cfg.max_operations_per_block as usize {
             warn!("Too many operations returned");
+            self.log_missed_block(
+                slot,
+                Some(block_producer_addr),
+                ProductionFailure::TooManyOperations,
+                format!(
+                    "the pool returned {} operations, the maximum is {}",
+                    op_ids.len(),
+                    self.cfg.max_operations_per_block
+                ),
+            );
             return;
         }
 
         block_storage.extend(op_storage);
 
+        // record the block in the signing history before signing it, unless the address already signed a block at this slot or a later one
+        if let Err(err) = self
+            .signing_history
+            .lock()
+            .check_and_record_block(block_producer_addr, slot)
+        {
+            warn!(
+                "block factory did not produce block for slot {}: {}",
+                slot, err
+            );
+            let failure = match err {
+                FactoryError::DoubleProductionError(_) => {
+                    ProductionFailure::DoubleProductionRefused
+                }
+                _ => ProductionFailure::SigningHistoryError,
+            };
+            self.log_missed_block(slot, Some(block_producer_addr), failure, err.to_string());
+            return;
+        }
+
         // create header
         let current_version = self.mip_store.get_network_version_current();
         let announced_version = self.mip_store.get_network_version_to_announce();
-        let header: SecuredHeader = BlockHeader::new_verifiable::<BlockHeaderSerializer, BlockId>(
+        let header: SecuredHeader = match sign_content(
+            self.signer.as_ref(),
+            &block_producer_addr,
             BlockHeader {
                 current_version,
                 announced_version,
@@ -222,10 +345,23 @@ impl BlockFactoryWorker {
                 denunciations: self.channels.pool.get_block_denunciations(&slot),
             },
             BlockHeaderSerializer::new(), // TODO reuse self.block_header_serializer
-            block_producer_keypair,
             self.cfg.chain_id,
-        )
-        .expect("error while producing block header");
+        ) {
+            Ok(header) => header,
+            Err(err) => {
+                warn!(
+                    "block factory could not sign block header for slot {}: {}",
+                    slot, err
+                );
+                self.log_missed_block(
+                    slot,
+                    Some(block_producer_addr),
+                    ProductionFailure::SignerError,
+                    err.to_string(),
+                );
+                return;
+            }
+        };
         // create block
         let block_ = Block {
             header,
@@ -235,7 +371,7 @@ impl BlockFactoryWorker {
         let block = Block::new_verifiable(
             block_,
             BlockSerializer::new(), // TODO reuse self.block_serializer
-            block_producer_keypair,
+            &self.block_keypair,
             self.cfg.chain_id,
         )
         .expect("error while producing block");
@@ -253,6 +389,27 @@ impl BlockFactoryWorker {
         self.channels
             .consensus
             .register_block(block_id, slot, block_storage, true);
+
+        // record the production, with the time elapsed since the slot start
+        let latency = get_block_slot_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            slot,
+        )
+        .ok()
+        .map(|slot_timestamp| MassaTime::now().saturating_sub(slot_timestamp));
+        self.production_log.write().push(ProductionEvent {
+            slot,
+            kind: ProductionKind::Block,
+            index: None,
+            address: Some(block_producer_addr),
+            outcome: ProductionOutcome::Produced,
+            failure: None,
+            details: None,
+            latency,
+            block_id: Some(block_id),
+        });
     }
 
     /// main run loop of the block creator thread
@@ -1,25 +1,29 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_channel::receiver::MassaReceiver;
-use massa_factory_exports::{FactoryChannels, FactoryConfig};
+use massa_factory_exports::{
+    FactoryChannels, FactoryConfig, FactoryError, ProductionEvent, ProductionFailure,
+    ProductionKind, ProductionLog, ProductionOutcome, SigningHistory,
+};
 use massa_models::{
+    address::Address,
     block_id::BlockId,
     endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
-    secure_share::SecureShareContent,
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
 };
-use massa_signature::KeyPair;
 use massa_time::MassaTime;
-use massa_wallet::Wallet;
-use parking_lot::RwLock;
+use massa_wallet::{sign_content, Signer};
+use parking_lot::{Mutex, RwLock};
 use std::{sync::Arc, thread, time::Instant};
 use tracing::{debug, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct EndorsementFactoryWorker {
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
+    signing_history: Arc<Mutex<SigningHistory>>,
+    production_log: Arc<RwLock<ProductionLog>>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
     half_t0: MassaTime,
@@ -31,7 +35,9 @@ impl EndorsementFactoryWorker {
     /// needed by the factory worker thread.
     pub(crate) fn spawn(
         cfg: FactoryConfig,
-        wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
+        signing_history: Arc<Mutex<SigningHistory>>,
+        production_log: Arc<RwLock<ProductionLog>>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
     ) -> thread::JoinHandle<()> {
@@ -44,7 +50,9 @@ impl EndorsementFactoryWorker {
                         .checked_div_u64(2)
                         .expect("could not compute half_t0"),
                     cfg,
-                    wallet,
+                    signer,
+                    signing_history,
+                    production_log,
                     channels,
                     factory_receiver,
                     endorsement_serializer: EndorsementSerializer::new(),
@@ -120,6 +128,27 @@ impl EndorsementFactoryWorker {
         }
     }
 
+    /// Record in the production log an endorsement draw that was missed
+    fn log_missed_endorsement(
+        &self,
+        slot: Slot,
+        draw: Option<(Address, usize)>,
+        failure: ProductionFailure,
+        details: String,
+    ) {
+        self.production_log.write().push(ProductionEvent {
+            slot,
+            kind: ProductionKind::Endorsement,
+            index: draw.map(|(_, index)| index as u32),
+            address: draw.map(|(address, _)| address),
+            outcome: ProductionOutcome::Missed,
+            failure: Some(failure),
+            details: Some(details),
+            latency: None,
+            block_id: None,
+        });
+    }
+
     /// Process a slot: produce an endorsement at that slot if one of the managed keys is drawn.
     fn process_slot(&mut self, slot: Slot) {
         // get endorsement producer addresses for that slot
@@ -130,25 +159,37 @@ impl EndorsementFactoryWorker {
                     "endorsement factory could not get selector draws for slot {}: {}",
                     slot, err
                 );
+                self.log_missed_endorsement(
+                    slot,
+                    None,
+                    ProductionFailure::SelectorError,
+                    err.to_string(),
+                );
                 return;
             }
         };
 
-        // get creators if they are managed by our wallet
-        let mut producers_indices: Vec<(KeyPair, usize)> = Vec::new();
-        {
-            let wallet = self.wallet.read();
-            for (index, producer_addr) in producer_addrs.into_iter().enumerate() {
-                // check if the block producer address is handled by the wallet
-                let producer_keypair =
-                    if let Some(kp) = wallet.find_associated_keypair(&producer_addr) {
-                        // the selected block producer is managed locally => continue to attempt endorsement production
-                        kp.clone()
-                    } else {
-                        // the selected block producer is not managed locally => continue
-                        continue;
-                    };
-                producers_indices.push((producer_keypair, index));
+        // get creators if they are managed by our signer
+        let mut producers_indices: Vec<(Address, usize)> = Vec::new();
+        for (index, producer_addr) in producer_addrs.into_iter().enumerate() {
+            // check if the endorsement producer address is handled by the signer
+            match self.signer.get_public_key(&producer_addr) {
+                // the selected endorsement producer is managed locally => continue to attempt endorsement production
+                Ok(Some(_)) => producers_indices.push((producer_addr, index)),
+                // the selected endorsement producer is not managed locally => continue
+                Ok(None) => {}
+                Err(err) => {
+                    warn!(
+                        "endorsement factory could not check the key of {} for slot {}: {}",
+                        producer_addr, slot, err
+                    );
+                    self.log_missed_endorsement(
+                        slot,
+                        Some((producer_addr, index)),
+                        ProductionFailure::SignerError,
+                        err.to_string(),
+                    );
+                }
             }
         }
 
@@ -163,6 +204,14 @@ impl EndorsementFactoryWorker {
             if let Ok(stats) = self.channels.protocol.get_stats() {
                 if stats.1.is_empty() {
                     warn!("endorsement factory could not produce endorsement for slot {} because there are no connections", slot);
+                    for draw in producers_indices {
+                        self.log_missed_endorsement(
+                            slot,
+                            Some(draw),
+                            ProductionFailure::NoConnections,
+                            "no connections".to_string(),
+                        );
+                    }
                     return;
                 }
             }
@@ -177,18 +226,65 @@ impl EndorsementFactoryWorker {
         // produce endorsements
         let mut endorsements: Vec<SecureShareEndorsement> =
             Vec::with_capacity(producers_indices.len());
-        for (keypair, index) in producers_indices {
-            let endorsement = Endorsement::new_verifiable(
+        let mut produced_indices: Vec<(Address, usize)> =
+            Vec::with_capacity(producers_indices.len());
+        // the signing history survives restarts: refuse what was already endorsed, record the rest before signing,
+        // all the endorsements of the slot at once
+        let draws: Vec<(Address, u32)> = producers_indices
+            .iter()
+            .map(|(producer_addr, index)| (*producer_addr, *index as u32))
+            .collect();
+        let recorded = self
+            .signing_history
+            .lock()
+            .check_and_record_endorsements(slot, &draws);
+        for ((producer_addr, index), recorded) in producers_indices.into_iter().zip(recorded) {
+            if let Err(err) = recorded {
+                warn!(
+                    "endorsement factory did not produce endorsement for slot {}: {}",
+                    slot, err
+                );
+                let failure = match err {
+                    FactoryError::DoubleProductionError(_) => {
+                        ProductionFailure::DoubleProductionRefused
+                    }
+                    _ => ProductionFailure::SigningHistoryError,
+                };
+                self.log_missed_endorsement(
+                    slot,
+                    Some((producer_addr, index)),
+                    failure,
+                    err.to_string(),
+                );
+                continue;
+            }
+
+            let endorsement: SecureShareEndorsement = match sign_content(
+                self.signer.as_ref(),
+                &producer_addr,
                 Endorsement {
                     slot,
                     index: index as u32,
                     endorsed_block,
                 },
                 self.endorsement_serializer.clone(),
-                &keypair,
                 self.cfg.chain_id,
-            )
-            .expect("could not create endorsement");
+            ) {
+                Ok(endorsement) => endorsement,
+                Err(err) => {
+                    warn!(
+                        "endorsement factory could not sign endorsement for slot {}: {}",
+                        slot, err
+                    );
+                    self.log_missed_endorsement(
+                        slot,
+                        Some((producer_addr, index)),
+                        ProductionFailure::SignerError,
+                        err.to_string(),
+                    );
+                    continue;
+                }
+            };
 
             // log endorsement creation
             debug!(
@@ -197,6 +293,12 @@ impl EndorsementFactoryWorker {
             );
 
             endorsements.push(endorsement);
+            produced_indices.push((producer_addr, index));
+        }
+
+        // quit if all the endorsements were refused
+        if endorsements.is_empty() {
+            return;
         }
 
         // store endorsements
@@ -209,6 +311,32 @@ impl EndorsementFactoryWorker {
         if let Err(err) = self.channels.protocol.propagate_endorsements(endo_storage) {
             warn!("could not propagate endorsements to protocol: {}", err);
         }
+
+        // record the productions. Endorsements are due half a period before their slot, so their latency counts from then.
+        let latency = get_block_slot_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            slot,
+        )
+        .ok()
+        .map(|slot_timestamp| {
+            MassaTime::now().saturating_sub(slot_timestamp.saturating_sub(self.half_t0))
+        });
+        let mut production_log = self.production_log.write();
+        for (producer_addr, index) in produced_indices {
+            production_log.push(ProductionEvent {
+                slot,
+                kind: ProductionKind::Endorsement,
+                index: Some(index as u32),
+                address: Some(producer_addr),
+                outcome: ProductionOutcome::Produced,
+                failure: None,
+                details: None,
+                latency,
+                block_id: None,
+            });
+        }
     }
 
     /// main run loop of the endorsement creator thread
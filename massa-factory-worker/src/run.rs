@@ -2,28 +2,34 @@
 
 use massa_channel::MassaChannel;
 use massa_versioning::versioning::MipStore;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
 
 use crate::{
     block_factory::BlockFactoryWorker, endorsement_factory::EndorsementFactoryWorker,
     manager::FactoryManagerImpl,
 };
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
-use massa_wallet::Wallet;
+use massa_factory_exports::{
+    FactoryChannels, FactoryConfig, FactoryManager, ProductionLog, SigningHistory,
+};
+use massa_wallet::Signer;
 
 /// Start factory
 ///
 /// # Arguments
 /// * `cfg`: factory configuration
-/// * `wallet`: atomic reference to the node wallet
+/// * `signer`: holder of the staking keys, local or remote
+/// * `signing_history`: history of the blocks and endorsements signed by the staking addresses, checked before every signature
+/// * `production_log`: log of the draws of the staking addresses, filled by the workers
 /// * `channels`: channels to communicate with other modules
 ///
 /// # Return value
 /// Returns a factory manager allowing to stop the workers cleanly.
 pub fn start_factory(
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
+    signing_history: Arc<Mutex<SigningHistory>>,
+    production_log: Arc<RwLock<ProductionLog>>,
     channels: FactoryChannels,
     mip_store: MipStore,
 ) -> Box<dyn FactoryManager> {
@@ -38,15 +44,23 @@ pub fn start_factory(
     // start block factory worker
     let block_worker_handle = BlockFactoryWorker::spawn(
         cfg.clone(),
-        wallet.clone(),
+        signer.clone(),
+        signing_history.clone(),
+        production_log.clone(),
         channels.clone(),
         block_worker_rx,
         mip_store,
     );
 
     // start endorsement factory worker
-    let endorsement_worker_handle =
-        EndorsementFactoryWorker::spawn(cfg, wallet, channels, endorsement_worker_rx);
+    let endorsement_worker_handle = EndorsementFactoryWorker::spawn(
+        cfg,
+        signer,
+        signing_history,
+        production_log,
+        channels,
+        endorsement_worker_rx,
+    );
 
     // create factory manager
     let manager = FactoryManagerImpl {
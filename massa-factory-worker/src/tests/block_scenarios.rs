@@ -93,6 +93,9 @@ fn basic_creation() {
         cvar.wait(&mut started);
     }
     test_factory.stop();
+    let stats = test_factory.production_log.read().get_stats();
+    assert_eq!(stats.blocks_produced, 1);
+    assert_eq!(stats.blocks_missed, 0);
 }
 
 /// Creates a block with a roll buy operation in it.
@@ -93,4 +93,7 @@ fn basic_creation() {
         cvar.wait(&mut started);
     }
     test_factory.stop();
+    let stats = test_factory.production_log.read().get_stats();
+    assert_eq!(stats.endorsements_produced, ENDORSEMENT_COUNT as u64);
+    assert_eq!(stats.endorsements_missed, 0);
 }
@@ -5,11 +5,14 @@ use massa_models::config::MIP_STORE_STATS_BLOCK_CONSIDERED;
 use massa_versioning::versioning::MipStatsConfig;
 use massa_versioning::versioning::MipStore;
 use num::rational::Ratio;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
 use std::thread::JoinHandle;
+use tempfile::TempDir;
 
-use massa_factory_exports::{test_exports::create_empty_block, FactoryChannels, FactoryConfig};
+use massa_factory_exports::{
+    test_exports::create_empty_block, FactoryChannels, FactoryConfig, ProductionLog, SigningHistory,
+};
 use massa_models::{address::Address, block_id::BlockId, prehash::PreHashMap, slot::Slot};
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::MockSelectorController;
@@ -19,7 +22,7 @@ use massa_storage::Storage;
 
 use crate::block_factory::BlockFactoryWorker;
 use crate::endorsement_factory::EndorsementFactoryWorker;
-use massa_wallet::test_exports::create_test_wallet;
+use massa_wallet::{test_exports::create_test_wallet, LocalSigner};
 
 /// This structure store all information and links to creates tests for the factory.
 pub struct BlockTestFactory {
@@ -28,6 +31,8 @@ pub struct BlockTestFactory {
     _genesis_blocks: Vec<(BlockId, u64)>,
     pub(crate) _storage: Storage,
     _keypair: KeyPair,
+    _signing_history_dir: TempDir,
+    pub(crate) production_log: Arc<RwLock<ProductionLog>>,
 }
 
 impl BlockTestFactory {
@@ -76,10 +81,17 @@ impl BlockTestFactory {
             MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store");
 
         let wallet = create_test_wallet(Some(accounts));
+        let signing_history_dir = TempDir::new().expect("cannot create temp dir");
+        let signing_history =
+            SigningHistory::load(signing_history_dir.path().join("signing_history.json"))
+                .expect("cannot load signing history");
+        let production_log = Arc::new(RwLock::new(ProductionLog::new(100)));
         let (tx, rx) = MassaChannel::new(String::from("test_block_factory"), None);
         let join_handle = BlockFactoryWorker::spawn(
             factory_config.clone(),
-            Arc::new(RwLock::new(wallet)),
+            Arc::new(LocalSigner::new(Arc::new(RwLock::new(wallet)))),
+            Arc::new(Mutex::new(signing_history)),
+            production_log.clone(),
             FactoryChannels {
                 selector: selector_controller,
                 consensus: consensus_controller,
@@ -97,6 +109,8 @@ impl BlockTestFactory {
             _genesis_blocks: genesis_blocks,
             _storage: storage,
             _keypair: default_keypair.clone(),
+            _signing_history_dir: signing_history_dir,
+            production_log,
         }
     }
 
@@ -114,6 +128,8 @@ pub struct EndorsementTestFactory {
     _genesis_blocks: Vec<(BlockId, u64)>,
     pub(crate) _storage: Storage,
     _keypair: KeyPair,
+    _signing_history_dir: TempDir,
+    pub(crate) production_log: Arc<RwLock<ProductionLog>>,
 }
 
 impl EndorsementTestFactory {
@@ -150,10 +166,17 @@ impl EndorsementTestFactory {
         accounts.insert(producer_address, producer_keypair.clone());
 
         let wallet = create_test_wallet(Some(accounts));
+        let signing_history_dir = TempDir::new().expect("cannot create temp dir");
+        let signing_history =
+            SigningHistory::load(signing_history_dir.path().join("signing_history.json"))
+                .expect("cannot load signing history");
+        let production_log = Arc::new(RwLock::new(ProductionLog::new(100)));
         let (tx, rx) = MassaChannel::new(String::from("test_block_factory"), None);
         let join_handle = EndorsementFactoryWorker::spawn(
             factory_config.clone(),
-            Arc::new(RwLock::new(wallet)),
+            Arc::new(LocalSigner::new(Arc::new(RwLock::new(wallet)))),
+            Arc::new(Mutex::new(signing_history)),
+            production_log.clone(),
             FactoryChannels {
                 selector: selector_controller,
                 consensus: consensus_controller,
@@ -170,6 +193,8 @@ impl EndorsementTestFactory {
             _genesis_blocks: genesis_blocks,
             _storage: storage,
             _keypair: default_keypair.clone(),
+            _signing_history_dir: signing_history_dir,
+            production_log,
         }
     }
 
@@ -0,0 +1,126 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bearer token authentication of the private gRPC API.
+//!
+//! Every call goes through `AuthInterceptor` before reaching its handler, so that a method cannot be
+//! exposed without a token check. Interceptors do not see the request path, so `WithGrpcMethodName`
+//! first records the name of the called method in the request extensions.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::error::GrpcError;
+use hyper::service::Service;
+use hyper::Request;
+use massa_api_exports::auth::{ApiAuth, ApiRole};
+use tonic::service::Interceptor;
+use tonic::transport::NamedService;
+
+/// Name of the private gRPC API in the audit logs
+const PRIVATE_API_NAME: &str = "private gRPC API";
+
+/// Minimum role needed to call a method of the private gRPC API
+pub(crate) fn private_method_role(method: &str) -> ApiRole {
+    match method {
+        "GetBootstrapBlacklist"
+        | "GetBootstrapWhitelist"
+        | "GetMipStatus"
+        | "GetNodeStatus"
+        | "GetPeersWhitelist" => ApiRole::ReadOnly,
+        "AddToBootstrapBlacklist"
+        | "AddToBootstrapWhitelist"
+        | "AddToPeersWhitelist"
+        | "AllowEveryoneToBootstrap"
+        | "BanNodesByIds"
+        | "BanNodesByIps"
+        | "RemoveFromBootstrapBlacklist"
+        | "RemoveFromBootstrapWhitelist"
+        | "RemoveFromPeersWhitelist"
+        | "SignMessages"
+        | "ShutdownGracefully"
+        | "UnbanNodesByIds"
+        | "UnbanNodesByIps" => ApiRole::Operator,
+        // staking keys management, and any method that was not classified yet
+        _ => ApiRole::StakingAdmin,
+    }
+}
+
+/// Name of the gRPC method called by a request
+#[derive(Debug, Clone)]
+pub(crate) struct GrpcMethodName(pub(crate) String);
+
+/// Service recording the name of the called gRPC method in the extensions of each request
+#[derive(Debug, Clone)]
+pub(crate) struct WithGrpcMethodName<S> {
+    inner: S,
+}
+
+impl<S> WithGrpcMethodName<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        WithGrpcMethodName { inner }
+    }
+}
+
+impl<S, B> Service<Request<B>> for WithGrpcMethodName<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        // the path of a gRPC request is `/<package>.<service>/<method>`
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        request.extensions_mut().insert(GrpcMethodName(method));
+        self.inner.call(request)
+    }
+}
+
+impl<S: NamedService> NamedService for WithGrpcMethodName<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+/// Interceptor checking that the bearer token of each request grants the role required by its method
+#[derive(Clone)]
+pub(crate) struct AuthInterceptor {
+    auth: Arc<ApiAuth>,
+}
+
+impl AuthInterceptor {
+    pub(crate) fn new(auth: Arc<ApiAuth>) -> Self {
+        AuthInterceptor { auth }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        let method = request
+            .extensions()
+            .get::<GrpcMethodName>()
+            .map_or("", |method| method.0.as_str());
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        self.auth
+            .authorize(
+                PRIVATE_API_NAME,
+                authorization,
+                method,
+                private_method_role(method),
+            )
+            .map_err(GrpcError::from)?;
+        Ok(request)
+    }
+}
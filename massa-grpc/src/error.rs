@@ -4,6 +4,8 @@ use std::error::Error;
 
 use displaydoc::Display;
 
+use massa_api_exports::auth::AuthError;
+use massa_api_exports::rate_limit::RateLimitError;
 use massa_consensus_exports::error::ConsensusError;
 use massa_execution_exports::ExecutionError;
 use massa_hash::MassaHashError;
@@ -39,6 +41,10 @@ pub enum GrpcError {
     FactoryError(#[from] FactoryError),
     /// Wallet error: {0}
     WalletError(#[from] WalletError),
+    /// Authentication error: {0}
+    AuthError(#[from] AuthError),
+    /// Rate limit error: {0}
+    RateLimitError(#[from] RateLimitError),
     /// Internal server error: {0}
     InternalServerError(String),
     /// Invalid argument error: {0}
@@ -60,6 +66,20 @@ impl From<GrpcError> for tonic::Status {
             GrpcError::TimeError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::FactoryError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::WalletError(e) => tonic::Status::internal(e.to_string()),
+            GrpcError::AuthError(e) => match e {
+                AuthError::Unauthenticated => tonic::Status::unauthenticated(e.to_string()),
+                AuthError::PermissionDenied(_, _) => {
+                    tonic::Status::permission_denied(e.to_string())
+                }
+                _ => tonic::Status::internal(e.to_string()),
+            },
+            GrpcError::RateLimitError(e) => match e {
+                RateLimitError::RateLimited(_) => tonic::Status::resource_exhausted(e.to_string()),
+                RateLimitError::ReadOnlyGasAboveBurst { .. } => {
+                    tonic::Status::invalid_argument(e.to_string())
+                }
+                _ => tonic::Status::internal(e.to_string()),
+            },
             GrpcError::InternalServerError(e) => tonic::Status::internal(e),
             GrpcError::ReflectionError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::InvalidArgument(e) => tonic::Status::invalid_argument(e),
@@ -11,6 +11,16 @@
 //! * `handler.rs`: defines the logic for handling incoming gRPC requests.
 //! * `server`: initializes the gRPC service and serve It.
 //! * `stream/`: contains the gRPC streaming methods implementations files.
+//!
+//! ## **Request metadata**
+//!
+//! Some options that the messages of [massa_proto_rs] cannot carry are read from the request metadata:
+//!
+//! * `authorization`: `Bearer <token>`, a token of `api.private_api_tokens_path` on the private API, or of the
+//!   `tokens_path` of `api.public_rate_limit` on the public API.
+//! * `datastore-key-prefix-bin`: binary metadata of `NewSlotExecutionOutputs`, one value per datastore key
+//!   prefix. The ledger changes of the stream only keep the datastore entries under these prefixes, for the
+//!   whole stream. At most `max_datastore_entries_per_request` prefixes are accepted.
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
@@ -24,6 +34,7 @@ use tonic_reflection as _;
 use tonic_web as _;
 
 /// gRPC configuration
+pub mod auth;
 pub mod config;
 /// models error
 pub mod error;
@@ -215,12 +215,19 @@ pub(crate) fn get_mip_status(
 
 /// Allow everyone to bootstrap from the node by removing bootstrap whitelist configuration file
 pub(crate) fn allow_everyone_to_bootstrap(
-    _grpc: &MassaPrivateGrpc,
+    grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::AllowEveryoneToBootstrapRequest>,
 ) -> Result<grpc_api::AllowEveryoneToBootstrapResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "allow_everyone_to_bootstrap".to_string(),
-    ))
+    if let Some(bs_list) = &grpc.bs_white_black_list {
+        bs_list.allow_everyone().map_err(|e| {
+            GrpcError::InternalServerError(format!(
+                "failed to delete bootstrap whitelist configuration file: {}",
+                e
+            ))
+        })?;
+    }
+
+    Ok(grpc_api::AllowEveryoneToBootstrapResponse {})
 }
 /// Get node status
 pub(crate) fn get_node_status(
@@ -35,6 +35,13 @@ pub(crate) fn execute_read_only_call(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::ExecuteReadOnlyCallRequest>,
 ) -> Result<grpc_api::ExecuteReadOnlyCallResponse, GrpcError> {
+    // charge the declared gas before running the call
+    let max_gas = request
+        .get_ref()
+        .call
+        .as_ref()
+        .map_or(0, |call| call.max_gas);
+    grpc.check_read_only_gas(&request, max_gas)?;
     let call: grpc_model::ReadOnlyExecutionCall = request
         .into_inner()
         .call
@@ -130,6 +137,8 @@ pub(crate) fn execute_read_only_call(
                     .map_err(|_| GrpcError::InvalidArgument("invalid amount".to_string()))
             })
             .transpose()?,
+        // the gRPC read-only call messages have no field to request or return a trace
+        trace: false,
     };
 
     let output = grpc
@@ -167,7 +176,7 @@ pub(crate) fn get_blocks(
         )));
     }
 
-    let mut block_ids: Vec<BlockId> = ids
+    let block_ids: Vec<BlockId> = ids
         .into_iter()
         .take(grpc.grpc_config.max_operation_ids_per_request as usize + 1)
         .map(|id| {
@@ -176,17 +185,41 @@ pub(crate) fn get_blocks(
         })
         .collect::<Result<_, _>>()?;
 
-    let mut blocks: Vec<Block> = Vec::with_capacity(block_ids.len());
-    {
+    let mut blocks: Vec<Option<Block>> = {
         let block_storage_lock = grpc.storage.read_blocks();
-        block_ids.retain(|id| {
-            if let Some(wrapped_block) = block_storage_lock.get(id) {
-                blocks.push(wrapped_block.content.clone());
-                return true;
-            };
-            false
-        });
+        block_ids
+            .iter()
+            .map(|id| {
+                block_storage_lock
+                    .get(id)
+                    .map(|wrapped_block| wrapped_block.content.clone())
+            })
+            .collect()
+    };
+
+    // final blocks pruned from storage can still be found in the block archive
+    let missing_ids: Vec<BlockId> = block_ids
+        .iter()
+        .zip(&blocks)
+        .filter_map(|(id, block)| block.is_none().then_some(*id))
+        .collect();
+    if !missing_ids.is_empty() {
+        let mut archived_blocks = grpc
+            .consensus_controller
+            .get_archived_blocks(&missing_ids)
+            .into_iter();
+        for block in blocks.iter_mut().filter(|block| block.is_none()) {
+            *block = archived_blocks
+                .next()
+                .flatten()
+                .map(|archived| archived.block.content);
+        }
     }
+    let (block_ids, blocks): (Vec<BlockId>, Vec<Block>) = block_ids
+        .into_iter()
+        .zip(blocks)
+        .filter_map(|(id, block)| block.map(|block| (id, block)))
+        .unzip();
 
     let block_statuses = grpc.consensus_controller.get_block_statuses(&block_ids);
 
@@ -509,7 +542,7 @@ pub(crate) fn get_operations(
             .collect()
     };
 
-    let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
+    let mut storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
         let read_blocks = grpc.storage.read_blocks();
         secure_share_operations
             .into_iter()
@@ -526,6 +559,32 @@ pub(crate) fn get_operations(
             .collect()
     };
 
+    // final operations pruned from storage can still be found in the block archive
+    let found_ops: PreHashSet<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
+    let missing_ops: Vec<OperationId> = operation_ids
+        .iter()
+        .filter(|id| !found_ops.contains(id))
+        .copied()
+        .collect();
+    if !missing_ops.is_empty() {
+        let archived = grpc
+            .consensus_controller
+            .get_archived_operations(&missing_ops);
+        for archived in archived.into_iter().flatten() {
+            storage_info.push((
+                archived.operation,
+                std::iter::once(archived.block_id).collect(),
+            ));
+        }
+        // keep the order of the request
+        let positions: PreHashMap<OperationId, usize> = operation_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+        storage_info.sort_by_key(|(op, _)| positions.get(&op.id).copied());
+    }
+
     let operations: Vec<grpc_model::OperationWrapper> = storage_info
         .into_iter()
         .map(|secure_share| {
@@ -869,12 +928,51 @@ pub(crate) fn search_blocks(
         return Err(GrpcError::InvalidArgument("no filter provided".to_string()));
     }
 
+    // slot range covered by the slot range filters
+    let slot_range = slot_ranges_filter.map(|slot_ranges| {
+        let mut start_slot = Slot::new(0, 0); // inclusive
+        let mut end_slot = Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1); // exclusive
+        for slot_range in &slot_ranges {
+            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
+            end_slot = end_slot.min(
+                slot_range
+                    .end_slot
+                    .unwrap_or_else(|| Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1)),
+            );
+        }
+        (start_slot, end_slot.max(start_slot))
+    });
+    // final blocks pruned from storage can still be found in the block archive,
+    // up to `max_block_ids_per_request` blocks per address or slot range
+    let archive_limit = grpc.grpc_config.max_block_ids_per_request as usize;
+    let (archive_start_slot, archive_end_slot) = slot_range.unwrap_or_else(|| {
+        (
+            Slot::new(0, 0),
+            Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1),
+        )
+    });
+
     let mut res: Option<PreHashSet<BlockId>> = None;
 
     // filter by block ids
     if let Some(mut b_ids) = block_ids_filter {
-        let read_lock = grpc.storage.read_blocks();
-        b_ids.retain(|id: &BlockId| read_lock.contains(id));
+        let missing_ids: Vec<BlockId> = {
+            let read_lock = grpc.storage.read_blocks();
+            b_ids
+                .iter()
+                .filter(|id| !read_lock.contains(id))
+                .copied()
+                .collect()
+        };
+        // blocks that are not in storage are only known as final if they were archived
+        if !missing_ids.is_empty() {
+            let statuses = grpc.consensus_controller.get_block_statuses(&missing_ids);
+            for (id, status) in missing_ids.iter().zip(statuses) {
+                if status != BlockGraphStatus::Final {
+                    b_ids.remove(id);
+                }
+            }
+        }
 
         res = Some(b_ids);
     }
@@ -882,13 +980,23 @@ pub(crate) fn search_blocks(
     // filter by addresses
     if let Some(addrs) = addresses_filter {
         let b_ids: PreHashSet<BlockId> = {
-            let read_lock = grpc.storage.read_blocks();
             let mut b_ids: PreHashSet<BlockId> = PreHashSet::default();
-            for addr in addrs {
-                if let Some(addr_b_ids) = read_lock.get_blocks_created_by(&addr) {
-                    b_ids.extend(addr_b_ids.clone());
+            {
+                let read_lock = grpc.storage.read_blocks();
+                for addr in &addrs {
+                    if let Some(addr_b_ids) = read_lock.get_blocks_created_by(addr) {
+                        b_ids.extend(addr_b_ids.clone());
+                    }
                 }
             }
+            for addr in addrs {
+                b_ids.extend(grpc.consensus_controller.search_archived_blocks(
+                    Some(addr),
+                    archive_start_slot,
+                    archive_end_slot,
+                    archive_limit,
+                ));
+            }
 
             b_ids
         };
@@ -900,22 +1008,17 @@ pub(crate) fn search_blocks(
     }
 
     // filter by slot ranges
-    if let Some(slot_ranges) = slot_ranges_filter {
-        let mut start_slot = Slot::new(0, 0); // inclusive
-        let mut end_slot = Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1); // exclusive
-        for slot_range in &slot_ranges {
-            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
-            end_slot = end_slot.min(
-                slot_range
-                    .end_slot
-                    .unwrap_or_else(|| Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1)),
-            );
-        }
-        end_slot = end_slot.max(start_slot);
-
-        let read_lock = grpc.storage.read_blocks();
-        let b_ids: PreHashSet<BlockId> =
-            read_lock.aggregate_blocks_by_slot_range(start_slot..end_slot);
+    if let Some((start_slot, end_slot)) = slot_range {
+        let mut b_ids: PreHashSet<BlockId> = {
+            let read_lock = grpc.storage.read_blocks();
+            read_lock.aggregate_blocks_by_slot_range(start_slot..end_slot)
+        };
+        b_ids.extend(grpc.consensus_controller.search_archived_blocks(
+            None,
+            start_slot,
+            end_slot,
+            archive_limit,
+        ));
 
         if let Some(block_ids) = res.as_mut() {
             block_ids.retain(|id: &BlockId| b_ids.contains(id));
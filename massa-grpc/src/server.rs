@@ -1,5 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use massa_api_exports::auth::ApiAuth;
+use massa_api_exports::rate_limit::{RateLimitError, RateLimitKey, RateLimiter};
 use massa_bootstrap::white_black_list::SharedWhiteBlackList;
 use massa_models::node::NodeId;
 use massa_versioning::keypair_factory::KeyPairFactory;
@@ -9,6 +11,7 @@ use std::convert::Infallible;
 use std::path::Path;
 use std::sync::{Arc, Condvar, Mutex};
 
+use crate::auth::{AuthInterceptor, WithGrpcMethodName};
 use crate::config::{GrpcConfig, ServiceName};
 use crate::error::GrpcError;
 use futures_util::FutureExt;
@@ -31,6 +34,7 @@ use massa_wallet::Wallet;
 use tokio::sync::oneshot;
 use tonic::body::BoxBody;
 use tonic::codegen::CompressionEncoding;
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::NamedService;
 use tonic::transport::{Certificate, Identity, ServerTlsConfig};
 use tonic_health::server::HealthReporter;
@@ -66,11 +70,14 @@ pub struct MassaPrivateGrpc {
     pub version: massa_models::version::Version,
     /// white/black list of bootstrap
     pub bs_white_black_list: Option<SharedWhiteBlackList<'static>>,
+    /// bearer tokens and roles of the clients allowed to call the API, if authentication is enabled
+    pub auth: Option<Arc<ApiAuth>>,
 }
 
 impl MassaPrivateGrpc {
     /// Start the gRPC PRIVATE API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let auth = self.auth.clone();
         let mut service = PrivateServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -87,7 +94,17 @@ impl MassaPrivateGrpc {
             };
         }
 
-        serve(service, config).await
+        match auth {
+            // check the token of every call before it reaches its handler
+            Some(auth) => {
+                let service = WithGrpcMethodName::new(InterceptedService::new(
+                    service,
+                    AuthInterceptor::new(auth),
+                ));
+                serve(service, config).await
+            }
+            None => serve(service, config).await,
+        }
     }
 }
 
@@ -122,11 +139,14 @@ pub struct MassaPublicGrpc {
     pub version: massa_models::version::Version,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// per-client rate limiter, if rate limiting is enabled
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl MassaPublicGrpc {
     /// Start the gRPC PUBLIC API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let rate_limiter = self.rate_limiter.clone();
         let mut service = PublicServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -142,8 +162,50 @@ impl MassaPublicGrpc {
                 service = service.send_compressed(CompressionEncoding::Gzip);
             };
         }
+
+        // count every call, including the opening of streams, against the budget of its client
+        let service = InterceptedService::new(service, move |request: tonic::Request<()>| {
+            if let Some(limiter) = &rate_limiter {
+                limiter
+                    .check_request(&rate_limit_key(limiter, &request))
+                    .map_err(|err| tonic::Status::resource_exhausted(err.to_string()))?;
+            }
+            Ok(request)
+        });
         serve(service, config).await
     }
+
+    /// Charge the declared gas of a read-only execution against the budget of its client
+    pub(crate) fn check_read_only_gas<T>(
+        &self,
+        request: &tonic::Request<T>,
+        max_gas: u64,
+    ) -> Result<(), RateLimitError> {
+        match &self.rate_limiter {
+            Some(limiter) => {
+                limiter.check_read_only_gas(&rate_limit_key(limiter, request), max_gas)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Identify the client of a public API request: by its bearer token if it is known, or else by its IP address
+fn rate_limit_key<T>(
+    limiter: &RateLimiter,
+    request: &tonic::Request<T>,
+) -> RateLimitKey {
+    let authorization = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok());
+    // the remote address is known for every TCP connection
+    let fallback = request
+        .remote_addr()
+        .map_or(RateLimitKey::Connection(0), |addr| {
+            RateLimitKey::from_ip(addr.ip())
+        });
+    limiter.client_key(authorization, fallback)
 }
 
 /// Used to be able to stop the gRPC API
@@ -5,14 +5,21 @@ use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
 use crate::SlotRange;
 use futures_util::StreamExt;
+use massa_async_pool::{AsyncMessage, AsyncMessageUpdate};
 use massa_execution_exports::{ExecutionOutput, SlotExecutionOutput};
+use massa_ledger_exports::{LedgerEntry, LedgerEntryUpdate, SetOrKeep, SetUpdateOrDelete};
+use massa_models::address::Address;
+use massa_models::operation::OperationId;
+use massa_models::output_event::SCOutputEvent;
 use massa_models::slot::Slot;
 use massa_proto_rs::massa::api::v1::{self as grpc_api, NewSlotExecutionOutputsRequest};
 use massa_proto_rs::massa::model::v1::{self as grpc_model};
 use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::pin::Pin;
+use std::str::FromStr;
 use tokio::select;
+use tonic::metadata::MetadataMap;
 use tonic::{Request, Streaming};
 use tracing::{error, warn};
 
@@ -26,7 +33,17 @@ pub type NewSlotExecutionOutputsStreamType = Pin<
     >,
 >;
 
-//TODO implement remaining sub filters
+/// Binary metadata key of the datastore key prefixes of the ledger changes filter,
+/// documented with the other request metadata in the crate documentation
+pub const DATASTORE_KEY_PREFIX_METADATA: &str = "datastore-key-prefix-bin";
+
+/// Async pool change types that a filter can select: all the values of the enum but the unspecified one
+const ASYNC_POOL_CHANGE_TYPES: [grpc_model::AsyncPoolChangeType; 3] = [
+    grpc_model::AsyncPoolChangeType::Set,
+    grpc_model::AsyncPoolChangeType::Update,
+    grpc_model::AsyncPoolChangeType::Delete,
+];
+
 // Type declaration for NewSlotExecutionOutputsFilter
 #[derive(Clone, Debug, Default)]
 struct Filter {
@@ -50,6 +67,16 @@ struct Filter {
 struct AsyncPoolChangesFilter {
     // Do not return any message
     none: Option<()>,
+    // Async pool change types to keep
+    change_types: Option<HashSet<i32>>,
+    // Handler function names to keep
+    handlers: Option<HashSet<String>>,
+    // Destination addresses to keep
+    destination_addresses: Option<HashSet<Address>>,
+    // Emitter addresses to keep
+    emitter_addresses: Option<HashSet<Address>>,
+    // Whether the message can be executed
+    can_be_executed: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -62,25 +89,236 @@ struct ExecutedDenounciationFilter {
 struct ExecutionEventFilter {
     // Do not return any message
     none: Option<()>,
+    // Original caller addresses to keep
+    caller_addresses: Option<HashSet<Address>>,
+    // Emitter addresses to keep
+    emitter_addresses: Option<HashSet<Address>>,
+    // Original operation ids to keep
+    original_operation_ids: Option<HashSet<OperationId>>,
+    // Whether the event is a failure
+    is_failure: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default)]
 struct ExecutedOpsChangesFilter {
     // Do not return any message
     none: Option<()>,
+    // Operation ids to keep
+    operation_ids: Option<HashSet<OperationId>>,
 }
 
 #[derive(Clone, Debug, Default)]
 struct LedgerChangesFilter {
     // Do not return any message
     none: Option<()>,
+    // Addresses to keep
+    addresses: Option<HashSet<Address>>,
+    // Prefixes of the datastore keys to keep in the kept ledger changes
+    datastore_key_prefixes: Option<Vec<Vec<u8>>>,
+}
+
+impl AsyncPoolChangesFilter {
+    // Return if an async pool change matches all the criteria of the filter.
+    // Updates only carry the fields that changed, so criteria on fields that are kept are not matched.
+    fn matches(&self, change: &SetUpdateOrDelete<AsyncMessage, AsyncMessageUpdate>) -> bool {
+        let (change_type, sender, destination, function, can_be_executed) = match change {
+            SetUpdateOrDelete::Set(message) => (
+                grpc_model::AsyncPoolChangeType::Set,
+                Some(&message.sender),
+                Some(&message.destination),
+                Some(&message.function),
+                Some(message.can_be_executed),
+            ),
+            SetUpdateOrDelete::Update(update) => (
+                grpc_model::AsyncPoolChangeType::Update,
+                set_value(&update.sender),
+                set_value(&update.destination),
+                set_value(&update.function),
+                set_value(&update.can_be_executed).copied(),
+            ),
+            SetUpdateOrDelete::Delete => (
+                grpc_model::AsyncPoolChangeType::Delete,
+                None,
+                None,
+                None,
+                None,
+            ),
+        };
+
+        if let Some(change_types) = &self.change_types {
+            if !change_types.contains(&(change_type as i32)) {
+                return false;
+            }
+        }
+        if let Some(handlers) = &self.handlers {
+            if !function.map_or(false, |function| handlers.contains(function)) {
+                return false;
+            }
+        }
+        if let Some(destination_addresses) = &self.destination_addresses {
+            if !destination.map_or(false, |addr| destination_addresses.contains(addr)) {
+                return false;
+            }
+        }
+        if let Some(emitter_addresses) = &self.emitter_addresses {
+            if !sender.map_or(false, |addr| emitter_addresses.contains(addr)) {
+                return false;
+            }
+        }
+        if let Some(expected) = self.can_be_executed {
+            if can_be_executed != Some(expected) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl ExecutionEventFilter {
+    // Return if an event matches all the criteria of the filter
+    fn matches(&self, event: &SCOutputEvent) -> bool {
+        if let Some(caller_addresses) = &self.caller_addresses {
+            if !event
+                .context
+                .call_stack
+                .front()
+                .map_or(false, |addr| caller_addresses.contains(addr))
+            {
+                return false;
+            }
+        }
+        if let Some(emitter_addresses) = &self.emitter_addresses {
+            if !event
+                .context
+                .call_stack
+                .back()
+                .map_or(false, |addr| emitter_addresses.contains(addr))
+            {
+                return false;
+            }
+        }
+        if let Some(original_operation_ids) = &self.original_operation_ids {
+            if !event
+                .context
+                .origin_operation_id
+                .as_ref()
+                .map_or(false, |id| original_operation_ids.contains(id))
+            {
+                return false;
+            }
+        }
+        if let Some(is_failure) = self.is_failure {
+            if event.context.is_error != is_failure {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl LedgerChangesFilter {
+    // Remove the datastore entries whose key does not start with one of the prefixes of the filter
+    fn prune_datastore(&self, change: &mut SetUpdateOrDelete<LedgerEntry, LedgerEntryUpdate>) {
+        if let Some(prefixes) = &self.datastore_key_prefixes {
+            let matches = |key: &Vec<u8>| prefixes.iter().any(|prefix| key.starts_with(prefix));
+            match change {
+                SetUpdateOrDelete::Set(entry) => entry.datastore.retain(|key, _| matches(key)),
+                SetUpdateOrDelete::Update(update) => update.datastore.retain(|key, _| matches(key)),
+                SetUpdateOrDelete::Delete => {}
+            }
+        }
+    }
+}
+
+// Return the value of a field update if it is set
+fn set_value<T: Clone>(value: &SetOrKeep<T>) -> Option<&T> {
+    match value {
+        SetOrKeep::Set(value) => Some(value),
+        SetOrKeep::Keep => None,
+    }
+}
+
+// Parse an address received in a filter
+fn parse_address(address: &str) -> Result<Address, GrpcError> {
+    Address::from_str(address)
+        .map_err(|_| GrpcError::InvalidArgument(format!("invalid address: {}", address)))
+}
+
+// Parse an operation id received in a filter
+fn parse_operation_id(operation_id: &str) -> Result<OperationId, GrpcError> {
+    OperationId::from_str(operation_id)
+        .map_err(|_| GrpcError::InvalidArgument(format!("invalid operation id: {}", operation_id)))
+}
+
+// Insert an address in a filter set, checking the maximum number of addresses
+fn insert_address(
+    addresses: &mut Option<HashSet<Address>>,
+    address: &str,
+    grpc_config: &GrpcConfig,
+) -> Result<(), GrpcError> {
+    let addresses = addresses.get_or_insert_with(HashSet::new);
+    if addresses.len() as u32 >= grpc_config.max_addresses_per_request {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many addresses received. Only a maximum of {} addresses are accepted per request",
+            grpc_config.max_addresses_per_request
+        )));
+    }
+    addresses.insert(parse_address(address)?);
+    Ok(())
+}
+
+// Insert an operation id in a filter set, checking the maximum number of operation ids
+fn insert_operation_id(
+    operation_ids: &mut Option<HashSet<OperationId>>,
+    operation_id: &str,
+    grpc_config: &GrpcConfig,
+) -> Result<(), GrpcError> {
+    let operation_ids = operation_ids.get_or_insert_with(HashSet::new);
+    if operation_ids.len() as u32 >= grpc_config.max_operation_ids_per_request {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many operation ids received. Only a maximum of {} operation ids are accepted per request",
+            grpc_config.max_operation_ids_per_request
+        )));
+    }
+    operation_ids.insert(parse_operation_id(operation_id)?);
+    Ok(())
+}
+
+// Read the datastore key prefixes of the ledger changes filter from the request metadata
+fn get_datastore_key_prefixes(
+    metadata: &MetadataMap,
+    grpc_config: &GrpcConfig,
+) -> Result<Option<Vec<Vec<u8>>>, GrpcError> {
+    let mut prefixes = Vec::new();
+    for value in metadata.get_all_bin(DATASTORE_KEY_PREFIX_METADATA) {
+        if prefixes.len() as u64 >= grpc_config.max_datastore_entries_per_request {
+            return Err(GrpcError::InvalidArgument(format!(
+                "too many datastore key prefixes received. Only a maximum of {} prefixes are accepted per request",
+                grpc_config.max_datastore_entries_per_request
+            )));
+        }
+        let prefix = value
+            .to_bytes()
+            .map_err(|_| GrpcError::InvalidArgument("invalid datastore key prefix".to_string()))?;
+        prefixes.push(prefix.to_vec());
+    }
+    Ok(if prefixes.is_empty() {
+        None
+    } else {
+        Some(prefixes)
+    })
 }
 
 /// Creates a new stream of new produced and received slot execution outputs
+///
+/// The ledger changes can be restricted to some datastore key prefixes with the
+/// `datastore-key-prefix-bin` binary metadata of the request, one value per prefix.
+/// These prefixes apply for the whole stream.
 pub(crate) async fn new_slot_execution_outputs(
     grpc: &MassaPublicGrpc,
     request: Request<Streaming<grpc_api::NewSlotExecutionOutputsRequest>>,
 ) -> Result<NewSlotExecutionOutputsStreamType, GrpcError> {
+    let datastore_key_prefixes = get_datastore_key_prefixes(request.metadata(), &grpc.grpc_config)?;
     // Create a channel to handle communication with the client
     let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
     // Get the inner stream from the request
@@ -94,17 +332,18 @@ pub(crate) async fn new_slot_execution_outputs(
 
     tokio::spawn(async move {
         if let Some(Ok(request)) = in_stream.next().await {
-            let mut filters: Filter = match get_filter(request.clone(), &grpc_config) {
-                Ok(filter) => filter,
-                Err(err) => {
-                    error!("failed to get filter: {}", err);
-                    // Send the error response back to the client
-                    if let Err(e) = tx.send(Err(err.into())).await {
-                        error!("failed to send back NewBlocks error response: {}", e);
+            let mut filters: Filter =
+                match get_filter(request.clone(), &datastore_key_prefixes, &grpc_config) {
+                    Ok(filter) => filter,
+                    Err(err) => {
+                        error!("failed to get filter: {}", err);
+                        // Send the error response back to the client
+                        if let Err(e) = tx.send(Err(err.into())).await {
+                            error!("failed to send back NewBlocks error response: {}", e);
+                        }
+                        return;
                     }
-                    return;
-                }
-            };
+                };
 
             loop {
                 select! {
@@ -135,7 +374,7 @@ pub(crate) async fn new_slot_execution_outputs(
                                 match res {
                                     Ok(message) => {
                                         // Update current filter
-                                        filters = match get_filter(message.clone(), &grpc_config) {
+                                        filters = match get_filter(message.clone(), &datastore_key_prefixes, &grpc_config) {
                                             Ok(filter) => filter,
                                             Err(err) => {
                                                 error!("failed to get filter: {}", err);
@@ -188,6 +427,7 @@ pub(crate) async fn new_slot_execution_outputs(
 // This function returns a filter from the request
 fn get_filter(
     request: NewSlotExecutionOutputsRequest,
+    datastore_key_prefixes: &Option<Vec<Vec<u8>>>,
     grpc_config: &GrpcConfig,
 ) -> Result<Filter, GrpcError> {
     if request.filters.len() as u32 > grpc_config.max_filters_per_request {
@@ -240,20 +480,36 @@ fn get_filter(
                 },
                 grpc_api::new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(filter) => {
                     if let Some(filter) = filter.filter {
+                        let async_pool_changes = async_pool_changes_filter.get_or_insert_with(Default::default);
                         match filter {
                             grpc_api::async_pool_changes_filter::Filter::None(_) => {
-                                async_pool_changes_filter = Some(AsyncPoolChangesFilter {
-                                    none: Some(()),
-                                });
+                                async_pool_changes.none = Some(());
+                            },
+                            grpc_api::async_pool_changes_filter::Filter::Type(change_type) => {
+                                // only valid types are kept, which bounds their number
+                                if !ASYNC_POOL_CHANGE_TYPES.iter().any(|valid| *valid as i32 == change_type) {
+                                    return Err(GrpcError::InvalidArgument(format!(
+                                        "invalid async pool change type {}",
+                                        change_type
+                                    )));
+                                }
+                                async_pool_changes.change_types.get_or_insert_with(HashSet::new).insert(change_type);
+                            },
+                            grpc_api::async_pool_changes_filter::Filter::Handler(handler) => {
+                                async_pool_changes.handlers.get_or_insert_with(HashSet::new).insert(handler);
+                            },
+                            grpc_api::async_pool_changes_filter::Filter::DestinationAddress(address) => {
+                                insert_address(&mut async_pool_changes.destination_addresses, &address, grpc_config)?;
+                            },
+                            grpc_api::async_pool_changes_filter::Filter::EmitterAddress(address) => {
+                                insert_address(&mut async_pool_changes.emitter_addresses, &address, grpc_config)?;
+                            },
+                            grpc_api::async_pool_changes_filter::Filter::CanBeExecuted(can_be_executed) => {
+                                async_pool_changes.can_be_executed = Some(can_be_executed);
                             },
-                            _ => {
-                                async_pool_changes_filter = Some(AsyncPoolChangesFilter {
-                                none: None,
-                            })
                         }
                     }
-                }
-            },
+                },
                 grpc_api::new_slot_execution_outputs_filter::Filter::ExecutedDenounciationFilter(filter) => {
                     if let Some(filter) = filter.filter {
                         match filter {
@@ -262,57 +518,67 @@ fn get_filter(
                                     none: Some(()),
                                 });
                             },
+                        }
                     }
-                }},
+                },
                 grpc_api::new_slot_execution_outputs_filter::Filter::EventFilter(filter) => {
                     if let Some(filter) = filter.filter {
+                        let execution_event = execution_event_filter.get_or_insert_with(Default::default);
                         match filter {
                             grpc_api::execution_event_filter::Filter::None(_) => {
-                                execution_event_filter = Some(ExecutionEventFilter {
-                                    none: Some(()),
-                                });
+                                execution_event.none = Some(());
+                            },
+                            grpc_api::execution_event_filter::Filter::CallerAddress(address) => {
+                                insert_address(&mut execution_event.caller_addresses, &address, grpc_config)?;
+                            },
+                            grpc_api::execution_event_filter::Filter::EmitterAddress(address) => {
+                                insert_address(&mut execution_event.emitter_addresses, &address, grpc_config)?;
+                            },
+                            grpc_api::execution_event_filter::Filter::OriginalOperationId(operation_id) => {
+                                insert_operation_id(&mut execution_event.original_operation_ids, &operation_id, grpc_config)?;
+                            },
+                            grpc_api::execution_event_filter::Filter::IsFailure(is_failure) => {
+                                execution_event.is_failure = Some(is_failure);
                             },
-                            _ => {
-                                execution_event_filter = Some(ExecutionEventFilter {
-                                none: None,
-                            })
                         }
-                }
-                }},
+                    }
+                },
                 grpc_api::new_slot_execution_outputs_filter::Filter::ExecutedOpsChangesFilter(filter) => {
                     if let Some(filter) = filter.filter {
+                        let executed_ops_changes = executed_ops_changes_filter.get_or_insert_with(Default::default);
                         match filter {
                             grpc_api::executed_ops_changes_filter::Filter::None(_) => {
-                                executed_ops_changes_filter = Some(ExecutedOpsChangesFilter {
-                                    none: Some(()),
-                                });
+                                executed_ops_changes.none = Some(());
+                            },
+                            grpc_api::executed_ops_changes_filter::Filter::OperationId(operation_id) => {
+                                insert_operation_id(&mut executed_ops_changes.operation_ids, &operation_id, grpc_config)?;
                             },
-                            _ => {
-                                executed_ops_changes_filter = Some(ExecutedOpsChangesFilter {
-                                none: None,
-                            })
                         }
-                }
-                }},
+                    }
+                },
                 grpc_api::new_slot_execution_outputs_filter::Filter::LedgerChangesFilter(filter) => {
                     if let Some(filter) = filter.filter {
+                        let ledger_changes = ledger_changes_filter.get_or_insert_with(Default::default);
                         match filter {
                             grpc_api::ledger_changes_filter::Filter::None(_) => {
-                                ledger_changes_filter = Some(LedgerChangesFilter {
-                                    none: Some(()),
-                                });
+                                ledger_changes.none = Some(());
+                            },
+                            grpc_api::ledger_changes_filter::Filter::Address(address) => {
+                                insert_address(&mut ledger_changes.addresses, &address, grpc_config)?;
                             },
-                            _ => {
-                                ledger_changes_filter = Some(LedgerChangesFilter {
-                                none: None,
-                            })
                         }
-                }
-                }},
+                    }
+                },
             }
         }
     }
 
+    if let Some(prefixes) = datastore_key_prefixes {
+        ledger_changes_filter
+            .get_or_insert_with(Default::default)
+            .datastore_key_prefixes = Some(prefixes.clone());
+    }
+
     Ok(Filter {
         status_filter,
         slot_ranges_filter,
@@ -400,12 +666,23 @@ fn filter_map_exec_output(
     if let Some(execution_event_filter) = &filters.execution_event_filter {
         if execution_event_filter.none.is_some() {
             exec_output.events.clear();
+        } else {
+            exec_output
+                .events
+                .0
+                .retain(|event| execution_event_filter.matches(event));
         }
     }
 
     if let Some(async_pool_changes_filter) = &filters.async_pool_changes_filter {
         if async_pool_changes_filter.none.is_some() {
             exec_output.state_changes.async_pool_changes.0.clear();
+        } else {
+            exec_output
+                .state_changes
+                .async_pool_changes
+                .0
+                .retain(|_, change| async_pool_changes_filter.matches(change));
         }
     }
     if let Some(executed_denounciation_filter) = &filters.executed_denounciation_filter {
@@ -419,11 +696,27 @@ fn filter_map_exec_output(
     if let Some(executed_ops_changes_filter) = &filters.executed_ops_changes_filter {
         if executed_ops_changes_filter.none.is_some() {
             exec_output.state_changes.executed_ops_changes.clear();
+        } else if let Some(operation_ids) = &executed_ops_changes_filter.operation_ids {
+            exec_output
+                .state_changes
+                .executed_ops_changes
+                .retain(|id, _| operation_ids.contains(id));
         }
     }
     if let Some(ledger_changes_filter) = &filters.ledger_changes_filter {
         if ledger_changes_filter.none.is_some() {
             exec_output.state_changes.ledger_changes.0.clear();
+        } else {
+            if let Some(addresses) = &ledger_changes_filter.addresses {
+                exec_output
+                    .state_changes
+                    .ledger_changes
+                    .0
+                    .retain(|addr, _| addresses.contains(addr));
+            }
+            for change in exec_output.state_changes.ledger_changes.0.values_mut() {
+                ledger_changes_filter.prune_datastore(change);
+            }
         }
     }
 
@@ -2,9 +2,11 @@
 use std::net::SocketAddr;
 
 use crate::config::{GrpcConfig, ServiceName};
-use crate::server::MassaPublicGrpc;
+use crate::server::{MassaPrivateGrpc, MassaPublicGrpc};
+use massa_api_exports::auth::ApiAuth;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
 use massa_execution_exports::{ExecutionChannels, MockExecutionController};
+use massa_models::config::CHAINID;
 use massa_models::{
     config::{
         ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
@@ -22,30 +24,20 @@ use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
-// use massa_wallet::test_exports::create_test_wallet;
-use massa_models::config::CHAINID;
+use massa_wallet::test_exports::create_test_wallet;
 use num::rational::Ratio;
+use parking_lot::RwLock;
 use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
 
-/// generate a grpc public service
+/// generate the configuration of a grpc service
 /// # Arguments
+/// * `name` - the service to configure
 /// * `addr` - the address to bind to
-/// # Returns
-/// * `MassaPublicGrpc` - the grpc public service
-pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
-    let consensus_ctrl = Box::new(MockConsensusController::new());
-    let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
-    let selector_ctrl = Box::new(MockSelectorController::new());
-    let pool_ctrl = Box::new(MockPoolController::new());
-    let execution_ctrl = Box::new(MockExecutionController::new());
-    let protocol_ctrl = Box::new(MockProtocolController::new());
-
-    let endorsement_sender = tokio::sync::broadcast::channel(2000).0;
-    let operation_sender = tokio::sync::broadcast::channel(5000).0;
-    let slot_execution_output_sender = tokio::sync::broadcast::channel(5000).0;
-    let keypair = KeyPair::generate(0).unwrap();
-    let grpc_config = GrpcConfig {
-        name: ServiceName::Public,
+/// * `keypair` - the keypair of the node
+fn grpc_config(name: ServiceName, addr: &SocketAddr, keypair: &KeyPair) -> GrpcConfig {
+    GrpcConfig {
+        name,
         enabled: true,
         accept_http1: true,
         enable_cors: true,
@@ -109,7 +101,27 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         client_private_key_path: PathBuf::default(),
         max_query_items_per_request: 50,
         chain_id: *CHAINID,
-    };
+    }
+}
+
+/// generate a grpc public service
+/// # Arguments
+/// * `addr` - the address to bind to
+/// # Returns
+/// * `MassaPublicGrpc` - the grpc public service
+pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
+    let consensus_ctrl = Box::new(MockConsensusController::new());
+    let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
+    let selector_ctrl = Box::new(MockSelectorController::new());
+    let pool_ctrl = Box::new(MockPoolController::new());
+    let execution_ctrl = Box::new(MockExecutionController::new());
+    let protocol_ctrl = Box::new(MockProtocolController::new());
+
+    let endorsement_sender = tokio::sync::broadcast::channel(2000).0;
+    let operation_sender = tokio::sync::broadcast::channel(5000).0;
+    let slot_execution_output_sender = tokio::sync::broadcast::channel(5000).0;
+    let keypair = KeyPair::generate(0).unwrap();
+    let grpc_config = grpc_config(ServiceName::Public, addr, &keypair);
 
     let mip_stats_config = MipStatsConfig {
         block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
@@ -123,6 +135,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
             block_sender: tokio::sync::broadcast::channel(100).0,
             block_header_sender: tokio::sync::broadcast::channel(100).0,
             filled_block_sender: tokio::sync::broadcast::channel(100).0,
+            block_graph_event_sender: tokio::sync::broadcast::channel(100).0,
         },
         consensus_controller: consensus_ctrl,
         execution_controller: execution_ctrl,
@@ -144,5 +157,39 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         keypair_factory: KeyPairFactory {
             mip_store: mip_store.clone(),
         },
+        rate_limiter: None,
+    }
+}
+
+/// generate a grpc private service
+/// # Arguments
+/// * `addr` - the address to bind to
+/// * `auth` - the bearer tokens accepted by the service, if authentication is enabled
+/// # Returns
+/// * `MassaPrivateGrpc` - the grpc private service
+pub(crate) fn grpc_private_service(
+    addr: &SocketAddr,
+    auth: Option<Arc<ApiAuth>>,
+) -> MassaPrivateGrpc {
+    let keypair = KeyPair::generate(0).unwrap();
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+
+    MassaPrivateGrpc {
+        consensus_controller: Box::new(MockConsensusController::new()),
+        execution_controller: Box::new(MockExecutionController::new()),
+        pool_controller: Box::new(MockPoolController::new()),
+        protocol_controller: Box::new(MockProtocolController::new()),
+        stop_cv: Arc::new((Mutex::new(false), Condvar::new())),
+        node_wallet: Arc::new(RwLock::new(create_test_wallet(None))),
+        grpc_config: grpc_config(ServiceName::Private, addr, &keypair),
+        protocol_config: ProtocolConfig::default(),
+        node_id: NodeId::new(keypair.get_public_key()),
+        mip_store: MipStore::try_from(([], mip_stats_config)).unwrap(),
+        version: *VERSION,
+        bs_white_black_list: None,
+        auth,
     }
 }
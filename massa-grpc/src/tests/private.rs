@@ -0,0 +1,99 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::tests::mock::grpc_private_service;
+use massa_api_exports::auth::{ApiAuth, ApiRole, ApiTokenEntry};
+use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
+use massa_proto_rs::massa::api::v1::{GetBootstrapWhitelistRequest, ShutdownGracefullyRequest};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Build a request carrying the given bearer token
+fn with_token<T>(message: T, token: &str) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    request
+}
+
+#[tokio::test]
+async fn private_api_auth() {
+    let addr: SocketAddr = "[::]:4032".parse().unwrap();
+    let auth = ApiAuth::new(vec![ApiTokenEntry {
+        name: "monitoring".to_string(),
+        token: "read-only-token".to_string(),
+        role: ApiRole::ReadOnly,
+    }]);
+    let private_server = grpc_private_service(&addr, Some(Arc::new(auth)));
+
+    let config = private_server.grpc_config.clone();
+    let stop_handle = private_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut private_client = PrivateServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // no token
+    let status = private_client
+        .get_bootstrap_whitelist(GetBootstrapWhitelistRequest {})
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    // unknown token
+    let status = private_client
+        .get_bootstrap_whitelist(with_token(GetBootstrapWhitelistRequest {}, "wrong-token"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    // valid token, but a role too low for the method
+    let status = private_client
+        .shutdown_gracefully(with_token(ShutdownGracefullyRequest {}, "read-only-token"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+    // valid token
+    let response = private_client
+        .get_bootstrap_whitelist(with_token(
+            GetBootstrapWhitelistRequest {},
+            "read-only-token",
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ips.is_empty());
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn private_api_without_auth() {
+    let addr: SocketAddr = "[::]:4033".parse().unwrap();
+    let private_server = grpc_private_service(&addr, None);
+
+    let config = private_server.grpc_config.clone();
+    let stop_handle = private_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut private_client = PrivateServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // no token is needed when authentication is disabled
+    let response = private_client
+        .get_bootstrap_whitelist(GetBootstrapWhitelistRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ips.is_empty());
+
+    stop_handle.stop();
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use crate::tests::mock::grpc_public_service;
+use massa_api_exports::rate_limit::{RateLimitConfig, RateLimiter};
 use massa_consensus_exports::MockConsensusController;
 use massa_execution_exports::{EventStore, MockExecutionController};
 use massa_models::address::Address;
@@ -35,6 +36,7 @@ use massa_time::MassaTime;
 use std::collections::{BTreeMap, VecDeque};
 use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[tokio::test]
@@ -358,6 +360,7 @@ async fn execute_read_only_call() {
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                trace: None,
             })
         });
 
@@ -452,6 +455,85 @@ async fn execute_read_only_call() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn execute_read_only_call_gas_limit() {
+    let addr: SocketAddr = "[::]:4034".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_execute_readonly_request()
+        .returning(|_req| {
+            Ok(massa_execution_exports::ReadOnlyExecutionOutput {
+                out: massa_execution_exports::ExecutionOutput {
+                    slot: Slot::new(1, 5),
+                    block_info: None,
+                    state_changes: massa_final_state::StateChanges::default(),
+                    events: EventStore::default(),
+                },
+                gas_cost: 100,
+                call_result: vec![],
+                trace: None,
+            })
+        });
+    public_server.execution_controller = exec_ctrl;
+    public_server.rate_limiter = Some(Arc::new(
+        RateLimiter::new(RateLimitConfig {
+            requests_per_second: 0,
+            request_burst: 0,
+            read_only_gas_per_second: 1_000,
+            read_only_gas_burst: 10_000,
+            max_tracked_clients: 10,
+            trust_forwarded_for: false,
+            tokens_path: None,
+        })
+        .unwrap(),
+    ));
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let call = |max_gas| ExecuteReadOnlyCallRequest {
+        call: Some(ReadOnlyExecutionCall {
+            max_gas,
+            call_stack: vec![],
+            caller_address: None,
+            target: Some(Target::FunctionCall(FunctionCall {
+                target_address: "AS12cx6BJHSrBPPSE86E6LYgYS44dvXoHW77cdPbTT8H41wm6xGN5".to_string(),
+                target_function: "function".to_string(),
+                parameter: vec![],
+                coins: None,
+            })),
+            fee: None,
+        }),
+    };
+
+    // the declared gas is charged against the budget of the client
+    assert!(public_client
+        .execute_read_only_call(call(8_000))
+        .await
+        .is_ok());
+    let status = public_client
+        .execute_read_only_call(call(8_000))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    // waiting would not help a call over the gas burst
+    let status = public_client
+        .execute_read_only_call(call(20_000))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_endorsements() {
     let addr: SocketAddr = "[::]:4008".parse().unwrap();
@@ -972,6 +1054,9 @@ async fn search_blocks() {
             .map(|_| BlockGraphStatus::Final)
             .collect::<Vec<BlockGraphStatus>>()
     });
+    consensus_ctrl
+        .expect_search_archived_blocks()
+        .returning(|_, _, _, _| Vec::new());
 
     public_server.consensus_controller = consensus_ctrl;
 
@@ -1189,6 +1274,140 @@ async fn search_blocks() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn search_archived_blocks() {
+    let addr: SocketAddr = "[::]:4031".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&keypair.get_public_key());
+    // a final block pruned from storage, only found in the block archive
+    let archived_block = create_block(&keypair);
+    let archived_block_id = archived_block.id;
+    let unknown_block_id = create_block(&keypair).id;
+
+    let mut consensus_ctrl = Box::new(MockConsensusController::new());
+    consensus_ctrl
+        .expect_get_block_statuses()
+        .returning(move |ids| {
+            ids.iter()
+                .map(|id| {
+                    if id == &archived_block_id {
+                        BlockGraphStatus::Final
+                    } else {
+                        BlockGraphStatus::NotFound
+                    }
+                })
+                .collect()
+        });
+    consensus_ctrl.expect_search_archived_blocks().returning(
+        move |creator, start_slot, end_slot, _limit| {
+            let slot = Slot::new(1, 0);
+            if creator.map_or(true, |creator| creator == address)
+                && start_slot <= slot
+                && slot < end_slot
+            {
+                vec![archived_block_id]
+            } else {
+                Vec::new()
+            }
+        },
+    );
+
+    public_server.consensus_controller = consensus_ctrl;
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // by block ids: only the archived block is known
+    let result = public_client
+        .search_blocks(SearchBlocksRequest {
+            filters: vec![SearchBlocksFilter {
+                filter: Some(search_blocks_filter::Filter::BlockIds(BlockIds {
+                    block_ids: vec![archived_block_id.to_string(), unknown_block_id.to_string()],
+                })),
+            }],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(result.block_infos.len(), 1);
+    assert_eq!(
+        result.block_infos[0].block_id,
+        archived_block_id.to_string()
+    );
+
+    // by creator, through the creator index of the archive
+    let creator_filter = SearchBlocksFilter {
+        filter: Some(search_blocks_filter::Filter::Addresses(Addresses {
+            addresses: vec![address.to_string()],
+        })),
+    };
+    let result = public_client
+        .search_blocks(SearchBlocksRequest {
+            filters: vec![creator_filter.clone()],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(result.block_infos.len(), 1);
+    assert_eq!(
+        result.block_infos[0].block_id,
+        archived_block_id.to_string()
+    );
+
+    // by creator in a slot range that does not include the archived block
+    let result = public_client
+        .search_blocks(SearchBlocksRequest {
+            filters: vec![
+                creator_filter,
+                SearchBlocksFilter {
+                    filter: Some(search_blocks_filter::Filter::SlotRange(SlotRange {
+                        start_slot: Some(massa_proto_rs::massa::model::v1::Slot {
+                            period: 2,
+                            thread: 0,
+                        }),
+                        end_slot: None,
+                    })),
+                },
+            ],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(result.block_infos.is_empty());
+
+    // by slot range
+    let result = public_client
+        .search_blocks(SearchBlocksRequest {
+            filters: vec![SearchBlocksFilter {
+                filter: Some(search_blocks_filter::Filter::SlotRange(SlotRange {
+                    start_slot: Some(massa_proto_rs::massa::model::v1::Slot {
+                        period: 1,
+                        thread: 0,
+                    }),
+                    end_slot: Some(massa_proto_rs::massa::model::v1::Slot {
+                        period: 1,
+                        thread: 1,
+                    }),
+                })),
+            }],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(result.block_infos.len(), 1);
+    assert_eq!(result.block_infos[0].status, BlockStatus::Final as i32);
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn search_endorsements() {
     let addr: SocketAddr = "[::]:4014".parse().unwrap();
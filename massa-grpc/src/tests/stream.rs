@@ -1,20 +1,41 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::server::StopHandle;
+use crate::stream::new_slot_execution_outputs::DATASTORE_KEY_PREFIX_METADATA;
 use crate::tests::mock::grpc_public_service;
+use massa_async_pool::AsyncMessage;
 use massa_consensus_exports::MockConsensusController;
 use massa_execution_exports::{ExecutionOutput, MockExecutionController, SlotExecutionOutput};
+use massa_ledger_exports::{
+    LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
 use massa_models::{
-    address::Address, block::FilledBlock, secure_share::SecureShareSerializer, slot::Slot,
+    address::Address,
+    amount::Amount,
+    block::FilledBlock,
+    denunciation::DenunciationIndex,
+    operation::OperationId,
+    output_event::{EventExecutionContext, SCOutputEvent},
+    secure_share::SecureShareSerializer,
+    slot::Slot,
     stats::ExecutionStats,
 };
 use massa_pool_exports::MockPoolController;
 use massa_proto_rs::massa::{
     api::v1::{
-        public_service_client::PublicServiceClient, NewBlocksRequest, NewFilledBlocksRequest,
-        NewOperationsRequest, NewSlotExecutionOutputsRequest, SendEndorsementsRequest,
-        SendOperationsRequest, TransactionsThroughputRequest,
+        async_pool_changes_filter, executed_denounciation_filter, executed_ops_changes_filter,
+        execution_event_filter, ledger_changes_filter, new_slot_execution_outputs_filter,
+        public_service_client::PublicServiceClient, AsyncPoolChangesFilter,
+        ExecutedDenounciationFilter, ExecutedOpsChangesFilter, ExecutionEventFilter,
+        LedgerChangesFilter, NewBlocksRequest, NewFilledBlocksRequest, NewOperationsRequest,
+        NewSlotExecutionOutputsFilter, NewSlotExecutionOutputsRequest,
+        NewSlotExecutionOutputsResponse, SendEndorsementsRequest, SendOperationsRequest,
+        TransactionsThroughputRequest,
+    },
+    model::v1::{
+        ledger_change_value, set_or_delete_datastore_entry, Addresses, AsyncPoolChangeType, Empty,
+        Slot as ProtoSlot, SlotRange,
     },
-    model::v1::{Addresses, Slot as ProtoSlot, SlotRange},
 };
 use massa_protocol_exports::{
     test_exports::tools::{
@@ -26,7 +47,7 @@ use massa_protocol_exports::{
 use massa_serialization::Serializer;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
-use std::{net::SocketAddr, ops::Add, time::Duration};
+use std::{net::SocketAddr, ops::Add, str::FromStr, time::Duration};
 use tokio_stream::StreamExt;
 
 #[tokio::test]
@@ -1072,7 +1093,7 @@ async fn new_slot_execution_outputs() {
     let (tx_request, rx) = tokio::sync::mpsc::channel(10);
     let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
     let keypair = KeyPair::generate(0).unwrap();
-    let _address = Address::from_public_key(&keypair.get_public_key());
+    let address = Address::from_public_key(&keypair.get_public_key());
 
     let mut public_client = PublicServiceClient::connect(format!(
         "grpc://localhost:{}",
@@ -1188,37 +1209,702 @@ async fn new_slot_execution_outputs() {
     // start slot is after block slot
     assert!(result.is_err());
 
-    // TODO add test when filter is updated
+    let other_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let mut exec_output_2 = exec_output_1.clone();
+    exec_output_2.slot = Slot::new(2, 0);
+    exec_output_2
+        .state_changes
+        .ledger_changes
+        .0
+        .insert(address, SetUpdateOrDelete::Delete);
+    exec_output_2
+        .state_changes
+        .ledger_changes
+        .0
+        .insert(other_address, SetUpdateOrDelete::Delete);
+    exec_output_2.events.push(SCOutputEvent {
+        context: EventExecutionContext {
+            slot: Slot::new(2, 0),
+            block: None,
+            read_only: false,
+            index_in_slot: 0,
+            call_stack: vec![address].into(),
+            origin_operation_id: None,
+            is_final: false,
+            is_error: false,
+        },
+        data: "event".to_string(),
+    });
 
-    /*     filter = massa_proto_rs::massa::api::v1::NewSlotExecutionOutputsFilter {
-        filter: Some(
-            massa_proto_rs::massa::api::v1::new_slot_execution_outputs_filter::Filter::EventFilter(
-                massa_proto_rs::massa::api::v1::ExecutionEventFilter {
+    tx_request
+        .send(NewSlotExecutionOutputsRequest {
+            filters: vec![
+                massa_proto_rs::massa::api::v1::NewSlotExecutionOutputsFilter {
                     filter: Some(
-                        massa_proto_rs::massa::api::v1::execution_event_filter::Filter::OriginalOperationId( "O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC"
-                                    .to_string()
+                        massa_proto_rs::massa::api::v1::new_slot_execution_outputs_filter::Filter::LedgerChangesFilter(
+                            massa_proto_rs::massa::api::v1::LedgerChangesFilter {
+                                filter: Some(
+                                    massa_proto_rs::massa::api::v1::ledger_changes_filter::Filter::Address(
+                                        address.to_string(),
+                                    ),
+                                ),
+                            },
                         ),
                     ),
                 },
-            ),
-        ),
-    };
+                massa_proto_rs::massa::api::v1::NewSlotExecutionOutputsFilter {
+                    filter: Some(
+                        massa_proto_rs::massa::api::v1::new_slot_execution_outputs_filter::Filter::EventFilter(
+                            massa_proto_rs::massa::api::v1::ExecutionEventFilter {
+                                filter: Some(
+                                    massa_proto_rs::massa::api::v1::execution_event_filter::Filter::OriginalOperationId(
+                                        "O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC".to_string(),
+                                    ),
+                                ),
+                            },
+                        ),
+                    ),
+                },
+            ],
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    slot_tx
+        .send(SlotExecutionOutput::ExecutedSlot(exec_output_2.clone()))
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let execution_output = result.output.unwrap().execution_output.unwrap();
+    // only the ledger changes of the filtered address are kept
+    let ledger_changes = execution_output.state_changes.unwrap().ledger_changes;
+    assert_eq!(ledger_changes.len(), 1);
+    assert_eq!(ledger_changes[0].address, address.to_string());
+    // the event was not emitted by the filtered operation
+    assert!(execution_output.events.is_empty());
 
     tx_request
         .send(NewSlotExecutionOutputsRequest {
-            filters: vec![filter],
+            filters: vec![massa_proto_rs::massa::api::v1::NewSlotExecutionOutputsFilter {
+                filter: Some(
+                    massa_proto_rs::massa::api::v1::new_slot_execution_outputs_filter::Filter::LedgerChangesFilter(
+                        massa_proto_rs::massa::api::v1::LedgerChangesFilter {
+                            filter: Some(
+                                massa_proto_rs::massa::api::v1::ledger_changes_filter::Filter::Address(
+                                    "invalid".to_string(),
+                                ),
+                            ),
+                        },
+                    ),
+                ),
+            }],
         })
         .await
         .unwrap();
-    tokio::time::sleep(Duration::from_millis(50)).await;
 
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap();
+    // invalid address in filter
+    assert!(result.is_err());
+
+    stop_handle.stop();
+}
+
+// Start a public server and open a new_slot_execution_outputs stream with the given datastore key prefixes
+async fn open_slot_execution_outputs_stream(
+    addr: SocketAddr,
+    datastore_key_prefixes: &[&[u8]],
+) -> (
+    StopHandle,
+    tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    tokio::sync::mpsc::Sender<NewSlotExecutionOutputsRequest>,
+    tonic::Streaming<NewSlotExecutionOutputsResponse>,
+) {
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+    let (slot_tx, _slot_rx) = tokio::sync::broadcast::channel(10);
+    public_server
+        .execution_channels
+        .slot_execution_output_sender = slot_tx.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut request = tonic::Request::new(tokio_stream::wrappers::ReceiverStream::new(rx));
+    for prefix in datastore_key_prefixes {
+        request.metadata_mut().append_bin(
+            DATASTORE_KEY_PREFIX_METADATA,
+            tonic::metadata::MetadataValue::from_bytes(prefix),
+        );
+    }
+    let resp_stream = public_client
+        .new_slot_execution_outputs(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    (stop_handle, slot_tx, tx_request, resp_stream)
+}
+
+// Send the filters, then the execution output, and return the filtered state changes sent back
+async fn filtered_state_changes(
+    slot_tx: &tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    tx_request: &tokio::sync::mpsc::Sender<NewSlotExecutionOutputsRequest>,
+    resp_stream: &mut tonic::Streaming<NewSlotExecutionOutputsResponse>,
+    filters: Vec<new_slot_execution_outputs_filter::Filter>,
+    exec_output: &ExecutionOutput,
+) -> massa_proto_rs::massa::model::v1::StateChanges {
+    filtered_execution_output(slot_tx, tx_request, resp_stream, filters, exec_output)
+        .await
+        .state_changes
+        .unwrap()
+}
+
+// Send the filters, then the execution output, and return the filtered execution output sent back
+async fn filtered_execution_output(
+    slot_tx: &tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    tx_request: &tokio::sync::mpsc::Sender<NewSlotExecutionOutputsRequest>,
+    resp_stream: &mut tonic::Streaming<NewSlotExecutionOutputsResponse>,
+    filters: Vec<new_slot_execution_outputs_filter::Filter>,
+    exec_output: &ExecutionOutput,
+) -> massa_proto_rs::massa::model::v1::ExecutionOutput {
+    tx_request
+        .send(NewSlotExecutionOutputsRequest {
+            filters: filters
+                .into_iter()
+                .map(|filter| NewSlotExecutionOutputsFilter {
+                    filter: Some(filter),
+                })
+                .collect(),
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
     slot_tx
-        .send(SlotExecutionOutput::ExecutedSlot(exec_output_1.clone()))
+        .send(SlotExecutionOutput::ExecutedSlot(exec_output.clone()))
         .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap()
+        .output
+        .unwrap()
+        .execution_output
+        .unwrap()
+}
 
-    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
-    dbg!(&result);
-    assert!(result.is_err()); */
+#[tokio::test]
+async fn new_slot_execution_outputs_async_pool_changes_filter() {
+    let addr: SocketAddr = "[::]:4026".parse().unwrap();
+    let (stop_handle, slot_tx, tx_request, mut resp_stream) =
+        open_slot_execution_outputs_stream(addr, &[]).await;
+
+    let sender = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let destination = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let message = |emission_index: u64, function: &str, can_be_executed: bool| {
+        AsyncMessage::new(
+            Slot::new(1, 0),
+            emission_index,
+            sender,
+            destination,
+            function.to_string(),
+            100,
+            Amount::zero(),
+            Amount::zero(),
+            Slot::new(1, 0),
+            Slot::new(10, 0),
+            Vec::new(),
+            None,
+            Some(can_be_executed),
+        )
+    };
+    let mut exec_output = ExecutionOutput {
+        slot: Slot::new(1, 5),
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+    };
+    let executable = message(0, "receive", true);
+    let not_executable = message(1, "other", false);
+    let deleted = message(2, "receive", true);
+    for change in [
+        SetUpdateOrDelete::Set(executable.clone()),
+        SetUpdateOrDelete::Set(not_executable.clone()),
+    ] {
+        let id = match &change {
+            SetUpdateOrDelete::Set(message) => message.compute_id(),
+            _ => unreachable!(),
+        };
+        exec_output
+            .state_changes
+            .async_pool_changes
+            .0
+            .insert(id, change);
+    }
+    exec_output
+        .state_changes
+        .async_pool_changes
+        .0
+        .insert(deleted.compute_id(), SetUpdateOrDelete::Delete);
+
+    // no filter: all the changes are kept
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.async_pool_changes.len(), 3);
+
+    // none: no change is kept
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(
+                AsyncPoolChangesFilter {
+                    filter: Some(async_pool_changes_filter::Filter::None(Empty {})),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert!(state_changes.async_pool_changes.is_empty());
+
+    // change type
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(
+                AsyncPoolChangesFilter {
+                    filter: Some(async_pool_changes_filter::Filter::Type(
+                        AsyncPoolChangeType::Delete as i32,
+                    )),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.async_pool_changes.len(), 1);
+
+    // handler, combined with the emitter and the destination
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(
+                AsyncPoolChangesFilter {
+                    filter: Some(async_pool_changes_filter::Filter::Handler(
+                        "receive".to_string(),
+                    )),
+                },
+            ),
+            new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(
+                AsyncPoolChangesFilter {
+                    filter: Some(async_pool_changes_filter::Filter::EmitterAddress(
+                        sender.to_string(),
+                    )),
+                },
+            ),
+            new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(
+                AsyncPoolChangesFilter {
+                    filter: Some(async_pool_changes_filter::Filter::DestinationAddress(
+                        destination.to_string(),
+                    )),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    // the deletion carries no handler
+    assert_eq!(state_changes.async_pool_changes.len(), 1);
+
+    // can be executed
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::AsyncPoolChangesFilter(
+                AsyncPoolChangesFilter {
+                    filter: Some(async_pool_changes_filter::Filter::CanBeExecuted(false)),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.async_pool_changes.len(), 1);
+    assert_eq!(
+        state_changes.async_pool_changes[0]
+            .value
+            .as_ref()
+            .unwrap()
+            .r#type,
+        AsyncPoolChangeType::Set as i32
+    );
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_slot_execution_outputs_executed_denunciations_filter() {
+    let addr: SocketAddr = "[::]:4027".parse().unwrap();
+    let (stop_handle, slot_tx, tx_request, mut resp_stream) =
+        open_slot_execution_outputs_stream(addr, &[]).await;
+
+    let mut exec_output = ExecutionOutput {
+        slot: Slot::new(1, 5),
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+    };
+    exec_output
+        .state_changes
+        .executed_denunciations_changes
+        .insert(DenunciationIndex::BlockHeader {
+            slot: Slot::new(1, 0),
+        });
+
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.executed_denunciations_changes.len(), 1);
+
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::ExecutedDenounciationFilter(
+                ExecutedDenounciationFilter {
+                    filter: Some(executed_denounciation_filter::Filter::None(Empty {})),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert!(state_changes.executed_denunciations_changes.is_empty());
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_slot_execution_outputs_execution_event_filter() {
+    let addr: SocketAddr = "[::]:4028".parse().unwrap();
+    let (stop_handle, slot_tx, tx_request, mut resp_stream) =
+        open_slot_execution_outputs_stream(addr, &[]).await;
+
+    let caller = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let emitter = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let operation_id =
+        OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap();
+    let event = |index_in_slot: u64, call_stack: Vec<Address>, is_error: bool| SCOutputEvent {
+        context: EventExecutionContext {
+            slot: Slot::new(1, 5),
+            block: None,
+            read_only: false,
+            index_in_slot,
+            call_stack: call_stack.into(),
+            origin_operation_id: Some(operation_id),
+            is_final: false,
+            is_error,
+        },
+        data: index_in_slot.to_string(),
+    };
+    let mut exec_output = ExecutionOutput {
+        slot: Slot::new(1, 5),
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+    };
+    exec_output.events.push(event(0, vec![caller], false));
+    exec_output
+        .events
+        .push(event(1, vec![caller, emitter], false));
+    exec_output
+        .events
+        .push(event(2, vec![caller, emitter], true));
+    let data = |output: massa_proto_rs::massa::model::v1::ExecutionOutput| -> Vec<String> {
+        output
+            .events
+            .into_iter()
+            .map(|event| String::from_utf8(event.data).unwrap())
+            .collect()
+    };
+
+    let output = filtered_execution_output(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![new_slot_execution_outputs_filter::Filter::EventFilter(
+            ExecutionEventFilter {
+                filter: Some(execution_event_filter::Filter::None(Empty {})),
+            },
+        )],
+        &exec_output,
+    )
+    .await;
+    assert!(output.events.is_empty());
+
+    let output = filtered_execution_output(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![new_slot_execution_outputs_filter::Filter::EventFilter(
+            ExecutionEventFilter {
+                filter: Some(execution_event_filter::Filter::CallerAddress(
+                    caller.to_string(),
+                )),
+            },
+        )],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(data(output), vec!["0", "1", "2"]);
+
+    let output = filtered_execution_output(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![new_slot_execution_outputs_filter::Filter::EventFilter(
+            ExecutionEventFilter {
+                filter: Some(execution_event_filter::Filter::EmitterAddress(
+                    emitter.to_string(),
+                )),
+            },
+        )],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(data(output), vec!["1", "2"]);
+
+    let output = filtered_execution_output(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::EventFilter(ExecutionEventFilter {
+                filter: Some(execution_event_filter::Filter::OriginalOperationId(
+                    operation_id.to_string(),
+                )),
+            }),
+            new_slot_execution_outputs_filter::Filter::EventFilter(ExecutionEventFilter {
+                filter: Some(execution_event_filter::Filter::IsFailure(true)),
+            }),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(data(output), vec!["2"]);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_slot_execution_outputs_executed_ops_changes_filter() {
+    let addr: SocketAddr = "[::]:4029".parse().unwrap();
+    let (stop_handle, slot_tx, tx_request, mut resp_stream) =
+        open_slot_execution_outputs_stream(addr, &[]).await;
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let operation_id = create_operation_with_expire_period(&keypair, 10).id;
+    let other_operation_id = create_operation_with_expire_period(&keypair, 11).id;
+    let mut exec_output = ExecutionOutput {
+        slot: Slot::new(1, 5),
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+    };
+    exec_output
+        .state_changes
+        .executed_ops_changes
+        .insert(operation_id, (true, Slot::new(10, 0)));
+    exec_output
+        .state_changes
+        .executed_ops_changes
+        .insert(other_operation_id, (false, Slot::new(10, 0)));
+
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::ExecutedOpsChangesFilter(
+                ExecutedOpsChangesFilter {
+                    filter: Some(executed_ops_changes_filter::Filter::OperationId(
+                        operation_id.to_string(),
+                    )),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.executed_ops_changes.len(), 1);
+    assert_eq!(
+        state_changes.executed_ops_changes[0].operation_id,
+        operation_id.to_string()
+    );
+
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::ExecutedOpsChangesFilter(
+                ExecutedOpsChangesFilter {
+                    filter: Some(executed_ops_changes_filter::Filter::None(Empty {})),
+                },
+            ),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert!(state_changes.executed_ops_changes.is_empty());
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_slot_execution_outputs_ledger_changes_filter() {
+    let addr: SocketAddr = "[::]:4030".parse().unwrap();
+    let (stop_handle, slot_tx, tx_request, mut resp_stream) =
+        open_slot_execution_outputs_stream(addr, &[b"balance_", b"owner"]).await;
+
+    let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let other_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let mut exec_output = ExecutionOutput {
+        slot: Slot::new(1, 5),
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+    };
+    exec_output.state_changes.ledger_changes.0.insert(
+        address,
+        SetUpdateOrDelete::Update(LedgerEntryUpdate {
+            balance: SetOrKeep::Keep,
+            bytecode: SetOrKeep::Keep,
+            datastore: [
+                (b"balance_a".to_vec(), SetOrDelete::Set(b"1".to_vec())),
+                (b"name".to_vec(), SetOrDelete::Set(b"token".to_vec())),
+                (b"owner".to_vec(), SetOrDelete::Set(b"me".to_vec())),
+            ]
+            .into_iter()
+            .collect(),
+        }),
+    );
+    exec_output.state_changes.ledger_changes.0.insert(
+        other_address,
+        SetUpdateOrDelete::Set(LedgerEntry {
+            datastore: [(b"balance_b".to_vec(), b"2".to_vec())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }),
+    );
+    let datastore_keys = |state_changes: massa_proto_rs::massa::model::v1::StateChanges| {
+        let mut keys = Vec::new();
+        for change in state_changes.ledger_changes {
+            match change.value.unwrap().entry {
+                Some(ledger_change_value::Entry::UpdatedEntry(update)) => keys.extend(
+                    update
+                        .datastore
+                        .into_iter()
+                        .map(|entry| match entry.change {
+                            Some(set_or_delete_datastore_entry::Change::Set(entry)) => entry.key,
+                            _ => Vec::new(),
+                        }),
+                ),
+                Some(ledger_change_value::Entry::CreatedEntry(entry)) => {
+                    keys.extend(entry.datastore.into_iter().map(|entry| entry.key))
+                }
+                None => {}
+            }
+        }
+        keys.sort();
+        keys
+    };
+
+    // only the datastore keys of the prefixes are kept
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.ledger_changes.len(), 2);
+    assert_eq!(
+        datastore_keys(state_changes),
+        vec![
+            b"balance_a".to_vec(),
+            b"balance_b".to_vec(),
+            b"owner".to_vec()
+        ]
+    );
+
+    // the prefixes apply on top of the address filter
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::LedgerChangesFilter(LedgerChangesFilter {
+                filter: Some(ledger_changes_filter::Filter::Address(address.to_string())),
+            }),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert_eq!(state_changes.ledger_changes.len(), 1);
+    assert_eq!(
+        datastore_keys(state_changes),
+        vec![b"balance_a".to_vec(), b"owner".to_vec()]
+    );
+
+    let state_changes = filtered_state_changes(
+        &slot_tx,
+        &tx_request,
+        &mut resp_stream,
+        vec![
+            new_slot_execution_outputs_filter::Filter::LedgerChangesFilter(LedgerChangesFilter {
+                filter: Some(ledger_changes_filter::Filter::None(Empty {})),
+            }),
+        ],
+        &exec_output,
+    )
+    .await;
+    assert!(state_changes.ledger_changes.is_empty());
 
     stop_handle.stop();
 }
@@ -97,6 +97,32 @@ pub struct MassaMetrics {
     endorsements_pool: IntGauge,
     /// number of elements in the denunciation pool
     denunciations_pool: IntGauge,
+    // operations refused by the pool because their sender reached its limit of pending operations
+    operations_pool_rejected_sender_limit: IntCounter,
+    // operations refused by the pool because they did not pay enough to replace a pending operation
+    operations_pool_rejected_replacement_fee: IntCounter,
+    // pending operations replaced by an operation paying a higher fee
+    operations_pool_replaced_by_fee: IntCounter,
+
+    // public API requests refused because their client exceeded its request rate
+    public_api_rate_limited_requests: IntCounter,
+    // public API read-only executions refused because their client exceeded its gas rate
+    public_api_rate_limited_read_only_executions: IntCounter,
+    // number of public API clients whose rate limit budgets are tracked
+    public_api_rate_limit_tracked_clients: IntGauge,
+
+    // blocks produced by the staking addresses
+    staking_blocks_produced: IntCounter,
+    // block draws of the staking addresses that were missed
+    staking_blocks_missed: IntCounter,
+    // blocks produced by the staking addresses that went stale
+    staking_blocks_stale: IntCounter,
+    // endorsements produced by the staking addresses
+    staking_endorsements_produced: IntCounter,
+    // endorsement draws of the staking addresses that were missed
+    staking_endorsements_missed: IntCounter,
+    // milliseconds from the slot start to the broadcast of the last produced block
+    staking_last_block_latency: IntGauge,
 
     // number of autonomous SCs messages in pool
     async_message_pool_size: IntGauge,
@@ -250,6 +276,78 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let operations_pool_rejected_sender_limit = IntCounter::new(
+            "operations_pool_rejected_sender_limit",
+            "operations refused because their sender reached its limit of pending operations",
+        )
+        .unwrap();
+
+        let operations_pool_rejected_replacement_fee = IntCounter::new(
+            "operations_pool_rejected_replacement_fee",
+            "operations refused because they did not pay more than the pending operation they replace",
+        )
+        .unwrap();
+
+        let operations_pool_replaced_by_fee = IntCounter::new(
+            "operations_pool_replaced_by_fee",
+            "pending operations replaced by an operation paying a higher fee",
+        )
+        .unwrap();
+
+        let public_api_rate_limited_requests = IntCounter::new(
+            "public_api_rate_limited_requests",
+            "public API requests refused because their client exceeded its request rate",
+        )
+        .unwrap();
+
+        let public_api_rate_limited_read_only_executions = IntCounter::new(
+            "public_api_rate_limited_read_only_executions",
+            "public API read-only executions refused because their client exceeded its gas rate",
+        )
+        .unwrap();
+
+        let public_api_rate_limit_tracked_clients = IntGauge::new(
+            "public_api_rate_limit_tracked_clients",
+            "number of public API clients whose rate limit budgets are tracked",
+        )
+        .unwrap();
+
+        let staking_blocks_produced = IntCounter::new(
+            "staking_blocks_produced",
+            "blocks produced by the staking addresses",
+        )
+        .unwrap();
+
+        let staking_blocks_missed = IntCounter::new(
+            "staking_blocks_missed",
+            "block draws of the staking addresses that were missed",
+        )
+        .unwrap();
+
+        let staking_blocks_stale = IntCounter::new(
+            "staking_blocks_stale",
+            "blocks produced by the staking addresses that went stale",
+        )
+        .unwrap();
+
+        let staking_endorsements_produced = IntCounter::new(
+            "staking_endorsements_produced",
+            "endorsements produced by the staking addresses",
+        )
+        .unwrap();
+
+        let staking_endorsements_missed = IntCounter::new(
+            "staking_endorsements_missed",
+            "endorsement draws of the staking addresses that were missed",
+        )
+        .unwrap();
+
+        let staking_last_block_latency = IntGauge::new(
+            "staking_last_block_latency",
+            "milliseconds from the slot start to the broadcast of the last produced block",
+        )
+        .unwrap();
+
         let async_message_pool_size = IntGauge::new(
             "async_message_pool_size",
             "number of autonomous SCs messages in pool",
@@ -451,6 +549,24 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(operations_pool.clone()));
                 let _ = prometheus::register(Box::new(endorsements_pool.clone()));
                 let _ = prometheus::register(Box::new(denunciations_pool.clone()));
+                let _ =
+                    prometheus::register(Box::new(operations_pool_rejected_sender_limit.clone()));
+                let _ = prometheus::register(Box::new(
+                    operations_pool_rejected_replacement_fee.clone(),
+                ));
+                let _ = prometheus::register(Box::new(operations_pool_replaced_by_fee.clone()));
+                let _ = prometheus::register(Box::new(public_api_rate_limited_requests.clone()));
+                let _ = prometheus::register(Box::new(
+                    public_api_rate_limited_read_only_executions.clone(),
+                ));
+                let _ =
+                    prometheus::register(Box::new(public_api_rate_limit_tracked_clients.clone()));
+                let _ = prometheus::register(Box::new(staking_blocks_produced.clone()));
+                let _ = prometheus::register(Box::new(staking_blocks_missed.clone()));
+                let _ = prometheus::register(Box::new(staking_blocks_stale.clone()));
+                let _ = prometheus::register(Box::new(staking_endorsements_produced.clone()));
+                let _ = prometheus::register(Box::new(staking_endorsements_missed.clone()));
+                let _ = prometheus::register(Box::new(staking_last_block_latency.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_success.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_failed.clone()));
                 let _ = prometheus::register(Box::new(sc_messages_final.clone()));
@@ -476,6 +592,18 @@ impl MassaMetrics {
                 operations_pool,
                 endorsements_pool,
                 denunciations_pool,
+                operations_pool_rejected_sender_limit,
+                operations_pool_rejected_replacement_fee,
+                operations_pool_replaced_by_fee,
+                public_api_rate_limited_requests,
+                public_api_rate_limited_read_only_executions,
+                public_api_rate_limit_tracked_clients,
+                staking_blocks_produced,
+                staking_blocks_missed,
+                staking_blocks_stale,
+                staking_endorsements_produced,
+                staking_endorsements_missed,
+                staking_last_block_latency,
                 async_message_pool_size,
                 sc_messages_final,
                 bootstrap_counter,
@@ -662,6 +790,67 @@ impl MassaMetrics {
         self.denunciations_pool.set(nb as i64);
     }
 
+    /// Update the counters of the operations refused or replaced by the pool,
+    /// given their total values since the node started
+    pub fn set_operations_pool_rejections(
+        &self,
+        sender_limit: u64,
+        replacement_fee: u64,
+        replaced_by_fee: u64,
+    ) {
+        self.operations_pool_rejected_sender_limit
+            .inc_by(sender_limit.saturating_sub(self.operations_pool_rejected_sender_limit.get()));
+        self.operations_pool_rejected_replacement_fee.inc_by(
+            replacement_fee.saturating_sub(self.operations_pool_rejected_replacement_fee.get()),
+        );
+        self.operations_pool_replaced_by_fee
+            .inc_by(replaced_by_fee.saturating_sub(self.operations_pool_replaced_by_fee.get()));
+    }
+
+    /// Update the public API rate limit metrics,
+    /// given the totals of the refused requests and read-only executions since the node started
+    pub fn set_public_api_rate_limits(
+        &self,
+        limited_requests: u64,
+        limited_read_only_executions: u64,
+        tracked_clients: usize,
+    ) {
+        self.public_api_rate_limited_requests
+            .inc_by(limited_requests.saturating_sub(self.public_api_rate_limited_requests.get()));
+        self.public_api_rate_limited_read_only_executions.inc_by(
+            limited_read_only_executions
+                .saturating_sub(self.public_api_rate_limited_read_only_executions.get()),
+        );
+        self.public_api_rate_limit_tracked_clients
+            .set(tracked_clients as i64);
+    }
+
+    /// Update the staking production metrics,
+    /// given the totals of the draws of the staking addresses since the node started
+    pub fn set_staking_production(
+        &self,
+        blocks_produced: u64,
+        blocks_missed: u64,
+        blocks_stale: u64,
+        endorsements_produced: u64,
+        endorsements_missed: u64,
+        last_block_latency_ms: Option<u64>,
+    ) {
+        self.staking_blocks_produced
+            .inc_by(blocks_produced.saturating_sub(self.staking_blocks_produced.get()));
+        self.staking_blocks_missed
+            .inc_by(blocks_missed.saturating_sub(self.staking_blocks_missed.get()));
+        self.staking_blocks_stale
+            .inc_by(blocks_stale.saturating_sub(self.staking_blocks_stale.get()));
+        self.staking_endorsements_produced
+            .inc_by(endorsements_produced.saturating_sub(self.staking_endorsements_produced.get()));
+        self.staking_endorsements_missed
+            .inc_by(endorsements_missed.saturating_sub(self.staking_endorsements_missed.get()));
+        if let Some(latency) = last_block_latency_ms {
+            self.staking_last_block_latency.set(latency as i64);
+        }
+    }
+
     pub fn inc_protocol_tester_success(&self) {
         self.protocol_tester_success.inc();
     }
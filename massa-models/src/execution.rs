@@ -1,6 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{address::Address, operation::OperationId, slot::Slot};
+use crate::{address::Address, operation::OperationId, output_event::SCOutputEvent, slot::Slot};
 use serde::{Deserialize, Serialize};
 
 /// filter used when retrieving SC output events
@@ -29,3 +29,95 @@ pub struct EventFilter {
     /// None means both
     pub is_error: Option<bool>,
 }
+
+impl EventFilter {
+    /// Returns true if the given event matches all the criteria of the filter
+    pub fn matches(&self, event: &SCOutputEvent) -> bool {
+        if let Some(start) = self.start {
+            if event.context.slot < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.end {
+            if event.context.slot >= end {
+                return false;
+            }
+        }
+        if let Some(is_final) = self.is_final {
+            if event.context.is_final != is_final {
+                return false;
+            }
+        }
+        if let Some(is_error) = self.is_error {
+            if event.context.is_error != is_error {
+                return false;
+            }
+        }
+        match (
+            self.original_caller_address,
+            event.context.call_stack.front(),
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (self.emitter_address, event.context.call_stack.back()) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (
+            self.original_operation_id,
+            event.context.origin_operation_id,
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        true
+    }
+}
+
+/// Position in the SC output events, which are ordered by slot, then by index in the slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EventCursor {
+    /// slot of the event
+    pub slot: Slot,
+    /// index of the event in the slot
+    pub index_in_slot: u64,
+}
+
+impl EventCursor {
+    /// Position of the first event of a slot
+    pub fn from_slot(slot: Slot) -> Self {
+        EventCursor {
+            slot,
+            index_in_slot: 0,
+        }
+    }
+
+    /// Position of an event
+    pub fn of_event(event: &SCOutputEvent) -> Self {
+        EventCursor {
+            slot: event.context.slot,
+            index_in_slot: event.context.index_in_slot,
+        }
+    }
+
+    /// Position right after an event
+    pub fn after_event(event: &SCOutputEvent) -> Self {
+        EventCursor {
+            slot: event.context.slot,
+            index_in_slot: event.context.index_in_slot.saturating_add(1),
+        }
+    }
+}
+
+/// A page of SC output events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SCOutputEventPage {
+    /// events of the page, ordered by slot (final events first)
+    pub events: Vec<SCOutputEvent>,
+    /// cursor to request the next page with, `None` if there are no more events
+    pub next_cursor: Option<EventCursor>,
+}
@@ -49,6 +49,14 @@ impl From<Amount> for grpc_model::NativeAmount {
     }
 }
 
+impl TryFrom<grpc_model::NativeAmount> for Amount {
+    type Error = ModelsError;
+
+    fn try_from(value: grpc_model::NativeAmount) -> Result<Self, Self::Error> {
+        Amount::from_mantissa_scale(value.mantissa, value.scale)
+    }
+}
+
 impl From<Block> for grpc_model::Block {
     fn from(value: Block) -> Self {
         grpc_model::Block {
@@ -368,6 +376,36 @@ impl From<CompactConfig> for grpc_model::CompactConfig {
     }
 }
 
+impl TryFrom<grpc_model::CompactConfig> for CompactConfig {
+    type Error = ModelsError;
+
+    fn try_from(value: grpc_model::CompactConfig) -> Result<Self, Self::Error> {
+        let missing = |field: &str| ModelsError::ErrorRaised(format!("no {} found", field));
+        Ok(CompactConfig {
+            genesis_timestamp: value
+                .genesis_timestamp
+                .ok_or_else(|| missing("genesis_timestamp"))?
+                .into(),
+            end_timestamp: value.end_timestamp.map(|time| time.into()),
+            thread_count: u8::try_from(value.thread_count)
+                .map_err(|_| ModelsError::ErrorRaised("invalid thread_count".to_string()))?,
+            t0: value.t0.ok_or_else(|| missing("t0"))?.into(),
+            delta_f0: value.delta_f0,
+            operation_validity_periods: value.operation_validity_periods,
+            periods_per_cycle: value.periods_per_cycle,
+            block_reward: value
+                .block_reward
+                .ok_or_else(|| missing("block_reward"))?
+                .try_into()?,
+            roll_price: value
+                .roll_price
+                .ok_or_else(|| missing("roll_price"))?
+                .try_into()?,
+            max_block_size: value.max_block_size,
+        })
+    }
+}
+
 impl From<ConsensusStats> for grpc_model::ConsensusStats {
     fn from(value: ConsensusStats) -> Self {
         grpc_model::ConsensusStats {
@@ -14,7 +14,7 @@ use clap::{crate_version, Parser};
 use crossbeam_channel::TryRecvError;
 use dialoguer::Password;
 use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
-use massa_api_exports::config::APIConfig;
+use massa_api_exports::{auth::ApiAuth, config::APIConfig, rate_limit::RateLimiter};
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
@@ -35,7 +35,9 @@ use massa_execution_exports::{
     ExecutionChannels, ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants,
 };
 use massa_execution_worker::start_execution_worker;
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+use massa_factory_exports::{
+    FactoryChannels, FactoryConfig, FactoryManager, ProductionLog, SigningHistory,
+};
 use massa_factory_worker::start_factory;
 use massa_final_state::{FinalState, FinalStateConfig, FinalStateController};
 use massa_grpc::config::{GrpcConfig, ServiceName};
@@ -92,7 +94,7 @@ use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_versioning::mips::get_mip_list;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
-use massa_wallet::Wallet;
+use massa_wallet::{LocalSigner, RemoteSigner, Signer, Wallet};
 use num::rational::Ratio;
 use parking_lot::RwLock;
 use settings::GrpcSettings;
@@ -116,6 +118,9 @@ mod survey;
 async fn launch(
     args: &Args,
     node_wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
+    signing_history: Arc<parking_lot::Mutex<SigningHistory>>,
+    production_log: Arc<RwLock<ProductionLog>>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
 ) -> (
     MassaReceiver<ConsensusEvent>,
@@ -460,6 +465,9 @@ async fn launch(
         lru_cache_size: SETTINGS.execution.lru_cache_size,
         hd_cache_size: SETTINGS.execution.hd_cache_size,
         snip_amount: SETTINGS.execution.snip_amount,
+        event_archive_path: SETTINGS.execution.event_archive_path.clone(),
+        ledger_archive_path: SETTINGS.execution.ledger_archive_path.clone(),
+        address_history_path: SETTINGS.execution.address_history_path.clone(),
         roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
         broadcast_enabled: SETTINGS.api.enable_broadcast,
@@ -492,6 +500,7 @@ async fn launch(
     // launch pool controller
     let pool_config = PoolConfig {
         thread_count: THREAD_COUNT,
+        chain_id: *CHAINID,
         max_block_size: MAX_BLOCK_SIZE,
         max_block_gas: MAX_GAS_PER_BLOCK,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
@@ -502,6 +511,9 @@ async fn launch(
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
         max_operation_pool_excess_items: SETTINGS.pool.max_operation_pool_excess_items,
+        max_operations_per_sender: SETTINGS.pool.max_operations_per_sender,
+        max_evicted_operations_history: SETTINGS.pool.max_evicted_operations_history,
+        mempool_mode: SETTINGS.pool.mempool_mode,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
@@ -539,6 +551,7 @@ async fn launch(
         &shared_storage,
         pool_channels.clone(),
         node_wallet.clone(),
+        SETTINGS.pool.snapshot_path.clone(),
     );
 
     // launch protocol controller
@@ -619,6 +632,8 @@ async fn launch(
             as u128,
         try_connection_timer: SETTINGS.protocol.try_connection_timer,
         unban_everyone_timer: SETTINGS.protocol.unban_everyone_timer,
+        peer_ban_base_duration: SETTINGS.protocol.peer_ban_base_duration,
+        peer_ban_max_duration: SETTINGS.protocol.peer_ban_max_duration,
         max_in_connections: SETTINGS.protocol.max_in_connections,
         timeout_connection: SETTINGS.protocol.timeout_connection,
         message_timeout: SETTINGS.protocol.message_timeout,
@@ -667,11 +682,24 @@ async fn launch(
         broadcast_filled_blocks_channel_capacity: SETTINGS
             .consensus
             .broadcast_filled_blocks_channel_capacity,
+        broadcast_block_graph_events_channel_capacity: SETTINGS
+            .consensus
+            .broadcast_block_graph_events_channel_capacity,
         last_start_period: final_state.read().get_last_start_period(),
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
             .force_keep_final_periods_without_ops,
         chain_id: *CHAINID,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameters_size: MAX_PARAMETERS_SIZE,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        block_archive_path: SETTINGS.consensus.block_archive_path.clone(),
+        fee_estimation_final_periods: SETTINGS.consensus.fee_estimation_final_periods,
     };
 
     let (consensus_event_sender, consensus_event_receiver) =
@@ -692,6 +720,10 @@ async fn launch(
                 consensus_config.broadcast_filled_blocks_channel_capacity,
             )
             .0,
+            block_graph_event_sender: broadcast::channel(
+                consensus_config.broadcast_block_graph_events_channel_capacity,
+            )
+            .0,
         },
     };
 
@@ -742,7 +774,9 @@ async fn launch(
     };
     let factory_manager = start_factory(
         factory_config,
-        node_wallet.clone(),
+        signer,
+        signing_history,
+        production_log.clone(),
         factory_channels,
         mip_store.clone(),
     );
@@ -776,6 +810,7 @@ async fn launch(
         bind_api: SETTINGS.api.bind_api,
         draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
         max_arguments: SETTINGS.api.max_arguments,
+        max_next_draws: SETTINGS.api.max_next_draws,
         openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
@@ -807,14 +842,24 @@ async fn launch(
         chain_id: *CHAINID,
     };
 
+    // per-client rate limiter shared by the public APIs, if rate limiting is enabled
+    let public_rate_limiter = SETTINGS.api.public_rate_limit.as_ref().map(|config| {
+        Arc::new(
+            RateLimiter::new(config.clone())
+                .expect("failed to load the public API rate limit tokens file"),
+        )
+    });
+
     // spawn Massa API
     let api = API::<ApiV2>::new(
         consensus_controller.clone(),
         consensus_channels.broadcasts.clone(),
         execution_controller.clone(),
+        execution_channels.clone(),
         pool_channels.broadcasts.clone(),
         api_config.clone(),
         *VERSION,
+        public_rate_limiter.clone(),
     );
     let api_handle = api
         .serve(&SETTINGS.api.bind_api, &api_config)
@@ -856,6 +901,7 @@ async fn launch(
             keypair_factory: KeyPairFactory {
                 mip_store: mip_store.clone(),
             },
+            rate_limiter: public_rate_limiter.clone(),
         };
 
         // Spawn gRPC PUBLIC API
@@ -870,6 +916,11 @@ async fn launch(
         None
     };
 
+    // bearer tokens of the private APIs, if authentication is enabled
+    let private_api_auth = SETTINGS.api.private_api_tokens_path.as_ref().map(|path| {
+        Arc::new(ApiAuth::load(path).expect("failed to load the private API tokens file"))
+    });
+
     // Whether to spawn gRPC PRIVATE API
     let grpc_private_handle = if SETTINGS.grpc.private.enabled {
         let grpc_private_config = configure_grpc(
@@ -896,6 +947,7 @@ async fn launch(
             stop_cv: sig_int_toggled.clone(),
             node_wallet: node_wallet.clone(),
             bs_white_black_list,
+            auth: private_api_auth.clone(),
         };
 
         // Spawn gRPC PRIVATE API
@@ -930,6 +982,8 @@ async fn launch(
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        production_log.clone(),
+        private_api_auth,
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -953,6 +1007,7 @@ async fn launch(
         node_id,
         shared_storage.clone(),
         mip_store.clone(),
+        public_rate_limiter.clone(),
     );
     let api_public_handle = api_public
         .serve(&SETTINGS.api.bind_public, &api_config)
@@ -967,6 +1022,8 @@ async fn launch(
         SETTINGS.metrics.tick_delay.to_duration(),
         execution_controller,
         pool_controller,
+        public_rate_limiter,
+        production_log,
         massa_metrics,
         (
             api_config.thread_count,
@@ -1208,6 +1265,14 @@ struct Args {
     #[arg(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// Merge an exported signing history into the signing history of the node before starting
+    #[arg(long = "import-signing-history")]
+    import_signing_history: Option<PathBuf>,
+
+    /// Export the signing history of the node to a file and exit, to move the staking keys to another host
+    #[arg(long = "export-signing-history")]
+    export_signing_history: Option<PathBuf>,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[arg(
@@ -1308,6 +1373,24 @@ async fn run(args: Args) -> anyhow::Result<()> {
 
     info!("Node version : {}", *VERSION);
 
+    // load the history of the blocks and endorsements signed by the staking keys
+    let mut signing_history = SigningHistory::load(SETTINGS.factory.signing_history_path.clone())?;
+    if let Some(path) = &cur_args.export_signing_history {
+        signing_history.export(path)?;
+        info!("signing history exported to {}", path.display());
+        return Ok(());
+    }
+    if let Some(path) = &cur_args.import_signing_history {
+        signing_history.import(path)?;
+        info!("signing history imported from {}", path.display());
+    }
+    let signing_history = Arc::new(parking_lot::Mutex::new(signing_history));
+
+    // draws of the staking addresses, kept across restarts so that the totals count since the node started
+    let production_log = Arc::new(RwLock::new(ProductionLog::new(
+        SETTINGS.factory.production_log_size,
+    )));
+
     // load or create wallet, asking for password if necessary
     let node_wallet = load_wallet(
         cur_args.password.clone(),
@@ -1315,6 +1398,29 @@ async fn run(args: Args) -> anyhow::Result<()> {
         *CHAINID,
     )?;
 
+    // sign blocks, endorsements and operations with a remote signer if one is configured, else with the staking wallet
+    let signer: Arc<dyn Signer> = match &SETTINGS.factory.remote_signer {
+        Some(socket_path) => {
+            info!("using the remote signer {}", socket_path.display());
+            let remote_signer = RemoteSigner::connect(
+                socket_path.clone(),
+                SETTINGS.factory.remote_signer_timeout.to_duration(),
+            )?;
+            remote_signer.spawn_public_keys_refresh(
+                SETTINGS
+                    .factory
+                    .remote_signer_keys_refresh_interval
+                    .to_duration(),
+            )?;
+            let remote_signer: Arc<dyn Signer> = Arc::new(remote_signer);
+            node_wallet
+                .write()
+                .set_remote_signer(Some(remote_signer.clone()));
+            remote_signer
+        }
+        None => Arc::new(LocalSigner::new(node_wallet.clone())),
+    };
+
     // interrupt signal listener
     let sig_int_toggled = Arc::new((Mutex::new(false), Condvar::new()));
 
@@ -1348,7 +1454,15 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+        ) = launch(
+            &cur_args,
+            node_wallet.clone(),
+            signer.clone(),
+            signing_history.clone(),
+            production_log.clone(),
+            Arc::clone(&sig_int_toggled),
+        )
+        .await;
 
         // loop over messages
         let restart = loop {
@@ -3,6 +3,7 @@
 //! Build here the default node settings from the configuration file toml
 use std::{collections::HashMap, path::PathBuf};
 
+use massa_api_exports::rate_limit::RateLimitConfig;
 use massa_bootstrap::IpType;
 use massa_models::{config::build_massa_settings, node::NodeId};
 use massa_protocol_exports::PeerCategoryInfo;
@@ -32,6 +33,12 @@ pub struct ExecutionSettings {
     pub lru_cache_size: u32,
     pub hd_cache_size: usize,
     pub snip_amount: usize,
+    /// path to the on-disk archive of final SC output events, disabled if not set
+    pub event_archive_path: Option<PathBuf>,
+    /// path to the on-disk history of final balances and datastore entries, disabled if not set
+    pub ledger_archive_path: Option<PathBuf>,
+    /// path to the on-disk index of the final history of each address, disabled if not set
+    pub address_history_path: Option<PathBuf>,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
 }
@@ -83,6 +90,16 @@ pub struct FactorySettings {
     pub staking_wallet_path: PathBuf,
     /// stop the production in case we are not connected to anyone
     pub stop_production_when_zero_connections: bool,
+    /// Signing history file, checked before every block and endorsement signature to prevent double production
+    pub signing_history_path: PathBuf,
+    /// Unix socket of the remote signer holding the staking keys. The staking wallet signs if not set.
+    pub remote_signer: Option<PathBuf>,
+    /// Timeout of the requests to the remote signer
+    pub remote_signer_timeout: MassaTime,
+    /// Interval between two fetches of the staking addresses of the remote signer
+    pub remote_signer_keys_refresh_interval: MassaTime,
+    /// Number of block and endorsement draws of the staking addresses kept for the private API
+    pub production_log_size: usize,
 }
 
 /// Pool configuration, read from a file configuration
@@ -90,6 +107,13 @@ pub struct FactorySettings {
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
     pub max_operation_pool_excess_items: usize,
+    /// max number of pending operations per sender address
+    pub max_operations_per_sender: usize,
+    pub max_evicted_operations_history: usize,
+    /// keep operations for any selected producer, useful for non-staking API nodes
+    pub mempool_mode: bool,
+    /// where to save the pool contents on stop, to reload them on the next start
+    pub snapshot_path: Option<PathBuf>,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
     pub max_endorsements_pool_size_per_thread: usize,
@@ -108,6 +132,7 @@ pub struct APISettings {
     pub bind_public: SocketAddr,
     pub bind_api: SocketAddr,
     pub max_arguments: u64,
+    pub max_next_draws: u64,
     pub openrpc_spec_path: PathBuf,
     pub max_request_body_size: u32,
     pub max_response_body_size: u32,
@@ -121,6 +146,10 @@ pub struct APISettings {
     pub enable_ws: bool,
     // whether to broadcast for blocks, endorsement and operations
     pub enable_broadcast: bool,
+    /// bearer tokens file of the private JSON-RPC and gRPC APIs. Authentication is disabled if not set
+    pub private_api_tokens_path: Option<PathBuf>,
+    /// per-client rate limits of the public JSON-RPC and gRPC APIs. Rate limiting is disabled if not set
+    pub public_rate_limit: Option<RateLimitConfig>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -165,6 +194,12 @@ pub struct ConsensusSettings {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// block graph events channel capacity
+    pub broadcast_block_graph_events_channel_capacity: usize,
+    /// where to archive final blocks, none to disable the block archive
+    pub block_archive_path: Option<PathBuf>,
+    /// number of recent final periods whose blocks are used to estimate fees
+    pub fee_estimation_final_periods: u64,
 }
 
 // TODO: Remove one date. Kept for retro compatibility.
@@ -253,6 +288,10 @@ pub struct ProtocolSettings {
     pub try_connection_timer_same_peer: MassaTime,
     /// periodically unban every peer
     pub unban_everyone_timer: MassaTime,
+    /// duration of the first ban of a peer whose reputation dropped, doubling with each of its bans
+    pub peer_ban_base_duration: MassaTime,
+    /// maximum duration of the ban of a peer whose reputation dropped
+    pub peer_ban_max_duration: MassaTime,
     /// Timeout connection
     pub timeout_connection: MassaTime,
     /// Message timeout
@@ -1,13 +1,17 @@
 #![allow(unused_imports)]
+use std::sync::Arc;
 use std::thread::JoinHandle;
 
 use crossbeam_channel::{select, tick};
+use massa_api_exports::rate_limit::RateLimiter;
 use massa_channel::{sender::MassaSender, MassaChannel};
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::ProductionLog;
 use massa_metrics::MassaMetrics;
 use massa_models::{address::Address, slot::Slot, timeslots::get_latest_block_slot_at_timestamp};
 use massa_pool_exports::PoolController;
 use massa_time::MassaTime;
+use parking_lot::RwLock;
 use tracing::info;
 // use std::time::Duration;
 use tracing::warn;
@@ -43,6 +47,8 @@ impl MassaSurvey {
         tick_delay: std::time::Duration,
         execution_controller: Box<dyn ExecutionController>,
         pool_controller: Box<dyn PoolController>,
+        public_rate_limiter: Option<Arc<RateLimiter>>,
+        production_log: Arc<RwLock<ProductionLog>>,
         massa_metrics: MassaMetrics,
         config: (u8, MassaTime, MassaTime, u64, u64),
     ) -> MassaSurveyStopper {
@@ -118,12 +124,39 @@ impl MassaSurvey {
                                     massa_metrics.set_operations_pool(pool_controller.get_operation_count());
                                     massa_metrics.set_endorsements_pool(pool_controller.get_endorsement_count());
                                     massa_metrics.set_denunciations_pool(pool_controller.get_denunciation_count());
+                                    let rejection_stats = pool_controller.get_operation_rejection_stats();
+                                    massa_metrics.set_operations_pool_rejections(
+                                        rejection_stats.sender_limit_reached,
+                                        rejection_stats.replacement_fee_too_low,
+                                        rejection_stats.replaced_by_fee,
+                                    );
 
                                     let count = std::thread::available_parallelism()
                                     .unwrap_or(std::num::NonZeroUsize::MIN)
                                     .get();
                                     massa_metrics.set_available_processors(count);
                                 }
+
+                                if let Some(rate_limiter) = &public_rate_limiter {
+                                    let stats = rate_limiter.get_stats();
+                                    massa_metrics.set_public_api_rate_limits(
+                                        stats.limited_requests,
+                                        stats.limited_read_only_executions,
+                                        stats.tracked_clients,
+                                    );
+                                }
+
+                                {
+                                    let stats = production_log.read().get_stats();
+                                    massa_metrics.set_staking_production(
+                                        stats.blocks_produced,
+                                        stats.blocks_missed,
+                                        stats.blocks_stale,
+                                        stats.endorsements_produced,
+                                        stats.endorsements_missed,
+                                        stats.last_block_latency.map(|latency| latency.as_millis()),
+                                    );
+                                }
                             }
                         }
                     }) {
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 pub struct PoolConfig {
     /// thread count
     pub thread_count: u8,
+    /// chain id
+    pub chain_id: u64,
     /// maximal total block operations size
     pub max_block_size: u32,
     /// maximal gas per block
@@ -31,6 +33,13 @@ pub struct PoolConfig {
     pub max_operation_pool_size: usize,
     /// max excess on pool size (in-between refreshes)
     pub max_operation_pool_excess_items: usize,
+    /// max number of pending operations per sender address
+    pub max_operations_per_sender: usize,
+    /// max number of recently evicted operations kept for inspection
+    pub max_evicted_operations_history: usize,
+    /// keep the operations that can be included by any selected producer,
+    /// instead of only the ones that can be included by our staking addresses
+    pub mempool_mode: bool,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
     /// max number of endorsements per block
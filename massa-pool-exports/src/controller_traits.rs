@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    amount::Amount,
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
@@ -9,6 +10,8 @@ use massa_models::{
 };
 use massa_storage::Storage;
 
+use crate::{OperationPoolRejectionStats, PoolOperationFilter, PoolOperationInfo};
+
 #[cfg(feature = "test-exports")]
 use std::sync::{Arc, RwLock};
 
@@ -52,6 +55,30 @@ pub trait PoolController: Send + Sync {
     /// Check if the pool contains a list of operations. Returns one boolean per item.
     fn contains_operations(&self, operations: &[OperationId]) -> Vec<bool>;
 
+    /// List the operations pending in the pool, from best to worst score,
+    /// followed by the recently evicted ones, from the most recent eviction.
+    /// Only the `limit` matching operations following the first `offset` ones are listed.
+    fn get_operations_info(
+        &self,
+        filter: &PoolOperationFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<PoolOperationInfo>;
+
+    /// Estimate the lowest fee for which an operation of the given size (in bytes) and max gas usage
+    /// would currently be scored among the operations that fit in the blocks of the next `periods` periods.
+    /// Returns `None` if such an operation does not fit in a block.
+    fn estimate_inclusion_fee(
+        &self,
+        size: usize,
+        max_gas_usage: u64,
+        periods: u64,
+    ) -> Option<Amount>;
+
+    /// Get the counters of the operations refused or replaced by the pool
+    /// because of the per-sender limit and the replace-by-fee rule
+    fn get_operation_rejection_stats(&self) -> OperationPoolRejectionStats;
+
     /// Get the number of denunciations in the pool
     fn get_denunciation_count(&self) -> usize;
 
@@ -8,10 +8,14 @@
 mod channels;
 mod config;
 mod controller_traits;
+mod types;
 
 pub use channels::{PoolBroadcasts, PoolChannels};
 pub use config::PoolConfig;
 pub use controller_traits::{PoolController, PoolManager};
+pub use types::{
+    OperationEvictionReason, OperationPoolRejectionStats, PoolOperationFilter, PoolOperationInfo,
+};
 
 #[cfg(feature = "test-exports")]
 pub use controller_traits::{MockPoolController, MockPoolControllerWrapper};
@@ -1,9 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::config::{
-    BASE_OPERATION_GAS_COST, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, MAX_BLOCK_SIZE,
-    MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
-    OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, ROLL_PRICE, T0, THREAD_COUNT,
+    BASE_OPERATION_GAS_COST, CHAINID, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT,
+    MAX_BLOCK_SIZE, MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK,
+    MAX_OPERATIONS_PER_BLOCK, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, ROLL_PRICE, T0,
+    THREAD_COUNT,
 };
 use massa_time::MassaTime;
 
@@ -13,6 +14,7 @@ impl Default for PoolConfig {
     fn default() -> Self {
         Self {
             thread_count: THREAD_COUNT,
+            chain_id: *CHAINID,
             operation_validity_periods: OPERATION_VALIDITY_PERIODS,
             max_block_gas: MAX_GAS_PER_BLOCK,
             sp_compilation_cost: 314_000_000,
@@ -21,6 +23,9 @@ impl Default for PoolConfig {
             base_operation_gas_cost: BASE_OPERATION_GAS_COST,
             max_operation_pool_size: 32000,
             max_operation_pool_excess_items: 10000,
+            max_operations_per_sender: 1000,
+            max_evicted_operations_history: 1000,
+            mempool_mode: false,
             max_endorsements_pool_size_per_thread: 1000,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
@@ -0,0 +1,73 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use serde::{Deserialize, Serialize};
+
+/// Reason why an operation was evicted from the operation pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationEvictionReason {
+    /// The operation uses more gas or space than a whole block
+    ExceedsBlockLimits,
+    /// The operation is not valid during any of the PoS draws of interest
+    NoInclusionOpportunity,
+    /// The operation was already executed in a final or candidate slot
+    AlreadyExecuted,
+    /// The sender does not exist or cannot afford the operation
+    InsufficientBalance,
+    /// The sender cannot afford the operation on top of its better scored operations
+    BalanceOverflow,
+    /// The pool is full and the score of the operation is too low to be kept
+    PoolFull,
+    /// The sender replaced the operation by an otherwise identical one paying a higher fee
+    ReplacedByFee,
+}
+
+/// Information about an operation pending in the pool or recently evicted from it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolOperationInfo {
+    /// operation id
+    pub id: OperationId,
+    /// address of the operation sender
+    pub sender: Address,
+    /// thread of the operation
+    pub thread: u8,
+    /// operation fee
+    pub fee: Amount,
+    /// first period during which the operation can be included (included)
+    pub validity_start_period: u64,
+    /// last period during which the operation can be included (included)
+    pub validity_end_period: u64,
+    /// score computed at the last pool refresh, `None` if the operation was not scored yet
+    pub score: Option<f32>,
+    /// `None` if the operation is pending, otherwise the reason why it was evicted
+    pub eviction_reason: Option<OperationEvictionReason>,
+}
+
+/// Filter used when listing the operations of the pool
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolOperationFilter {
+    /// only list the operations sent by this address
+    pub sender: Option<Address>,
+    /// only list the operations of this thread
+    pub thread: Option<u8>,
+}
+
+impl PoolOperationFilter {
+    /// Returns true if the operation matches all the criteria of the filter
+    pub fn matches(&self, info: &PoolOperationInfo) -> bool {
+        self.sender.map_or(true, |sender| sender == info.sender)
+            && self.thread.map_or(true, |thread| thread == info.thread)
+    }
+}
+
+/// Counters of the operations refused or replaced by the operation pool since the node started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationPoolRejectionStats {
+    /// operations refused because their sender already had the maximum number of pending operations
+    pub sender_limit_reached: u64,
+    /// operations refused because they did not pay a strictly higher fee
+    /// than the otherwise identical pending operation of their sender
+    pub replacement_fee_too_low: u64,
+    /// pending operations replaced by an otherwise identical operation paying a higher fee
+    pub replaced_by_fee: u64,
+}
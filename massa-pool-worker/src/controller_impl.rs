@@ -3,19 +3,24 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    amount::Amount, block_id::BlockId, denunciation::Denunciation,
+    denunciation::DenunciationPrecursor, endorsement::EndorsementId, operation::OperationId,
+    slot::Slot,
+};
+use massa_pool_exports::{
+    OperationPoolRejectionStats, PoolConfig, PoolController, PoolManager, PoolOperationFilter,
+    PoolOperationInfo,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::sync::mpsc::TrySendError;
 use std::sync::{mpsc::SyncSender, Arc};
 use tracing::{info, warn};
 
 use crate::{
     denunciation_pool::DenunciationPool, endorsement_pool::EndorsementPool,
-    operation_pool::OperationPool,
+    operation_pool::OperationPool, snapshot::PoolSnapshot,
 };
 
 /// A generic command to send commands to a pool
@@ -216,6 +221,35 @@ impl PoolController for PoolControllerImpl {
         operations.iter().map(|id| lck.contains(id)).collect()
     }
 
+    /// List the pending and recently evicted operations of the pool
+    fn get_operations_info(
+        &self,
+        filter: &PoolOperationFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<PoolOperationInfo> {
+        self.operation_pool
+            .read()
+            .get_operations_info(filter, offset, limit)
+    }
+
+    /// Estimate the lowest fee for an operation to be included in the blocks of the next periods
+    fn estimate_inclusion_fee(
+        &self,
+        size: usize,
+        max_gas_usage: u64,
+        periods: u64,
+    ) -> Option<Amount> {
+        self.operation_pool
+            .read()
+            .estimate_inclusion_fee(size, max_gas_usage, periods)
+    }
+
+    /// Get the counters of the operations refused or replaced by the operation pool
+    fn get_operation_rejection_stats(&self) -> OperationPoolRejectionStats {
+        self.operation_pool.read().get_rejection_stats()
+    }
+
     /// Get the number of denunciations in the pool
     fn get_denunciation_count(&self) -> usize {
         self.denunciation_pool.read().len()
@@ -249,6 +283,14 @@ pub struct PoolManagerImpl {
     pub(crate) endorsements_input_sender: SyncSender<Command>,
     /// Denunciations input data mpsc (used to stop the pool thread)
     pub(crate) denunciations_input_sender: SyncSender<Command>,
+    /// Shared reference to the operation pool (used to save its contents on stop)
+    pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
+    /// Shared reference to the endorsement pool (used to save its contents on stop)
+    pub(crate) endorsement_pool: Arc<RwLock<EndorsementPool>>,
+    /// Shared reference to the denunciation pool (used to save its contents on stop)
+    pub(crate) denunciation_pool: Arc<RwLock<DenunciationPool>>,
+    /// Where to save the pool contents on stop
+    pub(crate) snapshot_path: Option<PathBuf>,
 }
 
 impl PoolManager for PoolManagerImpl {
@@ -273,6 +315,14 @@ impl PoolManager for PoolManagerImpl {
                 .join()
                 .expect("denunciations pool thread panicked on try to join");
         }
+        if let Some(path) = &self.snapshot_path {
+            PoolSnapshot::new(
+                &self.operation_pool.read(),
+                &self.endorsement_pool.read(),
+                &self.denunciation_pool.read(),
+            )
+            .save(path);
+        }
         info!("pool workers stopped");
     }
 }
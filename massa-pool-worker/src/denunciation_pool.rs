@@ -184,6 +184,35 @@ impl DenunciationPool {
         // cleanup function only when it is needed
     }
 
+    /// Add an already created denunciation to the pool, unless it is too old
+    pub(crate) fn add_denunciation(&mut self, denunciation: Denunciation) {
+        let slot = denunciation.get_slot();
+        if slot.period <= self.config.last_start_period
+            || Denunciation::is_expired(
+                &slot.period,
+                self.last_cs_final_periods.iter().min().unwrap_or(&0),
+                &self.config.denunciation_expire_periods,
+            )
+        {
+            return;
+        }
+        self.denunciations_cache.insert(
+            DenunciationIndex::from(&denunciation),
+            DenunciationStatus::DenunciationEmitted(denunciation),
+        );
+    }
+
+    /// Get the denunciations created by the pool
+    pub(crate) fn get_denunciations(&self) -> Vec<Denunciation> {
+        self.denunciations_cache
+            .values()
+            .filter_map(|de_st| match de_st {
+                DenunciationStatus::DenunciationEmitted(de) => Some(de.clone()),
+                DenunciationStatus::Accumulating(_) => None,
+            })
+            .collect()
+    }
+
     /// cleanup internal cache, removing too old denunciation
     fn cleanup_caches(&mut self) {
         cleanup_cache(
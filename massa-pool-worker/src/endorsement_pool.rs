@@ -2,7 +2,7 @@
 
 use massa_models::{
     block_id::BlockId,
-    endorsement::EndorsementId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
     prehash::{CapacityAllocator, PreHashSet},
     slot::Slot,
 };
@@ -68,6 +68,16 @@ impl EndorsementPool {
         self.storage.get_endorsement_refs().contains(id)
     }
 
+    /// Get the pending endorsements
+    pub(crate) fn get_endorsements(&self) -> Vec<SecureShareEndorsement> {
+        let endorsements = self.storage.read_endorsements();
+        self.storage
+            .get_endorsement_refs()
+            .iter()
+            .filter_map(|id| endorsements.get(id).cloned())
+            .collect()
+    }
+
     /// notify of new final CS periods
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final CS period counter
@@ -9,6 +9,7 @@ mod controller_impl;
 mod denunciation_pool;
 mod endorsement_pool;
 mod operation_pool;
+mod snapshot;
 mod types;
 mod worker;
 
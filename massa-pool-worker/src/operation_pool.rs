@@ -3,17 +3,26 @@
 use massa_models::{
     address::Address,
     amount::Amount,
-    operation::OperationId,
+    operation::{OperationId, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{
+    OperationEvictionReason, OperationPoolRejectionStats, PoolChannels, PoolConfig,
+    PoolOperationFilter, PoolOperationInfo,
+};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
+use std::{
+    cmp::max,
+    cmp::Ordering,
+    cmp::PartialOrd,
+    collections::{BTreeSet, VecDeque},
+    sync::Arc,
+};
 use tracing::{debug, trace, warn};
 
 use crate::types::OperationInfo;
@@ -25,6 +34,15 @@ pub struct OperationPool {
     /// operations map
     sorted_ops: Vec<OperationInfo>,
 
+    /// scores computed at the last refresh
+    scores: PreHashMap<OperationId, f32>,
+
+    /// recently evicted operations, from the most recent
+    evicted_ops: VecDeque<PoolOperationInfo>,
+
+    /// counters of the operations refused or replaced because of the per-sender limit and replace-by-fee
+    rejection_stats: OperationPoolRejectionStats,
+
     /// storage instance
     pub(crate) storage: Storage,
 
@@ -51,6 +69,9 @@ impl OperationPool {
                     .max_operation_pool_size
                     .saturating_add(config.max_operation_pool_excess_items),
             ),
+            scores: Default::default(),
+            evicted_ops: VecDeque::with_capacity(config.max_evicted_operations_history),
+            rejection_stats: Default::default(),
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
             config,
             storage: storage.clone_without_refs(),
@@ -59,7 +80,8 @@ impl OperationPool {
         }
     }
 
-    /// Get the relevant PoS draws of our staking addresses
+    /// Get the relevant PoS draws of our staking addresses,
+    /// or of any producer when the pool runs in mempool mode
     fn get_pos_draws(&mut self) -> BTreeSet<Slot> {
         let now = MassaTime::now();
 
@@ -83,22 +105,31 @@ impl OperationPool {
         .unwrap_or(min_slot);
         let max_slot = max(max_slot, min_slot);
 
-        // search for all our PoS draws in the interval of interest
-        let addrs: PreHashSet<Address> = self.wallet.read().keys.keys().copied().collect();
-        let mut pos_draws: BTreeSet<Slot> = self
-            .channels
-            .selector
-            .get_available_selections_in_range(min_slot..=max_slot, Some(&addrs))
-            .expect("could not get PoS draws")
-            .into_iter()
-            .filter_map(|(v_slot, v_sel)| {
-                if addrs.contains(&v_sel.producer) {
-                    Some(v_slot)
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let mut pos_draws: BTreeSet<Slot> = if self.config.mempool_mode {
+            // search for the PoS draws of any producer in the interval of interest
+            self.channels
+                .selector
+                .get_available_selections_in_range(min_slot..=max_slot, None)
+                .expect("could not get PoS draws")
+                .into_keys()
+                .collect()
+        } else {
+            // search for all our PoS draws in the interval of interest
+            let addrs: PreHashSet<Address> = self.wallet.read().keys.keys().copied().collect();
+            self.channels
+                .selector
+                .get_available_selections_in_range(min_slot..=max_slot, Some(&addrs))
+                .expect("could not get PoS draws")
+                .into_iter()
+                .filter_map(|(v_slot, v_sel)| {
+                    if addrs.contains(&v_sel.producer) {
+                        Some(v_slot)
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
 
         // retain only the ones that are strictly after the last final slot of their thread
         pos_draws.retain(|s| s.period > self.last_cs_final_periods[s.thread as usize]);
@@ -124,6 +155,16 @@ impl OperationPool {
             .collect()
     }
 
+    /// Check whether operations were executed in final or candidate slots. Returns one boolean per item.
+    pub(crate) fn are_executed(&self, op_ids: &[OperationId]) -> Vec<bool> {
+        self.channels
+            .execution_controller
+            .get_ops_exec_status(op_ids)
+            .into_iter()
+            .map(|(spec_status, final_status)| spec_status.is_some() || final_status.is_some())
+            .collect()
+    }
+
     /// Get the candidate balances of the addresses sending the ops.
     /// Addresses that don't exist are not returned.
     fn get_sender_balances(&self) -> PreHashMap<Address, Amount> {
@@ -152,41 +193,44 @@ impl OperationPool {
         sender_balances: &PreHashMap<Address, Amount>,
     ) {
         let mut removed = PreHashSet::default();
+        let mut evicted = Vec::new();
         self.sorted_ops.retain(|op_info| {
-            // filter out ops that use too much resources
-            let mut retain = (op_info.max_gas_usage <= self.config.max_block_gas)
-                && (op_info.size <= self.config.max_block_size as usize);
-
-            // filter out ops that are not valid during our PoS draws
-            if retain {
-                retain = pos_draws.iter().any(|slot| {
-                    op_info.thread == slot.thread
-                        && op_info.validity_period_range.contains(&slot.period)
-                });
-            }
-
-            // filter out ops that have been executed in final or candidate slots
-            // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
-            if retain {
-                retain = !exec_statuses.contains_key(&op_info.id);
-            }
-
-            // filter out ops that spend more than the sender's balance
-            if retain {
-                retain = match sender_balances.get(&op_info.creator_address) {
-                    Some(v) => &op_info.max_spending <= v,
-                    None => false, // filter out ops for which the sender does not exist
-                };
-            }
+            let eviction_reason = if op_info.max_gas_usage > self.config.max_block_gas
+                || op_info.size > self.config.max_block_size as usize
+            {
+                // filter out ops that use too much resources
+                Some(OperationEvictionReason::ExceedsBlockLimits)
+            } else if !pos_draws.iter().any(|slot| {
+                op_info.thread == slot.thread
+                    && op_info.validity_period_range.contains(&slot.period)
+            }) {
+                // filter out ops that are not valid during our PoS draws
+                Some(OperationEvictionReason::NoInclusionOpportunity)
+            } else if exec_statuses.contains_key(&op_info.id) {
+                // filter out ops that have been executed in final or candidate slots
+                // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
+                Some(OperationEvictionReason::AlreadyExecuted)
+            } else if !sender_balances
+                .get(&op_info.creator_address)
+                .map_or(false, |v| &op_info.max_spending <= v)
+            {
+                // filter out ops that spend more than the sender's balance
+                // as well as ops for which the sender does not exist
+                Some(OperationEvictionReason::InsufficientBalance)
+            } else {
+                None
+            };
 
-            if !retain {
+            if let Some(reason) = eviction_reason {
                 removed.insert(op_info.id);
+                evicted.push(op_info.to_pool_info(None, Some(reason)));
                 return false;
             }
             true
         });
         // drop from storage
         self.storage.drop_operation_refs(&removed);
+        self.record_evictions(evicted);
     }
 
     /// Eliminate all operations that would cause a sender balance overflow.
@@ -194,6 +238,7 @@ impl OperationPool {
     fn eliminate_balance_overflows(&mut self, sender_balances: &PreHashMap<Address, Amount>) {
         let mut balance_cache = PreHashMap::default();
         let mut removed = PreHashSet::default();
+        let mut evicted = Vec::new();
         self.sorted_ops.retain(|op_info| {
             let balance = balance_cache
                 .entry(op_info.creator_address)
@@ -210,32 +255,68 @@ impl OperationPool {
                 }
                 None => {
                     removed.insert(op_info.id);
+                    evicted.push(op_info.to_pool_info(
+                        self.scores.get(&op_info.id).copied(),
+                        Some(OperationEvictionReason::BalanceOverflow),
+                    ));
                     false
                 }
             }
         });
         // drop from storage
         self.storage.drop_operation_refs(&removed);
+        self.record_evictions(evicted);
     }
 
     /// Truncates the container to the max allowed size
     fn truncate_container(&mut self) {
         if self.sorted_ops.len() > self.config.max_operation_pool_size {
             let mut removed = PreHashSet::default();
+            let mut evicted = Vec::new();
             for op_info in self
                 .sorted_ops
                 .iter()
                 .skip(self.config.max_operation_pool_size)
             {
                 removed.insert(op_info.id);
+                evicted.push(op_info.to_pool_info(
+                    self.scores.get(&op_info.id).copied(),
+                    Some(OperationEvictionReason::PoolFull),
+                ));
             }
             self.sorted_ops
                 .truncate(self.config.max_operation_pool_size);
             // drop from storage
             self.storage.drop_operation_refs(&removed);
+            self.record_evictions(evicted);
         }
     }
 
+    /// Keep track of recently evicted operations, dropping the oldest ones beyond the history size
+    fn record_evictions(&mut self, evicted: Vec<PoolOperationInfo>) {
+        for info in evicted {
+            self.evicted_ops.push_front(info);
+        }
+        self.evicted_ops
+            .truncate(self.config.max_evicted_operations_history);
+    }
+
+    /// Resource factor of the score of an operation: mean of its size and gas scores
+    fn resource_factor(&self, size: usize, max_gas_usage: u64) -> f32 {
+        // size score:
+        //    0% of block size => score 1
+        //    100% of block size => score 0
+        let size_score = 1.0 - (size as f32) / (self.config.max_block_size as f32);
+
+        // gas score:
+        //    0% of block gas => score 1
+        //    100% of block gas => score 0
+        let gas_score = 1.0 - (max_gas_usage as f32) / (self.config.max_block_gas as f32);
+
+        let epsilon_resource_factor = 0.0001; // avoids zero score when gas and size are a perfect fit in the block
+        (epsilon_resource_factor + size_score + gas_score) / (2.0 + epsilon_resource_factor)
+    }
+
     /// Score the operations
     fn score_operations(
         &self,
@@ -258,25 +339,14 @@ impl OperationPool {
             // (we add 1 to still sort zero-fee ops)
             let fee_factor = op_info.fee.to_raw().saturating_add(1) as f32;
 
-            // size score:
-            //    0% of block size => score 1
-            //    100% of block size => score 0
-            let size_score = 1.0 - (op_info.size as f32) / (self.config.max_block_size as f32);
-
-            // gas score:
-            //    0% of block gas => score 1
-            //    100% of block gas => score 0
-            let gas_score =
-                1.0 - (op_info.max_gas_usage as f32) / (self.config.max_block_gas as f32);
-
-            // general resource score (mean of gas and size scores)
-            let epsilon_resource_factor = 0.0001; // avoids zero score when gas and size are a perfect fit in the block
-            let resource_factor = (epsilon_resource_factor + size_score + gas_score)
-                / (2.0 + epsilon_resource_factor);
+            // general resource score
+            let resource_factor = self.resource_factor(op_info.size, op_info.max_gas_usage);
 
             // inclusion probability factor
             //    If we are selected to produce a block in a long time,
             //    there is exponential likelihood that someone includes the op before us.
+            //    In mempool mode, all the draws are considered so this factor is 1 for any op
+            //    that can be included soon: ops are then ranked by fee and resource usage.
             let tau_inclusion = 2.0; // exponential decay factor
             let earliest_inclusion_opportunity = pos_draws.iter().find_map(|s| {
                 if s.thread == op_info.thread
@@ -352,6 +422,7 @@ impl OperationPool {
                 .partial_cmp(&scores.get(&op1.id))
                 .unwrap_or(Ordering::Equal)
         });
+        self.scores = scores;
 
         // eliminate balance overflows in sorted ops
         self.eliminate_balance_overflows(&sender_balances);
@@ -365,6 +436,106 @@ impl OperationPool {
         self.sorted_ops.len()
     }
 
+    /// List the pending operations from best to worst, followed by the recently evicted ones.
+    /// Only the `limit` matching operations following the first `offset` ones are listed.
+    pub fn get_operations_info(
+        &self,
+        filter: &PoolOperationFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<PoolOperationInfo> {
+        self.sorted_ops
+            .iter()
+            .map(|op_info| op_info.to_pool_info(self.scores.get(&op_info.id).copied(), None))
+            .chain(self.evicted_ops.iter().cloned())
+            .filter(|info| filter.matches(info))
+            .skip(offset)
+            .take(limit)
+            .collect()
+    }
+
+    /// Estimate the lowest fee for which an operation of the given size and max gas usage
+    /// would currently be included in the blocks of its thread during the next `periods` periods.
+    ///
+    /// The blocks are filled with the pending operations from best to worst, within the size, gas and
+    /// operation count limits of a block, until the operation would no longer fit in any of them:
+    /// it then has to be scored above the operation that took its place.
+    /// Since an operation cannot wait longer than its validity, at most `operation_validity_periods`
+    /// periods are considered.
+    ///
+    /// The operation is assumed to have an immediate inclusion opportunity, and since its thread
+    /// depends on its sender, the most competitive thread is considered.
+    /// Returns `None` if the operation does not fit in a block.
+    pub fn estimate_inclusion_fee(
+        &self,
+        size: usize,
+        max_gas_usage: u64,
+        periods: u64,
+    ) -> Option<Amount> {
+        if size > self.config.max_block_size as usize || max_gas_usage > self.config.max_block_gas {
+            return None;
+        }
+        let block_count = periods
+            .min(self.config.operation_validity_periods)
+            .clamp(1, self.sorted_ops.len() as u64 + 1) as usize;
+
+        // score of the best operation that would take the place of the estimated one, in any thread
+        let mut threshold_score: f32 = 0.0;
+        for thread in 0..self.config.thread_count {
+            // remaining space, gas and operation count of the blocks of the thread
+            let mut blocks = vec![
+                (
+                    self.config.max_block_size as usize,
+                    self.config.max_block_gas,
+                    self.config.max_operations_per_block,
+                );
+                block_count
+            ];
+            for op_info in self.sorted_ops.iter().filter(|op| op.thread == thread) {
+                let Some(score) = self.scores.get(&op_info.id) else {
+                    continue;
+                };
+                if let Some(block) = blocks.iter_mut().find(|(space, gas, count)| {
+                    op_info.size <= *space && op_info.max_gas_usage <= *gas && *count > 0
+                }) {
+                    block.0 -= op_info.size;
+                    block.1 -= op_info.max_gas_usage;
+                    block.2 -= 1;
+                }
+                if !blocks.iter().any(|(space, gas, count)| {
+                    size <= *space && max_gas_usage <= *gas && *count > 0
+                }) {
+                    threshold_score = threshold_score.max(*score);
+                    break;
+                }
+            }
+        }
+        if threshold_score <= 0.0 {
+            return Some(Amount::zero());
+        }
+
+        // score = (fee + 1) * resource factor, with an inclusion factor of 1.
+        // The fee is rounded up and one unit is added to be scored strictly above the threshold
+        // despite the rounding of the scores.
+        let resource_factor = self.resource_factor(size, max_gas_usage);
+        let fee = (threshold_score / resource_factor).ceil() as u64;
+        Some(Amount::from_raw(fee.saturating_add(1)))
+    }
+
+    /// Get the counters of the operations refused or replaced by the pool
+    pub fn get_rejection_stats(&self) -> OperationPoolRejectionStats {
+        self.rejection_stats
+    }
+
+    /// Get the pending operations, from best to worst
+    pub(crate) fn get_operations(&self) -> Vec<SecureShareOperation> {
+        let ops = self.storage.read_operations();
+        self.sorted_ops
+            .iter()
+            .filter_map(|op_info| ops.get(&op_info.id).cloned())
+            .collect()
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &OperationId) -> bool {
         self.storage.get_op_refs().contains(id)
@@ -382,6 +553,10 @@ impl OperationPool {
 
     /// Add a list of operations to the end of the pool.
     /// They will be cleaned up at the next refresh.
+    ///
+    /// A new operation that is identical to a pending operation of the same sender except for its fee
+    /// replaces it if it pays a strictly higher fee, and is refused otherwise.
+    /// Other new operations are refused if their sender already has `max_operations_per_sender` pending operations.
     pub(crate) fn add_operations(&mut self, mut ops_storage: Storage) {
         // List all the new operations
         let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
@@ -413,12 +588,83 @@ impl OperationPool {
         // Note that the added items are put at the end of the sorted ops
         // so that they can still be picked for block production before refresh but with low priority
         // because in that case we don't know anything about their quality.
+        let mut replaced_op_ids = PreHashSet::default();
+        let mut rejected_op_ids = PreHashSet::default();
         {
             let ops = ops_storage.read_operations();
-            for new_op_id in &new_op_ids {
-                let op = ops
-                    .get(new_op_id)
-                    .expect("operation not found in storage but listed as owned");
+
+            // index the pending operations by sender
+            let mut sender_ops: PreHashMap<Address, Vec<OperationId>> = PreHashMap::default();
+            for op_info in &self.sorted_ops {
+                sender_ops
+                    .entry(op_info.creator_address)
+                    .or_default()
+                    .push(op_info.id);
+            }
+
+            // Process the new ops from the highest to the lowest fee to resolve replace-by-fee within the batch:
+            // a new op can only supersede ops that were pending before the batch, and the lower-fee duplicates
+            // that follow it in the batch are rejected instead of being broadcast and then replaced.
+            let mut new_ops: Vec<_> = new_op_ids
+                .iter()
+                .map(|new_op_id| {
+                    ops.get(new_op_id)
+                        .expect("operation not found in storage but listed as owned")
+                })
+                .collect();
+            new_ops.sort_unstable_by(|a, b| {
+                b.content
+                    .fee
+                    .cmp(&a.content.fee)
+                    .then_with(|| a.id.cmp(&b.id))
+            });
+
+            for op in new_ops {
+                let pending_op_ids = sender_ops.entry(op.content_creator_address).or_default();
+                // look for a pending op of the same sender that differs only by its fee
+                let replaceable = pending_op_ids.iter().position(|pending_op_id| {
+                    ops.get(pending_op_id).map_or(false, |pending_op| {
+                        pending_op.content.expire_period == op.content.expire_period
+                            && pending_op.content.op == op.content.op
+                    })
+                });
+                if let Some(index) = replaceable {
+                    let pending_fee = ops
+                        .get(&pending_op_ids[index])
+                        .expect("pending operation not found in storage")
+                        .content
+                        .fee;
+                    if op.content.fee <= pending_fee {
+                        debug!(
+                            "refusing operation {}: its fee does not exceed the one of the pending operation {} it would replace",
+                            op.id, pending_op_ids[index]
+                        );
+                        self.rejection_stats.replacement_fee_too_low = self
+                            .rejection_stats
+                            .replacement_fee_too_low
+                            .saturating_add(1);
+                        rejected_op_ids.insert(op.id);
+                        continue;
+                    }
+                    let replaced_op_id = pending_op_ids.swap_remove(index);
+                    debug!(
+                        "operation {} replaces operation {} with a higher fee",
+                        op.id, replaced_op_id
+                    );
+                    self.rejection_stats.replaced_by_fee =
+                        self.rejection_stats.replaced_by_fee.saturating_add(1);
+                    replaced_op_ids.insert(replaced_op_id);
+                } else if pending_op_ids.len() >= self.config.max_operations_per_sender {
+                    debug!(
+                        "refusing operation {}: sender {} reached the limit of pending operations",
+                        op.id, op.content_creator_address
+                    );
+                    self.rejection_stats.sender_limit_reached =
+                        self.rejection_stats.sender_limit_reached.saturating_add(1);
+                    rejected_op_ids.insert(op.id);
+                    continue;
+                }
+                pending_op_ids.push(op.id);
 
                 // Broadcast operations to active channel subscribers.
                 if self.config.broadcast_enabled {
@@ -438,6 +684,24 @@ impl OperationPool {
             }
         }
 
+        // Remove the replaced pending ops
+        if !replaced_op_ids.is_empty() {
+            let mut evicted = Vec::new();
+            self.sorted_ops.retain(|op_info| {
+                if replaced_op_ids.contains(&op_info.id) {
+                    evicted.push(op_info.to_pool_info(
+                        self.scores.get(&op_info.id).copied(),
+                        Some(OperationEvictionReason::ReplacedByFee),
+                    ));
+                    return false;
+                }
+                true
+            });
+            self.storage.drop_operation_refs(&replaced_op_ids);
+            self.record_evictions(evicted);
+        }
+        new_op_ids.retain(|id| !replaced_op_ids.contains(id) && !rejected_op_ids.contains(id));
+
         // This will add the new ops to the storage without taking locks.
         // It just take the local references from `ops_storage` if they are not in `self.storage` yet.
         // If the objects are already in `self.storage` the references in ops_storage it will not add them to `self.storage` and
@@ -0,0 +1,284 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Snapshot of the pools contents, saved on disk when the pool stops and reloaded at start
+//! so that pending items are not lost across node restarts.
+//!
+//! Reloaded items are not trusted: their ids and signatures are checked again,
+//! expired or already executed operations are dropped and everything else goes through
+//! the regular pool insertion logic.
+
+use massa_models::{
+    address::Address,
+    denunciation::Denunciation,
+    endorsement::{EndorsementSerializer, SecureShareEndorsement},
+    operation::{OperationSerializer, SecureShareOperation},
+    secure_share::{Id, SecureShare, SecureShareContent},
+    timeslots::get_latest_block_slot_at_timestamp,
+};
+use massa_pool_exports::PoolConfig;
+use massa_serialization::Serializer;
+use massa_storage::Storage;
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+use crate::{
+    denunciation_pool::DenunciationPool, endorsement_pool::EndorsementPool,
+    operation_pool::OperationPool,
+};
+
+/// Contents of the pools saved on disk
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct PoolSnapshot {
+    /// pending operations
+    operations: Vec<SecureShareOperation>,
+    /// pending endorsements
+    endorsements: Vec<SecureShareEndorsement>,
+    /// denunciations created by the pool
+    denunciations: Vec<Denunciation>,
+}
+
+impl PoolSnapshot {
+    /// Gather the current contents of the pools
+    pub fn new(
+        operation_pool: &OperationPool,
+        endorsement_pool: &EndorsementPool,
+        denunciation_pool: &DenunciationPool,
+    ) -> Self {
+        PoolSnapshot {
+            operations: operation_pool.get_operations(),
+            endorsements: endorsement_pool.get_endorsements(),
+            denunciations: denunciation_pool.get_denunciations(),
+        }
+    }
+
+    /// Write the snapshot to disk. Failures are only logged.
+    pub fn save(&self, path: &Path) {
+        let res = serde_json::to_vec(self)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| std::fs::write(path, bytes).map_err(|err| err.to_string()));
+        match res {
+            Ok(()) => info!(
+                "pool snapshot saved to {}: {} operations, {} endorsements, {} denunciations",
+                path.display(),
+                self.operations.len(),
+                self.endorsements.len(),
+                self.denunciations.len()
+            ),
+            Err(err) => warn!(
+                "could not save pool snapshot to {}: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+
+    /// Read a snapshot from disk, if any. Failures are only logged.
+    pub fn load(path: &Path) -> Option<Self> {
+        if !path.exists() {
+            return None;
+        }
+        let res = std::fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|err| err.to_string()));
+        match res {
+            Ok(snapshot) => Some(snapshot),
+            Err(err) => {
+                warn!(
+                    "could not load pool snapshot from {}: {}",
+                    path.display(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    /// Delete a snapshot once its contents were restored, so that it cannot be restored twice
+    /// (e.g. if the node crashes before saving a new one). Failures are only logged.
+    pub fn remove(path: &Path) {
+        if let Err(err) = std::fs::remove_file(path) {
+            warn!("could not remove pool snapshot {}: {}", path.display(), err);
+        }
+    }
+
+    /// Re-validate the items of the snapshot and insert the valid ones in the pools
+    pub fn restore(
+        self,
+        config: &PoolConfig,
+        storage: &Storage,
+        operation_pool: &mut OperationPool,
+        endorsement_pool: &mut EndorsementPool,
+        denunciation_pool: &mut DenunciationPool,
+    ) {
+        // operations that already expired can be dropped
+        let now_period = get_latest_block_slot_at_timestamp(
+            config.thread_count,
+            config.t0,
+            config.genesis_timestamp,
+            MassaTime::now(),
+        )
+        .ok()
+        .flatten()
+        .map_or(0, |slot| slot.period)
+        .max(config.last_start_period);
+        let operations: Vec<SecureShareOperation> = self
+            .operations
+            .into_iter()
+            .filter_map(|op| restore_secure_share(op, &OperationSerializer::new(), config.chain_id))
+            .filter(|op| op.content.expire_period >= now_period)
+            .collect();
+
+        // operations that were executed in the meantime can be dropped
+        let op_ids: Vec<_> = operations.iter().map(|op| op.id).collect();
+        let operations: Vec<SecureShareOperation> = operation_pool
+            .are_executed(&op_ids)
+            .into_iter()
+            .zip(operations)
+            .filter_map(|(executed, op)| if executed { None } else { Some(op) })
+            .collect();
+
+        let endorsements: Vec<SecureShareEndorsement> = self
+            .endorsements
+            .into_iter()
+            .filter_map(|endo| {
+                restore_secure_share(endo, &EndorsementSerializer::new(), config.chain_id)
+            })
+            .collect();
+
+        let denunciations: Vec<Denunciation> = self
+            .denunciations
+            .into_iter()
+            .filter(|de| de.is_valid())
+            .collect();
+
+        info!(
+            "restoring pool snapshot: {} operations, {} endorsements, {} denunciations",
+            operations.len(),
+            endorsements.len(),
+            denunciations.len()
+        );
+
+        let mut ops_storage = storage.clone_without_refs();
+        ops_storage.store_operations(operations);
+        operation_pool.add_operations(ops_storage);
+
+        let mut endorsements_storage = storage.clone_without_refs();
+        endorsements_storage.store_endorsements(endorsements);
+        endorsement_pool.add_endorsements(endorsements_storage);
+
+        for denunciation in denunciations {
+            denunciation_pool.add_denunciation(denunciation);
+        }
+    }
+}
+
+/// Rebuild the serialized content of a secure share read from a snapshot
+/// (it is not part of its serde representation) and check that its id,
+/// creator address and signature match that content.
+fn restore_secure_share<T, ID, Ser>(
+    mut secure_share: SecureShare<T, ID>,
+    content_serializer: &Ser,
+    chain_id: u64,
+) -> Option<SecureShare<T, ID>>
+where
+    T: SecureShareContent,
+    ID: Id,
+    Ser: Serializer<T>,
+{
+    let mut serialized_data = Vec::new();
+    if let Err(err) = content_serializer.serialize(&secure_share.content, &mut serialized_data) {
+        debug!(
+            "dropping snapshot item that could not be serialized: {}",
+            err
+        );
+        return None;
+    }
+    let hash = secure_share.content.compute_hash(
+        &serialized_data,
+        &secure_share.content_creator_pub_key,
+        chain_id,
+    );
+    if &hash != secure_share.id.get_hash()
+        || secure_share.content_creator_address
+            != Address::from_public_key(&secure_share.content_creator_pub_key)
+    {
+        debug!("dropping snapshot item with an inconsistent id or creator address");
+        return None;
+    }
+    secure_share.serialized_data = serialized_data;
+    if let Err(err) = secure_share.verify_signature() {
+        debug!("dropping snapshot item with an invalid signature: {}", err);
+        return None;
+    }
+    Some(secure_share)
+}
+
+#[test]
+fn test_restore_secure_share() {
+    use crate::tests::tools::OpGenerator;
+    use massa_models::{amount::Amount, config::CHAINID};
+    use std::str::FromStr;
+
+    let op = OpGenerator::default()
+        .fee(Amount::from_str("1").unwrap())
+        .generate();
+    let json = serde_json::to_string(&op).unwrap();
+
+    // a genuine operation is restored with its serialized content
+    let restored: SecureShareOperation = serde_json::from_str(&json).unwrap();
+    let restored = restore_secure_share(restored, &OperationSerializer::new(), *CHAINID)
+        .expect("valid operation dropped");
+    assert_eq!(restored.id, op.id);
+    assert_eq!(restored.serialized_data, op.serialized_data);
+
+    // a tampered operation is dropped
+    let mut tampered: SecureShareOperation = serde_json::from_str(&json).unwrap();
+    tampered.content.fee = Amount::from_str("1000").unwrap();
+    assert!(restore_secure_share(tampered, &OperationSerializer::new(), *CHAINID).is_none());
+}
+
+#[test]
+fn test_snapshot_save_load() {
+    use crate::tests::tools::{create_endorsement, OpGenerator};
+    use massa_models::{config::CHAINID, slot::Slot};
+    use massa_signature::KeyPair;
+
+    let op_gen = OpGenerator::default();
+    let snapshot = PoolSnapshot {
+        operations: vec![op_gen.generate(), op_gen.generate()],
+        endorsements: vec![create_endorsement(
+            &KeyPair::generate(0).unwrap(),
+            0,
+            Slot::new(1, 0),
+        )],
+        denunciations: Vec::new(),
+    };
+    let tmp_dir = tempfile::TempDir::new().unwrap();
+    let path = tmp_dir.path().join("pool_snapshot.json");
+    assert!(PoolSnapshot::load(&path).is_none());
+
+    snapshot.save(&path);
+    let loaded = PoolSnapshot::load(&path).expect("snapshot not loaded");
+    assert_eq!(loaded.operations.len(), 2);
+    assert_eq!(loaded.endorsements.len(), 1);
+    for (loaded, saved) in loaded.operations.into_iter().zip(&snapshot.operations) {
+        let restored = restore_secure_share(loaded, &OperationSerializer::new(), *CHAINID)
+            .expect("valid operation dropped");
+        assert_eq!(restored.id, saved.id);
+        assert_eq!(restored.serialized_data, saved.serialized_data);
+    }
+    let restored = restore_secure_share(
+        loaded.endorsements[0].clone(),
+        &EndorsementSerializer::new(),
+        *CHAINID,
+    )
+    .expect("valid endorsement dropped");
+    assert_eq!(restored.id, snapshot.endorsements[0].id);
+
+    // a restored snapshot is not restored again
+    PoolSnapshot::remove(&path);
+    assert!(!path.exists());
+    assert!(PoolSnapshot::load(&path).is_none());
+}
@@ -12,6 +12,28 @@
 //! Same as classic but we try to add irrelevant operation. (See the definition
 //! chapter below)
 //!
+//! # Inspect operations
+//! Function: [`test_get_operations_info`]
+//! Irrelevant operations are evicted and listed with their eviction reason,
+//! optionally filtered by sender.
+//!
+//! # Mempool mode
+//! Function: [`test_mempool_mode`]
+//! Operations are kept for any selected producer even if the node does not stake.
+//!
+//! # Fee estimation
+//! Function: [`test_estimate_inclusion_fee`]
+//! The estimated fee depends on how many pending operations fit in the blocks of the periods.
+//!
+//! # Per-sender limit and replace-by-fee
+//! Function: [`test_sender_limit_and_replace_by_fee`]
+//! A sender cannot exceed its quota of pending operations but can replace one of
+//! them by an otherwise identical operation paying a strictly higher fee.
+//!
+//! # Replace-by-fee within a batch
+//! Function: [`test_replace_by_fee_within_batch`]
+//! When both operations are added in the same batch, only the one paying more is kept.
+//!
 //! # Definition
 //! Relevant operation: Operation with a validity range corresponding to the
 //! latest period given his own thread. All operation which doesn't fit these
@@ -22,10 +44,15 @@ use crate::tests::tools::OpGenerator;
 use super::tools::{
     create_some_operations, default_mock_execution_controller, pool_test, PoolTestBoilerPlate,
 };
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
-use massa_pool_exports::PoolConfig;
+use massa_models::{
+    address::Address, amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot,
+};
+use massa_pool_exports::{
+    OperationEvictionReason, OperationPoolRejectionStats, PoolConfig, PoolOperationFilter,
+};
 use massa_pos_exports::{MockSelectorController, Selection};
-use std::{collections::BTreeMap, time::Duration};
+use massa_signature::KeyPair;
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
 
 #[test]
 fn test_add_operation() {
@@ -136,6 +163,423 @@ fn test_add_irrelevant_operation() {
     );
 }
 
+/// Test that evicted operations can be inspected along with their eviction reason.
+#[test]
+fn test_get_operations_info() {
+    let pool_config = PoolConfig::default();
+    let thread_count = pool_config.thread_count;
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, mut storage| {
+            let creator = KeyPair::generate(0).unwrap();
+            let creator_address = Address::from_public_key(&creator.get_public_key());
+            let op_gen = OpGenerator::default().expirery(2);
+            storage.store_operations(create_some_operations(5, &op_gen));
+            storage.store_operations(create_some_operations(5, &op_gen.creator(creator)));
+            operation_pool.notify_final_cs_periods(&vec![51; thread_count.into()]);
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add and refresh the operations
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(operation_pool.get_operation_count(), 0);
+
+            let infos =
+                operation_pool.get_operations_info(&PoolOperationFilter::default(), 0, usize::MAX);
+            assert_eq!(infos.len(), 10);
+            assert!(infos.iter().all(|info| info.eviction_reason
+                == Some(OperationEvictionReason::NoInclusionOpportunity)));
+
+            let filter = PoolOperationFilter {
+                sender: Some(creator_address),
+                thread: None,
+            };
+            let infos = operation_pool.get_operations_info(&filter, 0, usize::MAX);
+            assert_eq!(infos.len(), 5);
+            assert!(infos.iter().all(|info| info.sender == creator_address
+                && info.thread == creator_address.get_thread(thread_count)));
+
+            // paginated
+            let page = operation_pool.get_operations_info(&filter, 3, 10);
+            assert_eq!(page, infos[3..]);
+        },
+    );
+}
+
+/// Test that in mempool mode the operations are kept even if we are never selected.
+#[test]
+fn test_mempool_mode() {
+    let pool_config = PoolConfig {
+        mempool_mode: true,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    assert!(opt_addrs.is_none(), "draws restricted to our addresses");
+                    // the slots are drawn for a producer that is not in our staking wallet
+                    let addr =
+                        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+                    let mut all_slots = BTreeMap::new();
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, mut storage| {
+            let op_gen = OpGenerator::default().expirery(2);
+            storage.store_operations(create_some_operations(10, &op_gen));
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add and refresh the operations
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(operation_pool.get_operation_count(), 10);
+            assert!(operation_pool
+                .get_operations_info(&PoolOperationFilter::default(), 0, usize::MAX)
+                .iter()
+                .all(|info| info.eviction_reason.is_none() && info.score.is_some()));
+        },
+    );
+}
+
+/// Test that the fee estimation fills the blocks of the requested periods with the pending operations.
+#[test]
+fn test_estimate_inclusion_fee() {
+    let pool_config = PoolConfig {
+        mempool_mode: true,
+        max_operations_per_block: 2,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, _opt_addrs| {
+                    let addr =
+                        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+                    let mut all_slots = BTreeMap::new();
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, mut storage| {
+            // 5 operations of the same sender, thus of the same thread
+            let creator = KeyPair::generate(0).unwrap();
+            let ops: Vec<_> = (1..=5u64)
+                .map(|fee| {
+                    OpGenerator::default()
+                        .expirery(2)
+                        .creator(creator.clone())
+                        .fee(Amount::from_raw(fee * 1000))
+                        .generate()
+                })
+                .collect();
+            let size = ops[0].serialized_size();
+            let max_gas = ops[0].get_gas_usage(
+                pool_config.base_operation_gas_cost,
+                pool_config.sp_compilation_cost,
+            );
+            storage.store_operations(ops);
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add and refresh the operations
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(operation_pool.get_operation_count(), 5);
+
+            // one block of 2 operations: the op has to beat the 2nd best one
+            let one_period = operation_pool
+                .estimate_inclusion_fee(size, max_gas, 1)
+                .unwrap();
+            // two blocks: the op has to beat the 4th best one
+            let two_periods = operation_pool
+                .estimate_inclusion_fee(size, max_gas, 2)
+                .unwrap();
+            assert!(one_period > two_periods);
+            assert!(two_periods > Amount::zero());
+            // three blocks have room for all the pending operations and the new one
+            assert_eq!(
+                operation_pool.estimate_inclusion_fee(size, max_gas, 3),
+                Some(Amount::zero())
+            );
+            // an operation larger than a block is never included
+            assert_eq!(
+                operation_pool.estimate_inclusion_fee(
+                    pool_config.max_block_size as usize + 1,
+                    max_gas,
+                    1
+                ),
+                None
+            );
+        },
+    );
+}
+
+/// Test the per-sender limit and the replace-by-fee rule of the operation pool.
+#[test]
+fn test_sender_limit_and_replace_by_fee() {
+    let pool_config = PoolConfig {
+        mempool_mode: true,
+        max_operations_per_sender: 3,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, _opt_addrs| {
+                    let addr =
+                        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+                    let mut all_slots = BTreeMap::new();
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let creator = KeyPair::generate(0).unwrap();
+            let receiver = KeyPair::generate(0).unwrap();
+            // operations of the same sender that only differ by their fee
+            let op_with_fee = |fee: &str| {
+                OpGenerator::default()
+                    .expirery(2)
+                    .creator(creator.clone())
+                    .receiver(receiver.clone())
+                    .fee(Amount::from_str(fee).unwrap())
+                    .generate()
+            };
+            let mut add_ops = |ops| {
+                let mut ops_storage = storage.clone_without_refs();
+                ops_storage.store_operations(ops);
+                operation_pool.add_operations(ops_storage);
+                std::thread::sleep(Duration::from_millis(200));
+            };
+
+            let op = op_with_fee("10");
+            let other_ops =
+                create_some_operations(2, &OpGenerator::default().creator(creator.clone()));
+            add_ops([vec![op.clone()], other_ops].concat());
+            // the sender reached its limit
+            add_ops(create_some_operations(
+                1,
+                &OpGenerator::default().creator(creator.clone()),
+            ));
+            // the replacement does not pay enough
+            add_ops(vec![op_with_fee("5")]);
+            // the replacement pays more
+            let replacement = op_with_fee("20");
+            add_ops(vec![replacement.clone()]);
+
+            assert_eq!(operation_pool.get_operation_count(), 3);
+            assert_eq!(
+                operation_pool.contains_operations(&[op.id, replacement.id]),
+                vec![false, true]
+            );
+            assert!(operation_pool
+                .get_operations_info(&PoolOperationFilter::default(), 0, usize::MAX)
+                .iter()
+                .any(|info| info.id == op.id
+                    && info.eviction_reason == Some(OperationEvictionReason::ReplacedByFee)));
+            assert_eq!(
+                operation_pool.get_operation_rejection_stats(),
+                OperationPoolRejectionStats {
+                    sender_limit_reached: 1,
+                    replacement_fee_too_low: 1,
+                    replaced_by_fee: 1,
+                }
+            );
+        },
+    );
+}
+
+/// Test that replace-by-fee is resolved when both operations come in the same batch.
+#[test]
+fn test_replace_by_fee_within_batch() {
+    let pool_config = PoolConfig {
+        mempool_mode: true,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, _opt_addrs| {
+                    let addr =
+                        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+                    let mut all_slots = BTreeMap::new();
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let creator = KeyPair::generate(0).unwrap();
+            let receiver = KeyPair::generate(0).unwrap();
+            let op_with_fee = |fee: &str| {
+                OpGenerator::default()
+                    .expirery(2)
+                    .creator(creator.clone())
+                    .receiver(receiver.clone())
+                    .fee(Amount::from_str(fee).unwrap())
+                    .generate()
+            };
+
+            // the replacement comes along with the operation it replaces, whatever their order
+            let op = op_with_fee("10");
+            let replacement = op_with_fee("20");
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(vec![op.clone(), replacement.clone()]);
+            operation_pool.add_operations(ops_storage);
+            std::thread::sleep(Duration::from_millis(200));
+
+            assert_eq!(operation_pool.get_operation_count(), 1);
+            assert_eq!(
+                operation_pool.contains_operations(&[op.id, replacement.id]),
+                vec![false, true]
+            );
+            // the superseded operation was never pending
+            assert!(operation_pool
+                .get_operations_info(&PoolOperationFilter::default(), 0, usize::MAX)
+                .iter()
+                .all(|info| info.id != op.id));
+            assert_eq!(
+                operation_pool.get_operation_rejection_stats(),
+                OperationPoolRejectionStats {
+                    replacement_fee_too_low: 1,
+                    ..Default::default()
+                }
+            );
+        },
+    );
+}
+
 #[test]
 fn test_pool() {
     let pool_config = PoolConfig {
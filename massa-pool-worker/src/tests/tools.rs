@@ -125,6 +125,7 @@ impl PoolTestBoilerPlate {
                 selector: selector_story,
             },
             wallet,
+            None,
         );
 
         Self {
@@ -167,6 +168,7 @@ pub fn pool_test<F>(
             selector,
         },
         wallet,
+        None,
     );
     test(pool_controller, storage);
     pool_manager.stop();
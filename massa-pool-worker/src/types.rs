@@ -3,6 +3,7 @@ use massa_models::{
     amount::Amount,
     operation::{OperationId, SecureShareOperation},
 };
+use massa_pool_exports::{OperationEvictionReason, PoolOperationInfo};
 use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone)]
@@ -39,4 +40,22 @@ impl OperationInfo {
             max_spending: op.get_max_spending(roll_price),
         }
     }
+
+    /// Get the information exposed when inspecting the pool
+    pub fn to_pool_info(
+        &self,
+        score: Option<f32>,
+        eviction_reason: Option<OperationEvictionReason>,
+    ) -> PoolOperationInfo {
+        PoolOperationInfo {
+            id: self.id,
+            sender: self.creator_address,
+            thread: self.thread,
+            fee: self.fee,
+            validity_start_period: *self.validity_period_range.start(),
+            validity_end_period: *self.validity_period_range.end(),
+            score,
+            eviction_reason,
+        }
+    }
 }
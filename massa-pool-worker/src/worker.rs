@@ -5,12 +5,14 @@
 use crate::controller_impl::{Command, PoolManagerImpl};
 use crate::denunciation_pool::DenunciationPool;
 use crate::operation_pool::OperationPool;
+use crate::snapshot::PoolSnapshot;
 use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPool};
 use massa_pool_exports::PoolConfig;
 use massa_pool_exports::{PoolChannels, PoolController, PoolManager};
 use massa_storage::Storage;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::time::Instant;
 use std::{
     sync::mpsc::{sync_channel, Receiver, RecvError, RecvTimeoutError},
@@ -181,12 +183,16 @@ impl DenunciationPoolThread {
 }
 
 /// Start pool manager and controller
+///
+/// If a snapshot path is provided, the pool contents saved there when the pool was last stopped
+/// are reloaded and the snapshot is deleted, and the pool contents are saved there again when it stops.
 #[allow(clippy::type_complexity)]
 pub fn start_pool_controller(
     config: PoolConfig,
     storage: &Storage,
     channels: PoolChannels,
     wallet: Arc<RwLock<Wallet>>,
+    snapshot_path: Option<PathBuf>,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) =
         sync_channel(config.operations_channel_size);
@@ -194,19 +200,24 @@ pub fn start_pool_controller(
         sync_channel(config.endorsements_channel_size);
     let (denunciations_input_sender, denunciations_input_receiver) =
         sync_channel(config.denunciations_channel_size);
-    let operation_pool = Arc::new(RwLock::new(OperationPool::init(
-        config,
-        storage,
-        channels.clone(),
-        wallet.clone(),
-    )));
-    let endorsement_pool = Arc::new(RwLock::new(EndorsementPool::init(
-        config,
-        storage,
-        channels.clone(),
-        wallet,
-    )));
-    let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
+    let mut operation_pool = OperationPool::init(config, storage, channels.clone(), wallet.clone());
+    let mut endorsement_pool = EndorsementPool::init(config, storage, channels.clone(), wallet);
+    let mut denunciation_pool = DenunciationPool::init(config, channels);
+    if let Some(path) = snapshot_path.as_deref() {
+        if let Some(snapshot) = PoolSnapshot::load(path) {
+            snapshot.restore(
+                &config,
+                storage,
+                &mut operation_pool,
+                &mut endorsement_pool,
+                &mut denunciation_pool,
+            );
+            PoolSnapshot::remove(path);
+        }
+    }
+    let operation_pool = Arc::new(RwLock::new(operation_pool));
+    let endorsement_pool = Arc::new(RwLock::new(endorsement_pool));
+    let denunciation_pool = Arc::new(RwLock::new(denunciation_pool));
     let controller = PoolControllerImpl {
         _config: config,
         operation_pool: operation_pool.clone(),
@@ -219,11 +230,11 @@ pub fn start_pool_controller(
     };
 
     let operations_thread_handle =
-        OperationPoolThread::spawn(operations_input_receiver, operation_pool, config);
+        OperationPoolThread::spawn(operations_input_receiver, operation_pool.clone(), config);
     let endorsements_thread_handle =
-        EndorsementPoolThread::spawn(endorsements_input_receiver, endorsement_pool);
+        EndorsementPoolThread::spawn(endorsements_input_receiver, endorsement_pool.clone());
     let denunciations_thread_handle =
-        DenunciationPoolThread::spawn(denunciations_input_receiver, denunciation_pool);
+        DenunciationPoolThread::spawn(denunciations_input_receiver, denunciation_pool.clone());
 
     let manager = PoolManagerImpl {
         operations_thread_handle: Some(operations_thread_handle),
@@ -232,6 +243,10 @@ pub fn start_pool_controller(
         operations_input_sender,
         endorsements_input_sender,
         denunciations_input_sender,
+        operation_pool,
+        endorsement_pool,
+        denunciation_pool,
+        snapshot_path,
     };
     (Box::new(manager), Box::new(controller))
 }
@@ -7,6 +7,7 @@ use crate::error::ProtocolError;
 use crate::BootstrapPeers;
 
 use crate::PeerId;
+use crate::PeerReputation;
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
@@ -81,6 +82,9 @@ pub trait ProtocolController: Send + Sync {
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Get the reputation of the known peers, with the end of their ban
+    fn get_peer_reputations(&self) -> Result<Vec<PeerReputation>, ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
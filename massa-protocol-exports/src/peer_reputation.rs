@@ -0,0 +1,19 @@
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+/// Reputation of a known peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerReputation {
+    /// id of the peer
+    pub peer_id: PeerId,
+    /// score of the peer, fed by the data it sent us. The peer is banned when it drops too low.
+    pub score: i64,
+    /// whether the peer is banned
+    pub banned: bool,
+    /// number of bans following a drop of the score
+    pub ban_count: u32,
+    /// end of the ban, `None` if the peer is not banned or was banned by the operator
+    pub banned_until: Option<MassaTime>,
+}
@@ -150,6 +150,10 @@ pub struct ProtocolConfig {
     pub try_connection_timer_same_peer: MassaTime,
     /// periodically unban every peer
     pub unban_everyone_timer: MassaTime,
+    /// duration of the first ban of a peer whose reputation dropped, doubling with each of its bans
+    pub peer_ban_base_duration: MassaTime,
+    /// maximum duration of the ban of a peer whose reputation dropped
+    pub peer_ban_max_duration: MassaTime,
     /// Max in connections
     pub max_in_connections: usize,
     /// Timeout connection
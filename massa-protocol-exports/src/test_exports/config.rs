@@ -82,6 +82,8 @@ impl Default for ProtocolConfig {
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
             unban_everyone_timer: MassaTime::from_millis(ONE_DAY_MS),
+            peer_ban_base_duration: MassaTime::from_millis(600000),
+            peer_ban_max_duration: MassaTime::from_millis(ONE_DAY_MS),
             routable_ip: None,
             max_in_connections: 10,
             debug: true,
@@ -7,7 +7,9 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     stats::NetworkStats,
 };
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
+use massa_protocol_exports::{
+    BootstrapPeers, PeerId, PeerReputation, ProtocolController, ProtocolError,
+};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
 
@@ -186,6 +188,20 @@ impl ProtocolController for ProtocolControllerImpl {
         })
     }
 
+    fn get_peer_reputations(&self) -> Result<Vec<PeerReputation>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peer_reputations".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetReputations { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_peer_reputations command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_peer_reputations command receive error".into())
+        })
+    }
+
     fn clone_box(&self) -> Box<dyn ProtocolController> {
         Box::new(self.clone())
     }
@@ -16,7 +16,10 @@ use super::{
     BlockMessageSerializer,
 };
 use crate::{
-    handlers::{block_handler::BlockMessage, peer_handler::models::PeerManagementCmd},
+    handlers::{
+        block_handler::BlockMessage,
+        peer_handler::{models::PeerManagementCmd, reputation::PeerFeedback},
+    },
     messages::MessagesSerializer,
     wrap_network::ActiveConnectionsTrait,
 };
@@ -206,10 +209,13 @@ impl PropagationThread {
     fn ban_peers(&mut self, peer_ids: &[PeerId]) {
         if let Err(err) = self
             .peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(peer_ids.to_vec()))
+            .try_send(PeerManagementCmd::Report {
+                peer_ids: peer_ids.to_vec(),
+                feedback: PeerFeedback::InvalidData,
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
         {
-            warn!("could not send Ban command to peer manager: {}", err);
+            warn!("could not send Report command to peer manager: {}", err);
         }
     }
 }
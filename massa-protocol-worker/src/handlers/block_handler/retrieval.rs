@@ -14,7 +14,10 @@ use crate::{
         operation_handler::{
             cache::SharedOperationCache, commands_propagation::OperationHandlerPropagationCommand,
         },
-        peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        peer_handler::{
+            models::{PeerManagementCmd, PeerMessageTuple},
+            reputation::PeerFeedback,
+        },
     },
     messages::{Message, MessagesSerializer},
     wrap_network::ActiveConnectionsTrait,
@@ -451,7 +454,9 @@ impl RetrievalThread {
                 // This is done so that update_block_retrieval can prioritize asking the rest of the block data
                 // to that same peer that just gave us the header, and not exclude the peer
                 // because we still believe we are actively asking it for stuff.
-                self.remove_asked_blocks(&[block_id].into_iter().collect())
+                self.remove_asked_blocks(&[block_id].into_iter().collect());
+
+                self.report_peer(&from_peer_id, PeerFeedback::UsefulData);
             }
         } else if is_new {
             // if not in wishlist, and if the header is new, we send it to consensus
@@ -657,10 +662,26 @@ impl RetrievalThread {
     /// send a ban peer command to the peer handler
     fn ban_peers(&mut self, peer_ids: &[PeerId]) -> Result<(), ProtocolError> {
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(peer_ids.to_vec()))
+            .try_send(PeerManagementCmd::Report {
+                peer_ids: peer_ids.to_vec(),
+                feedback: PeerFeedback::InvalidData,
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 
+    /// send the outcome of an exchange with a peer to the peer handler, which keeps its score
+    fn report_peer(&mut self, peer_id: &PeerId, feedback: PeerFeedback) {
+        if let Err(err) = self.peer_cmd_sender.try_send(PeerManagementCmd::Report {
+            peer_ids: vec![*peer_id],
+            feedback,
+        }) {
+            debug!(
+                "could not report peer {} to the peer handler: {}",
+                peer_id, err
+            );
+        }
+    }
+
     /// Remove the given blocks from the local wishlist
     pub(crate) fn remove_asked_blocks(&mut self, remove_hashes: &PreHashSet<BlockId>) {
         for asked_blocks in self.asked_blocks.values_mut() {
@@ -747,6 +768,7 @@ impl RetrievalThread {
             self.cache
                 .write()
                 .insert_peer_known_block(&from_peer_id, &[block_id], true);
+            self.report_peer(&from_peer_id, PeerFeedback::UnrequestedData);
             return;
         };
 
@@ -779,6 +801,8 @@ impl RetrievalThread {
 
         // free up all the nodes that we asked for that operation list
         self.remove_asked_blocks(&[block_id].into_iter().collect());
+
+        self.report_peer(&from_peer_id, PeerFeedback::UsefulData);
     }
 
     /// Return the sum of all operation's serialized sizes in the id list
@@ -827,6 +851,7 @@ impl RetrievalThread {
                     .map(|op| op.id.prefix())
                     .collect::<Vec<_>>(),
             );
+            self.report_peer(&from_peer_id, PeerFeedback::UnrequestedData);
             return;
         };
 
@@ -894,6 +919,9 @@ impl RetrievalThread {
             return;
         }
 
+        // the sender gave us some of the missing operations
+        let useful = !operations.is_empty();
+
         // add received operations to local storage and claim ref
         wishlist_info
             .storage
@@ -919,6 +947,10 @@ impl RetrievalThread {
                 .write()
                 .insert_peer_known_block(&from_peer_id, &[block_id], false);
         }
+
+        if useful {
+            self.report_peer(&from_peer_id, PeerFeedback::UsefulData);
+        }
     }
 
     /// function that updates the global state of block retrieval
@@ -955,6 +987,8 @@ impl RetrievalThread {
         let mut to_ask: PreHashSet<BlockId> = self.block_wishlist.keys().copied().collect();
         // the number of things already being asked to those peers
         let mut peer_loads: HashMap<PeerId, usize> = Default::default();
+        // the peers that did not answer some of our asks in time
+        let mut timed_out_peers: Vec<PeerId> = Vec::new();
         for (peer_id, asked_blocks) in &mut self.asked_blocks {
             // init the list of items to remove from asked_blocks
             let mut to_remove_from_asked_blocks = Vec::new();
@@ -973,6 +1007,8 @@ impl RetrievalThread {
                     // We mark the block for removal from the asked_blocks list.
                     // This prevents us from re-detecting the timeout many times.
                     to_remove_from_asked_blocks.push(*block_id);
+
+                    timed_out_peers.push(*peer_id);
                 } else {
                     // this block was recently asked to this peer: no need to ask for the block for now
 
@@ -993,6 +1029,9 @@ impl RetrievalThread {
                 asked_blocks.remove(&remove_id);
             }
         }
+        for peer_id in timed_out_peers {
+            self.report_peer(&peer_id, PeerFeedback::AskTimeout);
+        }
 
         // for each block to ask, choose a peer to ask it from and perform the ask
         let mut to_ask = to_ask.into_iter().collect::<Vec<_>>();
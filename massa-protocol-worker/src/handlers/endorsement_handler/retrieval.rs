@@ -21,7 +21,10 @@ use tracing::{debug, info, warn};
 use crate::{
     handlers::{
         endorsement_handler::messages::EndorsementMessage,
-        peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        peer_handler::{
+            models::{PeerManagementCmd, PeerMessageTuple},
+            reputation::PeerFeedback,
+        },
     },
     sig_verifier::verify_sigs_batch,
 };
@@ -146,7 +149,10 @@ impl RetrievalThread {
     fn ban_peer(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![*peer_id]))
+            .try_send(PeerManagementCmd::Report {
+                peer_ids: vec![*peer_id],
+                feedback: PeerFeedback::InvalidData,
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
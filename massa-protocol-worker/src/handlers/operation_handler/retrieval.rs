@@ -20,7 +20,10 @@ use massa_time::{MassaTime, TimeError};
 use schnellru::{ByLength, LruMap};
 
 use crate::{
-    handlers::peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+    handlers::peer_handler::{
+        models::{PeerManagementCmd, PeerMessageTuple},
+        reputation::PeerFeedback,
+    },
     messages::MessagesSerializer,
     sig_verifier::verify_sigs_batch,
     wrap_network::ActiveConnectionsTrait,
@@ -355,7 +358,10 @@ impl RetrievalThread {
     fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![*peer_id]))
+            .try_send(PeerManagementCmd::Report {
+                peer_ids: vec![*peer_id],
+                feedback: PeerFeedback::InvalidData,
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
@@ -13,6 +13,7 @@ use massa_protocol_exports::{
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::Signature;
+use massa_time::MassaTime;
 use peernet::context::Context as _;
 use peernet::messages::MessagesSerializer as _;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
@@ -31,6 +32,7 @@ use crate::messages::{Message, MessagesHandler, MessagesSerializer};
 use crate::wrap_network::ActiveConnectionsTrait;
 
 use self::models::PeerInfo;
+use self::reputation::PeerReputations;
 use self::{
     models::{
         InitialPeers, PeerManagementChannel, PeerManagementCmd, PeerMessageTuple, SharedPeerDB,
@@ -52,6 +54,7 @@ use self::{
 mod announcement;
 mod messages;
 pub mod models;
+pub mod reputation;
 mod tester;
 
 pub(crate) use messages::{PeerManagementMessage, PeerManagementMessageSerializer};
@@ -102,6 +105,8 @@ impl PeerManagementHandler {
             let peer_db = peer_db.clone();
             let ticker = tick(Duration::from_secs(10));
             let config = config.clone();
+            let mut reputations =
+                PeerReputations::new(config.peer_ban_base_duration, config.peer_ban_max_duration);
             let message_serializer = MessagesSerializer::new()
                 .with_peer_management_message_serializer(PeerManagementMessageSerializer::new());
             let message_deserializer =
@@ -114,6 +119,25 @@ impl PeerManagementHandler {
                 loop {
                     select! {
                         recv(ticker) -> _ => {
+                            // lift the bans that expired, and forget the ones lifted by an unban of every peer
+                            let banned_peers = reputations.get_banned_peers();
+                            if !banned_peers.is_empty() {
+                                let now = MassaTime::now();
+                                let mut peer_db_write = peer_db.write();
+                                for (peer_id, banned_until) in banned_peers {
+                                    let still_banned = peer_db_write
+                                        .get_peers()
+                                        .get(&peer_id)
+                                        .map_or(false, |peer| peer.state == PeerState::Banned);
+                                    if !still_banned {
+                                        reputations.set_unbanned(&peer_id);
+                                    } else if banned_until.map_or(false, |banned_until| banned_until <= now) {
+                                        peer_db_write.unban_peer(&peer_id);
+                                        reputations.set_unbanned(&peer_id);
+                                    }
+                                }
+                            }
+
                             let peers_to_send = peer_db.read().get_rand_peers_to_send(100);
                             if peers_to_send.is_empty() {
                                 continue;
@@ -139,13 +163,30 @@ impl PeerManagementHandler {
 
                                     // update peer_db
                                     peer_db.write().ban_peer(&peer_id);
+                                    reputations.set_banned(&peer_id);
                                 }
                             },
                              Ok(PeerManagementCmd::Unban(peer_ids)) => {
                                 for peer_id in peer_ids {
                                     peer_db.write().unban_peer(&peer_id);
+                                    reputations.set_unbanned(&peer_id);
+                                }
+                            },
+                             Ok(PeerManagementCmd::Report { peer_ids, feedback }) => {
+                                let now = MassaTime::now();
+                                for peer_id in peer_ids {
+                                    if let Some(banned_until) = reputations.report(&peer_id, feedback, now) {
+                                        info!("Banning peer {} until {} after {:?}", peer_id, banned_until.format_instant(), feedback);
+                                        active_connections.shutdown_connection(&peer_id);
+                                        peer_db.write().ban_peer(&peer_id);
+                                    }
                                 }
                             },
+                             Ok(PeerManagementCmd::GetReputations { responder }) => {
+                                if let Err(err) = responder.try_send(reputations.get_reputations()) {
+                                    warn!("error sending peer reputations: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
                                 let mut peers = peer_db.read().get_rand_peers_to_send(100);
                                 // Add myself
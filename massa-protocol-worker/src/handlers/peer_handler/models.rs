@@ -1,5 +1,5 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::{BootstrapPeers, PeerId};
+use massa_protocol_exports::{BootstrapPeers, PeerId, PeerReputation};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
@@ -14,6 +14,7 @@ use tracing::info;
 use crate::wrap_peer_db::PeerDBTrait;
 
 use super::announcement::Announcement;
+use super::reputation::PeerFeedback;
 
 const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000;
 
@@ -180,9 +181,17 @@ pub enum PeerState {
 pub enum PeerManagementCmd {
     Ban(Vec<PeerId>),
     Unban(Vec<PeerId>),
+    /// Update the reputation of peers, banning them for a while if it drops too low
+    Report {
+        peer_ids: Vec<PeerId>,
+        feedback: PeerFeedback,
+    },
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
+    GetReputations {
+        responder: MassaSender<Vec<PeerReputation>>,
+    },
     Stop,
 }
 
@@ -0,0 +1,294 @@
+//! Reputation of the peers, fed by the outcome of what they send us.
+//!
+//! Every peer starts at a score of 0. Useful data raises it up to `MAX_SCORE`, unrequested data and
+//! unanswered asks lower it, and invalid data drops it straight to `BAN_SCORE`. A peer reaching `BAN_SCORE`
+//! is banned for a duration doubling with each of its bans, and starts again from 0 once unbanned.
+//! Only the peers with a non-zero score or a ban are tracked, up to `MAX_REPUTATIONS`: beyond it, the unbanned
+//! peer whose reputation is the closest to the one of an unknown peer is forgotten.
+
+use std::collections::HashMap;
+
+use massa_protocol_exports::{PeerId, PeerReputation};
+use massa_time::MassaTime;
+
+/// Highest score of a peer
+const MAX_SCORE: i64 = 100;
+
+/// Score at which a peer is banned
+const BAN_SCORE: i64 = -100;
+
+/// Number of tracked peers above which unbanned peers are forgotten
+const MAX_REPUTATIONS: usize = 10_000;
+
+/// Outcome of something a peer sent us, or did not send us
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerFeedback {
+    /// data with an invalid signature, or failing the validity checks
+    InvalidData,
+    /// block data we were not asking for
+    UnrequestedData,
+    /// no answer to one of our asks for block data
+    AskTimeout,
+    /// block data we were asking for
+    UsefulData,
+}
+
+impl PeerFeedback {
+    /// Change of the score of the peer
+    fn score_delta(&self) -> i64 {
+        match self {
+            PeerFeedback::InvalidData => BAN_SCORE - MAX_SCORE,
+            PeerFeedback::UnrequestedData => -5,
+            PeerFeedback::AskTimeout => -5,
+            PeerFeedback::UsefulData => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Reputation {
+    score: i64,
+    /// number of bans following a drop of the score
+    ban_count: u32,
+    banned: bool,
+    /// end of the ban, `None` for the bans decided by the operator
+    banned_until: Option<MassaTime>,
+}
+
+impl Reputation {
+    /// Whether the reputation is the one of an unknown peer, which does not need to be tracked
+    fn is_neutral(&self) -> bool {
+        self.score == 0 && self.ban_count == 0 && !self.banned
+    }
+}
+
+/// Reputation of the known peers
+pub struct PeerReputations {
+    reputations: HashMap<PeerId, Reputation>,
+    /// number of tracked peers above which unbanned peers are forgotten
+    max_reputations: usize,
+    /// duration of the first ban of a peer
+    ban_base_duration: MassaTime,
+    /// maximum duration of a ban
+    ban_max_duration: MassaTime,
+}
+
+impl PeerReputations {
+    pub fn new(ban_base_duration: MassaTime, ban_max_duration: MassaTime) -> Self {
+        PeerReputations {
+            reputations: HashMap::new(),
+            max_reputations: MAX_REPUTATIONS,
+            ban_base_duration,
+            ban_max_duration,
+        }
+    }
+
+    /// Get the reputation of a peer, tracking it if it is not yet
+    fn get_or_insert(&mut self, peer_id: &PeerId) -> &mut Reputation {
+        if !self.reputations.contains_key(peer_id) && self.reputations.len() >= self.max_reputations
+        {
+            // forget the unbanned peer with the fewest bans and the score closest to 0
+            let forgotten = self
+                .reputations
+                .iter()
+                .filter(|(_, reputation)| !reputation.banned)
+                .min_by_key(|(_, reputation)| (reputation.ban_count, reputation.score.abs()))
+                .map(|(peer_id, _)| *peer_id);
+            if let Some(forgotten) = forgotten {
+                self.reputations.remove(&forgotten);
+            }
+        }
+        self.reputations.entry(*peer_id).or_default()
+    }
+
+    /// Stop tracking a peer if its reputation is neutral
+    fn prune(&mut self, peer_id: &PeerId) {
+        if self
+            .reputations
+            .get(peer_id)
+            .is_some_and(Reputation::is_neutral)
+        {
+            self.reputations.remove(peer_id);
+        }
+    }
+
+    /// Update the score of a peer.
+    /// Returns the end of the ban if the peer has to be banned.
+    pub fn report(
+        &mut self,
+        peer_id: &PeerId,
+        feedback: PeerFeedback,
+        now: MassaTime,
+    ) -> Option<MassaTime> {
+        if self
+            .reputations
+            .get(peer_id)
+            .is_some_and(|reputation| reputation.banned)
+        {
+            return None;
+        }
+        let (ban_base_duration, ban_max_duration) = (self.ban_base_duration, self.ban_max_duration);
+        let reputation = self.get_or_insert(peer_id);
+        reputation.score = (reputation.score + feedback.score_delta()).clamp(BAN_SCORE, MAX_SCORE);
+        if reputation.score > BAN_SCORE {
+            self.prune(peer_id);
+            return None;
+        }
+        let factor = 1u64.checked_shl(reputation.ban_count).unwrap_or(u64::MAX);
+        let duration = ban_base_duration
+            .as_millis()
+            .saturating_mul(factor)
+            .min(ban_max_duration.as_millis());
+        let banned_until = now.saturating_add(MassaTime::from_millis(duration));
+        reputation.score = 0;
+        reputation.ban_count = reputation.ban_count.saturating_add(1);
+        reputation.banned = true;
+        reputation.banned_until = Some(banned_until);
+        Some(banned_until)
+    }
+
+    /// Record a ban decided by the operator, which does not expire
+    pub fn set_banned(&mut self, peer_id: &PeerId) {
+        let reputation = self.get_or_insert(peer_id);
+        reputation.banned = true;
+        reputation.banned_until = None;
+    }
+
+    /// Record the end of the ban of a peer
+    pub fn set_unbanned(&mut self, peer_id: &PeerId) {
+        if let Some(reputation) = self.reputations.get_mut(peer_id) {
+            reputation.score = 0;
+            reputation.banned = false;
+            reputation.banned_until = None;
+        }
+        self.prune(peer_id);
+    }
+
+    /// Peers recorded as banned, with the end of their ban
+    pub fn get_banned_peers(&self) -> Vec<(PeerId, Option<MassaTime>)> {
+        self.reputations
+            .iter()
+            .filter(|(_, reputation)| reputation.banned)
+            .map(|(peer_id, reputation)| (*peer_id, reputation.banned_until))
+            .collect()
+    }
+
+    /// Reputation of the known peers, lowest score first
+    pub fn get_reputations(&self) -> Vec<PeerReputation> {
+        let mut reputations: Vec<PeerReputation> = self
+            .reputations
+            .iter()
+            .map(|(peer_id, reputation)| PeerReputation {
+                peer_id: *peer_id,
+                score: reputation.score,
+                banned: reputation.banned,
+                ban_count: reputation.ban_count,
+                banned_until: reputation.banned_until,
+            })
+            .collect();
+        reputations.sort_by_key(|reputation| reputation.score);
+        reputations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_ban_back_off() {
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let mut reputations =
+            PeerReputations::new(MassaTime::from_millis(1000), MassaTime::from_millis(3000));
+        let now = MassaTime::from_millis(10_000);
+
+        // useful data makes up for a few timeouts
+        for _ in 0..10 {
+            assert_eq!(
+                reputations.report(&peer_id, PeerFeedback::UsefulData, now),
+                None
+            );
+        }
+        for _ in 0..21 {
+            assert_eq!(
+                reputations.report(&peer_id, PeerFeedback::AskTimeout, now),
+                None
+            );
+        }
+        assert_eq!(
+            reputations.report(&peer_id, PeerFeedback::AskTimeout, now),
+            Some(MassaTime::from_millis(11_000))
+        );
+        // a banned peer is not scored
+        assert_eq!(
+            reputations.report(&peer_id, PeerFeedback::InvalidData, now),
+            None
+        );
+        assert_eq!(
+            reputations.get_banned_peers(),
+            vec![(peer_id, Some(MassaTime::from_millis(11_000)))]
+        );
+
+        // each ban lasts twice as long as the previous one, up to the maximum
+        reputations.set_unbanned(&peer_id);
+        assert_eq!(
+            reputations.report(&peer_id, PeerFeedback::InvalidData, now),
+            Some(MassaTime::from_millis(12_000))
+        );
+        reputations.set_unbanned(&peer_id);
+        assert_eq!(
+            reputations.report(&peer_id, PeerFeedback::InvalidData, now),
+            Some(MassaTime::from_millis(13_000))
+        );
+
+        // the bans of the operator do not expire
+        reputations.set_banned(&peer_id);
+        let reputation = &reputations.get_reputations()[0];
+        assert!(reputation.banned);
+        assert_eq!(reputation.banned_until, None);
+        assert_eq!(reputation.ban_count, 3);
+    }
+
+    #[test]
+    fn test_reputations_are_pruned() {
+        let peer_ids: Vec<PeerId> = (0..3)
+            .map(|_| PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key()))
+            .collect();
+        let mut reputations =
+            PeerReputations::new(MassaTime::from_millis(1000), MassaTime::from_millis(3000));
+        let now = MassaTime::from_millis(10_000);
+
+        // a peer back to a neutral reputation is not tracked anymore
+        reputations.report(&peer_ids[0], PeerFeedback::UsefulData, now);
+        reputations.report(&peer_ids[0], PeerFeedback::UsefulData, now);
+        reputations.report(&peer_ids[0], PeerFeedback::AskTimeout, now);
+        assert_eq!(reputations.get_reputations().len(), 1);
+        for _ in 0..3 {
+            reputations.report(&peer_ids[0], PeerFeedback::UsefulData, now);
+        }
+        assert!(reputations.get_reputations().is_empty());
+
+        // nor a peer unbanned by the operator without having been banned for its score
+        reputations.set_banned(&peer_ids[0]);
+        reputations.set_unbanned(&peer_ids[0]);
+        assert!(reputations.get_reputations().is_empty());
+
+        // but a peer banned for its score keeps its ban count once unbanned
+        reputations.report(&peer_ids[0], PeerFeedback::InvalidData, now);
+        reputations.set_unbanned(&peer_ids[0]);
+        assert_eq!(reputations.get_reputations()[0].ban_count, 1);
+
+        // beyond the maximum, the unbanned peer closest to neutral is forgotten, and banned peers are kept
+        reputations.max_reputations = 2;
+        reputations.report(&peer_ids[1], PeerFeedback::UsefulData, now);
+        reputations.set_banned(&peer_ids[2]);
+        let tracked: HashSet<PeerId> = reputations
+            .get_reputations()
+            .iter()
+            .map(|reputation| reputation.peer_id)
+            .collect();
+        assert_eq!(tracked, HashSet::from([peer_ids[0], peer_ids[2]]));
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use massa_time::MassaTime;
+use std::path::PathBuf;
 
 /// Client common settings.
 /// the client common settings
@@ -45,3 +46,31 @@ pub struct WsConfig {
     /// Max number of redirections.
     pub max_redirections: usize,
 }
+
+/// gRPC client settings.
+/// the gRPC client settings
+#[derive(Debug, Clone)]
+pub struct GrpcConfig {
+    /// timeout of a request.
+    pub request_timeout: MassaTime,
+    /// custom metadata to pass with every request.
+    pub headers: Vec<(String, String)>,
+    /// TLS settings of the public API connection, in plain text if not set.
+    pub public_tls: Option<GrpcTlsConfig>,
+    /// TLS settings of the private API connection, in plain text if not set.
+    pub private_tls: Option<GrpcTlsConfig>,
+}
+
+/// gRPC client TLS settings.
+/// the certificates are the ones generated by the node when `generate_self_signed_certificates` is set.
+#[derive(Debug, Clone)]
+pub struct GrpcTlsConfig {
+    /// domain name expected in the server certificate.
+    pub domain_name: String,
+    /// certificate authority root of the server certificate.
+    pub certificate_authority_root_path: PathBuf,
+    /// client certificate, for mTLS.
+    pub client_certificate_path: Option<PathBuf>,
+    /// client private key, for mTLS.
+    pub client_private_key_path: Option<PathBuf>,
+}
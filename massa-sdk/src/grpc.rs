@@ -0,0 +1,1039 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! gRPC client of the public and private APIs of a node.
+//!
+//! The connections can use TLS or mTLS with the certificates generated by the node
+//! when `generate_self_signed_certificates` is set in its gRPC configuration.
+//! They are only opened, and their TLS files read, when a method of their API is first called.
+
+use crate::{ClientError, GrpcConfig, GrpcTlsConfig};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::block_id::BlockId;
+use massa_models::config::CompactConfig;
+use massa_models::node::NodeId;
+use massa_models::operation::{OperationId, SecureShareOperation};
+use massa_models::secure_share::SecureShareSerializer;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
+use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_serialization::Serializer;
+use massa_time::MassaTime;
+use serde::Serialize;
+use std::fmt::Display;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::Status;
+
+/// Block and endorsement producers drawn for a slot
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotDraws {
+    /// drawn slot
+    pub slot: Slot,
+    /// block producer
+    pub block_producer: Option<Address>,
+    /// endorsement producers, by endorsement index
+    pub endorsement_producers: Vec<Address>,
+}
+
+/// Status of a MIP in the versioning store of the node
+#[derive(Debug, Clone, Serialize)]
+pub struct MipStatus {
+    /// MIP name
+    pub name: String,
+    /// network version activated by the MIP
+    pub version: u32,
+    /// components updated by the MIP, with their new version
+    pub components: Vec<(String, u32)>,
+    /// start of the vote
+    pub start: MassaTime,
+    /// end of the vote
+    pub timeout: MassaTime,
+    /// delay between the vote success and the activation
+    pub activation_delay: MassaTime,
+    /// current state of the MIP
+    pub state: String,
+}
+
+/// Result of a state query
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryStateResult {
+    /// last executed final slot
+    pub final_cursor: Option<Slot>,
+    /// last executed candidate slot
+    pub candidate_cursor: Option<Slot>,
+    /// fingerprint of the final state
+    pub final_state_fingerprint: String,
+    /// result or error of each query item, in the order of the request
+    pub responses: Vec<Result<String, String>>,
+}
+
+/// Public status of a node
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicStatus {
+    /// node id
+    pub node_id: String,
+    /// node version
+    pub version: String,
+    /// chain id
+    pub chain_id: u64,
+    /// current time of the node
+    pub current_time: MassaTime,
+    /// current cycle
+    pub current_cycle: u64,
+    /// start of the current cycle
+    pub current_cycle_time: MassaTime,
+    /// start of the next cycle
+    pub next_cycle_time: MassaTime,
+    /// last executed final slot
+    pub last_executed_final_slot: Option<Slot>,
+    /// last executed candidate slot
+    pub last_executed_candidate_slot: Option<Slot>,
+    /// fingerprint of the final state
+    pub final_state_fingerprint: String,
+    /// network configuration
+    pub config: CompactConfig,
+}
+
+/// A block known by a node
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockOverview {
+    /// block id
+    pub id: BlockId,
+    /// status of the block in the graph
+    pub status: String,
+    /// slot of the block
+    pub slot: Slot,
+    /// block producer
+    pub creator_address: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// number of endorsements included in the block
+    pub endorsement_count: usize,
+    /// operations included in the block
+    pub operations: Vec<OperationId>,
+}
+
+/// An operation known by a node
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationOverview {
+    /// operation id
+    pub id: OperationId,
+    /// thread of the operation
+    pub thread: u32,
+    /// operation creator
+    pub creator_address: Address,
+    /// fee of the operation
+    pub fee: Amount,
+    /// last period at which the operation can be included in a block
+    pub expire_period: u64,
+    /// kind of the operation
+    pub op_type: String,
+    /// blocks including the operation
+    pub block_ids: Vec<BlockId>,
+}
+
+/// Balances and rolls of an address
+#[derive(Debug, Clone, Serialize)]
+pub struct AddressBalances {
+    /// address
+    pub address: Address,
+    /// final balance
+    pub final_balance: Amount,
+    /// candidate balance
+    pub candidate_balance: Amount,
+    /// final roll count
+    pub final_roll_count: u64,
+    /// candidate roll count
+    pub candidate_roll_count: u64,
+}
+
+/// Build the endpoint of a gRPC server, with TLS if configured
+fn endpoint(
+    addr: SocketAddr,
+    config: &GrpcConfig,
+    tls: Option<&GrpcTlsConfig>,
+) -> Result<Endpoint, ClientError> {
+    let Some(tls) = tls else {
+        return Ok(Endpoint::from_shared(format!("grpc://{}", addr))?
+            .timeout(config.request_timeout.to_duration()));
+    };
+    let ca_certificate = std::fs::read_to_string(&tls.certificate_authority_root_path)?;
+    let mut tls_config = ClientTlsConfig::new()
+        .domain_name(tls.domain_name.clone())
+        .ca_certificate(Certificate::from_pem(ca_certificate));
+    if let (Some(certificate_path), Some(private_key_path)) =
+        (&tls.client_certificate_path, &tls.client_private_key_path)
+    {
+        tls_config = tls_config.identity(Identity::from_pem(
+            std::fs::read_to_string(certificate_path)?,
+            std::fs::read_to_string(private_key_path)?,
+        ));
+    }
+    Ok(Endpoint::from_shared(format!("https://{}", addr))?
+        .timeout(config.request_timeout.to_duration())
+        .tls_config(tls_config)?)
+}
+
+/// Parse the IP addresses returned by the node
+fn parse_ips(ips: Vec<String>) -> Result<Vec<IpAddr>, Status> {
+    ips.iter()
+        .map(|ip| {
+            IpAddr::from_str(ip)
+                .map_err(|e| Status::internal(format!("invalid IP address {}: {}", ip, e)))
+        })
+        .collect()
+}
+
+/// Parse a value returned by the node
+fn parse<T: FromStr>(value: &str) -> Result<T, Status>
+where
+    T::Err: Display,
+{
+    T::from_str(value).map_err(|e| Status::internal(format!("invalid value {}: {}", value, e)))
+}
+
+/// Convert a block returned by the node
+fn to_block_overview(wrapper: grpc_model::BlockWrapper) -> Result<BlockOverview, Status> {
+    let status = grpc_model::BlockStatus::try_from(wrapper.status).map_or_else(
+        |_| wrapper.status.to_string(),
+        |status| status.as_str_name().to_string(),
+    );
+    let block = wrapper
+        .block
+        .ok_or_else(|| Status::internal("missing block"))?;
+    let header = block
+        .header
+        .ok_or_else(|| Status::internal("missing block header"))?;
+    let content = header
+        .content
+        .ok_or_else(|| Status::internal("missing block header content"))?;
+    Ok(BlockOverview {
+        id: parse(&header.secure_hash)?,
+        status,
+        slot: content
+            .slot
+            .ok_or_else(|| Status::internal("missing block slot"))?
+            .into(),
+        creator_address: parse(&header.content_creator_address)?,
+        parents: content
+            .parents
+            .iter()
+            .map(|parent| parse(parent))
+            .collect::<Result<_, _>>()?,
+        endorsement_count: content.endorsements.len(),
+        operations: block
+            .operations
+            .iter()
+            .map(|operation| parse(operation))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Convert an operation returned by the node
+fn to_operation_overview(
+    wrapper: grpc_model::OperationWrapper,
+) -> Result<OperationOverview, Status> {
+    use grpc_model::operation_type::Type;
+
+    let operation = wrapper
+        .operation
+        .ok_or_else(|| Status::internal("missing operation"))?;
+    let content = operation
+        .content
+        .ok_or_else(|| Status::internal("missing operation content"))?;
+    let op_type = match content.op.and_then(|op| op.r#type) {
+        Some(Type::Transaction(_)) => "Transaction",
+        Some(Type::RollBuy(_)) => "RollBuy",
+        Some(Type::RollSell(_)) => "RollSell",
+        Some(Type::ExecutSc(_)) => "ExecuteSC",
+        Some(Type::CallSc(_)) => "CallSC",
+        None => "Unknown",
+    };
+    Ok(OperationOverview {
+        id: parse(&operation.secure_hash)?,
+        thread: wrapper.thread,
+        creator_address: parse(&operation.content_creator_address)?,
+        fee: content
+            .fee
+            .ok_or_else(|| Status::internal("missing operation fee"))?
+            .try_into()
+            .map_err(|e| Status::internal(format!("invalid operation fee: {}", e)))?,
+        expire_period: content.expire_period,
+        op_type: op_type.to_string(),
+        block_ids: wrapper
+            .block_ids
+            .iter()
+            .map(|id| parse(id))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Describe a state query result
+fn query_response_to_string(item: grpc_api::execution_query_response_item::ResponseItem) -> String {
+    use grpc_api::execution_query_response_item::ResponseItem;
+    match item {
+        ResponseItem::Boolean(value) => value.to_string(),
+        ResponseItem::RollCount(count) => count.to_string(),
+        ResponseItem::Amount(amount) => Amount::from_mantissa_scale(amount.mantissa, amount.scale)
+            .map_or_else(|_| format!("{:?}", amount), |amount| amount.to_string()),
+        ResponseItem::ExecutionStatus(status) => {
+            grpc_api::ExecutionQueryExecutionStatus::try_from(status).map_or_else(
+                |_| status.to_string(),
+                |status| status.as_str_name().to_string(),
+            )
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// Convert a MIP store entry
+fn to_mip_status(entry: grpc_model::MipStatusEntry) -> Option<MipStatus> {
+    let info = entry.mip_info?;
+    let time = |time: Option<grpc_model::NativeTime>| {
+        MassaTime::from_millis(time.map_or(0, |time| time.milliseconds))
+    };
+    Some(MipStatus {
+        name: info.name,
+        version: info.version,
+        components: info
+            .components
+            .into_iter()
+            .map(|component| {
+                let kind = grpc_model::MipComponent::try_from(component.kind).map_or_else(
+                    |_| component.kind.to_string(),
+                    |kind| kind.as_str_name().to_string(),
+                );
+                (kind, component.version)
+            })
+            .collect(),
+        start: time(info.start),
+        timeout: time(info.timeout),
+        activation_delay: time(info.activation_delay),
+        state: grpc_model::ComponentStateId::try_from(entry.state_id).map_or_else(
+            |_| entry.state_id.to_string(),
+            |state| state.as_str_name().to_string(),
+        ),
+    })
+}
+
+/// gRPC client of the public and private APIs of a node.
+/// A connection that cannot be opened is reported when one of the methods of its API is called.
+pub struct GrpcClient {
+    /// address of the public API
+    public_addr: SocketAddr,
+    /// address of the private API
+    private_addr: SocketAddr,
+    /// client settings
+    config: GrpcConfig,
+    /// public API client, once connected
+    public: Option<PublicServiceClient<Channel>>,
+    /// private API client, once connected
+    private: Option<PrivateServiceClient<Channel>>,
+}
+
+impl GrpcClient {
+    /// Create a client of the public and private gRPC APIs of a node.
+    /// The connections are opened on first use.
+    pub fn new(public_addr: SocketAddr, private_addr: SocketAddr, config: &GrpcConfig) -> Self {
+        GrpcClient {
+            public_addr,
+            private_addr,
+            config: config.clone(),
+            public: None,
+            private: None,
+        }
+    }
+
+    /// Build a request with the configured metadata
+    fn request<T>(&self, message: T) -> Result<tonic::Request<T>, Status> {
+        let mut request = tonic::Request::new(message);
+        for (key, value) in &self.config.headers {
+            let invalid = |e: &dyn Display| {
+                Status::invalid_argument(
+                    ClientError::Metadata(format!("{}: {}", key, e)).to_string(),
+                )
+            };
+            request.metadata_mut().insert(
+                AsciiMetadataKey::from_str(&key.to_lowercase()).map_err(|e| invalid(&e))?,
+                AsciiMetadataValue::from_str(value).map_err(|e| invalid(&e))?,
+            );
+        }
+        Ok(request)
+    }
+
+    /// Open a connection to a gRPC server
+    async fn connect(
+        addr: SocketAddr,
+        config: &GrpcConfig,
+        tls: Option<&GrpcTlsConfig>,
+    ) -> Result<Channel, Status> {
+        let endpoint =
+            endpoint(addr, config, tls).map_err(|e| Status::unavailable(e.to_string()))?;
+        endpoint.connect().await.map_err(|e| {
+            Status::unavailable(format!("unable to connect to grpc server {}: {}", addr, e))
+        })
+    }
+
+    async fn public(&mut self) -> Result<&mut PublicServiceClient<Channel>, Status> {
+        if self.public.is_none() {
+            let channel = Self::connect(
+                self.public_addr,
+                &self.config,
+                self.config.public_tls.as_ref(),
+            )
+            .await?;
+            self.public = Some(PublicServiceClient::new(channel));
+        }
+        Ok(self.public.as_mut().expect("public client is connected"))
+    }
+
+    async fn private(&mut self) -> Result<&mut PrivateServiceClient<Channel>, Status> {
+        if self.private.is_none() {
+            let channel = Self::connect(
+                self.private_addr,
+                &self.config,
+                self.config.private_tls.as_ref(),
+            )
+            .await?;
+            self.private = Some(PrivateServiceClient::new(channel));
+        }
+        Ok(self.private.as_mut().expect("private client is connected"))
+    }
+
+    ////////////////
+    // public-api //
+    ////////////////
+
+    /// Get the public status of the node
+    pub async fn get_status(&mut self) -> Result<PublicStatus, Status> {
+        let request = self.request(grpc_api::GetStatusRequest {})?;
+        let status = self
+            .public()
+            .await?
+            .get_status(request)
+            .await?
+            .into_inner()
+            .status
+            .ok_or_else(|| Status::internal("missing status"))?;
+        let time = |time: Option<grpc_model::NativeTime>| {
+            time.map(MassaTime::from)
+                .ok_or_else(|| Status::internal("missing status time"))
+        };
+        Ok(PublicStatus {
+            node_id: status.node_id,
+            version: status.version,
+            chain_id: status.chain_id,
+            current_time: time(status.current_time)?,
+            current_cycle: status.current_cycle,
+            current_cycle_time: time(status.current_cycle_time)?,
+            next_cycle_time: time(status.next_cycle_time)?,
+            last_executed_final_slot: status.last_executed_final_slot.map(Into::into),
+            last_executed_candidate_slot: status.last_executed_speculative_slot.map(Into::into),
+            final_state_fingerprint: status.final_state_fingerprint,
+            config: status
+                .config
+                .ok_or_else(|| Status::internal("missing status config"))?
+                .try_into()
+                .map_err(|e| Status::internal(format!("invalid status config: {}", e)))?,
+        })
+    }
+
+    /// Get the blocks with the given ids, the unknown ones are left out
+    pub async fn get_blocks(&mut self, ids: Vec<BlockId>) -> Result<Vec<BlockOverview>, Status> {
+        let request = self.request(grpc_api::GetBlocksRequest {
+            block_ids: ids.iter().map(|id| id.to_string()).collect(),
+        })?;
+        let response = self.public().await?.get_blocks(request).await?;
+        response
+            .into_inner()
+            .wrapped_blocks
+            .into_iter()
+            .map(to_block_overview)
+            .collect()
+    }
+
+    /// Get the operations with the given ids, the unknown ones are left out
+    pub async fn get_operations(
+        &mut self,
+        ids: Vec<OperationId>,
+    ) -> Result<Vec<OperationOverview>, Status> {
+        let request = self.request(grpc_api::GetOperationsRequest {
+            operation_ids: ids.iter().map(|id| id.to_string()).collect(),
+        })?;
+        let response = self.public().await?.get_operations(request).await?;
+        response
+            .into_inner()
+            .wrapped_operations
+            .into_iter()
+            .map(to_operation_overview)
+            .collect()
+    }
+
+    /// Send signed operations to the node, which checks, stores and propagates them
+    pub async fn send_operations(
+        &mut self,
+        operations: Vec<SecureShareOperation>,
+    ) -> Result<Vec<OperationId>, Status> {
+        let serializer = SecureShareSerializer::new();
+        let operations = operations
+            .iter()
+            .map(|operation| {
+                let mut buffer = Vec::new();
+                serializer
+                    .serialize(operation, &mut buffer)
+                    .map(|_| buffer)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+        // the operations are sent as the only message of the stream
+        let request = self.request(tokio_stream::iter(vec![grpc_api::SendOperationsRequest {
+            operations,
+        }]))?;
+        let mut responses = self
+            .public()
+            .await?
+            .send_operations(request)
+            .await?
+            .into_inner();
+        match responses
+            .message()
+            .await?
+            .and_then(|response| response.result)
+        {
+            Some(grpc_api::send_operations_response::Result::OperationIds(ids)) => {
+                ids.operation_ids.iter().map(|id| parse(id)).collect()
+            }
+            Some(grpc_api::send_operations_response::Result::Error(error)) => {
+                Err(Status::new(tonic::Code::from(error.code), error.message))
+            }
+            None => Err(Status::internal("empty send_operations response")),
+        }
+    }
+
+    /// Get the final and candidate balances and roll counts of addresses
+    pub async fn get_addresses(
+        &mut self,
+        addresses: Vec<Address>,
+    ) -> Result<Vec<AddressBalances>, Status> {
+        use grpc_api::execution_query_request_item::RequestItem;
+        use grpc_api::execution_query_response_item::ResponseItem;
+
+        let queries = addresses
+            .iter()
+            .flat_map(|address| {
+                let address = address.to_string();
+                [
+                    RequestItem::AddressBalanceFinal(grpc_api::AddressBalanceFinal {
+                        address: address.clone(),
+                    }),
+                    RequestItem::AddressBalanceCandidate(grpc_api::AddressBalanceCandidate {
+                        address: address.clone(),
+                    }),
+                    RequestItem::AddressRollsFinal(grpc_api::AddressRollsFinal {
+                        address: address.clone(),
+                    }),
+                    RequestItem::AddressRollsCandidate(grpc_api::AddressRollsCandidate { address }),
+                ]
+            })
+            .map(|item| grpc_api::ExecutionQueryRequestItem {
+                request_item: Some(item),
+            })
+            .collect();
+        let request = self.request(grpc_api::QueryStateRequest { queries })?;
+        let response = self
+            .public()
+            .await?
+            .query_state(request)
+            .await?
+            .into_inner();
+
+        let mut items = response
+            .responses
+            .into_iter()
+            .map(|response| match response.response {
+                Some(grpc_api::execution_query_response::Response::Result(item)) => item
+                    .response_item
+                    .ok_or_else(|| Status::internal("empty response")),
+                Some(grpc_api::execution_query_response::Response::Error(error)) => {
+                    Err(Status::internal(error.message))
+                }
+                None => Err(Status::internal("empty response")),
+            });
+        let mut next_item = || {
+            items
+                .next()
+                .unwrap_or_else(|| Err(Status::internal("missing query_state response")))
+        };
+        let unexpected = || Status::internal("unexpected query_state response");
+        let to_amount = |item: ResponseItem| match item {
+            ResponseItem::Amount(amount) => Amount::try_from(amount)
+                .map_err(|e| Status::internal(format!("invalid balance: {}", e))),
+            _ => Err(unexpected()),
+        };
+        let to_roll_count = |item: ResponseItem| match item {
+            ResponseItem::RollCount(count) => Ok(count),
+            _ => Err(unexpected()),
+        };
+
+        // the responses come in the order of the queries, four per address
+        let mut balances = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            balances.push(AddressBalances {
+                address,
+                final_balance: to_amount(next_item()?)?,
+                candidate_balance: to_amount(next_item()?)?,
+                final_roll_count: to_roll_count(next_item()?)?,
+                candidate_roll_count: to_roll_count(next_item()?)?,
+            });
+        }
+        Ok(balances)
+    }
+
+    /// Get the block and endorsement producers drawn for the slots of a range,
+    /// optionally restricted to some addresses
+    pub async fn get_selector_draws(
+        &mut self,
+        start_slot: Slot,
+        end_slot: Slot,
+        addresses: Vec<Address>,
+    ) -> Result<Vec<SlotDraws>, Status> {
+        let mut filters = vec![grpc_api::SelectorDrawsFilter {
+            filter: Some(grpc_api::selector_draws_filter::Filter::SlotRange(
+                grpc_model::SlotRange {
+                    start_slot: Some(start_slot.into()),
+                    end_slot: Some(end_slot.into()),
+                },
+            )),
+        }];
+        if !addresses.is_empty() {
+            filters.push(grpc_api::SelectorDrawsFilter {
+                filter: Some(grpc_api::selector_draws_filter::Filter::Addresses(
+                    grpc_model::Addresses {
+                        addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+                    },
+                )),
+            });
+        }
+        let request = self.request(grpc_api::GetSelectorDrawsRequest { filters })?;
+        let response = self.public().await?.get_selector_draws(request).await?;
+
+        let mut draws: Vec<SlotDraws> = response
+            .into_inner()
+            .draws
+            .into_iter()
+            .filter_map(|draw| {
+                let mut endorsement_draws = draw.endorsement_draws;
+                endorsement_draws.sort_by_key(|endorsement_draw| endorsement_draw.index);
+                Some(SlotDraws {
+                    slot: draw.slot?.into(),
+                    block_producer: draw
+                        .block_producer
+                        .and_then(|producer| Address::from_str(&producer).ok()),
+                    endorsement_producers: endorsement_draws
+                        .into_iter()
+                        .filter_map(|endorsement_draw| {
+                            Address::from_str(&endorsement_draw.producer).ok()
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+        draws.sort_by_key(|draw| draw.slot);
+        Ok(draws)
+    }
+
+    /// Query the final or candidate execution state
+    pub async fn query_state(
+        &mut self,
+        queries: Vec<grpc_api::ExecutionQueryRequestItem>,
+    ) -> Result<QueryStateResult, Status> {
+        let request = self.request(grpc_api::QueryStateRequest { queries })?;
+        let response = self
+            .public()
+            .await?
+            .query_state(request)
+            .await?
+            .into_inner();
+        Ok(QueryStateResult {
+            final_cursor: response.final_cursor.map(Into::into),
+            candidate_cursor: response.candidate_cursor.map(Into::into),
+            final_state_fingerprint: response.final_state_fingerprint,
+            responses: response
+                .responses
+                .into_iter()
+                .map(|response| match response.response {
+                    Some(grpc_api::execution_query_response::Response::Result(item)) => item
+                        .response_item
+                        .map(query_response_to_string)
+                        .ok_or_else(|| "empty response".to_string()),
+                    Some(grpc_api::execution_query_response::Response::Error(error)) => {
+                        Err(error.message)
+                    }
+                    None => Err("empty response".to_string()),
+                })
+                .collect(),
+        })
+    }
+
+    /// Get the final execution status of an operation:
+    /// `Some(success)` if it was executed, `None` if it can still be executed or has expired
+    pub async fn get_op_execution_status_final(
+        &mut self,
+        operation_id: OperationId,
+    ) -> Result<Option<bool>, Status> {
+        use grpc_api::execution_query_request_item::RequestItem;
+        use grpc_api::execution_query_response_item::ResponseItem;
+
+        let request = self.request(grpc_api::QueryStateRequest {
+            queries: vec![grpc_api::ExecutionQueryRequestItem {
+                request_item: Some(RequestItem::OpExecutionStatusFinal(
+                    grpc_api::OpExecutionStatusFinal {
+                        operation_id: operation_id.to_string(),
+                    },
+                )),
+            }],
+        })?;
+        let response = self
+            .public()
+            .await?
+            .query_state(request)
+            .await?
+            .into_inner();
+        let status = match response
+            .responses
+            .into_iter()
+            .next()
+            .and_then(|r| r.response)
+        {
+            Some(grpc_api::execution_query_response::Response::Result(item)) => {
+                match item.response_item {
+                    Some(ResponseItem::ExecutionStatus(status)) => status,
+                    _ => return Err(Status::internal("unexpected query_state response")),
+                }
+            }
+            Some(grpc_api::execution_query_response::Response::Error(error)) => {
+                return Err(Status::internal(error.message))
+            }
+            None => return Err(Status::internal("empty query_state response")),
+        };
+        match grpc_api::ExecutionQueryExecutionStatus::try_from(status) {
+            Ok(grpc_api::ExecutionQueryExecutionStatus::AlreadyExecutedWithSuccess) => {
+                Ok(Some(true))
+            }
+            Ok(grpc_api::ExecutionQueryExecutionStatus::AlreadyExecutedWithFailure) => {
+                Ok(Some(false))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /////////////////
+    // private-api //
+    /////////////////
+
+    /// Get the status of the MIPs known by the node
+    pub async fn get_mip_status(&mut self) -> Result<Vec<MipStatus>, Status> {
+        let request = self.request(grpc_api::GetMipStatusRequest {})?;
+        let response = self.private().await?.get_mip_status(request).await?;
+        Ok(response
+            .into_inner()
+            .mipstatus_entries
+            .into_iter()
+            .filter_map(to_mip_status)
+            .collect())
+    }
+
+    /// Add a vector of new secret keys for the node to use to stake.
+    pub async fn add_staking_secret_keys(
+        &mut self,
+        secret_keys: Vec<String>,
+    ) -> Result<(), Status> {
+        let request = self.request(grpc_api::AddStakingSecretKeysRequest { secret_keys })?;
+        self.private()
+            .await?
+            .add_staking_secret_keys(request)
+            .await?;
+        Ok(())
+    }
+
+    /// Ban the given node ids
+    pub async fn ban_nodes_by_ids(&mut self, ids: Vec<NodeId>) -> Result<(), Status> {
+        let request = self.request(grpc_api::BanNodesByIdsRequest {
+            node_ids: ids.iter().map(|id| id.to_string()).collect(),
+        })?;
+        self.private().await?.ban_nodes_by_ids(request).await?;
+        Ok(())
+    }
+
+    /// Unban the given node ids
+    pub async fn unban_nodes_by_ids(&mut self, ids: Vec<NodeId>) -> Result<(), Status> {
+        let request = self.request(grpc_api::UnbanNodesByIdsRequest {
+            node_ids: ids.iter().map(|id| id.to_string()).collect(),
+        })?;
+        self.private().await?.unban_nodes_by_ids(request).await?;
+        Ok(())
+    }
+
+    /// Returns the node bootstrap whitelist IP addresses
+    pub async fn get_bootstrap_whitelist(&mut self) -> Result<Vec<IpAddr>, Status> {
+        let request = self.request(grpc_api::GetBootstrapWhitelistRequest {})?;
+        let response = self
+            .private()
+            .await?
+            .get_bootstrap_whitelist(request)
+            .await?;
+        parse_ips(response.into_inner().ips)
+    }
+
+    /// Add IP addresses to the node bootstrap whitelist
+    pub async fn add_to_bootstrap_whitelist(&mut self, ips: Vec<IpAddr>) -> Result<(), Status> {
+        let request = self.request(grpc_api::AddToBootstrapWhitelistRequest {
+            ips: ips.iter().map(|ip| ip.to_string()).collect(),
+        })?;
+        self.private()
+            .await?
+            .add_to_bootstrap_whitelist(request)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove IP addresses from the node bootstrap whitelist
+    pub async fn remove_from_bootstrap_whitelist(
+        &mut self,
+        ips: Vec<IpAddr>,
+    ) -> Result<(), Status> {
+        let request = self.request(grpc_api::RemoveFromBootstrapWhitelistRequest {
+            ips: ips.iter().map(|ip| ip.to_string()).collect(),
+        })?;
+        self.private()
+            .await?
+            .remove_from_bootstrap_whitelist(request)
+            .await?;
+        Ok(())
+    }
+
+    /// Allow everyone to bootstrap from the node by removing its bootstrap whitelist
+    pub async fn allow_everyone_to_bootstrap(&mut self) -> Result<(), Status> {
+        let request = self.request(grpc_api::AllowEveryoneToBootstrapRequest {})?;
+        self.private()
+            .await?
+            .allow_everyone_to_bootstrap(request)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the node bootstrap blacklist IP addresses
+    pub async fn get_bootstrap_blacklist(&mut self) -> Result<Vec<IpAddr>, Status> {
+        let request = self.request(grpc_api::GetBootstrapBlacklistRequest {})?;
+        let response = self
+            .private()
+            .await?
+            .get_bootstrap_blacklist(request)
+            .await?;
+        parse_ips(response.into_inner().ips)
+    }
+
+    /// Add IP addresses to the node bootstrap blacklist
+    pub async fn add_to_bootstrap_blacklist(&mut self, ips: Vec<IpAddr>) -> Result<(), Status> {
+        let request = self.request(grpc_api::AddToBootstrapBlacklistRequest {
+            ips: ips.iter().map(|ip| ip.to_string()).collect(),
+        })?;
+        self.private()
+            .await?
+            .add_to_bootstrap_blacklist(request)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove IP addresses from the node bootstrap blacklist
+    pub async fn remove_from_bootstrap_blacklist(
+        &mut self,
+        ips: Vec<IpAddr>,
+    ) -> Result<(), Status> {
+        let request = self.request(grpc_api::RemoveFromBootstrapBlacklistRequest {
+            ips: ips.iter().map(|ip| ip.to_string()).collect(),
+        })?;
+        self.private()
+            .await?
+            .remove_from_bootstrap_blacklist(request)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use tonic::Code;
+
+    const ADDRESS: &str = "AU12fZLkHnLED3okr8Lduyty7dz9ZKkd24xMCc2JJWPcdmfn2eUEx";
+    const BLOCK_ID: &str = "B12DvrcQkzF1Wi8BVoNfc4n93CD3E2qhCNe7nVhnEQGWHZ24fEmg";
+    const PARENT_ID: &str = "B12VVLWiMVjBLW7eoZqiv5eVWmqEQokZL7pAjFCaHHuyUnSo9LPb";
+    const OPERATION_ID: &str = "O1xcVGtyWAyrehW1NDpnZ1wE5K95n8qVJCV9dEJSp1ypU8eJsQU";
+
+    fn config(headers: Vec<(String, String)>, public_tls: Option<GrpcTlsConfig>) -> GrpcConfig {
+        GrpcConfig {
+            request_timeout: MassaTime::from_millis(1000),
+            headers,
+            public_tls,
+            private_tls: None,
+        }
+    }
+
+    /// An address on which nothing listens
+    fn unused_addr() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_unavailable() {
+        let addr = unused_addr();
+        let mut client = GrpcClient::new(addr, addr, &config(Vec::new(), None));
+        let err = client.get_status().await.unwrap_err();
+        assert_eq!(err.code(), Code::Unavailable);
+        let err = client.get_bootstrap_whitelist().await.unwrap_err();
+        assert_eq!(err.code(), Code::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_tls_files_are_read_lazily() {
+        let addr = unused_addr();
+        let tls = GrpcTlsConfig {
+            domain_name: "localhost".to_string(),
+            certificate_authority_root_path: PathBuf::from("/nonexistent/ca.pem"),
+            client_certificate_path: None,
+            client_private_key_path: None,
+        };
+        // creating the client must not touch the missing certificate
+        let mut client = GrpcClient::new(addr, addr, &config(Vec::new(), Some(tls)));
+        let err = client.get_status().await.unwrap_err();
+        assert_eq!(err.code(), Code::Unavailable);
+        // the private API has no TLS settings, it only fails because nothing listens
+        let err = client.get_bootstrap_whitelist().await.unwrap_err();
+        assert_eq!(err.code(), Code::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_header_is_invalid_argument() {
+        let addr = unused_addr();
+        let headers = vec![("bad header".to_string(), "value".to_string())];
+        let mut client = GrpcClient::new(addr, addr, &config(headers, None));
+        let err = client.get_status().await.unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_to_block_overview() {
+        let wrapper = grpc_model::BlockWrapper {
+            block: Some(grpc_model::Block {
+                header: Some(grpc_model::SignedBlockHeader {
+                    content: Some(grpc_model::BlockHeader {
+                        slot: Some(grpc_model::Slot {
+                            period: 12,
+                            thread: 3,
+                        }),
+                        parents: vec![PARENT_ID.to_string()],
+                        ..Default::default()
+                    }),
+                    secure_hash: BLOCK_ID.to_string(),
+                    content_creator_address: ADDRESS.to_string(),
+                    ..Default::default()
+                }),
+                operations: vec![OPERATION_ID.to_string()],
+            }),
+            status: grpc_model::BlockStatus::Final.into(),
+        };
+        let block = to_block_overview(wrapper.clone()).unwrap();
+        assert_eq!(block.id, BlockId::from_str(BLOCK_ID).unwrap());
+        assert_eq!(block.status, grpc_model::BlockStatus::Final.as_str_name());
+        assert_eq!(block.slot, Slot::new(12, 3));
+        assert_eq!(block.creator_address, Address::from_str(ADDRESS).unwrap());
+        assert_eq!(block.parents, vec![BlockId::from_str(PARENT_ID).unwrap()]);
+        assert_eq!(block.endorsement_count, 0);
+        assert_eq!(
+            block.operations,
+            vec![OperationId::from_str(OPERATION_ID).unwrap()]
+        );
+
+        let mut invalid = wrapper.clone();
+        invalid.block.as_mut().unwrap().header = None;
+        assert_eq!(
+            to_block_overview(invalid).unwrap_err().code(),
+            Code::Internal
+        );
+        let mut invalid = wrapper;
+        invalid.block.as_mut().unwrap().operations = vec!["not an id".to_string()];
+        assert_eq!(
+            to_block_overview(invalid).unwrap_err().code(),
+            Code::Internal
+        );
+    }
+
+    #[test]
+    fn test_to_operation_overview() {
+        let wrapper = grpc_model::OperationWrapper {
+            thread: 5,
+            operation: Some(grpc_model::SignedOperation {
+                content: Some(grpc_model::Operation {
+                    fee: Some(Amount::from_str("0.01").unwrap().into()),
+                    expire_period: 42,
+                    op: Some(grpc_model::OperationType {
+                        r#type: Some(grpc_model::operation_type::Type::RollBuy(
+                            grpc_model::RollBuy { roll_count: 1 },
+                        )),
+                    }),
+                }),
+                secure_hash: OPERATION_ID.to_string(),
+                content_creator_address: ADDRESS.to_string(),
+                ..Default::default()
+            }),
+            block_ids: vec![BLOCK_ID.to_string()],
+        };
+        let operation = to_operation_overview(wrapper.clone()).unwrap();
+        assert_eq!(operation.id, OperationId::from_str(OPERATION_ID).unwrap());
+        assert_eq!(operation.thread, 5);
+        assert_eq!(
+            operation.creator_address,
+            Address::from_str(ADDRESS).unwrap()
+        );
+        assert_eq!(operation.fee, Amount::from_str("0.01").unwrap());
+        assert_eq!(operation.expire_period, 42);
+        assert_eq!(operation.op_type, "RollBuy");
+        assert_eq!(
+            operation.block_ids,
+            vec![BlockId::from_str(BLOCK_ID).unwrap()]
+        );
+
+        let mut invalid = wrapper;
+        invalid.operation.as_mut().unwrap().content = None;
+        assert_eq!(
+            to_operation_overview(invalid).unwrap_err().code(),
+            Code::Internal
+        );
+    }
+
+    #[test]
+    fn test_parse_ips() {
+        assert_eq!(
+            parse_ips(vec!["127.0.0.1".to_string(), "::1".to_string()]).unwrap(),
+            vec![
+                IpAddr::from_str("127.0.0.1").unwrap(),
+                IpAddr::from_str("::1").unwrap()
+            ]
+        );
+        assert_eq!(
+            parse_ips(vec!["not an ip".to_string()]).unwrap_err().code(),
+            Code::Internal
+        );
+    }
+}
@@ -17,7 +17,7 @@ use jsonrpsee_ws_client as _;
 use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressInfo, AddressNextDraws},
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
@@ -42,17 +42,24 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     version::Version,
 };
-use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
-use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use thiserror::Error;
 
 pub mod cert_manager;
 mod config;
+mod grpc;
+mod wait;
 pub use config::ClientConfig;
+pub use config::GrpcConfig;
+pub use config::GrpcTlsConfig;
 pub use config::HttpConfig;
 pub use config::WsConfig;
+pub use grpc::{
+    AddressBalances, BlockOverview, GrpcClient, MipStatus, OperationOverview, PublicStatus,
+    QueryStateResult, SlotDraws,
+};
+pub use wait::{OperationWaitOutcome, OperationWaitStatus, OperationWaitTarget};
 
 /// Error when creating a new client
 #[derive(Error, Debug)]
@@ -63,6 +70,12 @@ pub enum ClientError {
     /// Connection error
     #[error("Cannot connect to grpc server: {0}")]
     Connect(#[from] tonic::transport::Error),
+    /// TLS file error
+    #[error("Cannot read grpc TLS file: {0}")]
+    TlsFile(#[from] std::io::Error),
+    /// Metadata error
+    #[error("Invalid grpc header: {0}")]
+    Metadata(String),
 }
 
 /// Client
@@ -71,16 +84,15 @@ pub struct Client {
     pub public: RpcClient,
     /// private component
     pub private: RpcClient,
-    /// grpc public client
-    pub grpc_public: Option<PublicServiceClient<tonic::transport::Channel>>,
-    /// grpc private client
-    pub grpc_private: Option<PrivateServiceClient<tonic::transport::Channel>>,
+    /// grpc public and private clients
+    pub grpc: GrpcClient,
     /// Chain id
     pub chain_id: u64,
 }
 
 impl Client {
     /// creates a new client
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         ip: IpAddr,
         public_port: u16,
@@ -89,6 +101,7 @@ impl Client {
         grpc_private_port: u16,
         chain_id: u64,
         http_config: &HttpConfig,
+        grpc_config: &GrpcConfig,
     ) -> Result<Client, ClientError> {
         let public_socket_addr = SocketAddr::new(ip, public_port);
         let private_socket_addr = SocketAddr::new(ip, private_port);
@@ -96,38 +109,15 @@ impl Client {
         let grpc_private_socket_addr = SocketAddr::new(ip, grpc_private_port);
         let public_url = format!("http://{}", public_socket_addr);
         let private_url = format!("http://{}", private_socket_addr);
-        let grpc_public_url = format!("grpc://{}", grpc_public_socket_addr);
-        let grpc_private_url = format!("grpc://{}", grpc_private_socket_addr);
-
-        // try to start grpc client (public api) and connect to the server
-        let grpc_pub_client = match tonic::transport::Channel::from_shared(grpc_public_url)?
-            .connect()
-            .await
-        {
-            Ok(channel) => Some(PublicServiceClient::new(channel)),
-            Err(e) => {
-                tracing::warn!("unable to connect to grpc server {}", e);
-                None
-            }
-        };
-
-        // try to start grpc client (private api) and connect to the server
-        let grpc_priv_client = match tonic::transport::Channel::from_shared(grpc_private_url)?
-            .connect()
-            .await
-        {
-            Ok(channel) => Some(PrivateServiceClient::new(channel)),
-            Err(e) => {
-                tracing::warn!("unable to connect to grpc server {}", e);
-                None
-            }
-        };
 
         Ok(Client {
             public: RpcClient::from_url(&public_url, http_config).await,
             private: RpcClient::from_url(&private_url, http_config).await,
-            grpc_public: grpc_pub_client,
-            grpc_private: grpc_priv_client,
+            grpc: GrpcClient::new(
+                grpc_public_socket_addr,
+                grpc_private_socket_addr,
+                grpc_config,
+            ),
             chain_id,
         })
     }
@@ -398,6 +388,17 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get the upcoming block and endorsement draws of addresses
+    pub async fn get_next_draws(
+        &self,
+        addresses: Vec<Address>,
+    ) -> RpcResult<Vec<AddressNextDraws>> {
+        self.http_client
+            .request("get_next_draws", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get datastore entries
     pub async fn get_datastore_entries(
         &self,
@@ -566,7 +567,7 @@ impl RpcClientV2 {
     /// New produced blocks
     pub async fn subscribe_new_blocks(
         &self,
-    ) -> Result<Subscription<BlockInfo>, jsonrpsee::core::Error> {
+    ) -> Result<Subscription<BlockInfo>, jsonrpsee::core::ClientError> {
         if let Some(client) = self.ws_client.as_ref() {
             client
                 .subscribe(
@@ -583,7 +584,7 @@ impl RpcClientV2 {
     /// New produced blocks headers
     pub async fn subscribe_new_blocks_headers(
         &self,
-    ) -> Result<Subscription<SecureShare<BlockHeader, BlockId>>, jsonrpsee::core::Error> {
+    ) -> Result<Subscription<SecureShare<BlockHeader, BlockId>>, jsonrpsee::core::ClientError> {
         if let Some(client) = self.ws_client.as_ref() {
             client
                 .subscribe(
@@ -600,7 +601,7 @@ impl RpcClientV2 {
     /// New produced blocks with operations content.
     pub async fn subscribe_new_filled_blocks(
         &self,
-    ) -> Result<Subscription<FilledBlock>, jsonrpsee::core::Error> {
+    ) -> Result<Subscription<FilledBlock>, jsonrpsee::core::ClientError> {
         if let Some(client) = self.ws_client.as_ref() {
             client
                 .subscribe(
@@ -617,7 +618,7 @@ impl RpcClientV2 {
     /// New produced operations.
     pub async fn subscribe_new_operations(
         &self,
-    ) -> Result<Subscription<Operation>, jsonrpsee::core::Error> {
+    ) -> Result<Subscription<Operation>, jsonrpsee::core::ClientError> {
         if let Some(client) = self.ws_client.as_ref() {
             client
                 .subscribe(
@@ -0,0 +1,280 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Wait for a sent operation to be included in a block or executed in a final slot.
+
+use crate::Client;
+use jsonrpsee::core::RpcResult;
+use massa_models::block_id::BlockId;
+use massa_models::execution::EventFilter;
+use massa_models::operation::OperationId;
+use massa_models::output_event::SCOutputEvent;
+use massa_time::MassaTime;
+use serde::Serialize;
+use tokio::time::Instant;
+
+/// Stage of the life of an operation to wait for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationWaitTarget {
+    /// included in a block, final or not
+    Included,
+    /// executed in a final slot
+    Final,
+}
+
+/// Status reached by an awaited operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OperationWaitStatus {
+    /// included in a block that is not final yet
+    Included,
+    /// executed in a final slot
+    Final,
+    /// expired without being executed in a final slot
+    Expired,
+    /// neither reached the target nor expired before the timeout
+    Pending,
+}
+
+/// Outcome of waiting for an operation
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationWaitOutcome {
+    /// operation id
+    pub operation_id: OperationId,
+    /// status reached by the operation
+    pub status: OperationWaitStatus,
+    /// blocks including the operation, as far as the node knows
+    pub in_blocks: Vec<BlockId>,
+    /// true if the execution succeeded, false if it failed, None if unknown
+    pub execution_success: Option<bool>,
+    /// events emitted by the execution of the operation
+    pub events: Vec<SCOutputEvent>,
+}
+
+/// What the node knows about an awaited operation when it is polled
+#[derive(Debug, Clone, Default)]
+struct OperationPoll {
+    /// period of the last executed final slot
+    final_period: u64,
+    /// expire period of the operation, if known by the node
+    expire_period: Option<u64>,
+    /// blocks including the operation
+    in_blocks: Vec<BlockId>,
+    /// execution status of the operation in a final slot
+    final_execution: Option<bool>,
+    /// execution status of the operation, final or not
+    execution: Option<bool>,
+}
+
+/// Status reached by an operation after a poll, `None` if the wait goes on
+///
+/// # Arguments
+/// * `state`: what the node knows about the operation
+/// * `expire_period`: expire period of the operation, the one known by the node if `None`
+/// * `target`: stage of the life of the operation to wait for
+/// * `timed_out`: whether the wait timed out
+fn wait_status(
+    state: &OperationPoll,
+    expire_period: Option<u64>,
+    target: OperationWaitTarget,
+    timed_out: bool,
+) -> Option<OperationWaitStatus> {
+    if state.final_execution.is_some() {
+        Some(OperationWaitStatus::Final)
+    } else if target == OperationWaitTarget::Included && !state.in_blocks.is_empty() {
+        Some(OperationWaitStatus::Included)
+    } else if expire_period
+        .or(state.expire_period)
+        .is_some_and(|expire_period| state.final_period > expire_period)
+    {
+        Some(OperationWaitStatus::Expired)
+    } else if timed_out {
+        Some(OperationWaitStatus::Pending)
+    } else {
+        None
+    }
+}
+
+impl Client {
+    /// Poll the node every `poll_interval` until an operation reaches `target`,
+    /// or the final slots go past its expire period without it being executed,
+    /// or `timeout` elapses.
+    ///
+    /// The expire period is the one of the operation known by the node if `expire_period` is not given.
+    /// The final execution status comes from the `OpExecutionStatusFinal` state query of the gRPC API
+    /// when it is reachable, and from the operation info of the JSON-RPC API otherwise.
+    pub async fn wait_for_operation(
+        &mut self,
+        operation_id: OperationId,
+        expire_period: Option<u64>,
+        target: OperationWaitTarget,
+        poll_interval: MassaTime,
+        timeout: Option<MassaTime>,
+    ) -> RpcResult<OperationWaitOutcome> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout.to_duration());
+        let (status, state) = loop {
+            let state = self.poll_operation(operation_id).await?;
+            let timed_out = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            if let Some(status) = wait_status(&state, expire_period, target, timed_out) {
+                break (status, state);
+            }
+            tokio::time::sleep(poll_interval.to_duration()).await;
+        };
+
+        let events = self
+            .public
+            .get_filtered_sc_output_event(EventFilter {
+                original_operation_id: Some(operation_id),
+                ..Default::default()
+            })
+            .await?;
+        Ok(OperationWaitOutcome {
+            operation_id,
+            status,
+            in_blocks: state.in_blocks,
+            execution_success: state.final_execution.or(state.execution),
+            events,
+        })
+    }
+
+    /// Get what the node knows about an operation
+    async fn poll_operation(&mut self, operation_id: OperationId) -> RpcResult<OperationPoll> {
+        let final_cursor = self.public.get_status().await?.execution_stats.final_cursor;
+        let info = self
+            .public
+            .get_operations(vec![operation_id])
+            .await?
+            .into_iter()
+            .next();
+        let final_execution = match self.grpc.get_op_execution_status_final(operation_id).await {
+            Ok(execution) => execution,
+            Err(_) => info
+                .as_ref()
+                .filter(|info| info.is_operation_final == Some(true))
+                .and_then(|info| info.op_exec_status),
+        };
+        Ok(OperationPoll {
+            final_period: final_cursor.period,
+            expire_period: info
+                .as_ref()
+                .map(|info| info.operation.content.expire_period),
+            in_blocks: info
+                .as_ref()
+                .map(|info| info.in_blocks.clone())
+                .unwrap_or_default(),
+            final_execution,
+            execution: info.and_then(|info| info.op_exec_status),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const BLOCK_ID: &str = "B12DvrcQkzF1Wi8BVoNfc4n93CD3E2qhCNe7nVhnEQGWHZ24fEmg";
+
+    #[test]
+    fn test_wait_success() {
+        let mut state = OperationPoll {
+            final_period: 10,
+            expire_period: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Included, false),
+            None
+        );
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, false),
+            None
+        );
+
+        state.in_blocks = vec![BlockId::from_str(BLOCK_ID).unwrap()];
+        state.execution = Some(true);
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Included, false),
+            Some(OperationWaitStatus::Included)
+        );
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, false),
+            None
+        );
+
+        state.final_execution = Some(false);
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, false),
+            Some(OperationWaitStatus::Final)
+        );
+        // a final execution wins over the timeout and the expiry
+        state.final_period = 30;
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Included, true),
+            Some(OperationWaitStatus::Final)
+        );
+    }
+
+    #[test]
+    fn test_wait_expired() {
+        let mut state = OperationPoll {
+            final_period: 20,
+            ..Default::default()
+        };
+        // the operation is unknown to the node and no expire period is given
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, false),
+            None
+        );
+        assert_eq!(
+            wait_status(&state, Some(20), OperationWaitTarget::Final, false),
+            None
+        );
+        assert_eq!(
+            wait_status(&state, Some(19), OperationWaitTarget::Final, false),
+            Some(OperationWaitStatus::Expired)
+        );
+
+        // the expire period known by the node is used when none is given
+        state.expire_period = Some(19);
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, false),
+            Some(OperationWaitStatus::Expired)
+        );
+        // being included is not enough when waiting for the final execution
+        state.in_blocks = vec![BlockId::from_str(BLOCK_ID).unwrap()];
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, false),
+            Some(OperationWaitStatus::Expired)
+        );
+    }
+
+    #[test]
+    fn test_wait_timeout() {
+        let mut state = OperationPoll {
+            final_period: 10,
+            expire_period: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, true),
+            Some(OperationWaitStatus::Pending)
+        );
+
+        // an included operation times out only when waiting for its final execution
+        state.in_blocks = vec![BlockId::from_str(BLOCK_ID).unwrap()];
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Included, true),
+            Some(OperationWaitStatus::Included)
+        );
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, true),
+            Some(OperationWaitStatus::Pending)
+        );
+
+        // an expired operation is reported as such
+        state.final_period = 21;
+        assert_eq!(
+            wait_status(&state, None, OperationWaitTarget::Final, true),
+            Some(OperationWaitStatus::Expired)
+        );
+    }
+}
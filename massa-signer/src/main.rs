@@ -0,0 +1,258 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Signer process holding the staking keys of a massa node, which reaches it with `massa_wallet::RemoteSigner`.
+//!
+//! It listens on a Unix socket only accessible to its user: put the socket in a directory shared only with the
+//! user of the node. It checks every block header and endorsement against its own signing history before signing
+//! it, so that a node restarted on another host, or compromised, cannot make it double produce.
+#![warn(missing_docs)]
+#![warn(unused_crate_dependencies)]
+
+use anyhow::Result;
+use clap::Parser;
+use massa_factory_exports::SigningHistory;
+use massa_wallet::{
+    sign_payload, SignedContent, SignerRequest, SignerResponse, Wallet, WalletError,
+};
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Signer of the blocks, endorsements and operations of a massa node"
+)]
+struct Args {
+    /// Unix socket to listen on
+    #[arg(long = "socket")]
+    socket: PathBuf,
+    /// Wallet holding the staking keys
+    #[arg(long = "wallet", default_value = "config/staking_wallets")]
+    wallet: PathBuf,
+    /// History of the blocks and endorsements signed by the staking keys
+    #[arg(
+        long = "signing-history",
+        default_value = "config/signing_history.json"
+    )]
+    signing_history: PathBuf,
+    /// Chain id of the network, the one of this build if not set
+    #[arg(long = "chain-id")]
+    chain_id: Option<u64>,
+    #[arg(short = 'p', long = "pwd")]
+    /// Wallet password
+    password: Option<String>,
+}
+
+/// Keys and history of the signer
+struct SignerState {
+    wallet: Wallet,
+    signing_history: SigningHistory,
+    chain_id: u64,
+}
+
+impl SignerState {
+    /// Answers a request of the node.
+    /// Block headers and endorsements are recorded in the signing history before being signed,
+    /// and refused if they could be a double production.
+    fn handle_request(&mut self, request: SignerRequest) -> Result<SignerResponse, WalletError> {
+        match request {
+            SignerRequest::GetPublicKeys => Ok(SignerResponse::PublicKeys {
+                public_keys: self
+                    .wallet
+                    .get_full_wallet()
+                    .values()
+                    .map(|keypair| keypair.get_public_key())
+                    .collect(),
+            }),
+            SignerRequest::Sign {
+                address,
+                chain_id,
+                payload,
+            } => {
+                if chain_id != self.chain_id {
+                    return Err(WalletError::SignerError(format!(
+                        "refusing to sign for chain id {}, the signer is on chain id {}",
+                        chain_id, self.chain_id
+                    )));
+                }
+                let keypair = self
+                    .wallet
+                    .find_associated_keypair(&address)
+                    .ok_or(WalletError::MissingKeyError(address))?;
+                let signing_history = &mut self.signing_history;
+                let signature = sign_payload(keypair, &payload, chain_id, |content| {
+                    let recorded = match content {
+                        SignedContent::BlockHeader(header) => {
+                            info!(
+                                "signing block header of {} at slot {}",
+                                address, header.slot
+                            );
+                            signing_history.check_and_record_block(address, header.slot)
+                        }
+                        SignedContent::Endorsement(endorsement) => {
+                            info!(
+                                "signing endorsement of {} at slot {} index {}",
+                                address, endorsement.slot, endorsement.index
+                            );
+                            signing_history.check_and_record_endorsement(
+                                address,
+                                endorsement.slot,
+                                endorsement.index,
+                            )
+                        }
+                        SignedContent::Operation(_) => {
+                            info!("signing operation of {}", address);
+                            Ok(())
+                        }
+                    };
+                    recorded.map_err(|err| WalletError::SignerError(err.to_string()))
+                })?;
+                Ok(SignerResponse::Signature { signature })
+            }
+        }
+    }
+}
+
+/// Load the wallet, asking for its password if necessary
+fn load_wallet(password: Option<String>, path: PathBuf, chain_id: u64) -> Result<Wallet> {
+    if !path.is_dir() {
+        anyhow::bail!("no staking wallet at {}", path.display());
+    }
+    let password = password.unwrap_or_else(|| {
+        dialoguer::Password::new()
+            .with_prompt("Enter staking keys file password")
+            .interact()
+            .expect("IO error: Password reading failed, staking keys file couldn't be unlocked")
+    });
+    Ok(Wallet::new(path, password, chain_id)?)
+}
+
+#[cfg(unix)]
+fn main() -> Result<()> {
+    use massa_wallet::serve_signer_connection;
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+    use std::time::Duration;
+    use tracing::warn;
+
+    /// Timeout of the reads and writes on a connection, so that a stuck client does not block the others
+    const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+    tracing_subscriber::fmt::init();
+    let args = Args::parse();
+    let chain_id = args.chain_id.unwrap_or(*massa_models::config::CHAINID);
+    let mut state = SignerState {
+        wallet: load_wallet(args.password, args.wallet, chain_id)?,
+        signing_history: SigningHistory::load(args.signing_history)?,
+        chain_id,
+    };
+
+    // replace the socket left by a previous run
+    if std::fs::symlink_metadata(&args.socket)
+        .is_ok_and(|metadata| metadata.file_type().is_socket())
+    {
+        std::fs::remove_file(&args.socket)?;
+    }
+    let listener = UnixListener::bind(&args.socket)?;
+    std::fs::set_permissions(&args.socket, std::fs::Permissions::from_mode(0o600))?;
+    info!(
+        "signing with {} keys on chain id {}, listening on {}",
+        state.wallet.get_full_wallet().len(),
+        chain_id,
+        args.socket.display()
+    );
+
+    // the requests are answered one at a time, so that the signing history checks cannot race
+    for stream in listener.incoming() {
+        let served = stream
+            .and_then(|stream| {
+                stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+                stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+                Ok(stream)
+            })
+            .map_err(WalletError::from)
+            .and_then(|stream| {
+                serve_signer_connection(stream, |request| state.handle_request(request))
+            });
+        if let Err(err) = served {
+            warn!("signer connection failed: {}", err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn main() -> Result<()> {
+    anyhow::bail!("the signer listens on a Unix socket, which this platform does not support")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::block_id::BlockId;
+    use massa_models::endorsement::{Endorsement, EndorsementSerializer};
+    use massa_models::slot::Slot;
+    use massa_serialization::Serializer;
+    use massa_signature::KeyPair;
+    use massa_wallet::SigningPayload;
+    use tempfile::TempDir;
+
+    fn sign_endorsement(
+        state: &mut SignerState,
+        keypair: &KeyPair,
+        index: u32,
+        chain_id: u64,
+    ) -> Result<SignerResponse, WalletError> {
+        let mut serialized = Vec::new();
+        EndorsementSerializer::new()
+            .serialize(
+                &Endorsement {
+                    slot: Slot::new(1, 0),
+                    index,
+                    endorsed_block: BlockId::generate_from_hash(Hash::compute_from(b"block")),
+                },
+                &mut serialized,
+            )
+            .unwrap();
+        state.handle_request(SignerRequest::Sign {
+            address: massa_models::address::Address::from_public_key(&keypair.get_public_key()),
+            chain_id,
+            payload: SigningPayload::Endorsement(serialized),
+        })
+    }
+
+    #[test]
+    fn test_signer_checks_its_signing_history() {
+        let folder = TempDir::new().unwrap();
+        let keypair = KeyPair::generate(0).unwrap();
+        let mut wallet = Wallet::new(folder.path().join("wallet"), "pwd".to_string(), 77).unwrap();
+        wallet.add_keypairs(vec![keypair.clone()]).unwrap();
+        let mut state = SignerState {
+            wallet,
+            signing_history: SigningHistory::load(folder.path().join("history.json")).unwrap(),
+            chain_id: 77,
+        };
+
+        match state.handle_request(SignerRequest::GetPublicKeys).unwrap() {
+            SignerResponse::PublicKeys { public_keys } => {
+                assert_eq!(public_keys, vec![keypair.get_public_key()])
+            }
+            response => panic!("unexpected response {:?}", response),
+        }
+        assert!(matches!(
+            sign_endorsement(&mut state, &keypair, 3, 77),
+            Ok(SignerResponse::Signature { .. })
+        ));
+        // the same endorsement is not signed twice
+        assert!(sign_endorsement(&mut state, &keypair, 3, 77).is_err());
+        // nor an endorsement of another network
+        assert!(sign_endorsement(&mut state, &keypair, 4, 78).is_err());
+        assert!(matches!(
+            sign_endorsement(&mut state, &keypair, 4, 77),
+            Ok(SignerResponse::Signature { .. })
+        ));
+        // nor with a key that the signer does not hold
+        assert!(sign_endorsement(&mut state, &KeyPair::generate(0).unwrap(), 5, 77).is_err());
+    }
+}
@@ -10,3 +10,9 @@ impl From<MassaTime> for grpc_model::NativeTime {
         }
     }
 }
+
+impl From<grpc_model::NativeTime> for MassaTime {
+    fn from(value: grpc_model::NativeTime) -> Self {
+        MassaTime::from_millis(value.milliseconds)
+    }
+}
@@ -26,4 +26,14 @@ pub enum WalletError {
     MassaCipherError(#[from] massa_cipher::CipherError),
     /// Version error: {0}
     VersionError(String),
+    /// Mnemonic error: {0}
+    MnemonicError(String),
+    /// Key derivation error: {0}
+    DerivationError(String),
+    /// Watch-only address error: {0}
+    WatchOnlyError(String),
+    /// JSON error: {0}
+    JSONError(#[from] serde_json::Error),
+    /// Signer error: {0}
+    SignerError(String),
 }
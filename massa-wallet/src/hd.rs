@@ -0,0 +1,197 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Hierarchical deterministic keys: BIP-39 mnemonic seeds and SLIP-10 ed25519 derivation.
+//!
+//! ed25519 only supports hardened derivation, so every index of a derivation path is hardened.
+//! The addresses of a wallet are derived along `m/44'/632'/0'/0'/index'`, 632 being the SLIP-44 coin type of Massa.
+
+use crate::WalletError;
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use massa_signature::KeyPair;
+use serde::Serialize;
+use sha2::Sha512;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// SLIP-44 coin type of Massa
+pub const MASSA_COIN_TYPE: u32 = 632;
+
+/// Number of words of the generated mnemonics
+pub const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// Offset of the hardened indexes
+pub(crate) const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// HMAC key of the SLIP-10 ed25519 master key
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// Path of hardened indexes from the master key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Path of the address of the given index: `m/44'/632'/0'/0'/index'`
+    pub fn address(index: u32) -> Self {
+        DerivationPath(vec![44, MASSA_COIN_TYPE, 0, 0, index])
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WalletError::DerivationError(format!("invalid derivation path: {}", s));
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+        parts
+            .map(|part| {
+                let index = part
+                    .strip_suffix('\'')
+                    .or_else(|| part.strip_suffix('H'))
+                    .ok_or_else(|| {
+                        WalletError::DerivationError(format!(
+                            "ed25519 only supports hardened derivation: {}",
+                            s
+                        ))
+                    })?;
+                match index.parse::<u32>() {
+                    Ok(index) if index < HARDENED_OFFSET => Ok(index),
+                    _ => Err(invalid()),
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(DerivationPath)
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for DerivationPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// An address derived from the mnemonic of a wallet
+#[derive(Debug, Clone, Serialize)]
+pub struct DerivedAddress {
+    /// derivation path of the key of the address
+    pub path: DerivationPath,
+    /// derived address
+    pub address: massa_models::address::Address,
+}
+
+/// Generate a new mnemonic from OS randomness
+pub fn generate_mnemonic() -> Result<Mnemonic, WalletError> {
+    let mut entropy = [0u8; MNEMONIC_WORD_COUNT * 4 / 3];
+    rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut entropy);
+    Mnemonic::from_entropy(&entropy).map_err(|e| WalletError::MnemonicError(e.to_string()))
+}
+
+/// Parse a mnemonic phrase, checking its words and checksum
+pub fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, WalletError> {
+    Mnemonic::parse(phrase).map_err(|e| WalletError::MnemonicError(e.to_string()))
+}
+
+/// HMAC-SHA512 of `data`, split into a key and a chain code
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    let bytes = mac.finalize().into_bytes();
+    let mut secret = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    secret.copy_from_slice(&bytes[..32]);
+    chain_code.copy_from_slice(&bytes[32..]);
+    (secret, chain_code)
+}
+
+/// Derive the ed25519 secret key of a path from a seed, following SLIP-10
+fn derive_secret_key(seed: &[u8], path: &DerivationPath) -> [u8; 32] {
+    let (mut secret, mut chain_code) = hmac_sha512(ED25519_SEED_KEY, &[seed]);
+    for index in &path.0 {
+        let index = (index | HARDENED_OFFSET).to_be_bytes();
+        (secret, chain_code) = hmac_sha512(&chain_code, &[&[0u8][..], &secret[..], &index[..]]);
+    }
+    secret
+}
+
+/// Derive the keypair of a path from a mnemonic
+pub fn derive_keypair(mnemonic: &Mnemonic, path: &DerivationPath) -> Result<KeyPair, WalletError> {
+    let secret = derive_secret_key(&mnemonic.to_seed(""), path);
+    // version 0 keypair: version(1B) + secret key(32B)
+    let mut bytes = Vec::with_capacity(33);
+    bytes.push(0);
+    bytes.extend_from_slice(&secret);
+    Ok(KeyPair::from_bytes(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_slip10_ed25519_vector() {
+        // test vector 1 of SLIP-10 for ed25519
+        let seed = from_hex("000102030405060708090a0b0c0d0e0f");
+        assert_eq!(
+            derive_secret_key(&seed, &DerivationPath(vec![])).to_vec(),
+            from_hex("2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7")
+        );
+        assert_eq!(
+            derive_secret_key(&seed, &"m/0'".parse().unwrap()).to_vec(),
+            from_hex("68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3")
+        );
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let path = DerivationPath::address(3);
+        assert_eq!(path.to_string(), "m/44'/632'/0'/0'/3'");
+        assert_eq!(path.to_string().parse::<DerivationPath>().unwrap(), path);
+        assert!("m/44'/632'/0".parse::<DerivationPath>().is_err());
+        assert!("44'/632'".parse::<DerivationPath>().is_err());
+    }
+
+    #[test]
+    fn test_mnemonic_derivation_is_deterministic() {
+        let mnemonic = generate_mnemonic().unwrap();
+        assert_eq!(mnemonic.word_count(), MNEMONIC_WORD_COUNT);
+        let restored = parse_mnemonic(&mnemonic.to_string()).unwrap();
+        assert_eq!(
+            derive_keypair(&mnemonic, &DerivationPath::address(0))
+                .unwrap()
+                .to_bytes(),
+            derive_keypair(&restored, &DerivationPath::address(0))
+                .unwrap()
+                .to_bytes()
+        );
+        assert_ne!(
+            derive_keypair(&mnemonic, &DerivationPath::address(0))
+                .unwrap()
+                .to_bytes(),
+            derive_keypair(&mnemonic, &DerivationPath::address(1))
+                .unwrap()
+                .to_bytes()
+        );
+    }
+}
@@ -1,29 +1,64 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 //! Standalone massa wallet
 //! Keypair management
+//!
+//! A wallet is a directory with a file per address: `wallet_<address>.yaml` for the encrypted keys, whose
+//! `Nickname` is the label of the address, and `watch_only/<address>.yaml` for the addresses followed without
+//! their secret key. The encrypted mnemonic of the derived keys, if any, is in `hd/seed.yaml`.
+//! Other files of the directory are not part of the wallet and are left untouched.
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
 pub use error::WalletError;
+pub use hd::{DerivationPath, DerivedAddress, MNEMONIC_WORD_COUNT};
+pub use signer::{
+    serve_signer_connection, sign_content, sign_payload, LocalSigner, RemoteSigner,
+    SignableContent, SignedContent, Signer, SignerRequest, SignerResponse, SigningPayload,
+};
 
+use bip39::Mnemonic;
 use massa_cipher::{decrypt, encrypt, CipherData, Salt};
 use massa_hash::Hash;
 use massa_models::address::Address;
 use massa_models::composite::PubkeySig;
 use massa_models::operation::{Operation, OperationSerializer, SecureShareOperation};
 use massa_models::prehash::{PreHashMap, PreHashSet};
-use massa_models::secure_share::SecureShareContent;
 use massa_signature::{KeyPair, PublicKey};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 
 mod error;
+mod hd;
+mod signer;
 
 const WALLET_VERSION: u64 = 1;
 
+const HD_SEED_VERSION: u64 = 1;
+
+const WATCH_ONLY_VERSION: u64 = 1;
+
+const EXPORT_VERSION: u64 = 1;
+
+/// Prefix of the files of the encrypted keys, in the wallet directory
+const KEY_FILE_PREFIX: &str = "wallet_";
+
+/// Subdirectory of the wallet directory holding the encrypted mnemonic.
+/// Older versions load every file at the root of the wallet directory as a key, so it must not be there.
+const HD_DIRECTORY_NAME: &str = "hd";
+
+/// Name of the file of the encrypted mnemonic, in the `hd` subdirectory
+const HD_SEED_FILE_NAME: &str = "seed.yaml";
+
+/// Subdirectory of the wallet directory holding a file per watch-only address.
+/// Older versions load every file at the root of the wallet directory as a key, so they must not be there.
+const WATCH_ONLY_DIRECTORY_NAME: &str = "watch_only";
+
 /// Contains the keypairs created in the wallet.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Wallet {
@@ -35,6 +70,26 @@ pub struct Wallet {
     password: String,
     /// chain id
     chain_id: u64,
+    /// Mnemonic the derived keys come from, if any
+    #[serde(skip)]
+    hd_seed: Option<HdSeed>,
+    /// Addresses followed without their secret key, with their public key if known
+    #[serde(default)]
+    watch_only: PreHashMap<Address, Option<PublicKey>>,
+    /// Labels given by the user to the addresses of the wallet
+    #[serde(default)]
+    labels: PreHashMap<Address, String>,
+    /// Signer of the operations of the addresses whose secret key is not in the wallet
+    #[serde(skip)]
+    remote_signer: Option<Arc<dyn Signer>>,
+}
+
+/// Mnemonic of a hierarchical deterministic wallet
+#[derive(Clone, Debug)]
+struct HdSeed {
+    mnemonic: Mnemonic,
+    /// index of the next address to derive
+    next_index: u32,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -50,16 +105,161 @@ struct WalletFileFormat {
     public_key: Vec<u8>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// Encrypted mnemonic of a hierarchical deterministic wallet, stored next to the key files
+struct HdSeedFileFormat {
+    version: u64,
+    salt: Salt,
+    nonce: [u8; 12],
+    /// encrypted mnemonic phrase
+    ciphered_data: Vec<u8>,
+    /// index of the next address to derive
+    next_index: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// Address followed by the wallet without its secret key, stored next to the key files
+struct WatchOnlyFileFormat {
+    version: u64,
+    nickname: String,
+    address: String,
+    /// public key of the address, if known
+    public_key: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// Whole wallet exported to a single file, encrypted with an export password
+struct WalletExportFileFormat {
+    version: u64,
+    salt: Salt,
+    nonce: [u8; 12],
+    /// encrypted `WalletExportContent` in YAML
+    ciphered_data: Vec<u8>,
+}
+
+/// Content of a wallet export file
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct WalletExportContent {
+    keys: Vec<ExportedKey>,
+    watch_only: Vec<ExportedWatchOnly>,
+    /// mnemonic phrase, if any
+    mnemonic: Option<String>,
+    /// index of the next address to derive from the mnemonic
+    next_index: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ExportedKey {
+    keypair: KeyPair,
+    label: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ExportedWatchOnly {
+    address: Address,
+    public_key: Option<PublicKey>,
+    label: Option<String>,
+}
+
+/// Label of an address read from the nickname of its file, the address itself meaning no label
+fn label_from_nickname(nickname: String, address: &Address) -> Option<String> {
+    if nickname.is_empty() || nickname == address.to_string() {
+        None
+    } else {
+        Some(nickname)
+    }
+}
+
+/// Whether a file at the root of a wallet directory holds the encrypted key of an address
+fn is_key_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .map_or(false, |name| {
+                name.starts_with(KEY_FILE_PREFIX) && name.ends_with(".yaml")
+            })
+}
+
+/// Files of the watch-only addresses of a wallet directory, empty if it has none
+fn watch_only_files(wallet_path: &Path) -> Result<Vec<PathBuf>, WalletError> {
+    let directory = wallet_path.join(WATCH_ONLY_DIRECTORY_NAME);
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some(OsStr::new("yaml")) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Load a watch-only address of a wallet
+fn load_watch_only(
+    path: &Path,
+) -> Result<(Address, Option<PublicKey>, Option<String>), WalletError> {
+    let file = serde_yaml::from_slice::<WatchOnlyFileFormat>(&std::fs::read(path)?)?;
+    if file.version != WATCH_ONLY_VERSION {
+        return Err(WalletError::VersionError(format!(
+            "Unsupported watch-only file version {}",
+            file.version
+        )));
+    }
+    let address = Address::from_str(&file.address)?;
+    let public_key = file
+        .public_key
+        .map(|bytes| PublicKey::from_bytes(&bytes))
+        .transpose()?;
+    Ok((
+        address,
+        public_key,
+        label_from_nickname(file.nickname, &address),
+    ))
+}
+
+/// Load the encrypted mnemonic of a wallet
+fn load_hd_seed(path: &Path, password: &str) -> Result<HdSeed, WalletError> {
+    let file = serde_yaml::from_slice::<HdSeedFileFormat>(&std::fs::read(path)?)?;
+    if file.version != HD_SEED_VERSION {
+        return Err(WalletError::VersionError(format!(
+            "Unsupported mnemonic file version {}",
+            file.version
+        )));
+    }
+    let phrase = decrypt(
+        password,
+        CipherData {
+            salt: file.salt,
+            nonce: file.nonce,
+            encrypted_bytes: file.ciphered_data,
+        },
+    )?;
+    let phrase = String::from_utf8(phrase)
+        .map_err(|_| WalletError::MnemonicError("invalid mnemonic encoding".to_string()))?;
+    Ok(HdSeed {
+        mnemonic: hd::parse_mnemonic(&phrase)?,
+        next_index: file.next_index,
+    })
+}
+
 //TODO: Use exports and mock it
 impl Wallet {
     /// Generates a new wallet initialized with the provided file content
     pub fn new(path: PathBuf, password: String, chain_id: u64) -> Result<Wallet, WalletError> {
         if path.is_dir() {
             let mut keys = PreHashMap::default();
+            let mut watch_only = PreHashMap::default();
+            let mut labels = PreHashMap::default();
             for entry in std::fs::read_dir(&path)? {
                 let entry = entry?;
                 let path = entry.path();
-                if path.is_file() {
+                if is_key_file(&path) {
                     let content = &std::fs::read(&path)?[..];
                     let mut wallet = serde_yaml::from_slice::<WalletFileFormat>(content)?;
                     if wallet.version == 0 {
@@ -98,17 +298,36 @@ impl Wallet {
                             return Err(WalletError::VersionError("Invalid wallet/version matching: your wallet does not follow its version's secret key encoding format.".to_string()))
                         }
                     }
-                    keys.insert(
-                        Address::from_str(&wallet.address)?,
-                        KeyPair::from_bytes(&secret_key)?,
-                    );
+                    let address = Address::from_str(&wallet.address)?;
+                    keys.insert(address, KeyPair::from_bytes(&secret_key)?);
+                    if let Some(label) = label_from_nickname(wallet.nickname, &address) {
+                        labels.insert(address, label);
+                    }
+                }
+                // any other entry is not part of the wallet and is skipped
+            }
+            for file in watch_only_files(&path)? {
+                let (address, public_key, label) = load_watch_only(&file)?;
+                watch_only.insert(address, public_key);
+                if let Some(label) = label {
+                    labels.insert(address, label);
                 }
             }
+            let hd_seed_path = path.join(HD_DIRECTORY_NAME).join(HD_SEED_FILE_NAME);
+            let hd_seed = if hd_seed_path.is_file() {
+                Some(load_hd_seed(&hd_seed_path, &password)?)
+            } else {
+                None
+            };
             Ok(Wallet {
                 keys,
                 wallet_path: path,
                 password,
                 chain_id,
+                hd_seed,
+                watch_only,
+                labels,
+                remote_signer: None,
             })
         } else {
             let wallet = Wallet {
@@ -116,6 +335,10 @@ impl Wallet {
                 wallet_path: path,
                 password,
                 chain_id,
+                hd_seed: None,
+                watch_only: PreHashMap::default(),
+                labels: PreHashMap::default(),
+                remote_signer: None,
             };
             wallet.save()?;
             Ok(wallet)
@@ -149,6 +372,8 @@ impl Wallet {
             let addr = Address::from_public_key(&key.get_public_key());
             if let Entry::Vacant(e) = self.keys.entry(addr) {
                 e.insert(key);
+                // the address is no longer watch-only
+                self.watch_only.remove(&addr);
                 changed = true;
             }
             addrs.push(addr);
@@ -164,13 +389,81 @@ impl Wallet {
     pub fn remove_addresses(&mut self, addresses: &Vec<Address>) -> Result<bool, WalletError> {
         let mut changed = false;
         for address in addresses {
-            if self.keys.remove(address).is_some() {
+            if self.keys.remove(address).is_some() || self.watch_only.remove(address).is_some() {
+                self.labels.remove(address);
                 changed = true;
             }
         }
         Ok(changed)
     }
 
+    /// Adds addresses followed without their secret key, with their public key if known.
+    /// Addresses whose secret key is in the wallet are ignored.
+    /// The wallet file is updated.
+    pub fn add_watch_only(
+        &mut self,
+        entries: Vec<(Address, Option<PublicKey>)>,
+    ) -> Result<Vec<Address>, WalletError> {
+        let mut changed = false;
+        let mut addrs = Vec::with_capacity(entries.len());
+        for (addr, public_key) in entries {
+            if let Some(public_key) = &public_key {
+                if Address::from_public_key(public_key) != addr {
+                    return Err(WalletError::WatchOnlyError(format!(
+                        "public key {} does not match address {}",
+                        public_key, addr
+                    )));
+                }
+            }
+            if !self.keys.contains_key(&addr) {
+                match self.watch_only.entry(addr) {
+                    Entry::Vacant(e) => {
+                        e.insert(public_key);
+                        changed = true;
+                    }
+                    // learn the public key of an address that was added without it
+                    Entry::Occupied(mut e) if e.get().is_none() && public_key.is_some() => {
+                        e.insert(public_key);
+                        changed = true;
+                    }
+                    Entry::Occupied(_) => {}
+                }
+            }
+            addrs.push(addr);
+        }
+        if changed {
+            self.save()?;
+        }
+        Ok(addrs)
+    }
+
+    /// Get the watch-only addresses of the wallet, with their public key if known
+    pub fn get_watch_only(&self) -> &PreHashMap<Address, Option<PublicKey>> {
+        &self.watch_only
+    }
+
+    /// Sets the label of an address of the wallet, or removes it if `label` is `None`.
+    /// The wallet file is updated.
+    pub fn set_label(
+        &mut self,
+        address: Address,
+        label: Option<String>,
+    ) -> Result<(), WalletError> {
+        if !self.keys.contains_key(&address) && !self.watch_only.contains_key(&address) {
+            return Err(WalletError::MissingKeyError(address));
+        }
+        match label.filter(|label| !label.is_empty()) {
+            Some(label) => self.labels.insert(address, label),
+            None => self.labels.remove(&address),
+        };
+        self.save()
+    }
+
+    /// Get the label of an address of the wallet, if any
+    pub fn get_label(&self, address: &Address) -> Option<&String> {
+        self.labels.get(address)
+    }
+
     /// Finds the keypair associated with given address
     pub fn find_associated_keypair(&self, address: &Address) -> Option<&KeyPair> {
         self.keys.get(address)
@@ -181,23 +474,42 @@ impl Wallet {
         self.keys
             .get(address)
             .map(|keypair| keypair.get_public_key())
+            .or_else(|| self.watch_only.get(address).copied().flatten())
     }
 
-    /// Get all addresses in the wallet
+    /// Get all addresses in the wallet whose secret key is known, watch-only addresses excluded
     pub fn get_wallet_address_list(&self) -> PreHashSet<Address> {
         self.keys.keys().copied().collect()
     }
 
+    /// Files of the wallet directory written by `save`
+    fn existing_wallet_files(&self) -> Result<HashSet<PathBuf>, WalletError> {
+        let mut files = HashSet::new();
+        for entry in std::fs::read_dir(&self.wallet_path)? {
+            let path = entry?.path();
+            if is_key_file(&path) {
+                files.insert(path);
+            }
+        }
+        files.extend(watch_only_files(&self.wallet_path)?);
+        let hd_seed_path = self
+            .wallet_path
+            .join(HD_DIRECTORY_NAME)
+            .join(HD_SEED_FILE_NAME);
+        if hd_seed_path.is_file() {
+            files.insert(hd_seed_path);
+        }
+        Ok(files)
+    }
+
     /// Save the wallets in a directory, each wallet in a yaml file.
+    /// Only the files of the wallet are written or removed, other entries of the directory are kept.
     pub fn save(&self) -> Result<(), WalletError> {
         let mut existing_keys: HashSet<PathBuf> = HashSet::new();
         if !self.wallet_path.exists() {
             std::fs::create_dir_all(&self.wallet_path)?;
         } else {
-            let read_dir = std::fs::read_dir(&self.wallet_path)?;
-            for path in read_dir {
-                existing_keys.insert(path?.path());
-            }
+            existing_keys = self.existing_wallet_files()?;
         }
         let mut persisted_keys: HashSet<PathBuf> = HashSet::new();
         // write the keys in the directory
@@ -205,7 +517,7 @@ impl Wallet {
             let encrypted_secret = encrypt(&self.password, &keypair.to_bytes())?;
             let file_formatted = WalletFileFormat {
                 version: WALLET_VERSION,
-                nickname: addr.to_string(),
+                nickname: self.label_or_address(addr),
                 address: addr.to_string(),
                 salt: encrypted_secret.salt,
                 nonce: encrypted_secret.nonce,
@@ -213,12 +525,51 @@ impl Wallet {
                 public_key: keypair.get_public_key().to_bytes().to_vec(),
             };
             let ser_keys = serde_yaml::to_string(&file_formatted)?;
-            let file_path = self.wallet_path.join(format!("wallet_{}.yaml", addr));
+            let file_path = self
+                .wallet_path
+                .join(format!("{}{}.yaml", KEY_FILE_PREFIX, addr));
 
             std::fs::write(&file_path, ser_keys)?;
             persisted_keys.insert(file_path);
         }
 
+        // write the watch-only addresses in their own subdirectory
+        if !self.watch_only.is_empty() {
+            std::fs::create_dir_all(self.wallet_path.join(WATCH_ONLY_DIRECTORY_NAME))?;
+        }
+        for (addr, public_key) in &self.watch_only {
+            let file_formatted = WatchOnlyFileFormat {
+                version: WATCH_ONLY_VERSION,
+                nickname: self.label_or_address(addr),
+                address: addr.to_string(),
+                public_key: public_key.map(|public_key| public_key.to_bytes().to_vec()),
+            };
+            let file_path = self
+                .wallet_path
+                .join(WATCH_ONLY_DIRECTORY_NAME)
+                .join(format!("{}.yaml", addr));
+            std::fs::write(&file_path, serde_yaml::to_string(&file_formatted)?)?;
+            persisted_keys.insert(file_path);
+        }
+
+        // write the mnemonic in its own subdirectory
+        if let Some(hd_seed) = &self.hd_seed {
+            let encrypted_phrase =
+                encrypt(&self.password, hd_seed.mnemonic.to_string().as_bytes())?;
+            let file_formatted = HdSeedFileFormat {
+                version: HD_SEED_VERSION,
+                salt: encrypted_phrase.salt,
+                nonce: encrypted_phrase.nonce,
+                ciphered_data: encrypted_phrase.encrypted_bytes,
+                next_index: hd_seed.next_index,
+            };
+            let hd_path = self.wallet_path.join(HD_DIRECTORY_NAME);
+            std::fs::create_dir_all(&hd_path)?;
+            let file_path = hd_path.join(HD_SEED_FILE_NAME);
+            std::fs::write(&file_path, serde_yaml::to_string(&file_formatted)?)?;
+            persisted_keys.insert(file_path);
+        }
+
         let to_remove = existing_keys.difference(&persisted_keys);
         for path in to_remove {
             std::fs::remove_file(path)?;
@@ -227,27 +578,278 @@ impl Wallet {
         Ok(())
     }
 
+    /// Nickname written in the file of an address: its label, or the address itself
+    fn label_or_address(&self, address: &Address) -> String {
+        self.labels
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| address.to_string())
+    }
+
+    /// Exports the whole wallet to a single file encrypted with `export_password`:
+    /// keys, watch-only addresses, labels and mnemonic.
+    pub fn export_to_file(&self, path: &Path, export_password: &str) -> Result<(), WalletError> {
+        let content = WalletExportContent {
+            keys: self
+                .keys
+                .iter()
+                .map(|(addr, keypair)| ExportedKey {
+                    keypair: keypair.clone(),
+                    label: self.labels.get(addr).cloned(),
+                })
+                .collect(),
+            watch_only: self
+                .watch_only
+                .iter()
+                .map(|(addr, public_key)| ExportedWatchOnly {
+                    address: *addr,
+                    public_key: *public_key,
+                    label: self.labels.get(addr).cloned(),
+                })
+                .collect(),
+            mnemonic: self.get_mnemonic(),
+            next_index: self
+                .hd_seed
+                .as_ref()
+                .map_or(0, |hd_seed| hd_seed.next_index),
+        };
+        let encrypted = encrypt(export_password, serde_yaml::to_string(&content)?.as_bytes())?;
+        let file_formatted = WalletExportFileFormat {
+            version: EXPORT_VERSION,
+            salt: encrypted.salt,
+            nonce: encrypted.nonce,
+            ciphered_data: encrypted.encrypted_bytes,
+        };
+        // write through a fresh temporary file next to `path`, so that an interrupted export never leaves
+        // a truncated file at `path` and no other file is overwritten
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut tmp_file = tempfile::NamedTempFile::new_in(directory)?;
+        tmp_file.write_all(serde_yaml::to_string(&file_formatted)?.as_bytes())?;
+        tmp_file.as_file().sync_all()?;
+        tmp_file.persist(path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// Imports a file written by `export_to_file` into the wallet, returns the imported addresses.
+    /// Entries already in the wallet and their labels are kept.
+    /// The mnemonic is imported if the wallet has none, and must be the same otherwise.
+    /// The wallet file is updated. If it cannot be, the wallet is left as it was before the import.
+    pub fn import_from_file(
+        &mut self,
+        path: &Path,
+        export_password: &str,
+    ) -> Result<Vec<Address>, WalletError> {
+        let file = serde_yaml::from_slice::<WalletExportFileFormat>(&std::fs::read(path)?)?;
+        if file.version != EXPORT_VERSION {
+            return Err(WalletError::VersionError(format!(
+                "Unsupported wallet export version {}",
+                file.version
+            )));
+        }
+        let content = decrypt(
+            export_password,
+            CipherData {
+                salt: file.salt,
+                nonce: file.nonce,
+                encrypted_bytes: file.ciphered_data,
+            },
+        )?;
+        let content = serde_yaml::from_slice::<WalletExportContent>(&content)?;
+        let previous = (
+            self.keys.clone(),
+            self.watch_only.clone(),
+            self.labels.clone(),
+            self.hd_seed.clone(),
+        );
+
+        // check the mnemonic before changing anything
+        let mnemonic = content
+            .mnemonic
+            .as_deref()
+            .map(hd::parse_mnemonic)
+            .transpose()?;
+        match (&mut self.hd_seed, mnemonic) {
+            (Some(hd_seed), Some(mnemonic)) if hd_seed.mnemonic == mnemonic => {
+                hd_seed.next_index = hd_seed.next_index.max(content.next_index);
+            }
+            (Some(_), Some(_)) => {
+                return Err(WalletError::MnemonicError(
+                    "the wallet already has a different mnemonic".to_string(),
+                ))
+            }
+            (None, Some(mnemonic)) => {
+                self.hd_seed = Some(HdSeed {
+                    mnemonic,
+                    next_index: content.next_index,
+                });
+            }
+            (_, None) => {}
+        }
+
+        let mut addrs = Vec::with_capacity(content.keys.len() + content.watch_only.len());
+        for key in content.keys {
+            let addr = Address::from_public_key(&key.keypair.get_public_key());
+            self.keys.entry(addr).or_insert(key.keypair);
+            self.watch_only.remove(&addr);
+            if let Some(label) = key.label {
+                self.labels.entry(addr).or_insert(label);
+            }
+            addrs.push(addr);
+        }
+        for entry in content.watch_only {
+            if !self.keys.contains_key(&entry.address) {
+                let public_key = self.watch_only.entry(entry.address).or_default();
+                if public_key.is_none() {
+                    *public_key = entry.public_key;
+                }
+            }
+            if let Some(label) = entry.label {
+                self.labels.entry(entry.address).or_insert(label);
+            }
+            addrs.push(entry.address);
+        }
+        if let Err(err) = self.save() {
+            (self.keys, self.watch_only, self.labels, self.hd_seed) = previous;
+            return Err(err);
+        }
+        Ok(addrs)
+    }
+
+    /// Generates a mnemonic for the wallet and derives its first address.
+    /// Returns the mnemonic phrase, which the user has to write down to recover the derived keys.
+    /// The wallet file is updated.
+    pub fn create_mnemonic(&mut self) -> Result<(String, DerivedAddress), WalletError> {
+        self.set_mnemonic(hd::generate_mnemonic()?)?;
+        let derived = self.derive_next(None)?;
+        self.save()?;
+        Ok((self.get_mnemonic().unwrap_or_default(), derived))
+    }
+
+    /// Sets the mnemonic of the wallet from its phrase and derives its first `address_count` addresses.
+    /// The wallet file is updated.
+    pub fn import_mnemonic(
+        &mut self,
+        phrase: &str,
+        address_count: u32,
+    ) -> Result<Vec<DerivedAddress>, WalletError> {
+        self.set_mnemonic(hd::parse_mnemonic(phrase)?)?;
+        let derived = (0..address_count)
+            .map(|_| self.derive_next(None))
+            .collect::<Result<_, _>>()?;
+        self.save()?;
+        Ok(derived)
+    }
+
+    /// Derives the next address of the wallet from its mnemonic.
+    /// If a thread is given, the indexes of the addresses of other threads are skipped.
+    /// The wallet file is updated.
+    pub fn derive_next_address(
+        &mut self,
+        thread: Option<u8>,
+        thread_count: u8,
+    ) -> Result<DerivedAddress, WalletError> {
+        let derived = self.derive_next(thread.map(|thread| (thread, thread_count)))?;
+        self.save()?;
+        Ok(derived)
+    }
+
+    /// Derives the next address of each thread from the mnemonic of the wallet.
+    /// The wallet file is updated.
+    pub fn derive_address_per_thread(
+        &mut self,
+        thread_count: u8,
+    ) -> Result<Vec<DerivedAddress>, WalletError> {
+        let derived = (0..thread_count)
+            .map(|thread| self.derive_next(Some((thread, thread_count))))
+            .collect::<Result<_, _>>()?;
+        self.save()?;
+        Ok(derived)
+    }
+
+    /// Returns the mnemonic phrase of the wallet, if any
+    pub fn get_mnemonic(&self) -> Option<String> {
+        self.hd_seed
+            .as_ref()
+            .map(|hd_seed| hd_seed.mnemonic.to_string())
+    }
+
+    fn set_mnemonic(&mut self, mnemonic: Mnemonic) -> Result<(), WalletError> {
+        if self.hd_seed.is_some() {
+            return Err(WalletError::MnemonicError(
+                "the wallet already has a mnemonic".to_string(),
+            ));
+        }
+        self.hd_seed = Some(HdSeed {
+            mnemonic,
+            next_index: 0,
+        });
+        Ok(())
+    }
+
+    /// Derives the next address, in the given `(thread, thread_count)` if any, and adds its key to the wallet
+    fn derive_next(&mut self, thread: Option<(u8, u8)>) -> Result<DerivedAddress, WalletError> {
+        if let Some((thread, thread_count)) = thread {
+            if thread >= thread_count {
+                return Err(WalletError::DerivationError(format!(
+                    "invalid thread {}",
+                    thread
+                )));
+            }
+        }
+        let hd_seed = self
+            .hd_seed
+            .as_mut()
+            .ok_or_else(|| WalletError::MnemonicError("the wallet has no mnemonic".to_string()))?;
+        loop {
+            if hd_seed.next_index >= hd::HARDENED_OFFSET {
+                return Err(WalletError::DerivationError(
+                    "no more addresses to derive".to_string(),
+                ));
+            }
+            let path = DerivationPath::address(hd_seed.next_index);
+            let keypair = hd::derive_keypair(&hd_seed.mnemonic, &path)?;
+            hd_seed.next_index += 1;
+            let address = Address::from_public_key(&keypair.get_public_key());
+            if thread.map_or(true, |(thread, thread_count)| {
+                address.get_thread(thread_count) == thread
+            }) {
+                self.keys.insert(address, keypair);
+                return Ok(DerivedAddress { path, address });
+            }
+        }
+    }
+
     /// Export keys and addresses
     pub fn get_full_wallet(&self) -> &PreHashMap<Address, KeyPair> {
         &self.keys
     }
 
-    /// Signs an operation with the keypair corresponding to the given address
+    /// Sets the signer of the operations of the addresses whose secret key is not in the wallet
+    pub fn set_remote_signer(&mut self, signer: Option<Arc<dyn Signer>>) {
+        self.remote_signer = signer;
+    }
+
+    /// Signs an operation with the keypair corresponding to the given address,
+    /// or with the remote signer if the keypair is not in the wallet
     pub fn create_operation(
         &self,
         content: Operation,
         address: Address,
     ) -> Result<SecureShareOperation, WalletError> {
-        let sender_keypair = self
-            .find_associated_keypair(&address)
-            .ok_or_else(|| WalletError::MissingKeyError(address))?;
-        Ok(Operation::new_verifiable(
+        let signer: &dyn Signer = match &self.remote_signer {
+            Some(remote_signer) if !self.keys.contains_key(&address) => remote_signer.as_ref(),
+            _ => self,
+        };
+        sign_content(
+            signer,
+            &address,
             content,
             OperationSerializer::new(),
-            sender_keypair,
             self.chain_id,
         )
-        .unwrap())
     }
 }
 
@@ -255,10 +857,22 @@ impl std::fmt::Display for Wallet {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         writeln!(f)?;
         for (addr, keypair) in &self.keys {
+            if let Some(label) = self.labels.get(addr) {
+                writeln!(f, "Label: {}", label)?;
+            }
             writeln!(f, "Secret key: {}", keypair)?;
             writeln!(f, "Public key: {}", keypair.get_public_key())?;
             writeln!(f, "Address: {}", addr)?;
         }
+        for (addr, public_key) in &self.watch_only {
+            if let Some(label) = self.labels.get(addr) {
+                writeln!(f, "Label: {}", label)?;
+            }
+            if let Some(public_key) = public_key {
+                writeln!(f, "Public key: {}", public_key)?;
+            }
+            writeln!(f, "Watch-only address: {}", addr)?;
+        }
         Ok(())
     }
 }
@@ -266,3 +880,125 @@ impl std::fmt::Display for Wallet {
 /// Test utils
 #[cfg(feature = "test-exports")]
 pub mod test_exports;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_labels_and_watch_only_are_persisted() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        let mut wallet = Wallet::new(path.clone(), "pwd".to_string(), 77).unwrap();
+        let address = wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+        let watched = KeyPair::generate(0).unwrap().get_public_key();
+        let watched_address = Address::from_public_key(&watched);
+        wallet
+            .add_watch_only(vec![(watched_address, Some(watched))])
+            .unwrap();
+        wallet
+            .set_label(address, Some("savings".to_string()))
+            .unwrap();
+        wallet
+            .set_label(watched_address, Some("cold".to_string()))
+            .unwrap();
+
+        let wallet = Wallet::new(path.clone(), "pwd".to_string(), 77).unwrap();
+        assert_eq!(wallet.get_label(&address).unwrap(), "savings");
+        assert_eq!(wallet.get_label(&watched_address).unwrap(), "cold");
+        assert_eq!(
+            wallet.find_associated_public_key(&watched_address),
+            Some(watched)
+        );
+        assert!(!wallet.get_wallet_address_list().contains(&watched_address));
+        assert!(path
+            .join(WATCH_ONLY_DIRECTORY_NAME)
+            .join(format!("{}.yaml", watched_address))
+            .is_file());
+
+        let other = KeyPair::generate(0).unwrap().get_public_key();
+        let mut wallet = wallet;
+        assert!(wallet
+            .add_watch_only(vec![(watched_address, Some(other))])
+            .is_err());
+    }
+
+    #[test]
+    fn test_hd_seed_is_persisted() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        let phrase = format!("{} art", ["abandon"; 23].join(" "));
+        let mut wallet = Wallet::new(path.clone(), "pwd".to_string(), 77).unwrap();
+        let derived = wallet.import_mnemonic(&phrase, 2).unwrap();
+        let in_thread = wallet.derive_next_address(Some(5), 32).unwrap();
+        assert_eq!(in_thread.address.get_thread(32), 5);
+
+        // the mnemonic is stored encrypted
+        let content =
+            std::fs::read_to_string(path.join(HD_DIRECTORY_NAME).join(HD_SEED_FILE_NAME)).unwrap();
+        assert!(!content.contains("abandon"));
+
+        // files that are not part of the wallet are skipped on load and kept on save
+        std::fs::write(path.join("notes.txt"), "not a key").unwrap();
+        let mut loaded = Wallet::new(path.clone(), "pwd".to_string(), 77).unwrap();
+        loaded.save().unwrap();
+        assert!(path.join("notes.txt").is_file());
+        assert_eq!(loaded.get_mnemonic().as_deref(), Some(phrase.as_str()));
+        for address in derived.iter().map(|d| d.address).chain([in_thread.address]) {
+            assert_eq!(
+                loaded.find_associated_keypair(&address).unwrap().to_bytes(),
+                wallet.find_associated_keypair(&address).unwrap().to_bytes()
+            );
+        }
+        // the index of the next address to derive is restored too
+        assert_eq!(
+            loaded.derive_next_address(None, 32).unwrap().address,
+            wallet.derive_next_address(None, 32).unwrap().address
+        );
+
+        assert!(Wallet::new(path, "wrong".to_string(), 77).is_err());
+    }
+
+    #[test]
+    fn test_export_import() {
+        let folder = TempDir::new().unwrap();
+        let mut wallet = Wallet::new(folder.path().join("a"), "pwd".to_string(), 77).unwrap();
+        wallet.create_mnemonic().unwrap();
+        let address = wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+        wallet.set_label(address, Some("main".to_string())).unwrap();
+        let export_path = folder.path().join("export.yaml");
+        let unrelated_path = folder.path().join("export.tmp");
+        std::fs::write(&unrelated_path, "unrelated").unwrap();
+        wallet.export_to_file(&export_path, "export").unwrap();
+
+        // no temporary file is left and the other files are untouched
+        assert_eq!(std::fs::read_dir(folder.path()).unwrap().count(), 3);
+        assert_eq!(
+            std::fs::read_to_string(&unrelated_path).unwrap(),
+            "unrelated"
+        );
+
+        let imported_path = folder.path().join("b");
+        let mut imported = Wallet::new(imported_path.clone(), "other".to_string(), 77).unwrap();
+        assert!(imported.import_from_file(&export_path, "wrong").is_err());
+        // an import that cannot be saved leaves the wallet unchanged
+        std::fs::write(&imported_path, "").unwrap();
+        assert!(imported.import_from_file(&export_path, "export").is_err());
+        assert!(imported.get_full_wallet().is_empty());
+        assert_eq!(imported.get_mnemonic(), None);
+        std::fs::remove_file(&imported_path).unwrap();
+        let addresses = imported.import_from_file(&export_path, "export").unwrap();
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(imported.get_mnemonic(), wallet.get_mnemonic());
+        assert_eq!(imported.get_label(&address).unwrap(), "main");
+        assert_eq!(
+            imported.derive_next_address(None, 32).unwrap().address,
+            wallet.derive_next_address(None, 32).unwrap().address
+        );
+    }
+}
@@ -0,0 +1,623 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Signers of blocks, endorsements and operations.
+//!
+//! The keys can be held by the wallet itself (`LocalSigner`), or by a separate signer process reached
+//! over a Unix socket (`RemoteSigner`), so that they never live on an internet-facing node.
+//! The `massa-signer` binary is such a process.
+//!
+//! Remote signer protocol: the node opens a connection per request, writes a `SignerRequest` as one line of JSON
+//! and reads a `SignerResponse` as one line of JSON. A signature request carries the serialized block header,
+//! endorsement or operation (`SigningPayload`) and the chain id: the signer deserializes it to know what it signs
+//! and to enforce its own slashing protection, then hashes it itself, so that it never signs an opaque hash.
+//! The node checks every signature it gets back. Only the processes allowed to open the socket can get signatures.
+
+use crate::{Wallet, WalletError};
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_models::block_header::{BlockHeader, BlockHeaderDeserializer};
+use massa_models::config::{
+    ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+    MAX_FUNCTION_NAME_LENGTH, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
+    THREAD_COUNT,
+};
+use massa_models::endorsement::{Endorsement, EndorsementDeserializer};
+use massa_models::operation::{Operation, OperationDeserializer};
+use massa_models::prehash::PreHashMap;
+use massa_models::secure_share::{Id, SecureShare, SecureShareContent};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use massa_signature::{KeyPair, PublicKey, Signature};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::warn;
+
+/// Content to sign, serialized as in the structure carrying its signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningPayload {
+    /// serialized header of a block
+    BlockHeader(Vec<u8>),
+    /// serialized endorsement
+    Endorsement(Vec<u8>),
+    /// serialized operation
+    Operation(Vec<u8>),
+}
+
+/// Deserialized content of a `SigningPayload`
+#[derive(Debug, Clone)]
+pub enum SignedContent {
+    /// header of a block
+    BlockHeader(BlockHeader),
+    /// endorsement
+    Endorsement(Endorsement),
+    /// operation
+    Operation(Operation),
+}
+
+/// Deserialize a whole buffer, refusing trailing bytes
+fn deserialize_all<T, D: Deserializer<T>>(
+    deserializer: &D,
+    serialized: &[u8],
+) -> Result<T, WalletError> {
+    let (rest, content) = deserializer
+        .deserialize::<DeserializeError>(serialized)
+        .map_err(|err| WalletError::SignerError(format!("invalid signing payload: {}", err)))?;
+    if !rest.is_empty() {
+        return Err(WalletError::SignerError(
+            "invalid signing payload: trailing bytes".to_string(),
+        ));
+    }
+    Ok(content)
+}
+
+/// Hash signed by the creator of a content, as computed by `SecureShareContent::new_verifiable`
+fn signed_hash<T: SecureShareContent>(
+    content: &T,
+    serialized: &[u8],
+    public_key: &PublicKey,
+    chain_id: u64,
+) -> Hash {
+    let hash = content.compute_hash(serialized, public_key, chain_id);
+    content.compute_signed_hash(public_key, &hash)
+}
+
+impl SigningPayload {
+    /// Serialized content
+    pub fn serialized(&self) -> &[u8] {
+        match self {
+            SigningPayload::BlockHeader(serialized)
+            | SigningPayload::Endorsement(serialized)
+            | SigningPayload::Operation(serialized) => serialized,
+        }
+    }
+
+    /// Deserialize the content, with the limits of the network
+    pub fn deserialize(&self, chain_id: u64) -> Result<SignedContent, WalletError> {
+        match self {
+            SigningPayload::BlockHeader(serialized) => deserialize_all(
+                &BlockHeaderDeserializer::new(
+                    THREAD_COUNT,
+                    ENDORSEMENT_COUNT,
+                    MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+                    None,
+                    chain_id,
+                ),
+                serialized,
+            )
+            .map(SignedContent::BlockHeader),
+            SigningPayload::Endorsement(serialized) => deserialize_all(
+                &EndorsementDeserializer::new(THREAD_COUNT, ENDORSEMENT_COUNT),
+                serialized,
+            )
+            .map(SignedContent::Endorsement),
+            SigningPayload::Operation(serialized) => deserialize_all(
+                &OperationDeserializer::new(
+                    MAX_DATASTORE_VALUE_LENGTH,
+                    MAX_FUNCTION_NAME_LENGTH,
+                    MAX_PARAMETERS_SIZE,
+                    MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+                    MAX_OPERATION_DATASTORE_KEY_LENGTH,
+                    MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+                ),
+                serialized,
+            )
+            .map(SignedContent::Operation),
+        }
+    }
+}
+
+/// Signs a payload with `keypair`, as `SecureShareContent::new_verifiable` signs its content.
+/// The payload is deserialized first, and `check` can refuse the signature of its content.
+pub fn sign_payload(
+    keypair: &KeyPair,
+    payload: &SigningPayload,
+    chain_id: u64,
+    check: impl FnOnce(&SignedContent) -> Result<(), WalletError>,
+) -> Result<Signature, WalletError> {
+    let content = payload.deserialize(chain_id)?;
+    check(&content)?;
+    let public_key = keypair.get_public_key();
+    let serialized = payload.serialized();
+    let hash = match &content {
+        SignedContent::BlockHeader(header) => {
+            signed_hash(header, serialized, &public_key, chain_id)
+        }
+        SignedContent::Endorsement(endorsement) => {
+            signed_hash(endorsement, serialized, &public_key, chain_id)
+        }
+        SignedContent::Operation(operation) => {
+            signed_hash(operation, serialized, &public_key, chain_id)
+        }
+    };
+    Ok(keypair.sign(&hash)?)
+}
+
+/// Contents that a `Signer` can sign
+pub trait SignableContent: SecureShareContent {
+    /// Wraps the serialized content in the payload of its kind
+    fn to_payload(serialized: Vec<u8>) -> SigningPayload;
+}
+
+impl SignableContent for BlockHeader {
+    fn to_payload(serialized: Vec<u8>) -> SigningPayload {
+        SigningPayload::BlockHeader(serialized)
+    }
+}
+
+impl SignableContent for Endorsement {
+    fn to_payload(serialized: Vec<u8>) -> SigningPayload {
+        SigningPayload::Endorsement(serialized)
+    }
+}
+
+impl SignableContent for Operation {
+    fn to_payload(serialized: Vec<u8>) -> SigningPayload {
+        SigningPayload::Operation(serialized)
+    }
+}
+
+/// Holder of secret keys, signing serialized contents with them
+pub trait Signer: Send + Sync + Debug {
+    /// Returns the public key of an address whose secret key is held by the signer, `None` if it is not held
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError>;
+
+    /// Signs a serialized content with the secret key of an address, for the chain `chain_id`
+    fn sign(
+        &self,
+        address: &Address,
+        payload: &SigningPayload,
+        chain_id: u64,
+    ) -> Result<Signature, WalletError>;
+}
+
+/// Signs a content with the key of `address` held by `signer`, like `SecureShareContent::new_verifiable` does with a keypair.
+/// The signature is checked before being returned, a remote signer not being trusted to sign with the right key.
+pub fn sign_content<T, Ser, ID>(
+    signer: &dyn Signer,
+    address: &Address,
+    content: T,
+    content_serializer: Ser,
+    chain_id: u64,
+) -> Result<SecureShare<T, ID>, WalletError>
+where
+    T: SignableContent,
+    Ser: Serializer<T>,
+    ID: Id,
+{
+    let public_key = signer
+        .get_public_key(address)?
+        .ok_or(WalletError::MissingKeyError(*address))?;
+    if Address::from_public_key(&public_key) != *address {
+        return Err(WalletError::SignerError(format!(
+            "the signer returned public key {} for address {}",
+            public_key, address
+        )));
+    }
+    let mut serialized_data = Vec::new();
+    content_serializer
+        .serialize(&content, &mut serialized_data)
+        .map_err(|err| WalletError::SignerError(err.to_string()))?;
+    let signature = signer.sign(address, &T::to_payload(serialized_data.clone()), chain_id)?;
+    let hash = content.compute_hash(&serialized_data, &public_key, chain_id);
+    content.verify_signature(&public_key, &hash, &signature)?;
+    Ok(SecureShare {
+        content,
+        serialized_data,
+        signature,
+        content_creator_pub_key: public_key,
+        content_creator_address: *address,
+        id: ID::new(hash),
+    })
+}
+
+impl Signer for Wallet {
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+        Ok(self
+            .find_associated_keypair(address)
+            .map(|keypair| keypair.get_public_key()))
+    }
+
+    fn sign(
+        &self,
+        address: &Address,
+        payload: &SigningPayload,
+        chain_id: u64,
+    ) -> Result<Signature, WalletError> {
+        let keypair = self
+            .find_associated_keypair(address)
+            .ok_or(WalletError::MissingKeyError(*address))?;
+        sign_payload(keypair, payload, chain_id, |_| Ok(()))
+    }
+}
+
+/// Signer using the keys of a shared wallet
+#[derive(Debug, Clone)]
+pub struct LocalSigner {
+    wallet: Arc<RwLock<Wallet>>,
+}
+
+impl LocalSigner {
+    /// Creates a signer using the keys of `wallet`, including the ones added later
+    pub fn new(wallet: Arc<RwLock<Wallet>>) -> Self {
+        LocalSigner { wallet }
+    }
+}
+
+impl Signer for LocalSigner {
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+        self.wallet.read().get_public_key(address)
+    }
+
+    fn sign(
+        &self,
+        address: &Address,
+        payload: &SigningPayload,
+        chain_id: u64,
+    ) -> Result<Signature, WalletError> {
+        self.wallet.read().sign(address, payload, chain_id)
+    }
+}
+
+/// Request sent to a remote signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// get the public keys of the addresses whose secret key is held by the signer
+    GetPublicKeys,
+    /// sign a serialized content with the key of an address
+    Sign {
+        /// address
+        address: Address,
+        /// chain id of the network of the content
+        chain_id: u64,
+        /// content to sign
+        payload: SigningPayload,
+    },
+}
+
+/// Response of a remote signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    /// public keys of the addresses whose secret key is held by the signer
+    PublicKeys {
+        /// public keys
+        public_keys: Vec<PublicKey>,
+    },
+    /// signature of the content
+    Signature {
+        /// signature
+        signature: Signature,
+    },
+    /// the request was refused or failed
+    Error {
+        /// reason
+        message: String,
+    },
+}
+
+/// Signer process reached over a Unix socket.
+/// The public keys of the addresses it holds are cached, so that checking whether an address is staked by it
+/// costs no request.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    /// timeout of each read and write
+    timeout: Duration,
+    /// public keys of the addresses held by the signer
+    public_keys: Arc<RwLock<PreHashMap<Address, PublicKey>>>,
+}
+
+impl RemoteSigner {
+    /// Creates a client of the signer listening on the Unix socket `socket_path`,
+    /// and caches the public keys of the addresses it holds
+    pub fn connect(socket_path: PathBuf, timeout: Duration) -> Result<Self, WalletError> {
+        let public_keys = fetch_public_keys(&socket_path, timeout)?;
+        Ok(RemoteSigner {
+            socket_path,
+            timeout,
+            public_keys: Arc::new(RwLock::new(public_keys)),
+        })
+    }
+
+    /// Fetches the public keys of the addresses held by the signer again
+    pub fn refresh_public_keys(&self) -> Result<(), WalletError> {
+        *self.public_keys.write() = fetch_public_keys(&self.socket_path, self.timeout)?;
+        Ok(())
+    }
+
+    /// Refreshes the cached public keys every `interval` in a background thread,
+    /// until all the clones of this signer are dropped.
+    /// A failed refresh is logged and keeps the previous keys.
+    pub fn spawn_public_keys_refresh(&self, interval: Duration) -> std::io::Result<JoinHandle<()>> {
+        let socket_path = self.socket_path.clone();
+        let timeout = self.timeout;
+        let public_keys = Arc::downgrade(&self.public_keys);
+        std::thread::Builder::new()
+            .name("remote-signer-keys".into())
+            .spawn(move || loop {
+                std::thread::sleep(interval);
+                let Some(public_keys) = public_keys.upgrade() else {
+                    return;
+                };
+                match fetch_public_keys(&socket_path, timeout) {
+                    Ok(keys) => *public_keys.write() = keys,
+                    Err(err) => warn!("could not refresh the keys of the remote signer: {}", err),
+                }
+            })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+        Ok(self.public_keys.read().get(address).copied())
+    }
+
+    fn sign(
+        &self,
+        address: &Address,
+        payload: &SigningPayload,
+        chain_id: u64,
+    ) -> Result<Signature, WalletError> {
+        let request = SignerRequest::Sign {
+            address: *address,
+            chain_id,
+            payload: payload.clone(),
+        };
+        match send_request(&self.socket_path, self.timeout, &request)? {
+            SignerResponse::Signature { signature } => Ok(signature),
+            SignerResponse::Error { message } => Err(WalletError::SignerError(message)),
+            response => Err(WalletError::SignerError(format!(
+                "unexpected signer response: {:?}",
+                response
+            ))),
+        }
+    }
+}
+
+/// Gets the public keys of the addresses held by the signer listening on `socket_path`
+fn fetch_public_keys(
+    socket_path: &Path,
+    timeout: Duration,
+) -> Result<PreHashMap<Address, PublicKey>, WalletError> {
+    match send_request(socket_path, timeout, &SignerRequest::GetPublicKeys)? {
+        SignerResponse::PublicKeys { public_keys } => Ok(public_keys
+            .into_iter()
+            .map(|public_key| (Address::from_public_key(&public_key), public_key))
+            .collect()),
+        SignerResponse::Error { message } => Err(WalletError::SignerError(message)),
+        response => Err(WalletError::SignerError(format!(
+            "unexpected signer response: {:?}",
+            response
+        ))),
+    }
+}
+
+/// Sends a request to the signer listening on `socket_path` and reads its response
+fn send_request(
+    socket_path: &Path,
+    timeout: Duration,
+    request: &SignerRequest,
+) -> Result<SignerResponse, WalletError> {
+    #[cfg(unix)]
+    let response = std::os::unix::net::UnixStream::connect(socket_path).and_then(|stream| {
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(stream)
+    });
+    #[cfg(not(unix))]
+    let response: std::io::Result<std::fs::File> = {
+        let _ = timeout;
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "unix sockets are not supported on this platform",
+        ))
+    };
+    response
+        .map_err(WalletError::from)
+        .and_then(|stream| exchange(stream, request))
+        .map_err(|err| {
+            WalletError::SignerError(format!("signer {}: {}", socket_path.display(), err))
+        })
+}
+
+/// Writes a request as a line of JSON and reads the response line
+fn exchange<S: Read + Write>(
+    mut stream: S,
+    request: &SignerRequest,
+) -> Result<SignerResponse, WalletError> {
+    let mut line = serde_json::to_vec(request)?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+    stream.flush()?;
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+/// Answers the requests of a connection with `handler`, until the connection is closed.
+/// A request that fails gets an error response.
+pub fn serve_signer_connection<S: Read + Write>(
+    stream: S,
+    mut handler: impl FnMut(SignerRequest) -> Result<SignerResponse, WalletError>,
+) -> Result<(), WalletError> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let response = serde_json::from_str::<SignerRequest>(&line)
+            .map_err(|err| WalletError::SignerError(format!("invalid request: {}", err)))
+            .and_then(&mut handler)
+            .unwrap_or_else(|err| SignerResponse::Error {
+                message: err.to_string(),
+            });
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        let stream = reader.get_mut();
+        stream.write_all(&response)?;
+        stream.flush()?;
+        line.clear();
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use massa_models::block_id::BlockId;
+    use massa_models::endorsement::{EndorsementId, EndorsementSerializer, SecureShareEndorsement};
+    use massa_models::slot::Slot;
+    use std::os::unix::net::UnixListener;
+    use tempfile::TempDir;
+
+    fn endorsement(index: u32) -> Endorsement {
+        Endorsement {
+            slot: Slot::new(1, 0),
+            index,
+            endorsed_block: BlockId::generate_from_hash(Hash::compute_from(b"block")),
+        }
+    }
+
+    #[test]
+    fn test_remote_signer_over_unix_socket() {
+        let folder = TempDir::new().unwrap();
+        let wallet = Arc::new(RwLock::new(
+            Wallet::new(folder.path().join("wallet"), "pwd".to_string(), 77).unwrap(),
+        ));
+        let address = wallet
+            .write()
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+        let unknown = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let socket_path = folder.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let served_wallet = wallet.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                serve_signer_connection(stream.unwrap(), |request| match request {
+                    SignerRequest::GetPublicKeys => Ok(SignerResponse::PublicKeys {
+                        public_keys: served_wallet
+                            .read()
+                            .get_full_wallet()
+                            .values()
+                            .map(|keypair| keypair.get_public_key())
+                            .collect(),
+                    }),
+                    SignerRequest::Sign {
+                        address,
+                        chain_id,
+                        payload,
+                    } => {
+                        // refuse the endorsements of index 0, like a slashing protection would
+                        let keypair = served_wallet
+                            .read()
+                            .find_associated_keypair(&address)
+                            .cloned()
+                            .ok_or(WalletError::MissingKeyError(address))?;
+                        let signature =
+                            sign_payload(&keypair, &payload, chain_id, |content| match content {
+                                SignedContent::Endorsement(endorsement)
+                                    if endorsement.index == 0 =>
+                                {
+                                    Err(WalletError::SignerError("refused".to_string()))
+                                }
+                                _ => Ok(()),
+                            })?;
+                        Ok(SignerResponse::Signature { signature })
+                    }
+                })
+                .unwrap();
+            }
+        });
+
+        let signer = RemoteSigner::connect(socket_path, Duration::from_secs(5)).unwrap();
+        assert!(signer.get_public_key(&address).unwrap().is_some());
+        assert_eq!(signer.get_public_key(&unknown).unwrap(), None);
+
+        let signed: SecureShareEndorsement = sign_content(
+            &signer,
+            &address,
+            endorsement(3),
+            EndorsementSerializer::new(),
+            77,
+        )
+        .unwrap();
+        assert_eq!(signed.content_creator_address, address);
+        signed.verify_signature().unwrap();
+        assert!(sign_content::<_, _, EndorsementId>(
+            &signer,
+            &address,
+            endorsement(0),
+            EndorsementSerializer::new(),
+            77,
+        )
+        .is_err());
+        assert!(sign_content::<_, _, EndorsementId>(
+            &signer,
+            &unknown,
+            endorsement(3),
+            EndorsementSerializer::new(),
+            77,
+        )
+        .is_err());
+
+        // the cached keys follow the keys of the signer once refreshed
+        let added = wallet
+            .write()
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+        assert_eq!(signer.get_public_key(&added).unwrap(), None);
+        signer.refresh_public_keys().unwrap();
+        assert!(signer.get_public_key(&added).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_sign_payload_hashes_the_payload() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let mut serialized = Vec::new();
+        EndorsementSerializer::new()
+            .serialize(&endorsement(3), &mut serialized)
+            .unwrap();
+        let payload = SigningPayload::Endorsement(serialized);
+        let signature = sign_payload(&keypair, &payload, 77, |_| Ok(())).unwrap();
+        let expected: SecureShareEndorsement = endorsement(3)
+            .new_verifiable(EndorsementSerializer::new(), &keypair, 77)
+            .unwrap();
+        assert_eq!(signature, expected.signature);
+
+        // a payload that is not the announced content is refused
+        let mut invalid = payload.serialized().to_vec();
+        invalid.push(0);
+        let refused = sign_payload(&keypair, &SigningPayload::Endorsement(invalid), 77, |_| {
+            Ok(())
+        });
+        assert!(refused.is_err());
+    }
+}